@@ -0,0 +1,488 @@
+//! HTTP server rendering frames on request, so other services can embed the
+//! globe without linking the library.
+//!
+//! Enabled with the `api` feature.
+
+use std::collections::BTreeSet;
+use std::io::Read;
+use std::time::{Duration, Instant};
+
+use globe::{
+    Atmosphere, CameraConfig, Canvas, Color, DataGrid, Float, GeoCoord, Globe, GlobeConfig,
+    LineLayer, Marker, MarkerStyle, RenderStyle, Specular,
+};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::flights::Flights;
+use crate::rng::Rng;
+use crate::{local_time, new_heatmap, parse_lat_lon, parse_size, Settings, CANVAS_PRESET};
+
+/// Size of frames requested without a `size` parameter.
+const DEFAULT_SIZE: (u16, u16) = (80, 24);
+/// Largest frame that can be requested, in characters.
+const MAX_SIZE: (u16, u16) = (500, 250);
+/// Largest request body accepted, in bytes.
+const MAX_BODY: u64 = 64 * 1024;
+/// Most markers kept on the globe, posting more is refused until some expire
+/// or are removed.
+const MAX_MARKERS: usize = 10_000;
+/// Most marker groups that can be styled, styling more is refused.
+const MAX_GROUPS: usize = 256;
+/// Longest marker group name, in bytes.
+const MAX_GROUP_NAME: usize = 64;
+/// Character of markers posted without one.
+const DEFAULT_MARKER: char = 'X';
+/// Path under which marker groups are addressed by name.
+const GROUPS_PATH: &str = "/groups/";
+
+/// Outcome of handling a request, errors carrying the status code and the
+/// message sent back.
+type ApiResult<T> = Result<T, (u16, String)>;
+
+/// State shared by all requests, which are handled one at a time.
+struct ApiState {
+    globe: Globe,
+    /// Location frames focus on unless the request names one
+    location: GeoCoord,
+    render_style: RenderStyle,
+    night: bool,
+    /// Look of posted markers, from the theme
+    marker_style: MarkerStyle,
+    /// Light frames from where the sun is at the time of the request
+    real_sun: bool,
+    /// Color of heatmaps started by posted samples
+    heatmap_color: Option<Color>,
+    /// Time expiring markers were last aged
+    aged_at: Instant,
+    /// Names of the marker groups styled so far, which the globe keeps
+    /// whether or not any markers belong to them
+    styled_groups: BTreeSet<String>,
+}
+
+/// Listens on the given address, answering requests until the process is
+/// killed.
+///
+/// Frames are rendered using the globe, style and camera distance given in
+/// the settings, the style and night side can be overridden per request.
+pub fn serve(address: &str, settings: &Settings) {
+    let mut globe = GlobeConfig::new()
+        .use_template(settings.template)
+        .with_camera(CameraConfig::new(settings.cam_zoom, 0., 0.))
+        .display_night(settings.night)
+        .with_render_style(settings.render_style)
+        .build()
+        .expect("built-in templates are valid");
+    if let Some(tilt) = settings.axial_tilt {
+        globe.set_axial_tilt(tilt);
+    }
+    if settings.glint {
+        globe.set_specular(Some(Specular::default()));
+    }
+    if settings.atmosphere {
+        globe.set_atmosphere(Some(Atmosphere::default()));
+    }
+    globe.set_terminator(settings.terminator);
+    globe.set_clouds(settings.clouds.clone());
+    globe.set_guides(settings.guides);
+    if settings.population {
+        globe.set_density(Some(DataGrid::population_density()));
+    }
+    if settings.borders {
+        globe.add_line_layer(LineLayer::country_borders());
+    }
+    for layer in &settings.line_layers {
+        // dense datasets only blur into the cells they cross
+        globe.add_line_layer(layer.clone().simplified_for(globe.texture()));
+    }
+    for grid in &settings.data_grids {
+        globe.add_data_grid(grid.clone());
+    }
+    if let Some(series) = &settings.data_series {
+        globe.add_data_series(series.clone());
+    }
+    for field in &settings.vector_fields {
+        globe.add_vector_field(field.clone());
+    }
+    for geofence in &settings.geofences {
+        globe.add_geofence(geofence.clone());
+    }
+    for &arc in &settings.arcs {
+        globe.add_arc(arc);
+    }
+    for (lat, lon, text) in &settings.labels {
+        globe.add_label(*lat, *lon, text);
+    }
+    for marker in &settings.markers {
+        globe.add_marker(marker.clone());
+    }
+    if !settings.routes.is_empty() {
+        // frames are rendered on request, too rarely to show planes moving
+        Flights::new(
+            &settings.routes,
+            false,
+            &mut globe,
+            &mut Rng::new(settings.seed),
+        );
+    }
+    if !settings.heatmap.is_empty() {
+        let mut heatmap = new_heatmap(&globe, settings.heatmap_color);
+        for &coord in &settings.heatmap {
+            heatmap.add(coord);
+        }
+        globe.set_heatmap(Some(heatmap));
+    }
+    globe.apply_theme(&settings.theme);
+    let mut state = ApiState {
+        globe,
+        location: settings.coords,
+        render_style: settings.render_style,
+        night: settings.night,
+        marker_style: settings.theme.marker_style,
+        real_sun: settings.real_sun,
+        heatmap_color: settings.heatmap_color,
+        aged_at: Instant::now(),
+        styled_groups: BTreeSet::new(),
+    };
+
+    let server =
+        Server::http(address).unwrap_or_else(|e| panic!("failed listening on {}: {}", address, e));
+    println!("serving frames on http://{}", server.server_addr());
+    for mut request in server.incoming_requests() {
+        let (status, body) = handle(&mut request, &mut state);
+        let content_type = Header::from_bytes("Content-Type", "text/plain; charset=utf-8")
+            .expect("header is valid");
+        let response = Response::from_string(body)
+            .with_status_code(status)
+            .with_header(content_type);
+        // the client hung up, nothing to do about it
+        let _ = request.respond(response);
+    }
+}
+
+/// Answers the request, returning the status code and the response text.
+fn handle(request: &mut Request, state: &mut ApiState) -> (u16, String) {
+    let url = request.url().to_string();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+    // markers expire in real time between requests
+    let now = Instant::now();
+    state.globe.age_markers(now - state.aged_at);
+    state.aged_at = now;
+
+    let result = match (request.method(), path) {
+        (Method::Get, "/frame") => frame(query, state),
+        (Method::Get, "/markers") => Ok((200, list_markers(&state.globe))),
+        (Method::Post, "/markers") => {
+            read_body(request).and_then(|body| add_markers(query, &body, state))
+        }
+        (Method::Delete, "/markers") => {
+            state.globe.clear_markers();
+            Ok((200, "removed all markers\n".to_string()))
+        }
+        (Method::Post, "/heatmap") => {
+            read_body(request).and_then(|body| add_heatmap_samples(&body, state))
+        }
+        (Method::Delete, "/heatmap") => {
+            state.globe.set_heatmap(None);
+            Ok((200, "removed the heatmap\n".to_string()))
+        }
+        (Method::Get, "/groups") => Ok((200, list_groups(&state.globe))),
+        (Method::Put, _) if path.starts_with(GROUPS_PATH) => {
+            update_group(&path[GROUPS_PATH.len()..], query, state)
+        }
+        (_, "/frame") | (_, "/markers") | (_, "/heatmap") | (_, "/groups") => {
+            Err((405, "method not allowed".to_string()))
+        }
+        _ if path.starts_with(GROUPS_PATH) => Err((405, "method not allowed".to_string())),
+        _ => Err((404, "not found".to_string())),
+    };
+    result.unwrap_or_else(|(status, message)| (status, format!("{}\n", message)))
+}
+
+/// Renders a frame as described by the query parameters: `lat` and `lon` in
+/// degrees, `size` as columns and rows, `style` and `night`.
+fn frame(query: &str, state: &mut ApiState) -> ApiResult<(u16, String)> {
+    let (mut lat, mut lon) = (None, None);
+    let mut size = DEFAULT_SIZE;
+    let mut style = state.render_style;
+    let mut night = state.night;
+    for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+        match key {
+            "lat" => lat = Some(parse_degrees(key, value)?),
+            "lon" => lon = Some(parse_degrees(key, value)?),
+            "size" => {
+                size = parse_size(value)
+                    .filter(|&(cols, rows)| cols <= MAX_SIZE.0 && rows <= MAX_SIZE.1)
+                    .ok_or_else(|| {
+                        bad_request(format!(
+                            "invalid size, expected up to {}x{}",
+                            MAX_SIZE.0, MAX_SIZE.1
+                        ))
+                    })?
+            }
+            "style" => style = parse_style(value)?,
+            "night" => night = parse_bool(key, value)?,
+            _ => return Err(bad_request(format!("unknown parameter {}", key))),
+        }
+    }
+    let location = match (lat, lon) {
+        (Some(lat), Some(lon)) => GeoCoord::from_lat_lon(lat, lon),
+        (None, None) => state.location,
+        _ => return Err(bad_request("lat and lon have to be given together")),
+    };
+
+    let globe = &mut state.globe;
+    globe.set_render_style(style);
+    globe.set_display_night(night);
+    globe.focus(location);
+    if state.real_sun {
+        globe.set_sun_from_time(local_time::unix_secs());
+    }
+    let mut canvas = Canvas::for_terminal(size, CANVAS_PRESET);
+    let mut frame = globe.render_to_string(&mut canvas);
+    frame.push('\n');
+    Ok((200, frame))
+}
+
+/// Adds the markers in the body, expiring after `ttl` seconds and fading out
+/// over the last `fade` seconds if those query parameters are given.
+fn add_markers(query: &str, body: &str, state: &mut ApiState) -> ApiResult<(u16, String)> {
+    let (mut ttl, mut fade) = (None, None);
+    for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+        match key {
+            "ttl" => ttl = Some(parse_seconds(key, value)?),
+            "fade" => fade = Some(parse_seconds(key, value)?),
+            _ => return Err(bad_request(format!("unknown parameter {}", key))),
+        }
+    }
+    if ttl.is_none() && fade.is_some() {
+        return Err(bad_request("fade has to be given along with ttl"));
+    }
+    let markers = parse_markers(body)?;
+    let count = markers.len();
+    if state.globe.markers().len() + count > MAX_MARKERS {
+        return Err((
+            429,
+            format!(
+                "too many markers, up to {} are kept, {} already are",
+                MAX_MARKERS,
+                state.globe.markers().len()
+            ),
+        ));
+    }
+    for mut marker in markers {
+        if let Some(ttl) = ttl {
+            marker = marker.with_ttl(ttl, fade.unwrap_or_default());
+        }
+        state.globe.add_marker(state.marker_style.apply(marker));
+    }
+    Ok((201, format!("added {} markers\n", count)))
+}
+
+/// Counts the locations in the body, one `lat,lon` line each, into the
+/// heatmap, starting one if there's none yet.
+fn add_heatmap_samples(body: &str, state: &mut ApiState) -> ApiResult<(u16, String)> {
+    let samples = body
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            parse_lat_lon(line).ok_or_else(|| {
+                bad_request(format!("invalid location {:?}, expected lat,lon", line))
+            })
+        })
+        .collect::<ApiResult<Vec<_>>>()?;
+    if state.globe.heatmap().is_none() {
+        let heatmap = new_heatmap(&state.globe, state.heatmap_color);
+        state.globe.set_heatmap(Some(heatmap));
+    }
+    if let Some(heatmap) = state.globe.heatmap_mut() {
+        for &coord in &samples {
+            heatmap.add(coord);
+        }
+    }
+    Ok((201, format!("added {} heatmap samples\n", samples.len())))
+}
+
+/// Returns the markers on the globe, one `lat,lon,marker` line each, followed
+/// by the group for markers in one.
+fn list_markers(globe: &Globe) -> String {
+    globe
+        .markers()
+        .iter()
+        .map(|marker| {
+            let (lat, lon) = marker.coord.lat_lon();
+            match &marker.group {
+                Some(group) => format!("{:.4},{:.4},{},{}\n", lat, lon, marker.ch, group),
+                None => format!("{:.4},{:.4},{}\n", lat, lon, marker.ch),
+            }
+        })
+        .collect()
+}
+
+/// Returns the groups markers on the globe belong to, one
+/// `name,visible,char,color` line each, leaving out what the group doesn't
+/// override.
+fn list_groups(globe: &Globe) -> String {
+    globe
+        .marker_group_names()
+        .into_iter()
+        .map(|name| {
+            let group = globe.marker_group(name);
+            let ch = group.style.ch.map(String::from).unwrap_or_default();
+            let color = group.style.color.map(hex_color).unwrap_or_default();
+            format!("{},{},{},{}\n", name, group.visible, ch, color)
+        })
+        .collect()
+}
+
+/// Changes the named marker group as described by the query parameters:
+/// `char` and `color` as `rrggbb` overriding those of its markers, or
+/// clearing the override if empty, and `visible`.
+fn update_group(name: &str, query: &str, state: &mut ApiState) -> ApiResult<(u16, String)> {
+    if name.is_empty() || name.len() > MAX_GROUP_NAME || name.contains(['/', ',']) {
+        return Err(bad_request(format!(
+            "invalid group name, expected up to {} bytes without / or ,",
+            MAX_GROUP_NAME
+        )));
+    }
+    if !state.styled_groups.contains(name) && state.styled_groups.len() >= MAX_GROUPS {
+        return Err((
+            429,
+            format!("too many groups, up to {} can be styled", MAX_GROUPS),
+        ));
+    }
+    let globe = &mut state.globe;
+    let mut group = globe.marker_group(name);
+    for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+        match key {
+            "char" => {
+                let mut chars = value.chars();
+                group.style.ch = match (chars.next(), chars.next()) {
+                    (ch, None) => ch,
+                    _ => return Err(bad_request("invalid char, expected one character")),
+                }
+            }
+            "color" => {
+                group.style.color = match value {
+                    "" => None,
+                    _ => Some(parse_color(value)?),
+                }
+            }
+            "visible" => group.visible = parse_bool(key, value)?,
+            _ => return Err(bad_request(format!("unknown parameter {}", key))),
+        }
+    }
+    globe.set_marker_group(name, group);
+    state.styled_groups.insert(name.to_string());
+    Ok((200, format!("updated group {}\n", name)))
+}
+
+/// Parses markers given one per line as `lat,lon`, in degrees, optionally
+/// followed by the marker character and the group, e.g. `51.5,-0.1,L` or
+/// `51.5,-0.1,,cities`.
+fn parse_markers(body: &str) -> ApiResult<Vec<Marker>> {
+    body.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let invalid = || {
+                bad_request(format!(
+                    "invalid marker {:?}, expected lat,lon[,char[,group]]",
+                    line
+                ))
+            };
+            let mut fields = line.split(',').map(str::trim);
+            let mut degrees = || {
+                fields
+                    .next()
+                    .and_then(|value| value.parse::<Float>().ok())
+                    .filter(|value| value.is_finite())
+            };
+            let (lat, lon) = (
+                degrees().ok_or_else(invalid)?,
+                degrees().ok_or_else(invalid)?,
+            );
+            let mut chars = fields.next().unwrap_or("").chars();
+            let ch = match (chars.next(), chars.next()) {
+                (None, _) => DEFAULT_MARKER,
+                (Some(ch), None) => ch,
+                _ => return Err(invalid()),
+            };
+            let marker = Marker::new(GeoCoord::from_lat_lon(lat, lon), ch);
+            match (fields.next(), fields.next()) {
+                (None, _) => Ok(marker),
+                (Some(group), None) if !group.is_empty() => Ok(marker.with_group(group)),
+                _ => Err(invalid()),
+            }
+        })
+        .collect()
+}
+
+/// Reads the request body as text, refusing overly large ones.
+fn read_body(request: &mut Request) -> ApiResult<String> {
+    let mut body = String::new();
+    request
+        .as_reader()
+        .take(MAX_BODY + 1)
+        .read_to_string(&mut body)
+        .map_err(|_| bad_request("request body is not valid UTF-8"))?;
+    if body.len() as u64 > MAX_BODY {
+        return Err((413, "request body too large".to_string()));
+    }
+    Ok(body)
+}
+
+fn parse_degrees(key: &str, value: &str) -> ApiResult<Float> {
+    value
+        .parse::<Float>()
+        .ok()
+        .filter(|degrees| degrees.is_finite())
+        .ok_or_else(|| bad_request(format!("invalid {}, expected degrees", key)))
+}
+
+fn parse_seconds(key: &str, value: &str) -> ApiResult<Duration> {
+    value
+        .parse::<f64>()
+        .ok()
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        .ok_or_else(|| bad_request(format!("invalid {}, expected seconds", key)))
+}
+
+fn parse_bool(key: &str, value: &str) -> ApiResult<bool> {
+    match value {
+        "true" | "1" => Ok(true),
+        "false" | "0" => Ok(false),
+        _ => Err(bad_request(format!(
+            "invalid {}, expected true or false",
+            key
+        ))),
+    }
+}
+
+/// Parses a color given as `rrggbb`, leaving out the `#` which would have to
+/// be escaped in the query.
+fn parse_color(value: &str) -> ApiResult<Color> {
+    crate::parse_color(value)
+        .filter(|_| !value.starts_with('#'))
+        .ok_or_else(|| bad_request("invalid color, expected rrggbb"))
+}
+
+fn hex_color(color: Color) -> String {
+    format!("{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+}
+
+fn parse_style(value: &str) -> ApiResult<RenderStyle> {
+    match value {
+        "ascii" => Ok(RenderStyle::Ascii),
+        "braille" => Ok(RenderStyle::Braille),
+        "half-block" => Ok(RenderStyle::HalfBlock),
+        "quadrant" => Ok(RenderStyle::Quadrant),
+        _ => Err(bad_request(
+            "invalid style, expected ascii, braille, half-block or quadrant",
+        )),
+    }
+}
+
+fn bad_request(message: impl Into<String>) -> (u16, String) {
+    (400, message.into())
+}
@@ -0,0 +1,866 @@
+//! Output backends used for displaying rendered frames.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, stdout, Stdout, Write};
+use std::panic;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crossterm::{
+    cursor, event,
+    style::ResetColor,
+    terminal::{self, ClearType},
+    ExecutableCommand, QueueableCommand,
+};
+use globe::{Canvas, Cell, CellColor, FontMetrics, Recorder};
+
+use crate::color::{ColorMode, TerminalColor};
+use crate::serial;
+
+/// Sequence asking the terminal to hold off showing what's written until the
+/// frame is complete (synchronized output), so it's never shown half drawn.
+/// Terminals that don't support it ignore it.
+const BEGIN_SYNCHRONIZED_UPDATE: &[u8] = b"\x1b[?2026h";
+/// Sequence showing the frame written since the update began.
+const END_SYNCHRONIZED_UPDATE: &[u8] = b"\x1b[?2026l";
+
+/// Destination for rendered frames.
+pub trait Backend {
+    /// Returns the size of the output area in characters.
+    fn size(&self) -> (u16, u16);
+
+    /// Notifies the backend that the output area was resized.
+    fn resize(&mut self, size: (u16, u16));
+
+    /// Outputs the canvas.
+    fn draw(&mut self, canvas: &Canvas);
+
+    /// Switches between redrawing whole frames and outputting only the cells
+    /// that changed since the previous frame.
+    fn set_diff_output(&mut self, enabled: bool);
+
+    /// Sets the colors frames are printed in, the terminal's defaults where
+    /// `None`. The next frame is then drawn whole in the new colors.
+    fn set_color(&mut self, color: CellColor);
+
+    /// Returns the time it took to write out the last frame.
+    fn flush_time(&self) -> Duration;
+
+    /// Returns the number of bytes the last frame took up.
+    fn frame_bytes(&self) -> usize;
+
+    /// Returns true once the backend doesn't take any more frames.
+    fn finished(&self) -> bool {
+        false
+    }
+}
+
+/// Backend printing frames to the terminal through stdout.
+///
+/// Puts the terminal in raw mode on creation and restores it when dropped.
+/// Frames are drawn on the alternate screen if enabled, leaving the screen
+/// and scrollback the program was started from untouched.
+pub struct TerminalBackend {
+    stdout: Stdout,
+    size: (u16, u16),
+    mouse_capture: bool,
+    alternate_screen: bool,
+    /// Whether the terminal was put in raw mode
+    raw_mode: bool,
+    /// Output of the frame being drawn, written out all at once
+    buffer: Vec<u8>,
+    /// Rows of the previously drawn frame, kept while diff output is on
+    previous: Option<Vec<Vec<Cell>>>,
+    /// Colors frames are printed in
+    color: CellColor,
+    /// Whether the color changed since the last frame
+    recolor: bool,
+    /// Colors the terminal can show
+    color_mode: ColorMode,
+    flush_time: Duration,
+}
+
+impl TerminalBackend {
+    /// Prepares the terminal for drawing, optionally enabling mouse capture
+    /// and switching to the alternate screen.
+    pub fn new(mouse_capture: bool, alternate_screen: bool) -> Self {
+        // without a terminal to read keys from, e.g. with stdin piped in and
+        // no controlling terminal, there's nothing to put in raw mode
+        let raw_mode = terminal::enable_raw_mode().is_ok();
+        let mut stdout = stdout();
+        if alternate_screen {
+            stdout.execute(terminal::EnterAlternateScreen).unwrap();
+            stdout.execute(terminal::Clear(ClearType::All)).unwrap();
+            // panic messages would be printed to the alternate screen and
+            // vanish along with it once it's left
+            let hook = panic::take_hook();
+            panic::set_hook(Box::new(move |info| {
+                let _ = io::stdout().execute(terminal::LeaveAlternateScreen);
+                let _ = terminal::disable_raw_mode();
+                hook(info);
+            }));
+        }
+        stdout.execute(cursor::Hide).unwrap();
+        stdout.execute(cursor::DisableBlinking).unwrap();
+        if mouse_capture {
+            stdout.execute(event::EnableMouseCapture).unwrap();
+        }
+
+        Self {
+            stdout,
+            size: terminal::size().unwrap(),
+            mouse_capture,
+            alternate_screen,
+            raw_mode,
+            buffer: Vec::new(),
+            previous: None,
+            color: CellColor::default(),
+            recolor: false,
+            color_mode: ColorMode::Truecolor,
+            flush_time: Duration::ZERO,
+        }
+    }
+
+    /// Sets the colors the terminal can show, the colors of the frames and
+    /// their cells being brought down to them.
+    pub fn with_color_mode(mut self, color_mode: ColorMode) -> Self {
+        self.color_mode = color_mode;
+        self
+    }
+}
+
+impl Backend for TerminalBackend {
+    fn size(&self) -> (u16, u16) {
+        self.size
+    }
+
+    fn resize(&mut self, size: (u16, u16)) {
+        self.size = size;
+    }
+
+    fn draw(&mut self, canvas: &Canvas) {
+        self.buffer.clear();
+        self.buffer.extend_from_slice(BEGIN_SYNCHRONIZED_UPDATE);
+        if std::mem::take(&mut self.recolor) {
+            queue_color(self.color, self.color_mode, &mut self.buffer);
+        }
+        let colors = FrameColors {
+            base: self.color,
+            mode: self.color_mode,
+        };
+        print_canvas(
+            canvas,
+            self.previous.as_deref(),
+            &self.size,
+            colors,
+            &mut self.buffer,
+        );
+        self.buffer.extend_from_slice(END_SYNCHRONIZED_UPDATE);
+        if let Some(previous) = &mut self.previous {
+            *previous = cell_rows(canvas);
+        }
+
+        let start = Instant::now();
+        self.stdout.write_all(&self.buffer).unwrap();
+        self.stdout.flush().unwrap();
+        self.flush_time = start.elapsed();
+    }
+
+    fn set_diff_output(&mut self, enabled: bool) {
+        // the first frame after enabling is always drawn whole
+        self.previous = if enabled { Some(Vec::new()) } else { None };
+    }
+
+    fn set_color(&mut self, color: CellColor) {
+        if color != self.color {
+            self.color = color;
+            self.recolor = true;
+            // cells left unchanged would keep the old color otherwise
+            if self.previous.is_some() {
+                self.previous = Some(Vec::new());
+            }
+        }
+    }
+
+    fn flush_time(&self) -> Duration {
+        self.flush_time
+    }
+
+    fn frame_bytes(&self) -> usize {
+        self.buffer.len()
+    }
+}
+
+impl Drop for TerminalBackend {
+    fn drop(&mut self) {
+        if self.color != CellColor::default() {
+            self.stdout.execute(ResetColor).unwrap();
+        }
+        self.stdout.execute(cursor::Show).unwrap();
+        self.stdout.execute(cursor::EnableBlinking).unwrap();
+        if self.mouse_capture {
+            self.stdout.execute(event::DisableMouseCapture).unwrap();
+        }
+
+        if self.raw_mode {
+            terminal::disable_raw_mode().unwrap();
+        }
+        if self.alternate_screen {
+            self.stdout.execute(terminal::LeaveAlternateScreen).unwrap();
+        } else {
+            self.stdout
+                .execute(terminal::Clear(ClearType::All))
+                .unwrap();
+        }
+    }
+}
+
+/// Line ending written after each row when drawing to a device.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineEnding {
+    /// Carriage return followed by line feed, for terminals that don't
+    /// return to the first column on a line feed
+    CrLf,
+    /// Line feed alone, for displays translating it into a new line
+    Lf,
+}
+
+impl LineEnding {
+    fn as_bytes(self) -> &'static [u8] {
+        match self {
+            LineEnding::CrLf => b"\r\n",
+            LineEnding::Lf => b"\n",
+        }
+    }
+}
+
+/// Backend writing frames to a terminal device, e.g. a serial console or a
+/// character display on `/dev/ttyUSB0`.
+///
+/// Only VT100 escape sequences are used, rows being separated with the
+/// chosen line ending. The device has a fixed size and input keeps coming
+/// from the controlling terminal, which is put in raw mode if there is one.
+pub struct DeviceBackend {
+    device: File,
+    size: (u16, u16),
+    line_ending: LineEnding,
+    /// Whether writes are waited on until they are transmitted
+    drain: bool,
+    raw_mode: bool,
+    buffer: Vec<u8>,
+    /// Rows of the previously drawn frame, kept while diff output is on
+    previous: Option<Vec<Vec<char>>>,
+    /// Colors frames are printed in
+    color: CellColor,
+    /// Whether the color changed since the last frame
+    recolor: bool,
+    flush_time: Duration,
+}
+
+impl DeviceBackend {
+    /// Opens the device for drawing. If the baud rate is given, the line is
+    /// switched to raw mode at that rate, otherwise its settings are left
+    /// untouched.
+    pub fn open(
+        path: &Path,
+        baud: Option<u32>,
+        size: (u16, u16),
+        line_ending: LineEnding,
+    ) -> io::Result<Self> {
+        let mut device = OpenOptions::new().write(true).open(path)?;
+        if let Some(baud) = baud {
+            serial::configure(&device, baud)?;
+        }
+        let mut setup = Vec::new();
+        setup.queue(terminal::Clear(ClearType::All)).unwrap();
+        setup.queue(cursor::Hide).unwrap();
+        device.write_all(&setup)?;
+        device.flush()?;
+
+        Ok(Self {
+            device,
+            size,
+            line_ending,
+            drain: baud.is_some(),
+            raw_mode: terminal::enable_raw_mode().is_ok(),
+            buffer: Vec::new(),
+            previous: None,
+            color: CellColor::default(),
+            recolor: false,
+            flush_time: Duration::ZERO,
+        })
+    }
+
+    /// Prints the whole canvas from the top left corner.
+    fn print_frame(&mut self, canvas: &Canvas) {
+        let out = &mut self.buffer;
+        out.queue(cursor::MoveTo(0, 0)).unwrap();
+        for (i, row) in canvas.rows().enumerate() {
+            if i > 0 {
+                out.extend_from_slice(self.line_ending.as_bytes());
+            }
+            // trailing blanks are cleared rather than printed
+            let len = row.iter().rposition(|&ch| ch != ' ').map_or(0, |n| n + 1);
+            write_chars(&row[..len], out);
+            out.queue(terminal::Clear(ClearType::UntilNewLine)).unwrap();
+        }
+    }
+
+    /// Prints the changed part of each row, placing the cursor directly.
+    fn print_changes(&mut self, canvas: &Canvas, previous: &[Vec<char>]) {
+        let changed = |(a, b): (&char, &char)| a != b;
+        for (i, (row, prev)) in canvas.rows().zip(previous).enumerate() {
+            let first = match row.iter().zip(prev).position(changed) {
+                Some(first) => first,
+                None => continue,
+            };
+            let last = row.len()
+                - 1
+                - row
+                    .iter()
+                    .rev()
+                    .zip(prev.iter().rev())
+                    .position(changed)
+                    .unwrap();
+            self.buffer
+                .queue(cursor::MoveTo(first as u16, i as u16))
+                .unwrap();
+            write_chars(&row[first..=last], &mut self.buffer);
+        }
+    }
+}
+
+impl Backend for DeviceBackend {
+    fn size(&self) -> (u16, u16) {
+        self.size
+    }
+
+    fn resize(&mut self, _size: (u16, u16)) {
+        // resizing the controlling terminal doesn't affect the device
+    }
+
+    fn draw(&mut self, canvas: &Canvas) {
+        self.buffer.clear();
+        if std::mem::take(&mut self.recolor) {
+            // cells are printed in the frame's colors alone
+            queue_color(self.color, ColorMode::Truecolor, &mut self.buffer);
+        }
+        let previous = self.previous.take();
+        match &previous {
+            Some(prev) if prev.len() == canvas.rows().count() => {
+                self.print_changes(canvas, prev);
+            }
+            _ => self.print_frame(canvas),
+        }
+        if previous.is_some() {
+            self.previous = Some(canvas.rows().map(<[char]>::to_vec).collect());
+        }
+
+        let start = Instant::now();
+        self.device.write_all(&self.buffer).unwrap();
+        self.device.flush().unwrap();
+        if self.drain {
+            serial::drain(&self.device);
+        }
+        self.flush_time = start.elapsed();
+    }
+
+    fn set_diff_output(&mut self, enabled: bool) {
+        // the first frame after enabling is always drawn whole
+        self.previous = if enabled { Some(Vec::new()) } else { None };
+    }
+
+    fn set_color(&mut self, color: CellColor) {
+        if color != self.color {
+            self.color = color;
+            self.recolor = true;
+            // cells left unchanged would keep the old color otherwise
+            if self.previous.is_some() {
+                self.previous = Some(Vec::new());
+            }
+        }
+    }
+
+    fn flush_time(&self) -> Duration {
+        self.flush_time
+    }
+
+    fn frame_bytes(&self) -> usize {
+        self.buffer.len()
+    }
+}
+
+impl Drop for DeviceBackend {
+    fn drop(&mut self) {
+        if self.color != CellColor::default() {
+            let _ = self.device.queue(ResetColor);
+        }
+        let _ = self.device.queue(terminal::Clear(ClearType::All));
+        let _ = self.device.queue(cursor::MoveTo(0, 0));
+        let _ = self.device.queue(cursor::Show);
+        let _ = self.device.flush();
+        if self.raw_mode {
+            terminal::disable_raw_mode().unwrap();
+        }
+    }
+}
+
+/// Backend recording the frames passed on to another backend.
+pub struct RecordingBackend<B> {
+    inner: B,
+    recorder: Recorder,
+    /// Number of frames to record, unlimited if `None`
+    max_frames: Option<usize>,
+    /// Time between frames assumed before any were drawn
+    frame_time: Duration,
+    last_frame_at: Option<Instant>,
+}
+
+impl<B: Backend> RecordingBackend<B> {
+    /// Starts recording frames drawn using the given backend.
+    pub fn new(inner: B, max_frames: Option<usize>, frame_time: Duration) -> Self {
+        Self {
+            inner,
+            recorder: Recorder::new(FontMetrics::default()),
+            max_frames,
+            frame_time,
+            last_frame_at: None,
+        }
+    }
+
+    /// Stops recording, returning the wrapped backend and the recording.
+    pub fn into_parts(self) -> (B, Recorder) {
+        (self.inner, self.recorder)
+    }
+}
+
+impl<B: Backend> Backend for RecordingBackend<B> {
+    fn size(&self) -> (u16, u16) {
+        self.inner.size()
+    }
+
+    fn resize(&mut self, size: (u16, u16)) {
+        self.inner.resize(size);
+    }
+
+    fn draw(&mut self, canvas: &Canvas) {
+        self.inner.draw(canvas);
+
+        // frames are shown for the time that passed since the previous one
+        let now = Instant::now();
+        let delay = self
+            .last_frame_at
+            .map_or(self.frame_time, |last| now - last);
+        self.last_frame_at = Some(now);
+        self.recorder.add_frame(canvas, delay);
+    }
+
+    fn set_diff_output(&mut self, enabled: bool) {
+        self.inner.set_diff_output(enabled);
+    }
+
+    fn set_color(&mut self, color: CellColor) {
+        self.inner.set_color(color);
+    }
+
+    fn flush_time(&self) -> Duration {
+        self.inner.flush_time()
+    }
+
+    fn frame_bytes(&self) -> usize {
+        self.inner.frame_bytes()
+    }
+
+    fn finished(&self) -> bool {
+        self.max_frames
+            .is_some_and(|max| self.recorder.frame_count() >= max)
+            || self.inner.finished()
+    }
+}
+
+/// Backend writing frames as plain text, rows separated by newlines, e.g.
+/// to a file stdout was redirected to. No escape sequences are written, so
+/// colors are left out.
+pub struct TextBackend<W> {
+    out: W,
+    size: (u16, u16),
+    frame_bytes: usize,
+    flush_time: Duration,
+}
+
+impl<W: Write> TextBackend<W> {
+    /// Creates a backend writing frames of the given size to `out`.
+    pub fn new(out: W, size: (u16, u16)) -> Self {
+        Self {
+            out,
+            size,
+            frame_bytes: 0,
+            flush_time: Duration::ZERO,
+        }
+    }
+}
+
+impl<W: Write> Backend for TextBackend<W> {
+    fn size(&self) -> (u16, u16) {
+        self.size
+    }
+
+    fn resize(&mut self, size: (u16, u16)) {
+        self.size = size;
+    }
+
+    fn draw(&mut self, canvas: &Canvas) {
+        let mut frame = String::new();
+        for row in canvas.rows() {
+            // trailing blanks would only pad the lines out
+            let len = row.iter().rposition(|&ch| ch != ' ').map_or(0, |n| n + 1);
+            frame.extend(&row[..len]);
+            frame.push('\n');
+        }
+        let start = Instant::now();
+        self.out.write_all(frame.as_bytes()).unwrap();
+        self.out.flush().unwrap();
+        self.flush_time = start.elapsed();
+        self.frame_bytes = frame.len();
+    }
+
+    fn set_diff_output(&mut self, _enabled: bool) {
+        // whole frames are always written, there's no screen to update
+    }
+
+    fn set_color(&mut self, _color: CellColor) {}
+
+    fn flush_time(&self) -> Duration {
+        self.flush_time
+    }
+
+    fn frame_bytes(&self) -> usize {
+        self.frame_bytes
+    }
+}
+
+/// Backend drawing frames into memory, for running scenes without a
+/// terminal.
+pub struct HeadlessBackend {
+    size: (u16, u16),
+    /// Output of the last frame
+    buffer: Vec<u8>,
+    /// Rows of the previously drawn frame, kept while diff output is on
+    previous: Option<Vec<Vec<Cell>>>,
+    /// Colors frames are printed in
+    color: CellColor,
+    /// Whether the color changed since the last frame
+    recolor: bool,
+    /// Colors the pretended terminal can show
+    color_mode: ColorMode,
+}
+
+impl HeadlessBackend {
+    /// Creates a backend pretending to be a terminal of the given size.
+    pub fn new(size: (u16, u16)) -> Self {
+        Self {
+            size,
+            buffer: Vec::new(),
+            previous: None,
+            color: CellColor::default(),
+            recolor: false,
+            color_mode: ColorMode::Truecolor,
+        }
+    }
+
+    /// Sets the colors the pretended terminal can show.
+    pub fn with_color_mode(mut self, color_mode: ColorMode) -> Self {
+        self.color_mode = color_mode;
+        self
+    }
+}
+
+impl Backend for HeadlessBackend {
+    fn size(&self) -> (u16, u16) {
+        self.size
+    }
+
+    fn resize(&mut self, size: (u16, u16)) {
+        self.size = size;
+    }
+
+    fn draw(&mut self, canvas: &Canvas) {
+        self.buffer.clear();
+        self.buffer.extend_from_slice(BEGIN_SYNCHRONIZED_UPDATE);
+        if std::mem::take(&mut self.recolor) {
+            queue_color(self.color, self.color_mode, &mut self.buffer);
+        }
+        let colors = FrameColors {
+            base: self.color,
+            mode: self.color_mode,
+        };
+        print_canvas(
+            canvas,
+            self.previous.as_deref(),
+            &self.size,
+            colors,
+            &mut self.buffer,
+        );
+        self.buffer.extend_from_slice(END_SYNCHRONIZED_UPDATE);
+        if let Some(previous) = &mut self.previous {
+            *previous = cell_rows(canvas);
+        }
+    }
+
+    fn set_diff_output(&mut self, enabled: bool) {
+        self.previous = if enabled { Some(Vec::new()) } else { None };
+    }
+
+    fn set_color(&mut self, color: CellColor) {
+        if color != self.color {
+            self.color = color;
+            self.recolor = true;
+            if self.previous.is_some() {
+                self.previous = Some(Vec::new());
+            }
+        }
+    }
+
+    fn flush_time(&self) -> Duration {
+        Duration::ZERO
+    }
+
+    fn frame_bytes(&self) -> usize {
+        self.buffer.len()
+    }
+}
+
+/// Backend keeping the cells of the last frame in memory, e.g. for comparing
+/// frames cell by cell.
+pub struct SnapshotBackend {
+    size: (u16, u16),
+    /// Cells of the last frame, in the frame's colors where they have none of
+    /// their own
+    cells: Vec<Vec<Cell>>,
+    /// Colors frames are drawn in
+    color: CellColor,
+}
+
+impl SnapshotBackend {
+    /// Creates a backend pretending to be a terminal of the given size.
+    pub fn new(size: (u16, u16)) -> Self {
+        Self {
+            size,
+            cells: Vec::new(),
+            color: CellColor::default(),
+        }
+    }
+
+    /// Returns the cells of the last frame row by row.
+    pub fn cells(&self) -> &[Vec<Cell>] {
+        &self.cells
+    }
+}
+
+impl Backend for SnapshotBackend {
+    fn size(&self) -> (u16, u16) {
+        self.size
+    }
+
+    fn resize(&mut self, size: (u16, u16)) {
+        self.size = size;
+    }
+
+    fn draw(&mut self, canvas: &Canvas) {
+        let color = self.color;
+        self.cells = cell_rows(canvas)
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|cell| Cell {
+                        fg: cell.fg.or(color.fg),
+                        bg: cell.bg.or(color.bg),
+                        ..cell
+                    })
+                    .collect()
+            })
+            .collect();
+    }
+
+    fn set_diff_output(&mut self, _enabled: bool) {
+        // whole frames are always kept
+    }
+
+    fn set_color(&mut self, color: CellColor) {
+        self.color = color;
+    }
+
+    fn flush_time(&self) -> Duration {
+        Duration::ZERO
+    }
+
+    fn frame_bytes(&self) -> usize {
+        0
+    }
+}
+
+/// Colors the cells of a frame are printed in.
+#[derive(Clone, Copy)]
+struct FrameColors {
+    /// Colors of the frame, which cells without colors of their own keep
+    base: CellColor,
+    /// Colors the terminal can show
+    mode: ColorMode,
+}
+
+/// Prints globe canvas to the given output.
+///
+/// If the previous frame is given, only the changed part of each row is
+/// printed. Frames always start at the same position, so that they don't
+/// depend on where the cursor was left and changes line up with what's
+/// already on the screen. Rows are overwritten in place rather than cleared
+/// first, which would show them blank for a moment.
+fn print_canvas(
+    canvas: &Canvas,
+    previous: Option<&[Vec<Cell>]>,
+    term_size: &(u16, u16),
+    colors: FrameColors,
+    out: &mut impl Write,
+) {
+    let (x, y) = frame_origin(canvas, term_size);
+    for (i, row) in cell_rows(canvas).iter().enumerate() {
+        let row_y = y + i as u16;
+        match previous.and_then(|p| p.get(i)) {
+            Some(prev) if prev.len() == row.len() => {
+                print_changes(row, prev, (x, row_y), colors, out);
+                continue;
+            }
+            _ => (),
+        }
+
+        // the margin left of the frame is blanked along with the row
+        out.queue(cursor::MoveTo(0, row_y)).unwrap();
+        out.write_all(" ".repeat(x as usize).as_bytes()).unwrap();
+        write_cells(row, colors, out);
+        // clearing from the last column would erase the character there
+        if x as usize + row.len() < term_size.0 as usize {
+            out.queue(terminal::Clear(ClearType::UntilNewLine)).unwrap();
+        }
+    }
+}
+
+/// Returns the cells of the canvas row by row, limited to the character
+/// grid.
+fn cell_rows(canvas: &Canvas) -> Vec<Vec<Cell>> {
+    let (cols, rows) = canvas.grid_size();
+    (0..rows)
+        .map(|row| (0..cols).filter_map(|col| canvas.cell(col, row)).collect())
+        .collect()
+}
+
+/// Writes the characters out in one go.
+fn write_chars(chars: &[char], out: &mut impl Write) {
+    let text: String = chars.iter().collect();
+    out.write_all(text.as_bytes()).unwrap();
+}
+
+/// Writes the cells out in their colors, switching colors only where they
+/// change and going back to the frame's colors at the end.
+fn write_cells(cells: &[Cell], colors: FrameColors, out: &mut impl Write) {
+    let terminal_color =
+        |color: Option<globe::Color>| color.and_then(|color| colors.mode.terminal_color(color));
+    let base = (
+        terminal_color(colors.base.fg),
+        terminal_color(colors.base.bg),
+    );
+    let mut current = base;
+    let mut text = String::new();
+    for cell in cells {
+        let color = (
+            terminal_color(cell.fg).or(base.0),
+            terminal_color(cell.bg).or(base.1),
+        );
+        if color != current {
+            out.write_all(text.as_bytes()).unwrap();
+            text.clear();
+            queue_change(current, color, out);
+            current = color;
+        }
+        text.push(cell.ch);
+    }
+    out.write_all(text.as_bytes()).unwrap();
+    if current != base {
+        queue_change(current, base, out);
+    }
+}
+
+/// Writes the sequence switching from one pair of foreground and background
+/// colors to another, the terminal's defaults where `None`.
+fn queue_change(
+    from: (Option<TerminalColor>, Option<TerminalColor>),
+    to: (Option<TerminalColor>, Option<TerminalColor>),
+    out: &mut impl Write,
+) {
+    // a single color can't be dropped, only both at once
+    let reset = (from.0.is_some() && to.0.is_none()) || (from.1.is_some() && to.1.is_none());
+    let mut params = Vec::new();
+    if reset {
+        params.push("0".to_string());
+    }
+    if let Some(fg) = to.0.filter(|&fg| reset || from.0 != Some(fg)) {
+        params.push(fg.sgr(false));
+    }
+    if let Some(bg) = to.1.filter(|&bg| reset || from.1 != Some(bg)) {
+        params.push(bg.sgr(true));
+    }
+    if !params.is_empty() {
+        write!(out, "\x1b[{}m", params.join(";")).unwrap();
+    }
+}
+
+/// Queues switching to the given colors, brought down to what the terminal
+/// can show, the terminal's defaults where `None`. With a background color,
+/// the whole screen is cleared to it.
+fn queue_color(color: CellColor, mode: ColorMode, out: &mut impl Write) {
+    let fg = color.fg.and_then(|fg| mode.terminal_color(fg));
+    let bg = color.bg.and_then(|bg| mode.terminal_color(bg));
+    out.queue(ResetColor).unwrap();
+    queue_change((None, None), (fg, bg), out);
+    if bg.is_some() {
+        out.queue(terminal::Clear(ClearType::All)).unwrap();
+    }
+}
+
+/// Returns the terminal position frames are drawn from.
+pub fn frame_origin(canvas: &Canvas, term_size: &(u16, u16)) -> (u16, u16) {
+    if term_size.0 / 2 > term_size.1 {
+        let (canvas_size_x, _) = canvas.get_size();
+        let char_pix = canvas.char_pix();
+        let x = (canvas_size_x / char_pix.1) as u16
+            - ((canvas_size_x / char_pix.1) / char_pix.0) as u16;
+        // rows running past the right edge would wrap and scroll the screen
+        let (cols, _) = canvas.grid_size();
+        (x.min(term_size.0.saturating_sub(cols as u16)), 0)
+    } else {
+        (0, 0)
+    }
+}
+
+/// Prints the span of the row between the first and the last cell that
+/// differ from the previous row, which starts at the given terminal position.
+fn print_changes(
+    row: &[Cell],
+    prev: &[Cell],
+    (x, y): (u16, u16),
+    colors: FrameColors,
+    out: &mut impl Write,
+) {
+    let changed = |(a, b): (&Cell, &Cell)| a != b;
+    if let Some(first) = row.iter().zip(prev).position(changed) {
+        let last = row.len()
+            - 1
+            - row
+                .iter()
+                .rev()
+                .zip(prev.iter().rev())
+                .position(changed)
+                .unwrap();
+        out.queue(cursor::MoveTo(x + first as u16, y)).unwrap();
+        write_cells(&row[first..=last], colors, out);
+    }
+}
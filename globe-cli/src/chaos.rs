@@ -0,0 +1,183 @@
+//! Chaos mode, feeding random input through the event loop to shake out
+//! crashes.
+//!
+//! Keys, mouse events and resizes to tiny and huge sizes are thrown at an
+//! interactive scene for a while. The run fails if anything panics or the
+//! terminal isn't restored afterwards. Without a terminal, frames are drawn
+//! into memory, so it can run headless, e.g. in CI.
+
+use std::io::{stdout, IsTerminal};
+use std::panic::{self, AssertUnwindSafe};
+use std::process;
+use std::time::{Duration, Instant};
+
+use crossterm::event::{Event, KeyCode, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+
+use crate::backend::{HeadlessBackend, TerminalBackend};
+use crate::events::EventSource;
+use crate::quality::{Quality, QualityScaler};
+use crate::rng::Rng;
+use crate::scene::{Mode, SceneState};
+use crate::Settings;
+
+/// Keys thrown at the scene. Other characters exit the interactive mode, so
+/// they're left out to keep the run going.
+const KEYS: [KeyCode; 19] = [
+    KeyCode::Char('+'),
+    KeyCode::Char('-'),
+    KeyCode::Char(','),
+    KeyCode::Char('.'),
+    KeyCode::Char('n'),
+    KeyCode::Char('h'),
+    KeyCode::Char('j'),
+    KeyCode::Char('k'),
+    KeyCode::Char('l'),
+    KeyCode::Up,
+    KeyCode::Down,
+    KeyCode::Left,
+    KeyCode::Right,
+    KeyCode::PageUp,
+    KeyCode::PageDown,
+    KeyCode::Enter,
+    KeyCode::Esc,
+    KeyCode::Tab,
+    KeyCode::F(1),
+];
+
+/// Terminal sizes resizes pick from half of the time, the other half being
+/// random sizes in between.
+const EDGE_SIZES: [(u16, u16); 8] = [
+    (0, 0),
+    (1, 1),
+    (0, 24),
+    (80, 0),
+    (2, 1),
+    (3, 5),
+    (1, 200),
+    (500, 250),
+];
+
+/// Random events produced until the time runs out.
+struct ChaosEvents {
+    rng: Rng,
+    until: Instant,
+    /// Number of events produced so far
+    count: usize,
+}
+
+impl EventSource for ChaosEvents {
+    fn poll(&mut self, _timeout: Duration) -> bool {
+        // no waiting, events keep coming in between the frames
+        self.rng.below(4) != 0
+    }
+
+    fn read(&mut self) -> Event {
+        self.count += 1;
+        let rng = &mut self.rng;
+        let (column, row) = (rng.below(600) as u16, rng.below(300) as u16);
+        let mouse = |kind| {
+            Event::Mouse(MouseEvent {
+                kind,
+                column,
+                row,
+                modifiers: KeyModifiers::empty(),
+            })
+        };
+        match rng.below(10) {
+            0..=3 => Event::Key(KEYS[rng.below(KEYS.len() as u64) as usize].into()),
+            4 => mouse(MouseEventKind::Drag(MouseButton::Left)),
+            5 => mouse(MouseEventKind::Moved),
+            6 => mouse(MouseEventKind::Up(MouseButton::Left)),
+            7 if rng.below(2) == 0 => mouse(MouseEventKind::ScrollUp),
+            7 => mouse(MouseEventKind::ScrollDown),
+            _ if rng.below(2) == 0 => {
+                let (width, height) = EDGE_SIZES[rng.below(EDGE_SIZES.len() as u64) as usize];
+                Event::Resize(width, height)
+            }
+            _ => Event::Resize(rng.below(300) as u16, rng.below(100) as u16),
+        }
+    }
+
+    fn finished(&self) -> bool {
+        Instant::now() >= self.until
+    }
+}
+
+/// Runs the scene on random input for the given time, exiting the process
+/// with an error if anything went wrong.
+pub fn run(settings: &Settings, duration: Duration) {
+    let seed = settings.seed;
+    println!("chaos: running for {:?} with seed {}", duration, seed);
+    let modes = terminal_modes();
+    let mut scene = SceneState::new(settings, Mode::Interactive);
+    // the input is drawn from the scene's seed along with anything else
+    let mut events = ChaosEvents {
+        rng: scene.rng().split(),
+        until: Instant::now() + duration,
+        count: 0,
+    };
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        // reduced quality exercises drawing only the changes as well
+        let scaler = QualityScaler::new(Quality::Reduced);
+        if stdout().is_terminal() {
+            let mut backend = TerminalBackend::new(true, settings.alternate_screen)
+                .with_color_mode(settings.color);
+            crate::run(
+                scene,
+                &mut backend,
+                &mut events,
+                settings.refresh_rate,
+                scaler,
+            );
+        } else {
+            let mut backend = HeadlessBackend::new((80, 24)).with_color_mode(settings.color);
+            crate::run(
+                scene,
+                &mut backend,
+                &mut events,
+                settings.refresh_rate,
+                scaler,
+            );
+        }
+    }));
+
+    if result.is_err() {
+        eprintln!(
+            "chaos: panicked after {} events, rerun with --seed {}",
+            events.count, seed
+        );
+        process::exit(1);
+    }
+    if terminal_modes() != modes {
+        eprintln!(
+            "chaos: terminal modes weren't restored after {} events, seed {}",
+            events.count, seed
+        );
+        process::exit(1);
+    }
+    println!("chaos: survived {} events", events.count);
+}
+
+/// Returns the input, output and local modes of the terminal on stdout, or
+/// `None` if stdout isn't a terminal.
+#[cfg(unix)]
+fn terminal_modes() -> Option<[u64; 3]> {
+    // SAFETY: `termios` is only read after `tcgetattr` filled it in
+    unsafe {
+        let mut termios: libc::termios = std::mem::zeroed();
+        if libc::tcgetattr(libc::STDOUT_FILENO, &mut termios) != 0 {
+            return None;
+        }
+        Some([
+            termios.c_iflag as u64,
+            termios.c_oflag as u64,
+            termios.c_lflag as u64,
+        ])
+    }
+}
+
+#[cfg(not(unix))]
+fn terminal_modes() -> Option<[u64; 3]> {
+    None
+}
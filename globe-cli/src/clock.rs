@@ -0,0 +1,37 @@
+//! Clock deciding when frames are drawn, independent of the input.
+
+use std::time::{Duration, Instant};
+
+/// Times at which frames are due at a steady rate, however often input
+/// arrives in between.
+pub struct FrameClock {
+    /// Time the next frame is due at
+    next: Instant,
+}
+
+impl FrameClock {
+    /// Creates a clock with the first frame due right away.
+    pub fn new(now: Instant) -> Self {
+        Self { next: now }
+    }
+
+    /// Returns true if a frame is due.
+    pub fn is_due(&self, now: Instant) -> bool {
+        now >= self.next
+    }
+
+    /// Returns how long it is until the next frame is due, zero if it
+    /// already is.
+    pub fn time_until_frame(&self, now: Instant) -> Duration {
+        self.next.saturating_duration_since(now)
+    }
+
+    /// Schedules the next frame the given time after the one due last.
+    ///
+    /// Frames keep their pace when drawing one takes a while, but a clock
+    /// that fell behind doesn't make up for the missed frames with a burst
+    /// of them.
+    pub fn schedule_next(&mut self, now: Instant, frame_time: Duration) {
+        self.next = (self.next + frame_time).max(now);
+    }
+}
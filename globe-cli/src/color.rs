@@ -0,0 +1,164 @@
+//! Colors frames are printed in, depending on what the terminal supports.
+
+use std::env;
+
+use globe::Color;
+
+/// Levels of the red, green and blue steps of the 256 color palette's
+/// color cube.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Colors the output can use, as given with `--color`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Any 24-bit color.
+    Truecolor,
+    /// The 256 color palette.
+    Ansi256,
+    /// The 16 basic colors.
+    Ansi16,
+    /// No colors at all, not even the theme's.
+    None,
+}
+
+impl ColorMode {
+    /// Parses a mode given on the command line, `auto` detecting what the
+    /// terminal supports.
+    pub fn parse(input: &str) -> Option<Self> {
+        match input {
+            "auto" => Some(Self::detect()),
+            "truecolor" => Some(Self::Truecolor),
+            "256" => Some(Self::Ansi256),
+            "16" => Some(Self::Ansi16),
+            "none" => Some(Self::None),
+            _ => None,
+        }
+    }
+
+    /// Guesses the colors the terminal supports from the environment,
+    /// honoring `NO_COLOR`.
+    pub fn detect() -> Self {
+        if env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            return Self::None;
+        }
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return Self::Truecolor;
+        }
+        match env::var("TERM") {
+            Ok(term) if term == "dumb" => Self::None,
+            Ok(term) if term.ends_with("-direct") => Self::Truecolor,
+            Ok(term) if term.contains("256color") => Self::Ansi256,
+            Ok(_) => Self::Ansi16,
+            // consoles on Windows don't set it, but take 24-bit colors
+            Err(_) if cfg!(windows) => Self::Truecolor,
+            Err(_) => Self::None,
+        }
+    }
+
+    /// Returns true if frames are printed with colors at all.
+    pub fn is_enabled(self) -> bool {
+        self != Self::None
+    }
+
+    /// Returns the closest color the mode can show, `None` if it shows no
+    /// colors.
+    pub fn terminal_color(self, color: Color) -> Option<TerminalColor> {
+        match self {
+            Self::Truecolor => Some(TerminalColor::Rgb(color)),
+            Self::Ansi256 => Some(TerminalColor::Indexed(ansi_256(color))),
+            Self::Ansi16 => Some(TerminalColor::Basic(ansi_16(color))),
+            Self::None => None,
+        }
+    }
+}
+
+/// Color as the terminal is told to show it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TerminalColor {
+    /// 24-bit color.
+    Rgb(Color),
+    /// Code of a color of the 256 color palette.
+    Indexed(u8),
+    /// Code of one of the 16 basic colors, set with the sequences terminals
+    /// without the larger palettes know too.
+    Basic(u8),
+}
+
+impl TerminalColor {
+    /// Returns the parameters of the sequence setting the color as the
+    /// foreground or the background one.
+    pub fn sgr(self, background: bool) -> String {
+        let layer = if background { 48 } else { 38 };
+        match self {
+            Self::Rgb(color) => format!("{};2;{};{};{}", layer, color.r, color.g, color.b),
+            Self::Indexed(code) => format!("{};5;{}", layer, code),
+            // bright colors have codes of their own, 60 above the others
+            Self::Basic(code) => {
+                let base = if background { 40 } else { 30 };
+                let bright = if code >= 8 { 60 } else { 0 };
+                (base + bright + code as u16 % 8).to_string()
+            }
+        }
+    }
+}
+
+/// Returns the code of the closest color of the 256 color palette, either
+/// from its color cube or its ramp of grays.
+fn ansi_256(color: Color) -> u8 {
+    let step = |level: u8| match level {
+        0..=47 => 0,
+        48..=114 => 1,
+        _ => (level - 35) / 40,
+    };
+    let (r, g, b) = (step(color.r), step(color.g), step(color.b));
+    let cube = Color::new(
+        CUBE_LEVELS[r as usize],
+        CUBE_LEVELS[g as usize],
+        CUBE_LEVELS[b as usize],
+    );
+    // grays run from 8 to 238 in steps of 10
+    let average = (color.r as u32 + color.g as u32 + color.b as u32) / 3;
+    let gray_step = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray_level = 8 + gray_step * 10;
+    let gray = Color::new(gray_level, gray_level, gray_level);
+    if distance(color, gray) < distance(color, cube) {
+        232 + gray_step
+    } else {
+        16 + 36 * r + 6 * g + b
+    }
+}
+
+/// Returns the code of the basic color closest in hue, or of the closest
+/// gray for washed out colors.
+///
+/// The basic colors vary a lot between terminals, so rather than matching
+/// them exactly, each channel reaching half of the brightest one is counted
+/// in, the codes of red, green and blue being 1, 2 and 4, and bright colors
+/// get the bright variants.
+fn ansi_16(color: Color) -> u8 {
+    let (r, g, b) = (color.r, color.g, color.b);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    if (max - min) as u32 * 4 < max as u32 || max < 32 {
+        return match max {
+            0..=47 => 0,
+            48..=127 => 8,
+            128..=207 => 7,
+            _ => 15,
+        };
+    }
+    let half = max / 2;
+    let hue = (r > half) as u8 | ((g > half) as u8) << 1 | ((b > half) as u8) << 2;
+    if max > 191 {
+        hue + 8
+    } else {
+        hue
+    }
+}
+
+/// Returns the squared distance between two colors.
+fn distance(a: Color, b: Color) -> u32 {
+    let channel = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    channel(a.r, b.r) + channel(a.g, b.g) + channel(a.b, b.b)
+}
@@ -0,0 +1,114 @@
+//! Settings read from the config file.
+//!
+//! The file holds one `key = value` pair per line, lines starting with `#`
+//! are comments:
+//!
+//! ```text
+//! # look of a desk display left running all day
+//! theme = amber
+//! color = 256
+//! time-of-day = true
+//! colorblind-safe = false
+//! # hour, day and night colors, replacing the built-in phases
+//! day-phase = 7 ffb080 302040
+//! day-phase = 13 fff4e0 305080
+//! day-phase = 20 6070c0 101030
+//! ```
+//!
+//! Options given on the command line take precedence over the file.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use globe::{DayPhase, Float};
+
+/// Settings that can be given in the config file, `None` where the file
+/// leaves them out.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Config {
+    /// Name of the built-in theme
+    pub theme: Option<String>,
+    /// Colors the terminal can show
+    pub color: Option<String>,
+    /// Tint the globe following the local time of day
+    pub time_of_day: Option<bool>,
+    /// Phases the time of day tint goes through, the built-in ones if empty
+    pub day_phases: Vec<DayPhase>,
+    /// Adjust the theme for color vision deficiencies
+    pub colorblind_safe: Option<bool>,
+}
+
+impl Config {
+    /// Returns the location of the config file, `globe/config` within the
+    /// user's config directory.
+    pub fn default_path() -> Option<PathBuf> {
+        let dir = match env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+            Some(dir) => PathBuf::from(dir),
+            None => PathBuf::from(env::var_os("HOME")?).join(".config"),
+        };
+        Some(dir.join("globe").join("config"))
+    }
+
+    /// Reads the config file at the given path. A missing file counts as an
+    /// empty one unless `required` is set.
+    pub fn load(path: &Path, required: bool) -> Result<Config, String> {
+        match fs::read_to_string(path) {
+            Ok(input) => Config::parse(&input),
+            Err(e) if e.kind() == io::ErrorKind::NotFound && !required => Ok(Config::default()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    /// Parses the contents of a config file.
+    pub fn parse(input: &str) -> Result<Config, String> {
+        let mut config = Config::default();
+        for (i, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("line {}: expected \"key = value\"", i + 1))?;
+            let value = value.trim();
+            match key.trim() {
+                "theme" => config.theme = Some(value.to_string()),
+                "color" => config.color = Some(value.to_string()),
+                "time-of-day" => config.time_of_day = Some(parse_bool(i, value)?),
+                "day-phase" => config.day_phases.push(parse_day_phase(i, value)?),
+                "colorblind-safe" => config.colorblind_safe = Some(parse_bool(i, value)?),
+                other => return Err(format!("line {}: unknown setting {:?}", i + 1, other)),
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// Parses a `true` or `false` value found on the line with the given index.
+fn parse_bool(line: usize, value: &str) -> Result<bool, String> {
+    value
+        .parse()
+        .map_err(|_| format!("line {}: expected true or false, got {:?}", line + 1, value))
+}
+
+/// Parses a day phase found on the line with the given index, given as the
+/// hour followed by the day and night colors, e.g. `18.5 ff8c3c 461e46`.
+fn parse_day_phase(line: usize, value: &str) -> Result<DayPhase, String> {
+    let mut fields = value.split_whitespace();
+    let hour = fields
+        .next()
+        .and_then(|hour| hour.parse::<Float>().ok())
+        .filter(|hour| (0. ..24.).contains(hour));
+    let day = fields.next().and_then(crate::parse_color);
+    let night = fields.next().and_then(crate::parse_color);
+    match (hour, day, night, fields.next()) {
+        (Some(hour), Some(day), Some(night), None) => Ok(DayPhase::new(hour, day, night)),
+        _ => Err(format!(
+            "line {}: expected an hour and day and night colors, e.g. \"18.5 ff8c3c 461e46\", got {:?}",
+            line + 1,
+            value
+        )),
+    }
+}
@@ -0,0 +1,264 @@
+//! Comparison of the frames of two scenes, e.g. for seeing what a change of
+//! palette, shading or texture does.
+//!
+//! A scene file holds one `key = value` pair per line, keys being the long
+//! options of the command line without the dashes, lines starting with `#`
+//! are comments:
+//!
+//! ```text
+//! # amber night side, with the land drawn from a texture file
+//! theme = "amber"
+//! night = true
+//! texture = "earth.txt"
+//! data-series = ["day1.csv", "day2.csv"]
+//! ```
+//!
+//! Values can be quoted as in TOML, flags are set with `true` and options
+//! taking several values are given lists. Options given on the command line
+//! before `diff` apply to both scenes.
+
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::process;
+use std::time::Duration;
+
+use clap::{AppSettings, ArgMatches};
+use globe::Cell;
+
+use crate::backend::SnapshotBackend;
+use crate::color::ColorMode;
+use crate::scene::{Mode, SceneState};
+use crate::{app, read_settings, Settings};
+
+/// Character marking the cells whose characters differ on the map of changes
+const CHANGED_CHAR: char = '*';
+/// Character marking the cells differing only in color on the map of changes
+const RECOLORED_CHAR: char = '~';
+/// Columns put between the frames printed side by side
+const SEPARATOR: &str = " | ";
+
+/// How a cell of the second frame differs from the first one.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Change {
+    Same,
+    /// Same character in different colors
+    Recolored,
+    /// Different character, or a cell only one of the frames has
+    Changed,
+}
+
+impl Change {
+    fn between(a: Option<&Cell>, b: Option<&Cell>) -> Self {
+        match (a, b) {
+            (Some(a), Some(b)) if a == b => Change::Same,
+            (Some(a), Some(b)) if a.ch == b.ch => Change::Recolored,
+            _ => Change::Changed,
+        }
+    }
+
+    fn map_char(self) -> char {
+        match self {
+            Change::Same => ' ',
+            Change::Recolored => RECOLORED_CHAR,
+            Change::Changed => CHANGED_CHAR,
+        }
+    }
+}
+
+/// Renders the scenes given to the `diff` command and prints how their frames
+/// differ. Exits with status 1 if they differ at all, like `diff` does.
+pub fn run(matches: &ArgMatches) {
+    let paths: Vec<&str> = matches.values_of("scenes").unwrap().collect();
+    let side_by_side = matches.is_present("side_by_side");
+
+    // the subcommand and its arguments come last, what's before them is
+    // shared by both scenes
+    let args: Vec<OsString> = env::args_os().collect();
+    let tail = 1 + paths.len() + matches.occurrences_of("side_by_side") as usize;
+    let shared = &args[..args.len() - tail];
+
+    let mut settings: Vec<Settings> = paths
+        .iter()
+        .map(|path| scene_settings(shared, path))
+        .collect();
+    // both frames are taken from the first scene's camera, at the same size,
+    // picking the same at random
+    let (coords, zoom, size, seed) = (
+        settings[0].coords,
+        settings[0].cam_zoom,
+        settings[0].tty_size,
+        settings[0].seed,
+    );
+    for scene in &mut settings[1..] {
+        scene.coords = coords;
+        scene.cam_zoom = zoom;
+        scene.tty_size = size;
+        scene.seed = seed;
+    }
+    let frames: Vec<Vec<Vec<Cell>>> = settings.iter().map(render).collect();
+    let (a, b) = (&frames[0], &frames[1]);
+
+    let rows = a.len().max(b.len());
+    let cols = a.iter().chain(b).map(Vec::len).max().unwrap_or(0);
+    let changes: Vec<Vec<Change>> = (0..rows)
+        .map(|row| {
+            (0..cols)
+                .map(|col| {
+                    Change::between(
+                        a.get(row).and_then(|cells| cells.get(col)),
+                        b.get(row).and_then(|cells| cells.get(col)),
+                    )
+                })
+                .collect()
+        })
+        .collect();
+
+    if side_by_side {
+        print_side_by_side(a, b, &changes, cols);
+        println!();
+    }
+    let differing = print_summary(&paths, &changes);
+    if differing {
+        process::exit(1);
+    }
+}
+
+/// Builds the settings of the scene in the given file, on top of the shared
+/// options.
+fn scene_settings(shared: &[OsString], path: &str) -> Settings {
+    let input =
+        fs::read_to_string(path).unwrap_or_else(|e| panic!("failed reading scene {}: {}", path, e));
+    let options = parse_scene(&input).unwrap_or_else(|e| panic!("{}: {}", path, e));
+    let args = shared
+        .iter()
+        .cloned()
+        .chain(options.into_iter().map(OsString::from));
+    let matches = app()
+        .unset_setting(AppSettings::ArgRequiredElseHelp)
+        .try_get_matches_from(args)
+        .unwrap_or_else(|e| panic!("{}: {}", path, e));
+    let mut settings = read_settings(&matches);
+    // colors are compared whatever the terminal shows
+    settings.color = ColorMode::Truecolor;
+    settings
+}
+
+/// Turns the contents of a scene file into command line options.
+fn parse_scene(input: &str) -> Result<Vec<String>, String> {
+    let mut options = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("line {}: expected \"key = value\"", i + 1))?;
+        let option = format!("--{}", key.trim());
+        let value = value.trim();
+        match value {
+            "true" => options.push(option),
+            "false" => (),
+            _ => match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+                Some(list) => {
+                    options.push(option);
+                    options.extend(
+                        list.split(',')
+                            .map(|item| unquote(item.trim()).to_string())
+                            .filter(|item| !item.is_empty()),
+                    );
+                }
+                // joined to the option, values starting with a minus aren't
+                // taken for options themselves
+                None => options.push(format!("{}={}", option, unquote(value))),
+            },
+        }
+    }
+    Ok(options)
+}
+
+/// Strips the quotes around a value, if it has them.
+fn unquote(value: &str) -> &str {
+    ['"', '\'']
+        .iter()
+        .find_map(|&quote| value.strip_prefix(quote)?.strip_suffix(quote))
+        .unwrap_or(value)
+}
+
+/// Renders the first frame of the scene and returns its cells.
+fn render(settings: &Settings) -> Vec<Vec<Cell>> {
+    let mut scene = SceneState::new(settings, Mode::Screensaver);
+    let mut backend = SnapshotBackend::new(settings.tty_size);
+    scene.tick(Duration::ZERO);
+    scene.draw(&mut backend);
+    backend.cells().to_vec()
+}
+
+/// Prints the frames next to each other, followed by the map of changes.
+fn print_side_by_side(a: &[Vec<Cell>], b: &[Vec<Cell>], changes: &[Vec<Change>], cols: usize) {
+    let text = |frame: &[Vec<Cell>], row: usize| -> String {
+        let chars = frame.get(row).map_or(&[][..], Vec::as_slice);
+        let text: String = chars.iter().map(|cell| cell.ch).collect();
+        format!("{:width$}", text, width = cols)
+    };
+    for (row, changes) in changes.iter().enumerate() {
+        let map: String = changes.iter().map(|change| change.map_char()).collect();
+        let line = [text(a, row), text(b, row), map].join(SEPARATOR);
+        println!("{}", line.trim_end());
+    }
+}
+
+/// Prints how many cells differ and where, returning true if any do.
+fn print_summary(paths: &[&str], changes: &[Vec<Change>]) -> bool {
+    let total: usize = changes.iter().map(Vec::len).sum();
+    let count = |kind: Change| {
+        changes
+            .iter()
+            .flatten()
+            .filter(|&&change| change == kind)
+            .count()
+    };
+    let (changed, recolored) = (count(Change::Changed), count(Change::Recolored));
+    let differing = changed + recolored;
+    if differing == 0 {
+        println!(
+            "{} and {} look the same in all {} cells",
+            paths[0], paths[1], total
+        );
+        return false;
+    }
+
+    println!(
+        "{} and {} differ in {} of {} cells ({:.1}%)",
+        paths[0],
+        paths[1],
+        differing,
+        total,
+        differing as f64 * 100. / total as f64
+    );
+    println!("  {} in character, {} only in color", changed, recolored);
+    // positions are counted from 1, like terminals do
+    let rows: Vec<usize> = (0..changes.len())
+        .filter(|&row| changes[row].iter().any(|&change| change != Change::Same))
+        .collect();
+    let cols: Vec<usize> = changes
+        .iter()
+        .flat_map(|row| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, &change)| change != Change::Same)
+                .map(|(col, _)| col)
+        })
+        .collect();
+    let span = |values: &[usize]| {
+        let (min, max) = (values.iter().min().unwrap(), values.iter().max().unwrap());
+        format!("{} to {}", min + 1, max + 1)
+    };
+    println!(
+        "  changes span rows {}, columns {}",
+        span(&rows),
+        span(&cols)
+    );
+    true
+}
@@ -0,0 +1,189 @@
+//! Frames written to files along with hashes of their contents, for reporting
+//! rendering regressions with the exact frames and checking for them.
+//!
+//! Frames are rendered without a terminal, the scene moving on by one frame
+//! at the refresh rate between them, so the same options render the same
+//! frames every time. Options following the clock, like `--sun` or `--wall`,
+//! are the exception. Next to the frames, a file of hashes lists the options
+//! they were rendered with and each frame's file and hash:
+//!
+//! ```text
+//! # frames dumped by globe, check them with `globe verify`
+//! arg -s
+//! arg -g10
+//! frame f_0001.txt 8d3f0a29c1b7e645
+//! ```
+
+use std::env;
+use std::ffi::OsString;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::Duration;
+
+use clap::{AppSettings, ArgMatches};
+use globe::Cell;
+
+use crate::backend::SnapshotBackend;
+use crate::color::{ColorMode, TerminalColor};
+use crate::scene::{Mode, SceneState};
+use crate::{app, frame_duration, read_settings, Settings};
+
+/// Suffix of the file of hashes, following the prefix frames are dumped with
+const HASHES_SUFFIX: &str = "hashes.txt";
+
+/// Renders the frames of the scene given on the command line and writes them,
+/// along with the file of hashes, to files starting with the prefix.
+pub fn run(matches: &ArgMatches, settings: Settings, prefix: &str) {
+    let count = matches
+        .value_of("frames")
+        .map_or(1, |n| n.parse().expect("failed parsing frame count value"));
+    let frames = render_frames(settings, scene_mode(matches), count);
+
+    let mut hashes = String::from("# frames dumped by globe, check them with `globe verify`\n");
+    for arg in env::args_os().skip(1) {
+        writeln!(hashes, "arg {}", arg.to_string_lossy()).unwrap();
+    }
+    for (i, frame) in frames.iter().enumerate() {
+        let path = PathBuf::from(format!("{}{:04}.txt", prefix, i + 1));
+        fs::write(&path, frame)
+            .unwrap_or_else(|e| panic!("failed writing frame {}: {}", path.display(), e));
+        let name = path.file_name().unwrap().to_string_lossy();
+        writeln!(hashes, "frame {} {:016x}", name, hash(frame)).unwrap();
+    }
+    let path = format!("{}{}", prefix, HASHES_SUFFIX);
+    fs::write(&path, hashes).unwrap_or_else(|e| panic!("failed writing {}: {}", path, e));
+    println!("dumped {} frames, hashes in {}", frames.len(), path);
+}
+
+/// Renders the frames listed in the file of hashes given to the `verify`
+/// command again and compares their hashes. Frames that differ are written
+/// next to the stored ones, and the process exits with status 1.
+pub fn verify(matches: &ArgMatches) {
+    let path = Path::new(matches.value_of("hashes").unwrap());
+    let input = fs::read_to_string(path)
+        .unwrap_or_else(|e| panic!("failed reading {}: {}", path.display(), e));
+    let mut args = vec![OsString::from("globe")];
+    let mut expected = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.split_once(' ') {
+            Some(("arg", arg)) => args.push(arg.into()),
+            Some(("frame", frame)) => {
+                let parsed = frame.rsplit_once(' ').and_then(|(name, hash)| {
+                    Some((name.to_string(), u64::from_str_radix(hash, 16).ok()?))
+                });
+                expected.push(parsed.unwrap_or_else(|| {
+                    panic!(
+                        "{}: line {}: expected \"frame file hash\"",
+                        path.display(),
+                        i + 1
+                    )
+                }));
+            }
+            _ => panic!("{}: line {}: unknown entry", path.display(), i + 1),
+        }
+    }
+
+    let matches = app()
+        .unset_setting(AppSettings::ArgRequiredElseHelp)
+        .try_get_matches_from(args)
+        .unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+    let settings = read_settings(&matches);
+    let frames = render_frames(settings, scene_mode(&matches), expected.len());
+
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    let mut differing = 0;
+    for (frame, (name, expected)) in frames.iter().zip(&expected) {
+        let actual = hash(frame);
+        if actual == *expected {
+            continue;
+        }
+        differing += 1;
+        // the frame rendered now goes next to the stored one, for comparing
+        // or attaching to a report
+        let actual_path = dir.join(format!("{}.actual", name));
+        fs::write(&actual_path, frame)
+            .unwrap_or_else(|e| panic!("failed writing {}: {}", actual_path.display(), e));
+        println!(
+            "{} differs: expected {:016x}, got {:016x}, written to {}",
+            name,
+            expected,
+            actual,
+            actual_path.display()
+        );
+    }
+    if differing > 0 {
+        println!("{} of {} frames differ", differing, frames.len());
+        process::exit(1);
+    }
+    println!("all {} frames match", frames.len());
+}
+
+/// Returns the mode frames are rendered in, which can't be one reading
+/// locations from stdin.
+fn scene_mode(matches: &ArgMatches) -> Mode {
+    if matches.is_present("interactive") {
+        Mode::Interactive
+    } else {
+        Mode::Screensaver
+    }
+}
+
+/// Renders the given number of frames of the scene as text, moving it on by
+/// one frame at the refresh rate in between.
+fn render_frames(mut settings: Settings, mode: Mode, count: usize) -> Vec<String> {
+    // frames don't depend on what the terminal they're rendered in shows
+    settings.color = ColorMode::Truecolor;
+    // frames drawn on demand are shown for a second, as when recording
+    let frame_time = frame_duration(settings.refresh_rate).unwrap_or(Duration::from_secs(1));
+    let mut scene = SceneState::new(&settings, mode);
+    let mut backend = SnapshotBackend::new(settings.tty_size);
+    (0..count)
+        .map(|i| {
+            scene.tick(if i == 0 { Duration::ZERO } else { frame_time });
+            scene.draw(&mut backend);
+            frame_text(backend.cells())
+        })
+        .collect()
+}
+
+/// Returns the frame as text, with its colors set by escape sequences, so
+/// they're shown by printing it to a terminal and count into its hash.
+fn frame_text(cells: &[Vec<Cell>]) -> String {
+    let mut text = String::new();
+    for row in cells {
+        let mut current = (None, None);
+        for cell in row {
+            let color = (cell.fg, cell.bg);
+            if color != current {
+                text.push_str("\x1b[0");
+                for (color, background) in [(cell.fg, false), (cell.bg, true)] {
+                    if let Some(color) = color {
+                        write!(text, ";{}", TerminalColor::Rgb(color).sgr(background)).unwrap();
+                    }
+                }
+                text.push('m');
+                current = color;
+            }
+            text.push(cell.ch);
+        }
+        if current != (None, None) {
+            text.push_str("\x1b[0m");
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// Returns the 64-bit FNV-1a hash of the text, which unlike the standard
+/// library's hashers stays the same across Rust versions and platforms.
+fn hash(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
@@ -0,0 +1,58 @@
+//! Sources of the input events driving the event loop.
+
+use std::thread;
+use std::time::Duration;
+
+use crossterm::event::{self, Event};
+
+/// Source of input events.
+pub trait EventSource {
+    /// Waits up to the given time for an event, returning true if one is
+    /// ready to be read.
+    fn poll(&mut self, timeout: Duration) -> bool;
+
+    /// Returns the next event, blocking until there is one.
+    fn read(&mut self) -> Event;
+
+    /// Returns true once the source doesn't produce any more events.
+    fn finished(&self) -> bool {
+        false
+    }
+}
+
+/// Events coming from the terminal the program runs in, read from stdin or,
+/// with stdin piped in, from the controlling terminal.
+pub struct TerminalEvents {
+    /// Whether there's a terminal to read from at all
+    available: bool,
+}
+
+impl TerminalEvents {
+    /// Starts reading events from the terminal. Without one to read from,
+    /// e.g. when run from a pipeline without a controlling terminal, no
+    /// events ever come.
+    pub fn open() -> Self {
+        Self {
+            available: event::poll(Duration::ZERO).is_ok(),
+        }
+    }
+
+    /// Returns true if there's a terminal events are read from.
+    pub fn is_available(&self) -> bool {
+        self.available
+    }
+}
+
+impl EventSource for TerminalEvents {
+    fn poll(&mut self, timeout: Duration) -> bool {
+        if !self.available {
+            thread::sleep(timeout);
+            return false;
+        }
+        event::poll(timeout).unwrap()
+    }
+
+    fn read(&mut self) -> Event {
+        event::read().unwrap()
+    }
+}
@@ -0,0 +1,143 @@
+//! Flight routes between airports, with planes flying along them.
+
+use std::time::Duration;
+
+use globe::airports::Airport;
+use globe::{Color, Float, GeoCoord, Globe, GreatArc, Marker};
+
+use crate::rng::Rng;
+
+/// Character routes are drawn with.
+const ROUTE_CHAR: char = '-';
+/// Color of the routes.
+const ROUTE_COLOR: Color = Color::new(120, 200, 255);
+/// Character marking the airports.
+const AIRPORT_MARKER: char = 'o';
+/// Color of the airports' markers.
+const AIRPORT_MARKER_COLOR: Color = Color::new(255, 255, 255);
+/// Character planes are drawn with when it's not known which way they're
+/// heading on the screen.
+pub const PLANE_CHAR: char = '>';
+/// Color of the planes.
+pub const PLANE_COLOR: Color = Color::new(255, 220, 80);
+/// Kilometers a plane flies per second, fast enough for the longest routes
+/// to take seconds rather than minutes.
+const PLANE_SPEED_KM: Float = 1500.;
+/// Kilometers ahead of a plane the point telling which way it flies is
+/// taken at.
+const HEADING_LOOKAHEAD_KM: Float = 300.;
+/// Fraction a plane's speed may differ from `PLANE_SPEED_KM` either way, so
+/// that planes don't fly in lockstep.
+const PLANE_SPEED_JITTER: Float = 0.2;
+
+/// Route along with where on it its plane took off from, as a fraction of
+/// its length, and how fast the plane flies relative to `PLANE_SPEED_KM`.
+struct Route {
+    arc: GreatArc,
+    start: Float,
+    pace: Float,
+}
+
+/// Routes drawn on the globe along with the planes flying them.
+pub struct Flights {
+    routes: Vec<Route>,
+    /// Fly a plane along each route
+    planes: bool,
+    /// Time the planes have been flying for
+    elapsed: Duration,
+}
+
+impl Flights {
+    /// Draws the routes between the pairs of airports on the globe, marking
+    /// and labeling each airport once. Planes start out somewhere along
+    /// their routes and fly at slightly different speeds, picked with `rng`.
+    pub fn new(
+        pairs: &[(Airport, Airport)],
+        planes: bool,
+        globe: &mut Globe,
+        rng: &mut Rng,
+    ) -> Self {
+        let mut airports: Vec<Airport> = Vec::new();
+        let mut routes = Vec::new();
+        for &(from, to) in pairs {
+            let route = GreatArc::new(from.coord, to.coord)
+                .with_char(ROUTE_CHAR)
+                .with_color(ROUTE_COLOR);
+            globe.add_arc(route);
+            routes.push(Route {
+                arc: route,
+                start: rng.unit(),
+                pace: 1. + PLANE_SPEED_JITTER * (2. * rng.unit() - 1.),
+            });
+            for &airport in &[from, to] {
+                if !airports.iter().any(|known| known.code == airport.code) {
+                    airports.push(airport);
+                }
+            }
+        }
+        for airport in &airports {
+            globe.add_marker(
+                Marker::new(airport.coord, AIRPORT_MARKER)
+                    .with_color(AIRPORT_MARKER_COLOR)
+                    .with_label(airport.name)
+                    .with_group("airports"),
+            );
+            let (lat, lon) = airport.coord.lat_lon();
+            globe.add_label(lat, lon, airport.code);
+        }
+        Self {
+            routes,
+            planes,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Moves the planes along by the given amount of time.
+    pub fn tick(&mut self, dt: Duration) {
+        self.elapsed += dt;
+    }
+
+    /// Returns where each plane currently is, along with a point a little
+    /// ahead of it telling which way it's flying. Planes start over from
+    /// the departure airport once they land.
+    pub fn planes(&self) -> Vec<(GeoCoord, GeoCoord)> {
+        if !self.planes {
+            return Vec::new();
+        }
+        let flown = self.elapsed.as_secs_f64() as Float * PLANE_SPEED_KM;
+        self.routes
+            .iter()
+            .filter_map(|route| {
+                let arc = &route.arc;
+                let length = arc.from.distance_km(arc.to);
+                if length <= 0. {
+                    return None;
+                }
+                let fraction = (route.start + flown * route.pace / length).fract();
+                let ahead = fraction + HEADING_LOOKAHEAD_KM / length;
+                Some((arc.point_at(fraction), arc.point_at(ahead.min(1.))))
+            })
+            .collect()
+    }
+}
+
+/// Returns the character showing a plane flying between the two cells, one
+/// pointing the way it's heading on the screen.
+pub fn plane_char(from: (usize, usize), to: (usize, usize)) -> char {
+    let dx = to.0 as isize - from.0 as isize;
+    let dy = to.1 as isize - from.1 as isize;
+    // cells are about twice as tall as they are wide
+    if dx == 0 && dy == 0 {
+        PLANE_CHAR
+    } else if dx.abs() >= 2 * dy.abs() {
+        if dx < 0 {
+            '<'
+        } else {
+            '>'
+        }
+    } else if dy < 0 {
+        '^'
+    } else {
+        'v'
+    }
+}
@@ -0,0 +1,133 @@
+//! Ground track of the International Space Station and its visible passes
+//! over a home location, shown by the `iss` command.
+
+use globe::orbit::{Pass, Tle};
+use globe::{Color, GeoCoord, Globe, LineLayer, Marker};
+
+use crate::local_time;
+
+/// Character the ground track is drawn with.
+const TRACK_CHAR: char = '~';
+/// Color of the ground track.
+const TRACK_COLOR: Color = Color::new(90, 200, 255);
+/// Character marking the home location.
+const HOME_MARKER: char = '^';
+/// Color of the home location's marker.
+const HOME_MARKER_COLOR: Color = Color::new(255, 255, 255);
+/// Character marking where the station currently is.
+pub const ISS_MARKER: char = 'X';
+/// Color of the station's marker.
+pub const ISS_MARKER_COLOR: Color = Color::new(255, 90, 200);
+/// Seconds of the track shown behind the station.
+const TRACK_BEHIND: i64 = 10 * 60;
+/// Seconds of the track shown ahead of the station, about an orbit and a
+/// half.
+const TRACK_AHEAD: i64 = 140 * 60;
+/// Seconds between the points of the track.
+const TRACK_STEP: i64 = 60;
+/// Seconds after which the track and the passes are worked out again.
+const UPDATE_SECS: i64 = 60;
+/// Number of days ahead passes are looked for.
+const PASS_DAYS: i64 = 10;
+/// Age in days after which element sets are too old to be relied on.
+const STALE_DAYS: i64 = 14;
+
+/// Orbit of the station along with the home location it's seen from.
+pub struct IssView {
+    tle: Tle,
+    home: GeoCoord,
+    /// Number of visible passes listed
+    count: usize,
+    /// Next visible passes, the first one possibly under way
+    passes: Vec<Pass>,
+    /// Index of the globe's line layer holding the ground track
+    track_layer: usize,
+    /// Unix time the track and passes were last worked out at
+    updated_at: i64,
+}
+
+impl IssView {
+    /// Marks the home location on the globe and adds the ground track around
+    /// the given Unix time in seconds.
+    pub fn new(tle: Tle, home: GeoCoord, count: usize, globe: &mut Globe, now: i64) -> Self {
+        globe.add_marker(
+            Marker::new(home, HOME_MARKER)
+                .with_color(HOME_MARKER_COLOR)
+                .with_label("home"),
+        );
+        globe.add_line_layer(LineLayer::new());
+        let mut view = Self {
+            tle,
+            home,
+            count,
+            passes: Vec::new(),
+            track_layer: globe.line_layers().len() - 1,
+            updated_at: now,
+        };
+        view.refresh(globe, now);
+        view
+    }
+
+    /// Moves the ground track along and looks for the next passes again once
+    /// a while has gone by.
+    pub fn update(&mut self, globe: &mut Globe, now: i64) {
+        if now - self.updated_at >= UPDATE_SECS {
+            self.refresh(globe, now);
+        }
+    }
+
+    /// Returns where the station is at the given Unix time in seconds.
+    pub fn position(&self, now: i64) -> GeoCoord {
+        self.tle.subpoint(now)
+    }
+
+    /// Returns the lines describing where the station is and its next
+    /// visible passes over the home location.
+    pub fn lines(&self, now: i64) -> Vec<String> {
+        let (lat, lon) = self.position(now).lat_lon();
+        let name = self.tle.name.as_deref().unwrap_or("ISS");
+        let mut lines = vec![format!(
+            "{} {:.2}, {:.2} {:.0} km",
+            name,
+            lat,
+            lon,
+            self.tle.altitude_km(now)
+        )];
+        let age = (now - self.tle.epoch as i64) / 86_400;
+        if age > STALE_DAYS {
+            lines.push(format!("elements {} days old", age));
+        }
+        if self.passes.is_empty() {
+            lines.push(format!("no visible passes in {} days", PASS_DAYS));
+        } else {
+            lines.push("next visible passes".to_string());
+        }
+        for pass in &self.passes {
+            lines.push(format!(
+                "{} {} min {:.0}°",
+                local_time::format_local(pass.rise),
+                (pass.set - pass.rise + 59) / 60,
+                pass.max_elevation
+            ));
+        }
+        lines
+    }
+
+    fn refresh(&mut self, globe: &mut Globe, now: i64) {
+        let track = self
+            .tle
+            .ground_track(now - TRACK_BEHIND, now + TRACK_AHEAD + 1, TRACK_STEP);
+        globe.line_layers_mut()[self.track_layer] = LineLayer::new()
+            .with_line(&track)
+            .with_char(TRACK_CHAR)
+            .with_color(Some(TRACK_COLOR));
+        self.passes = self
+            .tle
+            .passes(self.home, now, now + PASS_DAYS * 86_400)
+            .into_iter()
+            .filter(|pass| pass.visible)
+            .take(self.count)
+            .collect();
+        self.updated_at = now;
+    }
+}
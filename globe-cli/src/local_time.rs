@@ -0,0 +1,85 @@
+//! Reading the time from the system clock.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use globe::Float;
+
+/// Number of seconds in a day.
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+/// Returns the local time as hours since midnight, e.g. `13.5` for half past
+/// one in the afternoon.
+///
+/// Falls back to UTC where the time zone can't be determined.
+pub fn local_hour() -> Float {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let offset = utc_offset(now.as_secs()).unwrap_or(0);
+    let secs = (now.as_secs() as i64 + offset).rem_euclid(DAY_SECS as i64) as u64;
+    let since_midnight = Duration::new(secs, now.subsec_nanos());
+    (since_midnight.as_secs_f64() / 3600.) as Float
+}
+
+/// Returns the current Unix time in seconds.
+pub fn unix_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since_epoch| since_epoch.as_secs() as i64)
+}
+
+/// Formats the given Unix time in seconds as the local date and time, e.g.
+/// `10-17 21:04`.
+///
+/// Falls back to UTC where the time zone can't be determined.
+pub fn format_local(unix_secs: i64) -> String {
+    let offset = utc_offset(unix_secs.max(0) as u64).unwrap_or(0);
+    let secs = unix_secs + offset;
+    let (days, secs) = (
+        secs.div_euclid(DAY_SECS as i64),
+        secs.rem_euclid(DAY_SECS as i64),
+    );
+    let (month, day) = month_day(days);
+    format!(
+        "{:02}-{:02} {:02}:{:02}",
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60
+    )
+}
+
+/// Returns the month and day of the month of the given day counted from the
+/// Unix epoch, in the Gregorian calendar.
+fn month_day(days: i64) -> (i64, i64) {
+    // count from March, so that the leap day ends the year
+    let days = days + 719_468;
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    (if month < 10 { month + 3 } else { month - 9 }, day)
+}
+
+/// Returns the local time zone's offset from UTC, in seconds, at the given
+/// time.
+#[cfg(unix)]
+fn utc_offset(unix_secs: u64) -> Option<i64> {
+    let time = unix_secs as libc::time_t;
+    // SAFETY: `localtime_r` only writes to the `tm` passed in, which is
+    // valid for writes, and leaves it alone on failure
+    unsafe {
+        let mut tm: libc::tm = std::mem::zeroed();
+        if libc::localtime_r(&time, &mut tm).is_null() {
+            return None;
+        }
+        Some(tm.tm_gmtoff as i64)
+    }
+}
+
+#[cfg(not(unix))]
+fn utc_offset(_unix_secs: u64) -> Option<i64> {
+    None
+}
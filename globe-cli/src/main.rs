@@ -2,45 +2,304 @@
 
 #![allow(unused_variables)]
 
-use std::f32::consts::PI;
-use std::io::{stdin, stdout, Read, Stdout, Write};
-use std::time::Duration;
-
-use clap::{App, AppSettings, Arg};
-use crossterm::{
-    cursor,
-    event::{poll, read, Event, KeyCode},
-    style::Print,
-    ExecutableCommand, QueueableCommand,
+use std::fs;
+use std::io::{stdin, stdout, IsTerminal, Read};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use clap::{App, AppSettings, Arg, ArgGroup, ArgMatches};
+use crossterm::event::Event;
+use globe::{
+    airports::{self, Airport},
+    orbit::Tle,
+    sun, AnimationFormat, Blend, CanvasPreset, Clouds, Color, ColorSchedule, DataGrid, DataSeries,
+    Float, GeoCoord, Geofence, Globe, GlobeTemplate, GreatArc, Guides, Heatmap, LineLayer, Marker,
+    RenderStyle, Terminator, Theme, VectorField,
 };
-use crossterm::{event::MouseEvent, terminal};
 
-use crossterm::terminal::ClearType;
-use globe::{CameraConfig, Canvas, GlobeConfig, GlobeTemplate};
+use backend::{
+    Backend, DeviceBackend, HeadlessBackend, LineEnding, RecordingBackend, TerminalBackend,
+    TextBackend,
+};
+use clock::FrameClock;
+use color::ColorMode;
+use config::Config;
+use events::{EventSource, TerminalEvents};
+use quality::{parse_bandwidth, Quality, QualityScaler};
+use scene::{Mode, SceneState};
+use stream::CoordStream;
+use wall::Wall;
+
+#[cfg(feature = "api")]
+mod api;
+mod backend;
+mod chaos;
+mod clock;
+mod color;
+mod config;
+mod diff;
+mod dump;
+mod events;
+mod flights;
+mod iss;
+mod local_time;
+mod overlay;
+mod quality;
+mod rng;
+mod scene;
+mod serial;
+mod stream;
+mod wall;
 
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const AUTHORS: &str = env!("CARGO_PKG_AUTHORS");
 
-/// Collection of scene settings that get passed from clap to mode processing
-/// functions.
-struct Settings {
-    /// Refresh rate in cycles per second
-    refresh_rate: usize,
+/// Time waited for input at once while drawing on demand, after which the
+/// loop checks whether it should stop.
+const IDLE_POLL_TIME: Duration = Duration::from_millis(500);
+
+/// Time each listed location is shown for when there are no keys to step to
+/// the next one with.
+const LISTING_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Character cell preset used for all terminal canvases.
+const CANVAS_PRESET: CanvasPreset = CanvasPreset::Terminal4x8;
+
+/// Average length of a year in the Gregorian calendar, in seconds.
+const SECS_PER_YEAR: Float = 31_556_952.;
+
+/// Character used for tracing the analemma.
+const ANALEMMA_MARKER: char = 'o';
+/// Color used for tracing the analemma.
+const ANALEMMA_MARKER_COLOR: Color = Color::new(255, 210, 60);
+/// Color of paths read from files unless set otherwise, faint enough to
+/// stay out of the way of the texture.
+const LINES_COLOR: Color = Color::new(110, 130, 160);
+/// Color of the arrows of vector fields.
+const VECTORS_COLOR: Color = Color::new(200, 230, 255);
+/// Character contours of grids of values are drawn with.
+const CONTOUR_CHAR: char = '~';
+/// Color of the contours of grids of values.
+const CONTOUR_COLOR: Color = Color::new(225, 225, 225);
+
+/// Collection of scene settings that get passed from clap to the scene.
+pub struct Settings {
+    /// Refresh rate in cycles per second, zero meaning frames are only drawn
+    /// in response to input
+    pub refresh_rate: usize,
     /// Initial globe rotation speed
-    globe_rotation_speed: f32,
+    pub globe_rotation_speed: Float,
     /// Initial camera rotation speed
-    cam_rotation_speed: f32,
+    pub cam_rotation_speed: Float,
     /// Initial camera zoom
-    cam_zoom: f32,
+    pub cam_zoom: Float,
     /// Target focus speed
-    focus_speed: f32,
+    pub focus_speed: Float,
     /// Globe night side switch
-    night: bool,
+    pub night: bool,
+    /// Light the globe from where the sun currently is
+    pub real_sun: bool,
+    /// Show the phase of the moon and mark where it's overhead
+    pub moon: bool,
+    /// Reflect the light off shiny parts of the globe
+    pub glint: bool,
+    /// Draw a glow of the atmosphere around the globe
+    pub atmosphere: bool,
+    /// Line drawn between the day and night sides
+    pub terminator: Option<Terminator>,
+    /// Show facts about the location in the center of the view
+    pub info: bool,
+    /// Clouds drifting over the globe
+    pub clouds: Option<Clouds>,
+    /// Regions highlighted on the surface
+    pub geofences: Vec<Geofence>,
+    /// Shortest paths drawn between pairs of locations
+    pub arcs: Vec<GreatArc>,
+    /// Flight routes drawn between pairs of airports
+    pub routes: Vec<(Airport, Airport)>,
+    /// Fly a plane along each route
+    pub planes: bool,
+    /// Markers placed on the globe from the start
+    pub markers: Vec<Marker>,
+    /// Text written next to locations, as latitude, longitude and text
+    pub labels: Vec<(Float, Float, String)>,
+    /// Reference marks drawn over the surface
+    pub guides: Option<Guides>,
+    /// Draw the borders between countries from the start
+    pub borders: bool,
+    /// Thin out the land where few people live
+    pub population: bool,
+    /// Paths read from files and contours of grids of values, drawn over the
+    /// surface
+    pub line_layers: Vec<LineLayer>,
+    /// Grids of values read from files, laid over the surface
+    pub data_grids: Vec<DataGrid>,
+    /// Grids of values read from files, played back over the surface one
+    /// after another
+    pub data_series: Option<DataSeries>,
+    /// Show the scale of the topmost grid of values beside the globe
+    pub legend: bool,
+    /// Fields of vectors read from files, drawn as arrows
+    pub vector_fields: Vec<VectorField>,
+    /// Locations counted into a heatmap drawn over the surface
+    pub heatmap: Vec<GeoCoord>,
+    /// Color of the heatmap, the default one if `None`
+    pub heatmap_color: Option<Color>,
+    /// Colors the globe is tinted with following the local time of day
+    pub schedule: Option<ColorSchedule>,
+    /// Look of the globe and markers
+    pub theme: Theme,
+    /// Colors the terminal can show
+    pub color: ColorMode,
+    /// Style used for drawing the globe
+    pub render_style: RenderStyle,
+    /// Built-in globe template to display
+    pub template: GlobeTemplate,
+    /// Time limit for rendering a single frame
+    pub frame_budget: Option<Duration>,
+    /// Render alternating rows on alternating frames
+    pub interlace: bool,
+    /// Strength of smoothing between consecutive frames
+    pub smoothing: Float,
+    /// Tilt of the globe's axis in degrees, the template's own if not given
+    pub axial_tilt: Option<Float>,
+    /// Output quality, possibly scaled down for slow terminals
+    pub quality: Quality,
+    /// Limit on bytes written per second
+    pub max_bandwidth: Option<u64>,
+    /// File the displayed frames are recorded to
+    pub record: Option<PathBuf>,
+    /// Number of frames to record before exiting
+    pub record_frames: Option<usize>,
+    /// Draw on the terminal's alternate screen, keeping the main one intact
+    pub alternate_screen: bool,
+    /// Terminal device to draw on instead of stdout
+    pub tty: Option<PathBuf>,
+    /// Baud rate of the terminal device's serial line
+    pub baud: Option<u32>,
+    /// Size of the terminal device in characters
+    pub tty_size: (u16, u16),
+    /// Line ending used when drawing on the terminal device
+    pub line_ending: LineEnding,
+    /// Part of a wall of terminals this one shows
+    pub wall: Option<Wall>,
+    /// Orbit of the ISS, the home location its passes are listed for and how
+    /// many of them
+    pub iss: Option<(Tle, GeoCoord, usize)>,
+    /// MQTT broker address and topic to show device positions from
+    #[cfg(feature = "mqtt")]
+    pub mqtt: Option<(String, u16, String)>,
     /// Initial location coordinates
-    coords: (f32, f32),
+    pub coords: GeoCoord,
+    /// Seed of anything picked at random, the current time if not given
+    pub seed: u64,
 }
 
 fn main() {
+    let matches = app().get_matches();
+    if let Some(diff_matches) = matches.subcommand_matches("diff") {
+        diff::run(diff_matches);
+        return;
+    }
+    if let Some(verify_matches) = matches.subcommand_matches("verify") {
+        dump::verify(verify_matches);
+        return;
+    }
+    let settings = read_settings(&matches);
+
+    #[cfg(feature = "api")]
+    if let Some(api_matches) = matches.subcommand_matches("serve-api") {
+        api::serve(api_matches.value_of("listen").unwrap(), &settings);
+        return;
+    }
+
+    if let Some(prefix) = matches.value_of("dump_frame") {
+        dump::run(&matches, settings, prefix);
+        return;
+    }
+
+    if let Some(seconds) = matches.value_of("chaos") {
+        let duration = Duration::from_secs(seconds.parse().expect("failed parsing chaos duration"));
+        chaos::run(&settings, duration);
+        return;
+    }
+
+    let mode = if let Some(path) = matches.value_of("coords_file") {
+        let text = fs::read_to_string(path)
+            .unwrap_or_else(|e| panic!("failed reading coordinates {}: {}", path, e));
+        Mode::Listing {
+            coords: parse_coord_list(&text),
+            current: 0,
+        }
+    } else if matches.is_present("pipe") {
+        let stdin = stdin();
+        let mut stdin_string = String::new();
+        stdin.lock().read_to_string(&mut stdin_string).unwrap();
+        Mode::Listing {
+            coords: parse_coord_list(&stdin_string),
+            current: 0,
+        }
+    } else if matches.is_present("stream") {
+        Mode::Stream(CoordStream::spawn(stdin()))
+    } else if matches.is_present("interactive") {
+        Mode::Interactive
+    } else if matches.is_present("screensaver") || settings.iss.is_some() {
+        Mode::Screensaver
+    } else {
+        return;
+    };
+
+    let interactive = matches!(mode, Mode::Interactive);
+    let scene = SceneState::new(&settings, mode);
+    let mut scaler = QualityScaler::new(settings.quality);
+    // the serial line can't take more than its baud rate allows
+    let line_rate = settings.baud.map(serial::line_rate);
+    if let Some(bytes_per_sec) = settings.max_bandwidth.into_iter().chain(line_rate).min() {
+        scaler = scaler.with_max_bandwidth(bytes_per_sec);
+    }
+
+    // escape sequences would end up in whatever stdout is redirected to
+    let headless =
+        settings.tty.is_none() && !matches.is_present("force_tty") && !stdout().is_terminal();
+    if headless && settings.record.is_none() {
+        print_once(scene, settings.tty_size);
+        return;
+    }
+
+    // with stdin piped in, keys come from the controlling terminal, and
+    // without one the listed locations are stepped through on their own
+    let mut events = TerminalEvents::open();
+    let scene = if events.is_available() {
+        scene
+    } else {
+        scene.with_auto_advance(LISTING_INTERVAL)
+    };
+
+    match &settings.tty {
+        Some(path) => {
+            let backend =
+                DeviceBackend::open(path, settings.baud, settings.tty_size, settings.line_ending)
+                    .unwrap_or_else(|e| panic!("failed opening {}: {}", path.display(), e));
+            start(scene, backend, &mut events, &settings, scaler);
+        }
+        // recordings are still made, only without drawing the frames
+        None if headless => {
+            if settings.record_frames.is_none() && !events.is_available() {
+                panic!("recording without a terminal to stop it from needs --frames");
+            }
+            let backend = HeadlessBackend::new(settings.tty_size);
+            start(scene, backend, &mut events, &settings, scaler);
+        }
+        None => {
+            let backend = TerminalBackend::new(interactive, settings.alternate_screen)
+                .with_color_mode(settings.color);
+            start(scene, backend, &mut events, &settings, scaler);
+        }
+    }
+}
+
+/// Returns the command line interface, options and subcommands.
+fn app() -> App<'static> {
     let app = App::new("globe-cli")
         .version(VERSION)
         .author(AUTHORS)
@@ -64,7 +323,7 @@ fn main() {
             Arg::new("refresh_rate")
                 .short('r')
                 .long("refresh-rate")
-                .help("Refresh rate in frames per second")
+                .help("Refresh rate in frames per second, 0 to only redraw on input")
                 .takes_value(true)
                 .value_name("fps")
                 .default_value("60"),
@@ -96,6 +355,109 @@ fn main() {
                 .value_name("distance")
                 .default_value("1.7"),
         )
+        .arg(
+            Arg::new("axial_tilt")
+                .long("axial-tilt")
+                .help("Tilt the axis the globe spins around, e.g. 23.4 for the Earth")
+                .takes_value(true)
+                .value_name("degrees")
+                .allow_hyphen_values(true),
+        )
+        .arg(
+            Arg::new("frame_budget")
+                .long("frame-budget")
+                .help("Time limit for rendering a frame, rows left out are refreshed next frame")
+                .takes_value(true)
+                .value_name("ms"),
+        )
+        .arg(
+            Arg::new("interlace")
+                .long("interlace")
+                .help("Render every other row each frame, alternating between frames")
+                .conflicts_with("frame_budget"),
+        )
+        .arg(
+            Arg::new("smoothing")
+                .long("smoothing")
+                .help("Blend characters with previous frames to reduce flicker, from 0 to 1")
+                .takes_value(true)
+                .value_name("strength")
+                .default_value("0"),
+        )
+        .arg(
+            Arg::new("quality")
+                .long("quality")
+                .help(
+                    "Output quality (auto, full, reduced), auto reduces it over SSH or slow output",
+                )
+                .takes_value(true)
+                .value_name("quality")
+                .default_value("auto"),
+        )
+        .arg(
+            Arg::new("max_bandwidth")
+                .long("max-bandwidth")
+                .help("Limit output to the given bits per second, e.g. 9600 or 100kbps")
+                .takes_value(true)
+                .value_name("rate"),
+        )
+        .arg(
+            Arg::new("record")
+                .long("record")
+                .help("Record displayed frames to an animated GIF or PNG file")
+                .takes_value(true)
+                .value_name("path"),
+        )
+        .arg(
+            Arg::new("frames")
+                .long("frames")
+                .help("Number of frames to record or dump before exiting")
+                .takes_value(true)
+                .value_name("count")
+                .requires("frame_output"),
+        )
+        .group(ArgGroup::new("frame_output").args(&["record", "dump_frame"]))
+        .arg(
+            Arg::new("tty")
+                .long("tty")
+                .help("Draw on the given terminal device, e.g. a serial console")
+                .takes_value(true)
+                .value_name("path"),
+        )
+        .arg(
+            Arg::new("baud")
+                .long("baud")
+                .help("Set the baud rate of the terminal device and pace output to it")
+                .takes_value(true)
+                .value_name("rate")
+                .requires("tty"),
+        )
+        .arg(
+            Arg::new("no_altscreen")
+                .long("no-altscreen")
+                .help("Draw on the main screen, for terminals without an alternate one"),
+        )
+        .arg(
+            Arg::new("force_tty")
+                .long("force-tty")
+                .help("Draw on stdout as on a terminal even if it isn't one"),
+        )
+        .arg(
+            Arg::new("tty_size")
+                .long("tty-size")
+                .help("Size of the terminal device, or of the frame printed when stdout isn't one")
+                .takes_value(true)
+                .value_name("colsxrows")
+                .default_value("80x24"),
+        )
+        .arg(
+            Arg::new("newline")
+                .long("newline")
+                .help("Line ending used on the terminal device (crlf, lf)")
+                .takes_value(true)
+                .value_name("ending")
+                .default_value("crlf"),
+        )
         .arg(
             Arg::new("focus_speed")
                 .short('f')
@@ -120,11 +482,113 @@ fn main() {
                 .long("night")
                 .help("Enable displaying the night side of the globe"),
         )
+        .arg(
+            Arg::new("sun").long("sun").help(
+                "Light the globe from where the sun currently is, see the night side with -n",
+            ),
+        )
+        .arg(
+            Arg::new("moon")
+                .long("moon")
+                .help("Show the current phase of the moon and mark where it's overhead"),
+        )
+        .arg(
+            Arg::new("analemma")
+                .long("analemma")
+                .help("Trace where the sun is overhead at noon UTC on every day of the year")
+                .takes_value(true)
+                .value_name("year"),
+        )
+        .arg(
+            Arg::new("glint")
+                .long("glint")
+                .help("Show the light glinting off the oceans, see the lit side with -n or --sun"),
+        )
+        .arg(
+            Arg::new("atmosphere")
+                .long("atmosphere")
+                .help("Draw a glow of the atmosphere around the globe"),
+        )
+        .arg(
+            Arg::new("terminator")
+                .long("terminator")
+                .help("Draw the line between day and night, shown along with -n"),
+        )
+        .arg(
+            Arg::new("terminator_char")
+                .long("terminator-char")
+                .help("Character the line between day and night is drawn with")
+                .takes_value(true)
+                .value_name("char")
+                .default_value("*")
+                .requires("terminator"),
+        )
+        .arg(
+            Arg::new("twilight")
+                .long("twilight")
+                .help("Width of the twilight the line's color fades out over")
+                .takes_value(true)
+                .value_name("degrees")
+                .default_value("10")
+                .requires("terminator"),
+        )
+        .arg(
+            Arg::new("info")
+                .long("info")
+                .help("Show whether it's day in the center of the view, its antipode and distance from the starting location"),
+        )
+        .arg(
+            Arg::new("time_of_day")
+                .long("time-of-day")
+                .help("Tint the globe with colors shifting over the day, following local time"),
+        )
+        .arg(
+            Arg::new("theme")
+                .long("theme")
+                .help("Color theme (classic, green-phosphor, amber, high-contrast, solarized)")
+                .takes_value(true)
+                .value_name("name"),
+        )
+        .arg(
+            Arg::new("color")
+                .long("color")
+                .help("Colors the terminal can show, detected from the environment by default")
+                .takes_value(true)
+                .value_name("mode")
+                .possible_values(["auto", "truecolor", "256", "16", "none"]),
+        )
+        .arg(
+            Arg::new("colorblind_safe")
+                .long("colorblind-safe")
+                .help("Use colors that stay apart with color vision deficiencies"),
+        )
+        .arg(
+            Arg::new("config")
+                .long("config")
+                .help("Read settings from the given file instead of ~/.config/globe/config")
+                .takes_value(true)
+                .value_name("path"),
+        )
+        .arg(
+            Arg::new("braille")
+                .short('b')
+                .long("braille")
+                .help("Render using braille characters for higher resolution, same as --style braille"),
+        )
+        .arg(
+            Arg::new("style")
+                .long("style")
+                .help("Render style (ascii, braille, half-block, quadrant)")
+                .takes_value(true)
+                .value_name("style")
+                .default_value_if("braille", None, Some("braille"))
+                .conflicts_with("braille"),
+        )
         .arg(
             Arg::new("template")
                 .short('t')
                 .long("template")
-                .help("Display a built-in globe template")
+                .help("Display a built-in globe template (earth, moon, mars, saturn)")
                 .takes_value(true)
                 .value_name("planet")
                 .default_value("earth"),
@@ -143,13 +607,469 @@ fn main() {
                 .takes_value(true)
                 .value_name("path"),
         )
+        .arg(
+            Arg::new("clouds")
+                .long("clouds")
+                .help("Cover the globe with clouds from a texture file, blanks being gaps")
+                .takes_value(true)
+                .value_name("path"),
+        )
+        .arg(
+            Arg::new("cloud_speed")
+                .long("cloud-speed")
+                .help("Rotation speed of the clouds relative to the surface")
+                .takes_value(true)
+                .value_name("move_per_frame")
+                .default_value("2")
+                .requires("clouds"),
+        )
+        .arg(
+            Arg::new("geofence")
+                .long("geofence")
+                .help("Highlight a region read from a GeoJSON file or a list of lat,lon lines")
+                .takes_value(true)
+                .multiple_occurrences(true)
+                .value_name("path"),
+        )
+        .arg(
+            Arg::new("geofence_fill")
+                .long("geofence-fill")
+                .help("Character highlighted regions are filled with, empty keeping the texture")
+                .takes_value(true)
+                .value_name("char")
+                .default_value("#")
+                .requires("geofence"),
+        )
+        .arg(
+            Arg::new("geofence_color")
+                .long("geofence-color")
+                .help("Color of highlighted regions, as #rrggbb")
+                .takes_value(true)
+                .value_name("color")
+                .requires("geofence"),
+        )
+        .arg(
+            Arg::new("arc")
+                .long("arc")
+                .help("Draw the shortest path between two locations, as lat,lon:lat,lon")
+                .takes_value(true)
+                .multiple_occurrences(true)
+                .value_name("from:to"),
+        )
+        .arg(
+            Arg::new("label")
+                .long("label")
+                .help("Write text next to a location, as lat,lon:text")
+                .takes_value(true)
+                .multiple_occurrences(true)
+                .value_name("location:text"),
+        )
+        .arg(
+            Arg::new("arc_char")
+                .long("arc-char")
+                .help("Character paths are drawn with")
+                .takes_value(true)
+                .value_name("char")
+                .default_value("+")
+                .requires("arc"),
+        )
+        .arg(
+            Arg::new("arc_color")
+                .long("arc-color")
+                .help("Color of paths, as #rrggbb")
+                .takes_value(true)
+                .value_name("color")
+                .requires("arc"),
+        )
+        .arg(
+            Arg::new("route")
+                .long("route")
+                .help("Draw flight routes between airports, as IATA code pairs, e.g. WAW-JFK,JFK-NRT")
+                .takes_value(true)
+                .multiple_occurrences(true)
+                .value_name("routes"),
+        )
+        .arg(
+            Arg::new("planes")
+                .long("planes")
+                .help("Fly a plane along each route")
+                .requires("route"),
+        )
+        .arg(
+            Arg::new("guides")
+                .long("guides")
+                .help("Mark the poles and draw the equator and the tropics"),
+        )
+        .arg(
+            Arg::new("magnetic_poles")
+                .long("magnetic-poles")
+                .help("Mark where the magnetic poles currently are, along with the guides"),
+        )
+        .arg(
+            Arg::new("borders")
+                .long("borders")
+                .help("Draw the borders between countries"),
+        )
+        .arg(
+            Arg::new("population")
+                .long("population")
+                .help("Thin out the land where few people live"),
+        )
+        .arg(
+            Arg::new("lines")
+                .long("lines")
+                .help("Draw paths read from a GeoJSON file or CSV rows of line,lat,lon, e.g. undersea cables")
+                .takes_value(true)
+                .multiple_occurrences(true)
+                .value_name("path"),
+        )
+        .arg(
+            Arg::new("lines_char")
+                .long("lines-char")
+                .help("Character paths read from files are drawn with")
+                .takes_value(true)
+                .value_name("char")
+                .default_value(":")
+                .requires("lines"),
+        )
+        .arg(
+            Arg::new("lines_color")
+                .long("lines-color")
+                .help("Color of paths read from files, as #rrggbb")
+                .takes_value(true)
+                .value_name("color")
+                .requires("lines"),
+        )
+        .arg(
+            Arg::new("data")
+                .long("data")
+                .help("Lay a grid of values over the surface, read from an ESRI ASCII grid or CSV rows from the north")
+                .takes_value(true)
+                .multiple_occurrences(true)
+                .value_name("path"),
+        )
+        .arg(
+            Arg::new("data_series")
+                .long("data-series")
+                .help("Play back grids of values over the surface, one file per timestep in order")
+                .takes_value(true)
+                .multiple_values(true)
+                .value_name("path"),
+        )
+        .arg(
+            Arg::new("data_speed")
+                .long("data-speed")
+                .help("Timesteps of the data series played back per second")
+                .takes_value(true)
+                .value_name("steps")
+                .default_value("1")
+                .requires("data_series"),
+        )
+        .group(
+            ArgGroup::new("data_input")
+                .args(&["data", "data_series"])
+                .multiple(true),
+        )
+        .arg(
+            Arg::new("data_blend")
+                .long("data-blend")
+                .help("How grids of values are combined with the texture")
+                .takes_value(true)
+                .value_name("mode")
+                .possible_values(["replace", "tint", "mix"])
+                .default_value("replace")
+                .requires("data_input"),
+        )
+        .arg(
+            Arg::new("data_range")
+                .long("data-range")
+                .help("Values put at the ends of the grids' ramps, the grids' own range by default")
+                .takes_value(true)
+                .value_name("min,max")
+                .allow_hyphen_values(true)
+                .requires("data_input"),
+        )
+        .arg(
+            Arg::new("data_ramp")
+                .long("data-ramp")
+                .help("Characters grids of values are drawn with, from the lowest values to the highest")
+                .takes_value(true)
+                .value_name("chars")
+                .requires("data_input"),
+        )
+        .arg(
+            Arg::new("legend")
+                .long("legend")
+                .help("Show the scale of the grid of values drawn on top, with the values at its ends and in between")
+                .requires("data_input"),
+        )
+        .arg(
+            Arg::new("vectors")
+                .long("vectors")
+                .help("Draw arrows of a vector field, e.g. winds, read from grids of its eastward and northward components")
+                .takes_value(true)
+                .number_of_values(2)
+                .value_names(&["u", "v"])
+                .multiple_occurrences(true),
+        )
+        .arg(
+            Arg::new("vectors_color")
+                .long("vectors-color")
+                .help("Color of the arrows of vector fields, as #rrggbb")
+                .takes_value(true)
+                .value_name("color")
+                .requires("vectors"),
+        )
+        .arg(
+            Arg::new("contours")
+                .long("contours")
+                .help("Draw contours of grids of values at this many levels spread evenly over their range")
+                .takes_value(true)
+                .value_name("count")
+                .conflicts_with("contour_levels")
+                .requires("data"),
+        )
+        .arg(
+            Arg::new("contour_levels")
+                .long("contour-levels")
+                .help("Draw contours of grids of values at the given levels")
+                .takes_value(true)
+                .value_name("level,..")
+                .allow_hyphen_values(true)
+                .requires("data"),
+        )
+        .arg(
+            Arg::new("heatmap")
+                .long("heatmap")
+                .help("Draw the density of locations read from a file of lat,lon lines")
+                .takes_value(true)
+                .value_name("path"),
+        )
+        .arg(
+            Arg::new("heatmap_color")
+                .long("heatmap-color")
+                .help("Color of the heatmap, as #rrggbb")
+                .takes_value(true)
+                .value_name("color")
+                .requires("heatmap"),
+        )
         .arg(
             Arg::new("pipe")
                 .short('p')
                 .long("pipe")
                 .help("Read coordinates from stdin and display them on the globe"),
+        )
+        .arg(
+            Arg::new("coords_file")
+                .long("coords-file")
+                .help("Read coordinates from a file and display them like --pipe does")
+                .takes_value(true)
+                .value_name("path")
+                .conflicts_with("pipe"),
+        )
+        .arg(
+            Arg::new("stream")
+                .long("stream")
+                .help("Keep reading lat,lon lines from stdin, flying to each location as it arrives")
+                .conflicts_with_all(&["pipe", "coords_file"]),
+        )
+        .arg(
+            Arg::new("wall")
+                .long("wall")
+                .help("Show a tile of one globe spanning a wall of terminals of this total size")
+                .takes_value(true)
+                .value_name("colsxrows")
+                .requires_all(&["wall_offset", "screensaver"])
+                .conflicts_with_all(&["pipe", "interactive"]),
+        )
+        .arg(
+            Arg::new("wall_offset")
+                .long("wall-offset")
+                .help("Position of this terminal's top left character on the wall")
+                .takes_value(true)
+                .value_name("col,row")
+                .requires("wall"),
+        )
+        .arg(
+            Arg::new("dump_frame")
+                .long("dump-frame")
+                .help("Write frames and hashes of them to files starting with this, see verify")
+                .takes_value(true)
+                .value_name("prefix")
+                .hide(true),
+        )
+        .arg(
+            Arg::new("chaos")
+                .long("chaos")
+                .help("Feed random input to the interactive mode for this many seconds")
+                .takes_value(true)
+                .value_name("seconds")
+                .hide(true),
+        )
+        .arg(
+            Arg::new("seed")
+                .long("seed")
+                .alias("chaos-seed")
+                .help("Seed of anything picked at random, e.g. where planes start out or the input in chaos mode, to reproduce a run")
+                .takes_value(true)
+                .value_name("number"),
+        );
+    #[cfg(feature = "mqtt")]
+    let app = app
+        .arg(
+            Arg::new("mqtt")
+                .long("mqtt")
+                .help("Show device positions published to an MQTT broker")
+                .takes_value(true)
+                .value_name("host[:port]")
+                .requires("mqtt_topic"),
+        )
+        .arg(
+            Arg::new("mqtt_topic")
+                .long("mqtt-topic")
+                .help("Topic carrying JSON positions, e.g. fleet/+/position")
+                .takes_value(true)
+                .value_name("topic")
+                .requires("mqtt"),
         );
-    let matches = app.get_matches();
+    let app = app.subcommand(
+        App::new("iss")
+            .about("Show the ground track of the ISS and its next visible passes over home")
+            .arg(
+                Arg::new("home")
+                    .long("home")
+                    .help("Location to list the passes for, as lat,lon")
+                    .takes_value(true)
+                    .value_name("lat,lon")
+                    .required(true),
+            )
+            .arg(
+                Arg::new("tle")
+                    .long("tle")
+                    .help("File with the station's current two-line element set")
+                    .takes_value(true)
+                    .value_name("path")
+                    .required(true),
+            )
+            .arg(
+                Arg::new("passes")
+                    .long("passes")
+                    .help("Number of visible passes to list")
+                    .takes_value(true)
+                    .value_name("count")
+                    .default_value("5"),
+            ),
+    );
+    #[cfg(feature = "api")]
+    let app = app.subcommand(
+        App::new("serve-api")
+            .about("Serve rendered frames over HTTP")
+            .arg(
+                Arg::new("listen")
+                    .long("listen")
+                    .help("Address to listen on")
+                    .takes_value(true)
+                    .value_name("host:port")
+                    .default_value("127.0.0.1:8080"),
+            ),
+    );
+    let app = app.subcommand(
+        App::new("verify")
+            .about("Check that frames dumped with --dump-frame still render the same")
+            .arg(
+                Arg::new("hashes")
+                    .help("File of hashes written along with the frames")
+                    .takes_value(true)
+                    .value_name("path")
+                    .required(true),
+            ),
+    );
+    app.subcommand(
+        App::new("diff")
+            .about("Compare the frames of two scenes from the same camera, cell by cell")
+            .arg(
+                Arg::new("scenes")
+                    .help("Files with the options of each scene, as key = value lines")
+                    .takes_value(true)
+                    .value_name("scene")
+                    .number_of_values(2)
+                    .required(true),
+            )
+            .arg(
+                Arg::new("side_by_side")
+                    .long("side-by-side")
+                    .help("Print both frames next to each other, along with a map of the changes"),
+            ),
+    )
+}
+
+/// Builds the settings out of the options given on the command line and in
+/// the config file.
+fn read_settings(matches: &ArgMatches) -> Settings {
+    let config = match matches.value_of("config") {
+        Some(path) => Config::load(Path::new(path), true),
+        None => {
+            Config::default_path().map_or(Ok(Config::default()), |path| Config::load(&path, false))
+        }
+    }
+    .unwrap_or_else(|e| panic!("failed reading config file: {}", e));
+    let color = matches
+        .value_of("color")
+        .or(config.color.as_deref())
+        .unwrap_or("auto");
+    let color = ColorMode::parse(color).unwrap_or_else(|| {
+        panic!(
+            "unknown color mode {}, available: auto, truecolor, 256, 16, none",
+            color
+        )
+    });
+    let theme_name = matches
+        .value_of("theme")
+        .or(config.theme.as_deref())
+        .unwrap_or("classic");
+    let mut theme = Theme::named(theme_name).unwrap_or_else(|| {
+        panic!(
+            "unknown theme {}, available: {}",
+            theme_name,
+            Theme::NAMES.join(", ")
+        )
+    });
+    if matches.is_present("colorblind_safe") || config.colorblind_safe == Some(true) {
+        theme = theme.colorblind_safe();
+    }
+    let heatmap_color = matches.value_of("heatmap_color").map(|color| {
+        parse_color(color).expect("failed parsing heatmap color, format: \"#ff8000\"")
+    });
+    let time_of_day = matches.is_present("time_of_day") || config.time_of_day == Some(true);
+    let schedule = time_of_day.then(|| match &config.day_phases[..] {
+        [] => ColorSchedule::default(),
+        phases => ColorSchedule::new(phases.to_vec()),
+    });
+
+    // grids of values are drawn alike whether played back or not
+    let style_data_grid = |grid: DataGrid| {
+        let grid = grid.with_blend(match matches.value_of("data_blend").unwrap() {
+            "tint" => Blend::Tint,
+            "mix" => Blend::Mix(0.5),
+            _ => Blend::Replace,
+        });
+        let grid = match matches.value_of("data_range") {
+            Some(range) => {
+                let (min, max) = range
+                    .split_once(',')
+                    .and_then(|(min, max)| {
+                        Some((min.trim().parse().ok()?, max.trim().parse().ok()?))
+                    })
+                    .expect("failed parsing data range, format: min,max");
+                grid.with_scale(min, max)
+            }
+            None => grid,
+        };
+        match matches.value_of("data_ramp") {
+            Some(ramp) => grid.with_ramp(ramp),
+            None => grid,
+        }
+    };
 
     // parse coordinates into a tuple
     let coords = matches
@@ -160,7 +1080,7 @@ fn main() {
     if coords.len() != 2 {
         panic!("failed parsing location coordinates")
     }
-    let coords: (f32, f32) = (
+    let coords: (Float, Float) = (
         coords[0]
             .parse()
             .expect("failed parsing location coordinates (first value)"),
@@ -169,7 +1089,7 @@ fn main() {
             .expect("failed parsing location coordinates (second value)"),
     );
 
-    let settings = Settings {
+    let mut settings = Settings {
         refresh_rate: matches
             .value_of("refresh_rate")
             .unwrap()
@@ -196,458 +1116,559 @@ fn main() {
             .parse()
             .expect("failed parsing focus speed value"),
         night: matches.is_present("night"),
-        coords,
+        real_sun: matches.is_present("sun"),
+        moon: matches.is_present("moon"),
+        glint: matches.is_present("glint"),
+        atmosphere: matches.is_present("atmosphere"),
+        info: matches.is_present("info"),
+        terminator: matches.is_present("terminator").then(|| {
+            let mut ch = matches.value_of("terminator_char").unwrap().chars();
+            let ch = match (ch.next(), ch.next()) {
+                (Some(ch), None) => ch,
+                _ => panic!("failed parsing terminator character, expected a single character"),
+            };
+            let softness = matches
+                .value_of("twilight")
+                .unwrap()
+                .parse()
+                .expect("failed parsing twilight width value");
+            Terminator {
+                softness,
+                ..Terminator::default()
+            }
+            .with_char(ch)
+        }),
+        clouds: matches.value_of("clouds").map(|path| {
+            let texture = fs::read_to_string(path)
+                .unwrap_or_else(|e| panic!("failed reading cloud texture {}: {}", path, e));
+            let speed: Float = matches
+                .value_of("cloud_speed")
+                .unwrap()
+                .parse()
+                .expect("failed parsing cloud speed value");
+            Clouds::new(&texture)
+                .unwrap_or_else(|e| panic!("invalid cloud texture {}: {}", path, e))
+                .with_speed(speed / 1000.)
+        }),
+        geofences: matches
+            .values_of("geofence")
+            .into_iter()
+            .flatten()
+            .map(|path| {
+                let text = fs::read_to_string(path)
+                    .unwrap_or_else(|e| panic!("failed reading geofence {}: {}", path, e));
+                let geofence = if text.trim_start().starts_with('{') {
+                    Geofence::from_geojson(&text)
+                } else {
+                    Geofence::from_lat_lon_list(&text)
+                }
+                .unwrap_or_else(|e| panic!("invalid geofence {}: {}", path, e));
+                let mut fill = matches.value_of("geofence_fill").unwrap().chars();
+                let geofence = match (fill.next(), fill.next()) {
+                    (fill, None) => geofence.with_fill(fill),
+                    _ => panic!("failed parsing geofence fill, expected a single character"),
+                };
+                match matches.value_of("geofence_color") {
+                    Some(color) => geofence.with_color(
+                        parse_color(color)
+                            .expect("failed parsing geofence color, format: \"#ff8000\""),
+                    ),
+                    None => geofence,
+                }
+            })
+            .collect(),
+        labels: matches
+            .values_of("label")
+            .into_iter()
+            .flatten()
+            .map(|value| {
+                value
+                    .split_once(':')
+                    .and_then(|(coord, text)| {
+                        let (lat, lon) = parse_lat_lon(coord)?.lat_lon();
+                        Some((lat, lon, text.to_string()))
+                    })
+                    .expect("failed parsing label, format: \"35.7,139.7:Tokyo\"")
+            })
+            .collect(),
+        arcs: matches
+            .values_of("arc")
+            .into_iter()
+            .flatten()
+            .map(|value| {
+                let arc = value
+                    .split_once(':')
+                    .and_then(|(from, to)| {
+                        Some(GreatArc::new(parse_lat_lon(from)?, parse_lat_lon(to)?))
+                    })
+                    .expect("failed parsing arc, format: \"51.5,-0.1:40.7,-74.0\"");
+                let mut ch = matches.value_of("arc_char").unwrap().chars();
+                let arc = match (ch.next(), ch.next()) {
+                    (Some(ch), None) => arc.with_char(ch),
+                    _ => panic!("failed parsing arc character, expected a single character"),
+                };
+                match matches.value_of("arc_color") {
+                    Some(color) => arc.with_color(
+                        parse_color(color).expect("failed parsing arc color, format: \"#ff8000\""),
+                    ),
+                    None => arc,
+                }
+            })
+            .collect(),
+        routes: matches
+            .values_of("route")
+            .into_iter()
+            .flatten()
+            .flat_map(|value| value.split(','))
+            .map(|route| {
+                let (from, to) = route
+                    .split_once('-')
+                    .expect("failed parsing route, format: \"WAW-JFK\"");
+                let find = |code: &str| {
+                    airports::find(code).unwrap_or_else(|| panic!("unknown airport: {}", code))
+                };
+                (find(from), find(to))
+            })
+            .collect(),
+        planes: matches.is_present("planes"),
+        markers: matches
+            .value_of("analemma")
+            .map(|year| {
+                let year = year.parse().expect("failed parsing analemma year");
+                sun::analemma(year)
+                    .into_iter()
+                    .map(|coord| {
+                        Marker::new(coord, ANALEMMA_MARKER)
+                            .with_color(ANALEMMA_MARKER_COLOR)
+                            .with_group("analemma")
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        guides: if matches.is_present("magnetic_poles") {
+            let year = 1970. + local_time::unix_secs() as Float / SECS_PER_YEAR;
+            Some(Guides::default().with_magnetic_poles(year))
+        } else {
+            matches.is_present("guides").then(Guides::default)
+        },
+        borders: matches.is_present("borders"),
+        population: matches.is_present("population"),
+        line_layers: matches
+            .values_of("lines")
+            .into_iter()
+            .flatten()
+            .map(|path| {
+                let text = fs::read_to_string(path)
+                    .unwrap_or_else(|e| panic!("failed reading lines {}: {}", path, e));
+                let layer = if text.trim_start().starts_with('{') {
+                    LineLayer::from_geojson(&text)
+                } else {
+                    LineLayer::from_csv(&text)
+                }
+                .unwrap_or_else(|e| panic!("invalid lines {}: {}", path, e));
+                let mut ch = matches.value_of("lines_char").unwrap().chars();
+                let layer = match (ch.next(), ch.next()) {
+                    (Some(ch), None) => layer.with_char(ch),
+                    _ => panic!("failed parsing lines character, expected a single character"),
+                };
+                let color = match matches.value_of("lines_color") {
+                    Some(color) => {
+                        parse_color(color).expect("failed parsing lines color, format: \"#ff8000\"")
+                    }
+                    None => LINES_COLOR,
+                };
+                layer.with_color(Some(color))
+            })
+            .collect(),
+        data_grids: matches
+            .values_of("data")
+            .into_iter()
+            .flatten()
+            .map(|path| style_data_grid(read_data_grid(path)))
+            .collect(),
+        data_series: matches.values_of("data_series").map(|paths| {
+            // the series takes its look from the first step
+            let mut steps: Vec<DataGrid> = paths.map(read_data_grid).collect();
+            steps[0] = style_data_grid(steps[0].clone());
+            let speed = matches
+                .value_of("data_speed")
+                .unwrap()
+                .parse()
+                .expect("failed parsing data speed");
+            DataSeries::new(steps)
+                .unwrap_or_else(|e| panic!("invalid data series: {}", e))
+                .with_speed(speed)
+        }),
+        legend: matches.is_present("legend"),
+        vector_fields: matches
+            .values_of("vectors")
+            .map(|paths| paths.collect::<Vec<_>>())
+            .unwrap_or_default()
+            .chunks(2)
+            .map(|paths| {
+                let field = VectorField::new(read_data_grid(paths[0]), read_data_grid(paths[1]))
+                    .unwrap_or_else(|e| panic!("invalid vectors {}: {}", paths[1], e));
+                let color = matches.value_of("vectors_color").map(|color| {
+                    parse_color(color).expect("failed parsing vectors color, format: \"#ff8000\"")
+                });
+                field.with_color(color.or(Some(VECTORS_COLOR)))
+            })
+            .collect(),
+        heatmap: matches
+            .value_of("heatmap")
+            .map(|path| {
+                let text = fs::read_to_string(path)
+                    .unwrap_or_else(|e| panic!("failed reading heatmap {}: {}", path, e));
+                text.lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .map(|line| {
+                        parse_lat_lon(line).unwrap_or_else(|| {
+                            panic!(
+                                "failed parsing heatmap location {:?}, format: \"51.5,-0.1\"",
+                                line
+                            )
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        // the theme restyles the heatmap, including ones started later on
+        heatmap_color: theme.heatmap_color.or(heatmap_color),
+        schedule,
+        theme,
+        color,
+        render_style: match matches.value_of("style") {
+            None | Some("ascii") => RenderStyle::Ascii,
+            Some("braille") => RenderStyle::Braille,
+            Some("half-block") => RenderStyle::HalfBlock,
+            Some("quadrant") => RenderStyle::Quadrant,
+            Some(_) => panic!("failed parsing render style"),
+        },
+        template: match matches.value_of("template").unwrap() {
+            "earth" => GlobeTemplate::Earth,
+            "moon" => GlobeTemplate::Moon,
+            "mars" => GlobeTemplate::Mars,
+            "saturn" => GlobeTemplate::Saturn,
+            _ => panic!("failed parsing globe template"),
+        },
+        frame_budget: matches.value_of("frame_budget").map(|ms| {
+            Duration::from_millis(ms.parse().expect("failed parsing frame budget value"))
+        }),
+        interlace: matches.is_present("interlace"),
+        smoothing: matches
+            .value_of("smoothing")
+            .unwrap()
+            .parse()
+            .expect("failed parsing smoothing strength value"),
+        axial_tilt: matches
+            .value_of("axial_tilt")
+            .map(|degrees| degrees.parse().expect("failed parsing axial tilt value")),
+        quality: match matches.value_of("quality").unwrap() {
+            "auto" => Quality::Auto,
+            "full" => Quality::Full,
+            "reduced" => Quality::Reduced,
+            _ => panic!("failed parsing output quality"),
+        },
+        max_bandwidth: matches
+            .value_of("max_bandwidth")
+            .map(|rate| parse_bandwidth(rate).expect("failed parsing max bandwidth value")),
+        record: matches.value_of("record").map(PathBuf::from),
+        record_frames: matches
+            .value_of("frames")
+            .map(|n| n.parse().expect("failed parsing frame count value")),
+        alternate_screen: !matches.is_present("no_altscreen"),
+        tty: matches.value_of("tty").map(PathBuf::from),
+        baud: matches
+            .value_of("baud")
+            .map(|baud| baud.parse().expect("failed parsing baud rate value")),
+        tty_size: parse_size(matches.value_of("tty_size").unwrap())
+            .expect("failed parsing terminal device size, format: \"80x24\""),
+        line_ending: match matches.value_of("newline").unwrap() {
+            "crlf" => LineEnding::CrLf,
+            "lf" => LineEnding::Lf,
+            _ => panic!("failed parsing line ending"),
+        },
+        wall: matches.value_of("wall").map(|size| Wall {
+            size: parse_size(size).expect("failed parsing wall size, format: \"320x96\""),
+            offset: parse_position(matches.value_of("wall_offset").unwrap())
+                .expect("failed parsing wall offset, format: \"160,48\""),
+        }),
+        #[cfg(feature = "mqtt")]
+        mqtt: matches.value_of("mqtt").map(|address| {
+            let (host, port) = parse_broker_address(address)
+                .expect("failed parsing MQTT broker address, format: \"host:1883\"");
+            let topic = matches.value_of("mqtt_topic").unwrap().to_string();
+            (host, port, topic)
+        }),
+        iss: matches.subcommand_matches("iss").map(|iss| {
+            let path = iss.value_of("tle").unwrap();
+            let text = fs::read_to_string(path)
+                .unwrap_or_else(|e| panic!("failed reading TLE {}: {}", path, e));
+            let tle = Tle::parse(&text).unwrap_or_else(|e| panic!("{}: {}", path, e));
+            let home = parse_lat_lon(iss.value_of("home").unwrap())
+                .expect("failed parsing home location, format: \"lat,lon\"");
+            let count = iss
+                .value_of("passes")
+                .unwrap()
+                .parse()
+                .expect("failed parsing number of passes");
+            (tle, home, count)
+        }),
+        coords: coords.into(),
+        seed: matches.value_of("seed").map_or_else(
+            || local_time::unix_secs() as u64,
+            |seed| seed.parse().expect("failed parsing seed"),
+        ),
     };
-
-    if matches.is_present("pipe") {
-        let stdin = stdin();
-        let mut stdin_string = String::new();
-        stdin.lock().read_to_string(&mut stdin_string).unwrap();
-        let coord_list = stdin_string.split(";").collect::<Vec<&str>>();
-        start_listing(settings, coord_list)
-    } else if matches.is_present("interactive") {
-        start_interactive(settings);
-    } else if matches.is_present("screensaver") {
-        start_screensaver(settings);
+    // the ISS command starts over home unless told otherwise
+    if let Some((_, home, _)) = &settings.iss {
+        if matches.occurrences_of("location") == 0 {
+            settings.coords = *home;
+        }
     }
-}
-
-/// Listing mode goes through a list of location coordinates. Pressing any key
-/// triggers stepping to the next location, or if there are no more locations,
-/// exits the program.
-fn start_listing(settings: Settings, coords_input: Vec<&str>) {
-    terminal::enable_raw_mode().unwrap();
-    let mut stdout = stdout();
-    stdout.execute(cursor::Hide).unwrap();
-    stdout.execute(cursor::DisableBlinking).unwrap();
-
-    let mut term_size = terminal::size().unwrap();
-    let mut canvas = if term_size.0 > term_size.1 {
-        Canvas::new(term_size.1 * 8, term_size.1 * 8, None)
-    } else {
-        Canvas::new(term_size.0 * 4, term_size.0 * 4, None)
-    };
-
-    let mut cam_zoom = settings.cam_zoom;
-    let mut cam_xy = 0.;
-    let mut cam_z = 0.;
-
-    let mut globe = GlobeConfig::new()
-        .use_template(GlobeTemplate::Earth)
-        .with_camera(CameraConfig::new(cam_zoom, cam_xy, cam_z))
-        .display_night(settings.night)
-        .build();
 
-    let coord_list: Vec<(f32, f32)> = coords_input
+    // contours are traced at levels depending on each grid's scale
+    let contours: Vec<LineLayer> = settings
+        .data_grids
         .iter()
-        .map(|c| {
-            let split = c.split(",").collect::<Vec<&str>>();
-            if split.len() != 2 {
-                panic!("failed parsing coordinates, format: \"51.23,51.23\"");
-            }
-            (
-                split[0]
-                    .trim()
-                    .parse()
-                    .expect("failed parsing coord as float"),
-                split[1]
-                    .trim()
-                    .parse()
-                    .expect("failed parsing coord as float"),
-            )
+        .filter_map(|grid| {
+            let levels = match (
+                matches.value_of("contours"),
+                matches.value_of("contour_levels"),
+            ) {
+                (Some(count), _) => {
+                    grid.contour_levels(count.parse().expect("failed parsing number of contours"))
+                }
+                (_, Some(levels)) => levels
+                    .split(',')
+                    .map(|level| {
+                        level
+                            .trim()
+                            .parse()
+                            .expect("failed parsing contour levels, format: level,level,..")
+                    })
+                    .collect(),
+                _ => return None,
+            };
+            let layer = grid.contour_layer(&levels).with_char(CONTOUR_CHAR);
+            Some(layer.with_color(Some(CONTOUR_COLOR)))
         })
         .collect();
+    settings.line_layers.extend(contours);
 
-    // set the initial coordinates
-    focus_target(settings.coords, 0., &mut cam_xy, &mut cam_z);
-
-    let globe_rot_speed = settings.globe_rotation_speed / 1000.;
-    let cam_rot_speed = settings.cam_rotation_speed / 1000.;
-
-    let mut current_index = 0;
-    let mut moving_towards_target: Option<(f32, f32)> = Some(coord_list[current_index]);
-
-    loop {
-        if poll(Duration::from_millis(1000 / settings.refresh_rate as u64)).unwrap() {
-            match read().unwrap() {
-                // pressing any key exists the program
-                Event::Key(key) => match key.code {
-                    KeyCode::Char(char) => match char {
-                        'c' | 'd' => break,
-                        _ => {
-                            current_index += 1;
-                            if current_index >= coord_list.len() {
-                                break;
-                            }
-                            moving_towards_target = Some(coord_list[current_index]);
-                        }
-                    },
-                    _ => {
-                        current_index += 1;
-                        if current_index >= coord_list.len() {
-                            break;
-                        }
-                        moving_towards_target = Some(coord_list[current_index]);
-                    }
-                },
-                Event::Resize(width, height) => {
-                    term_size = (width, height);
-                    canvas = if width > height {
-                        Canvas::new(height * 8, height * 8, None)
-                    } else {
-                        Canvas::new(width * 4, width * 4, None)
-                    };
-                }
-                Event::Mouse(_) => (),
-            }
+    if let Some(path) = &settings.record {
+        if AnimationFormat::from_path(path).is_none() {
+            panic!("recordings can only be saved to .gif or .png files");
         }
-
-        // apply globe rotation
-        globe.angle += globe_rot_speed;
-        cam_xy -= globe_rot_speed / 2.;
-
-        // apply camera rotation
-        cam_xy -= cam_rot_speed;
-
-        if let Some(target_coords) = moving_towards_target {
-            if move_towards_target(
-                settings.focus_speed,
-                target_coords,
-                cam_zoom,
-                globe.angle / 2.,
-                &mut cam_xy,
-                &mut cam_z,
-                &mut cam_zoom,
-            ) {
-                moving_towards_target = None;
-            }
-        }
-
-        globe.camera.update(cam_zoom, cam_xy, cam_z);
-
-        // render globe on the canvas
-        canvas.clear();
-        globe.render_on(&mut canvas);
-
-        // print canvas to terminal
-        print_canvas(&mut canvas, &term_size, &mut stdout);
     }
 
-    stdout.execute(cursor::Show).unwrap();
-    stdout.execute(cursor::EnableBlinking).unwrap();
-
-    terminal::disable_raw_mode().unwrap();
-    stdout.execute(terminal::Clear(ClearType::All)).unwrap();
+    settings
 }
 
-/// Screensaver mode doesn't allow for user input. Any key press exits the
-/// program.
-fn start_screensaver(settings: Settings) {
-    terminal::enable_raw_mode().unwrap();
-    let mut stdout = stdout();
-    stdout.execute(cursor::Hide).unwrap();
-    stdout.execute(cursor::DisableBlinking).unwrap();
-
-    let mut term_size = terminal::size().unwrap();
-    let mut canvas = if term_size.0 > term_size.1 {
-        Canvas::new(term_size.1 * 8, term_size.1 * 8, None)
-    } else {
-        Canvas::new(term_size.0 * 4, term_size.0 * 4, None)
-    };
-
-    let cam_zoom = settings.cam_zoom;
-    let mut cam_xy = 0.;
-    let mut cam_z = 0.;
-
-    // set the initial coordinates
-    focus_target(settings.coords, 0., &mut cam_xy, &mut cam_z);
-
-    let mut globe = GlobeConfig::new()
-        .use_template(GlobeTemplate::Earth)
-        .with_camera(CameraConfig::new(cam_zoom, cam_xy, cam_z))
-        .display_night(settings.night)
-        .build();
-
-    let globe_rot_speed = settings.globe_rotation_speed / 1000.;
-    let cam_rot_speed = settings.cam_rotation_speed / 1000.;
-
-    loop {
-        if poll(Duration::from_millis(1000 / settings.refresh_rate as u64)).unwrap() {
-            match read().unwrap() {
-                // pressing any key exists the program
-                Event::Key(_) => break,
-                Event::Resize(width, height) => {
-                    term_size = (width, height);
-                    canvas = if width > height {
-                        Canvas::new(height * 8, height * 8, None)
-                    } else {
-                        Canvas::new(width * 4, width * 4, None)
-                    };
-                }
-                Event::Mouse(_) => (),
-            }
+/// Runs the scene on the given backend with input from the given source,
+/// recording it if requested.
+fn start<B: Backend>(
+    scene: SceneState,
+    mut backend: B,
+    events: &mut impl EventSource,
+    settings: &Settings,
+    scaler: QualityScaler,
+) {
+    match &settings.record {
+        Some(path) => {
+            // frames drawn on demand are shown until the next one anyway
+            let frame_time =
+                frame_duration(settings.refresh_rate).unwrap_or(Duration::from_secs(1));
+            let mut backend = RecordingBackend::new(backend, settings.record_frames, frame_time);
+            run(scene, &mut backend, events, settings.refresh_rate, scaler);
+
+            // restore the terminal before saving, which can take a while
+            let (backend, recorder) = backend.into_parts();
+            drop(backend);
+            println!(
+                "saving {} frames to {}",
+                recorder.frame_count(),
+                path.display()
+            );
+            recorder
+                .save(path)
+                .unwrap_or_else(|e| panic!("failed saving recording: {}", e));
         }
+        None => run(scene, &mut backend, events, settings.refresh_rate, scaler),
+    }
+}
 
-        // apply globe rotation
-        globe.angle += globe_rot_speed;
-        cam_xy -= globe_rot_speed / 2.;
+/// Returns the time between frames at the given refresh rate, `None` for a
+/// zero rate, meaning frames are drawn on demand.
+fn frame_duration(refresh_rate: usize) -> Option<Duration> {
+    (refresh_rate > 0).then(|| Duration::from_millis(1000 / refresh_rate as u64))
+}
 
-        // apply camera rotation
-        cam_xy -= cam_rot_speed;
+/// Prints a single frame of the given size to stdout as plain text.
+fn print_once(mut scene: SceneState, size: (u16, u16)) {
+    let mut backend = TextBackend::new(stdout(), size);
+    scene.tick(Duration::ZERO);
+    scene.draw(&mut backend);
+}
 
-        globe.camera.update(cam_zoom, cam_xy, cam_z);
+/// Parses a size given as columns and rows, e.g. `80x24`.
+fn parse_size(input: &str) -> Option<(u16, u16)> {
+    let (cols, rows) = input.split_once('x')?;
+    Some((cols.trim().parse().ok()?, rows.trim().parse().ok()?))
+}
 
-        // render globe on the canvas
-        canvas.clear();
-        globe.render_on(&mut canvas);
+/// Parses a position given as column and row, e.g. `160,48`.
+fn parse_position(input: &str) -> Option<(u16, u16)> {
+    let (col, row) = input.split_once(',')?;
+    Some((col.trim().parse().ok()?, row.trim().parse().ok()?))
+}
 
-        // print canvas to terminal
-        print_canvas(&mut canvas, &term_size, &mut stdout);
+/// Parses a color given as `#rrggbb`, the `#` being optional.
+fn parse_color(input: &str) -> Option<Color> {
+    let hex = input.strip_prefix('#').unwrap_or(input);
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
     }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(Color::new(channel(0)?, channel(2)?, channel(4)?))
+}
 
-    stdout.execute(cursor::Show).unwrap();
-    stdout.execute(cursor::EnableBlinking).unwrap();
-
-    terminal::disable_raw_mode().unwrap();
-    stdout.execute(terminal::Clear(ClearType::All)).unwrap();
+/// Parses a location given as `lat,lon` in degrees.
+fn parse_lat_lon(input: &str) -> Option<GeoCoord> {
+    let (lat, lon) = input.split_once(',')?;
+    let lat: Float = lat.trim().parse().ok()?;
+    let lon: Float = lon.trim().parse().ok()?;
+    Some(GeoCoord::from_lat_lon(lat, lon))
 }
 
-/// Interactive mode allows using mouse and/or keyboard to control the globe.
-fn start_interactive(settings: Settings) {
-    terminal::enable_raw_mode().unwrap();
-    let mut stdout = stdout();
-    stdout.execute(cursor::Hide).unwrap();
-    stdout.execute(cursor::DisableBlinking).unwrap();
-    stdout
-        .execute(crossterm::event::EnableMouseCapture)
-        .unwrap();
-
-    let mut term_size = terminal::size().unwrap();
-    let mut canvas = if term_size.0 > term_size.1 {
-        Canvas::new(term_size.1 * 8, term_size.1 * 8, None)
+/// Reads a grid of values from an ESRI ASCII grid or CSV file.
+fn read_data_grid(path: &str) -> DataGrid {
+    let text =
+        fs::read_to_string(path).unwrap_or_else(|e| panic!("failed reading data {}: {}", path, e));
+    if text.trim_start().to_ascii_lowercase().starts_with("ncols") {
+        DataGrid::from_ascii_grid(&text)
     } else {
-        Canvas::new(term_size.0 * 4, term_size.0 * 4, None)
-    };
-
-    let mut cam_zoom = settings.cam_zoom;
-    let mut cam_xy = 0.;
-    let mut cam_z = 0.;
-
-    // set the initial coordinates
-    focus_target(settings.coords, 0., &mut cam_xy, &mut cam_z);
-
-    let mut globe = GlobeConfig::new()
-        .use_template(GlobeTemplate::Earth)
-        .with_camera(CameraConfig::new(cam_zoom, cam_xy, cam_z))
-        .display_night(settings.night)
-        .build();
-
-    let mut globe_rot_speed = settings.globe_rotation_speed / 1000.;
-    let mut cam_rot_speed = settings.cam_rotation_speed / 1000.;
-
-    let mut last_drag_pos = None;
-    let mut moving_towards_target: Option<(f32, f32)> = None;
-
-    loop {
-        if poll(Duration::from_millis(1000 / settings.refresh_rate as u64)).unwrap() {
-            match read().unwrap() {
-                Event::Key(event) => match event.code {
-                    KeyCode::Char(char) => match char {
-                        '-' => globe_rot_speed -= 0.005,
-                        '+' => globe_rot_speed += 0.005,
-                        ',' => cam_rot_speed -= 0.005,
-                        '.' => cam_rot_speed += 0.005,
-                        'n' => globe.display_night = !globe.display_night,
-                        // vim-style navigation with hjkl
-                        'h' => cam_xy += 0.1,
-                        'l' => cam_xy -= 0.1,
-                        'k' => {
-                            if cam_z < 1.5 {
-                                cam_z += 0.1;
-                            }
-                        }
-                        'j' => {
-                            if cam_z > -1.5 {
-                                cam_z -= 0.1;
-                            }
-                        }
-                        _ => break,
-                    },
-                    KeyCode::PageUp => cam_zoom += 0.1,
-                    KeyCode::PageDown => cam_zoom -= 0.1,
-                    KeyCode::Up => {
-                        if cam_z < 1.5 {
-                            cam_z += 0.1;
-                        }
-                    }
-                    KeyCode::Down => {
-                        if cam_z > -1.5 {
-                            cam_z -= 0.1;
-                        }
-                    }
-                    KeyCode::Left => cam_xy += 0.1,
-                    KeyCode::Right => cam_xy -= 0.1,
-                    KeyCode::Enter => {
-                        focus_target(settings.coords, globe.angle / 2., &mut cam_xy, &mut cam_z);
-                        // moving_towards_target = Some(settings.coords);
-                    }
-                    _ => (),
-                },
-                Event::Mouse(event) => match event {
-                    MouseEvent::Drag(_, x, y, _) => {
-                        if let Some(last) = last_drag_pos {
-                            let (x_last, y_last) = last;
-                            let x_diff = x as globe::Float - x_last as globe::Float;
-                            let y_diff = y as globe::Float - y_last as globe::Float;
-
-                            if y_diff > 0. && cam_z < 1.5 {
-                                cam_z += 0.1;
-                            } else if y_diff < 0. && cam_z > -1.5 {
-                                cam_z -= 0.1;
-                            }
-
-                            cam_xy += x_diff * PI / 30.;
-                            cam_xy += y_diff * PI / 30.;
-                        }
-                        last_drag_pos = Some((x, y))
-                    }
-                    MouseEvent::ScrollUp(..) => cam_zoom -= 0.1,
-                    MouseEvent::ScrollDown(..) => cam_zoom += 0.1,
-                    _ => last_drag_pos = None,
-                },
-                Event::Resize(width, height) => {
-                    term_size = (width, height);
-                    canvas = if width > height {
-                        Canvas::new(height * 8, height * 8, None)
-                    } else {
-                        Canvas::new(width * 4, width * 4, None)
-                    };
-                }
-            }
-        }
-
-        // apply globe rotation
-        globe.angle += globe_rot_speed;
-        cam_xy -= globe_rot_speed / 2.;
-
-        // apply camera rotation
-        cam_xy -= cam_rot_speed;
-
-        // clip camera zoom
-        if cam_zoom < 1.0 {
-            cam_zoom = 1.0;
-        }
-
-        if let Some(target_coords) = moving_towards_target {
-            if move_towards_target(
-                settings.focus_speed,
-                target_coords,
-                cam_zoom,
-                globe.angle / 2.,
-                &mut cam_xy,
-                &mut cam_z,
-                &mut cam_zoom,
-            ) {
-                moving_towards_target = None;
-            }
-        }
-
-        globe.camera.update(cam_zoom, cam_xy, cam_z);
-
-        // render globe on the canvas
-        canvas.clear();
-        globe.render_on(&mut canvas);
-
-        // print canvas to terminal
-        print_canvas(&mut canvas, &term_size, &mut stdout);
+        DataGrid::from_csv(&text)
     }
-
-    stdout.execute(cursor::Show).unwrap();
-    stdout.execute(cursor::EnableBlinking).unwrap();
-    stdout
-        .execute(crossterm::event::DisableMouseCapture)
-        .unwrap();
-
-    terminal::disable_raw_mode().unwrap();
-    stdout.execute(terminal::Clear(ClearType::All)).unwrap();
+    .unwrap_or_else(|e| panic!("invalid data {}: {}", path, e))
 }
 
-/// Prints globe canvas to stdout.
-fn print_canvas(canvas: &mut Canvas, term_size: &(u16, u16), stdout: &mut Stdout) {
-    let (canvas_size_x, canvas_size_y) = canvas.get_size();
-    for i in 0..canvas_size_y / canvas.char_pix.1 {
-        stdout
-            .queue(terminal::Clear(terminal::ClearType::CurrentLine))
-            .unwrap();
-        for j in 0..canvas_size_x / canvas.char_pix.0 {
-            stdout.queue(Print(canvas.matrix[i][j])).unwrap();
-        }
-        stdout.queue(cursor::MoveDown(1)).unwrap();
-        stdout
-            .queue(cursor::MoveLeft((canvas_size_x / 4) as u16))
-            .unwrap();
-        stdout.flush().unwrap();
+/// Returns an empty heatmap binning locations over the globe's texture, in
+/// the given color or the default one.
+fn new_heatmap(globe: &Globe, color: Option<Color>) -> Heatmap {
+    let heatmap = Heatmap::for_texture(globe.texture());
+    match color {
+        Some(color) => heatmap.with_color(Some(color)),
+        None => heatmap,
     }
+}
 
-    if term_size.0 / 2 > term_size.1 {
-        stdout
-            .execute(crossterm::cursor::MoveTo(
-                (canvas_size_x / canvas.char_pix.1) as u16
-                    - ((canvas_size_x / canvas.char_pix.1) / canvas.char_pix.0) as u16,
-                0,
-            ))
-            .unwrap();
+/// Parses a broker address, using the default MQTT port unless given.
+#[cfg(feature = "mqtt")]
+fn parse_broker_address(input: &str) -> Option<(String, u16)> {
+    match input.rsplit_once(':') {
+        Some((host, port)) => Some((host.to_string(), port.parse().ok()?)),
+        None => Some((input.to_string(), 1883)),
     }
 }
 
-/// Orients the camera so that it focuses on the given target coordinates.
-pub fn focus_target(coords: (f32, f32), xy_offset: f32, cam_xy: &mut f32, cam_z: &mut f32) {
-    let (cx, cy) = coords;
-    *cam_xy = (cx * PI) * -1. - 1.5 - xy_offset;
-    *cam_z = cy * 3. - 1.5;
+/// Parses a list of location coordinates separated by semicolons or put on
+/// lines of their own.
+fn parse_coord_list(input: &str) -> Vec<GeoCoord> {
+    input
+        .split([';', '\n'])
+        .filter(|c| !c.trim().is_empty())
+        .map(|c| {
+            let split = c.split(',').collect::<Vec<&str>>();
+            if split.len() != 2 {
+                panic!("failed parsing coordinates, format: \"51.23,51.23\"");
+            }
+            GeoCoord::new(
+                split[0]
+                    .trim()
+                    .parse()
+                    .expect("failed parsing coord as float"),
+                split[1]
+                    .trim()
+                    .parse()
+                    .expect("failed parsing coord as float"),
+            )
+        })
+        .collect()
 }
 
-//TODO animate zoom
-/// Rotates the camera towards given target coordinates.
-pub fn move_towards_target(
-    speed: f32,
-    coords: (f32, f32),
-    target_zoom: f32,
-    xy_offset: f32,
-    cam_xy: &mut f32,
-    cam_z: &mut f32,
-    cam_zoom: &mut f32,
-) -> bool {
-    let (cx, cy) = coords;
-    let target_xy = (cx * PI - xy_offset) * -1. - 1.5;
-    let target_z = cy * 3. - 1.5;
-
-    let diff_xy = target_xy - *cam_xy;
-    let diff_z = target_z - *cam_z;
-
-    if diff_xy.abs() < 0.01 && diff_z.abs() < 0.01 {
-        return true;
-    }
+/// Runs the scene until it quits, drawing frames using the given backend and
+/// reacting to events from the given source.
+///
+/// Frames are drawn at the refresh rate however often events arrive, the
+/// scene handling them as they come. With automatic quality, the refresh
+/// rate is lowered and only changes are output once writing frames out turns
+/// out to be slow or over the bandwidth limit. With a zero refresh rate,
+/// frames are only drawn after events.
+fn run<B: Backend>(
+    mut scene: SceneState,
+    backend: &mut B,
+    events: &mut impl EventSource,
+    refresh_rate: usize,
+    mut scaler: QualityScaler,
+) {
+    let frame_duration = frame_duration(refresh_rate);
+    backend.set_diff_output(scaler.is_reduced());
+    let mut last_tick = Instant::now();
+    let mut clock = FrameClock::new(last_tick);
+    // whether a frame is due while drawing on demand, the first one always is
+    let mut pending = true;
+
+    while !scene.should_quit() && !backend.finished() && !events.finished() {
+        // a preview left from resizing is replaced once the size settles
+        let settling = scene.time_until_settled();
+        let timeout = match frame_duration {
+            Some(_) => clock.time_until_frame(Instant::now()),
+            None if pending => scaler.frame_time(Duration::ZERO),
+            None => settling.unwrap_or(IDLE_POLL_TIME),
+        };
+        if events.poll(timeout) {
+            // handle all the queued events before drawing, so that bursts of
+            // events, e.g. while resizing, don't trigger a frame each
+            loop {
+                let event = events.read();
+                if let Event::Resize(width, height) = event {
+                    backend.resize((width, height));
+                }
+                scene.handle_event(event);
+                if !events.poll(Duration::ZERO) {
+                    break;
+                }
+            }
+            pending = true;
+        } else if settling.is_some() {
+            pending = true;
+        }
 
-    let mut xy_move = 0.01 * speed + (diff_xy.abs() / 30. * speed);
-    if diff_xy.abs() < 0.07 {
-        xy_move = xy_move / 5.;
-    }
-    if diff_xy > 0. {
-        *cam_xy += xy_move;
-    } else if diff_xy < 0. {
-        *cam_xy -= xy_move;
-    }
+        let now = Instant::now();
+        scene.tick(now - last_tick);
+        last_tick = now;
 
-    let mut z_move = 0.005 * speed + (diff_z.abs() / 30. * speed);
-    if diff_z.abs() < 0.07 {
-        z_move = z_move / 5.;
-    }
-    if diff_z > 0. {
-        *cam_z += z_move;
-    } else if diff_z < 0. {
-        *cam_z -= z_move;
+        let due = match frame_duration {
+            Some(_) => clock.is_due(now),
+            None => pending,
+        };
+        if !due {
+            continue;
+        }
+        if let Some(frame_duration) = frame_duration {
+            clock.schedule_next(now, scaler.frame_time(frame_duration));
+        }
+        // the scene keeps moving while frames are held back
+        if !scaler.may_draw() {
+            continue;
+        }
+        scene.draw(backend);
+        pending = false;
+        // frames drawn on demand can't fall behind the refresh rate
+        let frame_time = frame_duration.unwrap_or(Duration::MAX);
+        if scaler.record_frame(backend.flush_time(), backend.frame_bytes(), frame_time) {
+            backend.set_diff_output(true);
+        }
     }
-
-    false
 }
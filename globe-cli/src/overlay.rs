@@ -0,0 +1,163 @@
+//! Overlays drawn on top of the rendered globe, like tooltips and the status
+//! bar.
+
+use globe::{Canvas, CellColor, CellRect, DataGrid, Float, RenderTarget};
+
+/// Cells between a marker and the edge of its tooltip.
+const GAP: usize = 1;
+/// Most rows the legend of a data grid spans.
+const LEGEND_ROWS: usize = 11;
+/// Rows from one labeled value of the legend to the next.
+const LEGEND_TICK_SPACING: usize = 5;
+
+/// Draws a box with the text next to the given canvas cell, on its right
+/// unless it only fits on its left, and returns the cells it covers.
+///
+/// Text too long for the canvas gets cut off. Returns `None` without drawing
+/// anything if the canvas can't fit even a box around a single character.
+pub fn draw_tooltip(canvas: &mut Canvas, cell: (usize, usize), text: &str) -> Option<CellRect> {
+    let (cols, rows) = canvas.grid_size();
+    if cols < 3 || rows < 3 {
+        return None;
+    }
+    let text: Vec<char> = text.chars().take(cols - 2).collect();
+    let (width, height) = (text.len() + 2, 3);
+
+    let right = cell.0 + GAP + 1;
+    let x = if right + width <= cols {
+        right
+    } else {
+        cell.0.saturating_sub(GAP + width).min(cols - width)
+    };
+    let y = cell.1.saturating_sub(1).min(rows - height);
+
+    for col in 0..width {
+        let edge = if col == 0 || col == width - 1 {
+            '+'
+        } else {
+            '-'
+        };
+        draw_plain(canvas, x + col, y, edge);
+        draw_plain(canvas, x + col, y + 2, edge);
+    }
+    draw_plain(canvas, x, y + 1, '|');
+    for (col, &ch) in text.iter().enumerate() {
+        draw_plain(canvas, x + 1 + col, y + 1, ch);
+    }
+    draw_plain(canvas, x + width - 1, y + 1, '|');
+
+    Some(CellRect::new(x, y, width, height))
+}
+
+/// Draws brackets on both sides of the given canvas cell, returning the cells
+/// they cover.
+pub fn draw_highlight(canvas: &mut Canvas, cell: (usize, usize)) -> CellRect {
+    let left = cell.0.saturating_sub(1);
+    if cell.0 > 0 {
+        draw_plain(canvas, left, cell.1, '[');
+    }
+    draw_plain(canvas, cell.0 + 1, cell.1, ']');
+    CellRect::new(left, cell.1, cell.0 + 2 - left, 1)
+}
+
+/// Replaces the bottom row of the canvas with the text, cut off where it
+/// doesn't fit, returning the cells it covers.
+pub fn draw_status(canvas: &mut Canvas, text: &str) -> Option<CellRect> {
+    let (cols, rows) = canvas.grid_size();
+    let row = rows.checked_sub(1)?;
+    let mut chars = text.chars();
+    for col in 0..cols {
+        draw_plain(canvas, col, row, chars.next().unwrap_or(' '));
+    }
+    Some(CellRect::new(0, row, cols, 1))
+}
+
+/// Writes the lines over the top left corner of the canvas, padded to the
+/// length of the longest one and cut off where they don't fit, returning the
+/// cells they cover.
+pub fn draw_panel(canvas: &mut Canvas, lines: &[String]) -> Option<CellRect> {
+    draw_corner_panel(canvas, lines, false)
+}
+
+/// Writes the lines over the top right corner of the canvas, see
+/// `draw_panel`.
+pub fn draw_panel_right(canvas: &mut Canvas, lines: &[String]) -> Option<CellRect> {
+    draw_corner_panel(canvas, lines, true)
+}
+
+/// Writes the lines over the top left or right corner of the canvas.
+fn draw_corner_panel(canvas: &mut Canvas, lines: &[String], right: bool) -> Option<CellRect> {
+    let (cols, rows) = canvas.grid_size();
+    let width = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()?
+        .min(cols);
+    let height = lines.len().min(rows);
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let x = if right { cols - width } else { 0 };
+    for (row, line) in lines.iter().take(height).enumerate() {
+        let mut chars = line.chars();
+        for col in 0..width {
+            draw_plain(canvas, x + col, row, chars.next().unwrap_or(' '));
+        }
+    }
+    Some(CellRect::new(x, 0, width, height))
+}
+
+/// Draws the scale of the data grid over the bottom right corner of the
+/// canvas, above the status bar: a column of the characters and colors the
+/// grid is drawn with, from the highest value at the top to the lowest,
+/// labeled with their values at the ends and in between. Returns the cells
+/// it covers, `None` if the grid holds no data or the canvas is too small.
+pub fn draw_legend(canvas: &mut Canvas, grid: &DataGrid) -> Option<CellRect> {
+    let (min, max) = grid.scale.or_else(|| grid.range())?;
+    let (cols, rows) = canvas.grid_size();
+    let height = LEGEND_ROWS.min(rows.checked_sub(1)?);
+    if height < 2 {
+        return None;
+    }
+    // one more decimal for every tenfold narrower range
+    let span = (max - min).abs().max(f32::EPSILON);
+    let decimals = (1. - span.log10().floor()).clamp(0., 4.) as usize;
+    let value_at = |row: usize| max - (max - min) * row as f32 / (height - 1) as f32;
+    let labels: Vec<Option<String>> = (0..height)
+        .map(|row| {
+            let last = height - 1;
+            // ticks in between keep a row apart from the bottom one
+            let tick = row == last || (row % LEGEND_TICK_SPACING == 0 && last - row > 1);
+            tick.then(|| format!("{:.*}", decimals, value_at(row)))
+        })
+        .collect();
+    let label_width = labels.iter().flatten().map(String::len).max()?;
+    // label, a space and the swatch
+    let width = label_width + 2;
+    if width > cols {
+        return None;
+    }
+    let (x, y) = (cols - width, rows - 1 - height);
+
+    for (row, label) in labels.iter().enumerate() {
+        let label = label.as_deref().unwrap_or("");
+        let padded = format!("{:>1$} ", label, label_width);
+        for (col, ch) in padded.chars().enumerate() {
+            draw_plain(canvas, x + col, y + row, ch);
+        }
+        let position = 1. - row as Float / (height - 1) as Float;
+        let color = CellColor {
+            fg: grid.color_for(position),
+            bg: None,
+        };
+        let ch = grid.char_for(position).unwrap_or('#');
+        canvas.draw_colored_point(x + width - 1, y + row, ch, color);
+    }
+    Some(CellRect::new(x, y, width, height))
+}
+
+/// Draws a character of an overlay in the frame's own colors, rather than
+/// those of the globe underneath.
+fn draw_plain(canvas: &mut Canvas, col: usize, row: usize, ch: char) {
+    canvas.draw_colored_point(col, row, ch, CellColor::default());
+}
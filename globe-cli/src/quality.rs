@@ -0,0 +1,209 @@
+//! Scaling down the output quality for slow terminals, e.g. over SSH or
+//! serial consoles.
+
+use std::env;
+use std::time::{Duration, Instant};
+
+/// Shortest time between frames while the quality is reduced, capping the
+/// refresh rate at about 15 frames per second.
+const REDUCED_FRAME_TIME: Duration = Duration::from_millis(66);
+/// Weight of the newest measurement in the moving averages.
+const AVERAGE_WEIGHT: f32 = 0.1;
+/// Fraction of the frame time that flushing may take up before the output
+/// is considered slow.
+const SLOW_FLUSH_RATIO: f32 = 0.5;
+/// Minimum number of measured frames before the output can be considered
+/// slow, so that a single hiccup doesn't trigger it.
+const MIN_MEASURED_FRAMES: usize = 10;
+/// Seconds worth of bandwidth that can be saved up while idle and spent at
+/// once.
+const MAX_BURST: f32 = 0.25;
+
+/// Output quality setting, as given on the command line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Quality {
+    /// Reduce quality for remote sessions and whenever output is slow.
+    Auto,
+    /// Always redraw whole frames, at the full refresh rate unless the
+    /// bandwidth is limited.
+    Full,
+    /// Always use the reduced quality.
+    Reduced,
+}
+
+/// Decides the refresh rate and output method based on how long it takes to
+/// write frames out and how large they are.
+pub struct QualityScaler {
+    quality: Quality,
+    reduced: bool,
+    /// Moving average of the time spent flushing a frame, in seconds
+    flush_time: f32,
+    /// Moving average of the size of a frame, in bytes
+    frame_bytes: f32,
+    measured_frames: usize,
+    bandwidth: Option<Bandwidth>,
+}
+
+impl QualityScaler {
+    /// Creates a new scaler, starting out reduced for SSH sessions unless
+    /// the quality is set explicitly.
+    pub fn new(quality: Quality) -> Self {
+        let reduced = match quality {
+            Quality::Auto => is_remote_session(),
+            Quality::Full => false,
+            Quality::Reduced => true,
+        };
+        Self {
+            quality,
+            reduced,
+            flush_time: 0.,
+            frame_bytes: 0.,
+            measured_frames: 0,
+            bandwidth: None,
+        }
+    }
+
+    /// Limits the output to the given number of bytes per second, holding
+    /// frames back as needed.
+    pub fn with_max_bandwidth(mut self, bytes_per_sec: u64) -> Self {
+        self.bandwidth = Some(Bandwidth::new(bytes_per_sec as f32));
+        self
+    }
+
+    /// Returns true if the quality is currently reduced, meaning frames are
+    /// drawn less often and only changed cells are output.
+    pub fn is_reduced(&self) -> bool {
+        self.reduced
+    }
+
+    /// Returns true if a frame can be drawn without going over the
+    /// bandwidth limit.
+    pub fn may_draw(&self) -> bool {
+        self.bandwidth
+            .as_ref()
+            .is_none_or(|bandwidth| bandwidth.available() >= 0.)
+    }
+
+    /// Records the time it took to flush a frame and its size. Returns true
+    /// if this caused the quality to be reduced.
+    ///
+    /// Once reduced, the quality stays that way, as otherwise the lighter
+    /// output would keep toggling it back and forth.
+    pub fn record_frame(
+        &mut self,
+        flush_time: Duration,
+        bytes: usize,
+        frame_time: Duration,
+    ) -> bool {
+        if let Some(bandwidth) = &mut self.bandwidth {
+            bandwidth.spend(bytes as f32);
+        }
+
+        let secs = flush_time.as_secs_f32();
+        if self.measured_frames == 0 {
+            self.flush_time = secs;
+            self.frame_bytes = bytes as f32;
+        } else {
+            self.flush_time += (secs - self.flush_time) * AVERAGE_WEIGHT;
+            self.frame_bytes += (bytes as f32 - self.frame_bytes) * AVERAGE_WEIGHT;
+        }
+        self.measured_frames += 1;
+
+        if self.reduced
+            || self.quality != Quality::Auto
+            || self.measured_frames < MIN_MEASURED_FRAMES
+        {
+            return false;
+        }
+        let frame_secs = frame_time.as_secs_f32();
+        let slow_flush = self.flush_time > frame_secs * SLOW_FLUSH_RATIO;
+        let over_bandwidth = self
+            .bandwidth
+            .as_ref()
+            .is_some_and(|bandwidth| self.frame_bytes > bandwidth.limit * frame_secs);
+        self.reduced = slow_flush || over_bandwidth;
+        self.reduced
+    }
+
+    /// Returns the time between frames, stretching the given one while the
+    /// quality is reduced so that the output doesn't saturate the link, and
+    /// while waiting for the bandwidth to free up.
+    pub fn frame_time(&self, frame_time: Duration) -> Duration {
+        let mut frame_time = frame_time;
+        if self.reduced {
+            // leave at least as much idle time as is spent flushing
+            let flush_bound = Duration::from_secs_f32(self.flush_time * 2.);
+            frame_time = frame_time.max(REDUCED_FRAME_TIME).max(flush_bound);
+        }
+        if let Some(bandwidth) = &self.bandwidth {
+            frame_time = frame_time.max(bandwidth.time_until_available());
+        }
+        frame_time
+    }
+}
+
+/// Budget of bytes that can be written out, refilled over time at the
+/// bandwidth limit.
+struct Bandwidth {
+    /// Bytes per second
+    limit: f32,
+    /// Bytes that could be written at the time of the last refill, negative
+    /// after going over the limit
+    budget: f32,
+    refilled_at: Instant,
+}
+
+impl Bandwidth {
+    fn new(limit: f32) -> Self {
+        Self {
+            limit,
+            budget: 0.,
+            refilled_at: Instant::now(),
+        }
+    }
+
+    /// Returns the number of bytes that can be written right now.
+    fn available(&self) -> f32 {
+        let refill = self.refilled_at.elapsed().as_secs_f32() * self.limit;
+        (self.budget + refill).min(self.limit * MAX_BURST)
+    }
+
+    /// Returns how long it takes until the budget is no longer overspent.
+    fn time_until_available(&self) -> Duration {
+        let available = self.available();
+        if available >= 0. {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f32(-available / self.limit)
+    }
+
+    fn spend(&mut self, bytes: f32) {
+        self.budget = self.available() - bytes;
+        self.refilled_at = Instant::now();
+    }
+}
+
+/// Parses a bandwidth given in bits per second, e.g. `9600`, `100kbps` or
+/// `2mbps`, returning it in bytes per second.
+pub fn parse_bandwidth(value: &str) -> Option<u64> {
+    let value = value.trim().to_lowercase();
+    let value = value.strip_suffix("bps").unwrap_or(&value);
+    let (number, multiplier) = if let Some(number) = value.strip_suffix('k') {
+        (number, 1e3)
+    } else if let Some(number) = value.strip_suffix('m') {
+        (number, 1e6)
+    } else {
+        (value, 1.)
+    };
+    let bits: f64 = number.trim().parse().ok()?;
+    let bytes = (bits * multiplier / 8.) as u64;
+    if bytes == 0 {
+        return None;
+    }
+    Some(bytes)
+}
+
+/// Returns true if the program runs within an SSH session.
+fn is_remote_session() -> bool {
+    env::var_os("SSH_CONNECTION").is_some() || env::var_os("SSH_TTY").is_some()
+}
@@ -0,0 +1,43 @@
+//! Random numbers drawn from a seed, so that anything the scene picks at
+//! random can be reproduced by running again with the same `--seed`.
+
+use globe::Float;
+
+/// Small xorshift generator, good enough for picking random events or looks and
+/// reproducible from its seed.
+pub struct Rng(u64);
+
+impl Rng {
+    /// Creates a generator starting from the given seed.
+    pub fn new(seed: u64) -> Self {
+        // the all zero state would only ever produce zeros
+        Self(seed ^ 0x9e37_79b9_7f4a_7c15)
+    }
+
+    /// Returns the next random number.
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    /// Returns a random number below `n`, which has to be above zero.
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+
+    /// Returns a random number in the `0..1` range.
+    pub fn unit(&mut self) -> Float {
+        // the top 24 bits fit into the mantissa of either float type
+        (self.next_u64() >> 40) as Float / (1u64 << 24) as Float
+    }
+
+    /// Splits off a generator of its own seeded from this one, for drawing
+    /// numbers elsewhere that are still fixed by the same seed.
+    pub fn split(&mut self) -> Rng {
+        Rng::new(self.next_u64())
+    }
+}
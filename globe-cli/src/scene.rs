@@ -0,0 +1,927 @@
+//! Scene state shared by all the CLI modes.
+
+use std::time::{Duration, Instant};
+
+use crossterm::event::{Event, KeyCode, KeyEventKind, MouseEventKind};
+use globe::controller::{Controller, Input};
+#[cfg(feature = "mqtt")]
+use globe::mqtt::MqttFeed;
+use globe::{
+    moon, sun, Atmosphere, CameraConfig, Canvas, CellColor, CellRect, Color, ColorSchedule,
+    Coloring, DataGrid, Float, FrameReport, GeoCoord, Globe, GlobeConfig, LineLayer, Marker,
+    RenderTarget, Specular,
+};
+
+use crate::backend::{self, Backend};
+use crate::flights::{self, Flights};
+use crate::iss::{self, IssView};
+use crate::local_time;
+use crate::overlay;
+use crate::rng::Rng;
+use crate::stream::CoordStream;
+use crate::wall::{self, Wall};
+use crate::{new_heatmap, Settings, CANVAS_PRESET};
+
+/// Character used for pinning locations in the listing and stream modes.
+const LISTING_MARKER: char = '+';
+/// Color used for pinning locations in the listing and stream modes.
+const LISTING_MARKER_COLOR: Color = Color::new(230, 60, 60);
+/// Time without size changes after which the terminal is considered done
+/// resizing.
+const RESIZE_SETTLE_TIME: Duration = Duration::from_millis(150);
+/// Factor by which previews shown while resizing are scaled down.
+const PREVIEW_SCALE: usize = 2;
+/// Smallest terminal size in characters the globe is drawn at, smaller ones
+/// showing `TOO_SMALL_TEXT` instead.
+const MIN_TERM_SIZE: (u16, u16) = (10, 5);
+/// Placeholder shown on terminals below the minimum size.
+const TOO_SMALL_TEXT: &str = "too small";
+/// Distance in cells from the mouse pointer within which markers show their
+/// labels.
+const POINTER_REACH: usize = 1;
+/// Time notices stay in the status bar.
+const NOTICE_TIME: Duration = Duration::from_secs(2);
+/// Character marking where the moon is overhead.
+const MOON_MARKER: char = 'O';
+/// Color of the moon's marker.
+const MOON_COLOR: Color = Color::new(220, 220, 200);
+/// Number of rows the moon's phase is drawn over.
+const MOON_ROWS: usize = 5;
+
+/// Mode determining how the scene reacts to user input.
+pub enum Mode {
+    /// User input is disabled, any key press exits the program.
+    Screensaver,
+    /// Mouse and keyboard control the globe.
+    Interactive,
+    /// Goes through a list of location coordinates. Pressing any key steps
+    /// to the next location, or if there are no more locations, exits the
+    /// program.
+    Listing {
+        coords: Vec<GeoCoord>,
+        current: usize,
+    },
+    /// Follows locations streamed in, pinning the latest ones and flying to
+    /// each as it arrives. Any key press exits the program.
+    Stream(CoordStream),
+}
+
+/// Starting point the animation of a wall tile is replayed from every frame.
+struct WallState {
+    wall: Wall,
+    controller: Controller,
+    angle: Float,
+    cloud_angle: Float,
+}
+
+/// Globe scene along with the state of its camera and animations.
+pub struct SceneState {
+    pub globe: Globe,
+    pub controller: Controller,
+    canvas: Canvas,
+    canvas_term_size: (u16, u16),
+    /// Time of the last size change, while the terminal is being resized
+    resized_at: Option<Instant>,
+    mode: Mode,
+    /// Time limit for rendering a single frame
+    frame_budget: Option<Duration>,
+    /// Render alternating rows on alternating frames
+    interlace: bool,
+    /// Tile of a wall of terminals shown instead of the whole globe
+    wall: Option<WallState>,
+    /// Light the globe from where the sun currently is
+    real_sun: bool,
+    /// Show the phase of the moon and mark where it's overhead
+    moon: bool,
+    /// Colors frames are printed in
+    colors: CellColor,
+    /// Print each cell in its own colors
+    cell_colors: bool,
+    /// Colors the globe is tinted with over the day
+    schedule: Option<ColorSchedule>,
+    /// Colors of the theme, tinted by the schedule
+    theme_colors: Option<Coloring>,
+    /// Device positions shown as markers
+    #[cfg(feature = "mqtt")]
+    feed: Option<MqttFeed>,
+    /// Last terminal position reported by the mouse
+    pointer: Option<(u16, u16)>,
+    /// Index of the marker selected with the keyboard
+    selected: Option<usize>,
+    /// Cells covered by overlays drawn on the last frame
+    overlays: Vec<CellRect>,
+    /// Message shown in the status bar and when it was posted
+    notice: Option<(String, Instant)>,
+    /// Show facts about the location in the center of the view
+    info: bool,
+    /// Show the scale of the topmost grid of values
+    legend: bool,
+    /// Ground track and passes of the ISS over a home location
+    iss: Option<IssView>,
+    /// Flight routes and the planes flying them
+    flights: Option<Flights>,
+    /// Time each listed location is shown for before stepping to the next
+    /// one on its own
+    auto_advance: Option<Duration>,
+    /// Time the current listed location has been shown for
+    shown_for: Duration,
+    /// Source of everything the scene picks at random, seeded with `--seed`
+    rng: Rng,
+    quit: bool,
+}
+
+impl SceneState {
+    /// Sets up a new scene using the given settings.
+    pub fn new(settings: &Settings, mode: Mode) -> Self {
+        let mut globe = GlobeConfig::new()
+            .use_template(settings.template)
+            .with_camera(CameraConfig::new(settings.cam_zoom, 0., 0.))
+            .display_night(settings.night)
+            .with_render_style(settings.render_style)
+            .with_temporal_smoothing(settings.smoothing)
+            .build()
+            .expect("built-in templates are valid");
+
+        if let Some(tilt) = settings.axial_tilt {
+            globe.set_axial_tilt(tilt);
+        }
+        if settings.glint {
+            globe.set_specular(Some(Specular::default()));
+        }
+        if settings.atmosphere {
+            globe.set_atmosphere(Some(Atmosphere::default()));
+        }
+        globe.set_terminator(settings.terminator);
+        globe.set_clouds(settings.clouds.clone());
+        globe.set_guides(settings.guides);
+        if settings.population {
+            globe.set_density(Some(DataGrid::population_density()));
+        }
+        // added hidden so it can be toggled on later
+        let mut borders = LineLayer::country_borders();
+        borders.visible = settings.borders;
+        globe.add_line_layer(borders);
+        for layer in &settings.line_layers {
+            // dense datasets only blur into the cells they cross
+            globe.add_line_layer(layer.clone().simplified_for(globe.texture()));
+        }
+        for grid in &settings.data_grids {
+            globe.add_data_grid(grid.clone());
+        }
+        if let Some(series) = &settings.data_series {
+            globe.add_data_series(series.clone());
+        }
+        for field in &settings.vector_fields {
+            globe.add_vector_field(field.clone());
+        }
+        for geofence in &settings.geofences {
+            globe.add_geofence(geofence.clone());
+        }
+        for &arc in &settings.arcs {
+            globe.add_arc(arc);
+        }
+        for (lat, lon, text) in &settings.labels {
+            globe.add_label(*lat, *lon, text);
+        }
+        for marker in &settings.markers {
+            globe.add_marker(marker.clone());
+        }
+        let mut rng = Rng::new(settings.seed);
+        let flights = (!settings.routes.is_empty())
+            .then(|| Flights::new(&settings.routes, settings.planes, &mut globe, &mut rng));
+        if !settings.heatmap.is_empty() {
+            let mut heatmap = new_heatmap(&globe, settings.heatmap_color);
+            for &coord in &settings.heatmap {
+                heatmap.add(coord);
+            }
+            globe.set_heatmap(Some(heatmap));
+        }
+        let iss = settings.iss.as_ref().map(|(tle, home, count)| {
+            IssView::new(
+                tle.clone(),
+                *home,
+                *count,
+                &mut globe,
+                local_time::unix_secs(),
+            )
+        });
+
+        let mut controller = Controller::new(&globe);
+        controller.globe_rot_speed = settings.globe_rotation_speed / 1000.;
+        controller.cam_rot_speed = settings.cam_rotation_speed / 1000.;
+        controller.refresh_rate = settings.refresh_rate as Float;
+        controller.focus_speed = settings.focus_speed;
+        controller.home = settings.coords;
+
+        // set the initial coordinates
+        controller.focus(&mut globe, settings.coords);
+
+        if let Mode::Listing { coords, current } = &mode {
+            // pin all the listed locations
+            for &coord in coords {
+                globe.add_marker(
+                    Marker::new(coord, LISTING_MARKER).with_color(LISTING_MARKER_COLOR),
+                );
+            }
+            if let Some(&target) = coords.get(*current) {
+                controller.focus_on(&globe, target);
+            }
+        }
+
+        globe.apply_theme(&settings.theme);
+        let cell_colors = settings.color.is_enabled();
+        // themes without colors of their own keep the texture's
+        if cell_colors && globe.coloring().is_none() {
+            globe.set_coloring(Some(Coloring::Texture));
+        }
+
+        let wall = settings.wall.map(|wall| WallState {
+            wall,
+            controller: controller.clone(),
+            angle: globe.angle(),
+            cloud_angle: globe.clouds().map_or(0., |clouds| clouds.angle),
+        });
+
+        Self {
+            globe,
+            controller,
+            canvas: Canvas::for_terminal((0, 0), CANVAS_PRESET),
+            canvas_term_size: (0, 0),
+            resized_at: None,
+            mode,
+            frame_budget: settings.frame_budget,
+            interlace: settings.interlace,
+            wall,
+            real_sun: settings.real_sun,
+            moon: settings.moon,
+            colors: CellColor {
+                fg: settings.theme.foreground(),
+                bg: settings.theme.background,
+            },
+            cell_colors,
+            schedule: settings.schedule.clone(),
+            theme_colors: settings.theme.colors,
+            #[cfg(feature = "mqtt")]
+            feed: settings.mqtt.as_ref().map(|(host, port, topic)| {
+                MqttFeed::connect(host, *port, topic)
+                    .unwrap_or_else(|e| panic!("failed connecting to {}: {}", host, e))
+            }),
+            pointer: None,
+            selected: None,
+            overlays: Vec::new(),
+            notice: None,
+            info: settings.info,
+            legend: settings.legend,
+            iss,
+            flights,
+            auto_advance: None,
+            shown_for: Duration::ZERO,
+            rng,
+            quit: false,
+        }
+    }
+
+    /// Makes the listing mode step to the next location on its own after
+    /// showing each for the given time, e.g. when there are no keys to step
+    /// with. Other modes aren't affected.
+    pub fn with_auto_advance(mut self, interval: Duration) -> Self {
+        self.auto_advance = Some(interval);
+        self
+    }
+
+    /// Returns the generator anything picked at random for the scene is
+    /// drawn from.
+    pub fn rng(&mut self) -> &mut Rng {
+        &mut self.rng
+    }
+
+    /// Returns true if the scene is done and the program should exit.
+    pub fn should_quit(&self) -> bool {
+        self.quit
+    }
+
+    /// Returns the time left until the terminal counts as done resizing,
+    /// while previews are drawn in place of full frames.
+    pub fn time_until_settled(&self) -> Option<Duration> {
+        let resized_at = self.resized_at?;
+        Some(RESIZE_SETTLE_TIME.saturating_sub(resized_at.elapsed()))
+    }
+
+    /// Reacts to a single input event.
+    pub fn handle_event(&mut self, event: Event) {
+        // terminals reporting key releases report the presses too
+        if matches!(event, Event::Key(key) if key.kind == KeyEventKind::Release) {
+            return;
+        }
+        match self.mode {
+            Mode::Screensaver | Mode::Stream(_) => self.handle_screensaver_event(event),
+            Mode::Interactive => self.handle_interactive_event(event),
+            Mode::Listing { .. } => self.handle_listing_event(event),
+        }
+    }
+
+    fn handle_screensaver_event(&mut self, event: Event) {
+        if let Event::Key(_) = event {
+            self.quit = true;
+        }
+    }
+
+    fn handle_listing_event(&mut self, event: Event) {
+        if let Event::Key(key) = event {
+            match key.code {
+                KeyCode::Char('c') | KeyCode::Char('d') => self.quit = true,
+                _ => self.next_location(),
+            }
+        }
+    }
+
+    /// Steps to the next location on the list, quitting if there are none
+    /// left.
+    fn next_location(&mut self) {
+        self.shown_for = Duration::ZERO;
+        let next = match &mut self.mode {
+            Mode::Listing { coords, current } => {
+                *current += 1;
+                coords.get(*current).copied()
+            }
+            _ => None,
+        };
+        match next {
+            Some(target) => self.controller.focus_on(&self.globe, target),
+            None => self.quit = true,
+        }
+    }
+
+    fn handle_interactive_event(&mut self, event: Event) {
+        if let Event::Mouse(mouse) = event {
+            self.pointer = Some((mouse.column, mouse.row));
+        }
+        if let Event::Key(key) = event {
+            if key.code == KeyCode::Char('i') {
+                self.info = !self.info;
+                return;
+            }
+            if key.code == KeyCode::Char('b') {
+                // the borders are added before any other layer
+                if let Some(borders) = self.globe.line_layers_mut().first_mut() {
+                    borders.visible = !borders.visible;
+                }
+                return;
+            }
+            if self.handle_selection_key(key.code)
+                || self.handle_group_key(key.code)
+                || self.handle_series_key(key.code)
+            {
+                return;
+            }
+        }
+        if let Some(input) = to_input(event) {
+            // keys not used by the controller exit the program
+            if !self.controller.handle_input(&mut self.globe, input) {
+                self.quit = true;
+            }
+        }
+    }
+
+    /// Handles keys selecting markers and flying to them, returning false
+    /// for other keys.
+    fn handle_selection_key(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Tab => self.cycle_selection(true),
+            KeyCode::BackTab => self.cycle_selection(false),
+            KeyCode::Esc => self.selected = None,
+            // without a selection, Enter takes the camera home
+            KeyCode::Enter => match self.selected_marker() {
+                Some(marker) => {
+                    let target = marker.coord;
+                    self.controller.focus_on(&self.globe, target);
+                }
+                None => return false,
+            },
+            _ => return false,
+        }
+        true
+    }
+
+    /// Handles digit keys showing and hiding the marker group with that
+    /// number, counting in the order the groups appeared, returning false
+    /// for other keys.
+    fn handle_group_key(&mut self, code: KeyCode) -> bool {
+        let number = match code {
+            KeyCode::Char(c) => match c.to_digit(10) {
+                Some(number) if number > 0 => number as usize,
+                _ => return false,
+            },
+            _ => return false,
+        };
+        let name = match self.globe.marker_group_names().get(number - 1) {
+            Some(name) => name.to_string(),
+            // keep the key from exiting when there are fewer groups
+            None => return true,
+        };
+        let visible = !self.globe.marker_group(&name).visible;
+        self.globe.set_marker_group_visible(&name, visible);
+        if self
+            .selected_marker()
+            .is_some_and(|m| is_hidden(&self.globe, m))
+        {
+            self.selected = None;
+        }
+        let state = if visible { "shown" } else { "hidden" };
+        self.notice = Some((format!("{} {} {}", number, name, state), Instant::now()));
+        true
+    }
+
+    /// Handles keys controlling the playback of the data series, returning
+    /// false for other keys or without a series: space pauses and resumes,
+    /// brackets step back and forth and angle brackets change the speed.
+    fn handle_series_key(&mut self, code: KeyCode) -> bool {
+        let series = match self.globe.data_series_mut().first_mut() {
+            Some(series) => series,
+            None => return false,
+        };
+        match code {
+            KeyCode::Char(' ') => series.playing = !series.playing,
+            KeyCode::Char('[') => series.seek(series.time().ceil() - 1.),
+            KeyCode::Char(']') => series.seek(series.time().floor() + 1.),
+            KeyCode::Char('<') => series.speed /= 2.,
+            KeyCode::Char('>') => series.speed *= 2.,
+            _ => return false,
+        }
+        let state = if series.playing { "playing" } else { "paused" };
+        let text = format!(
+            "step {}/{} {} at {} steps/s",
+            series.time().floor() as usize + 1,
+            series.step_count(),
+            state,
+            series.speed
+        );
+        self.notice = Some((text, Instant::now()));
+        true
+    }
+
+    /// Selects the next marker, or the previous one if `forward` is false,
+    /// wrapping around at the ends and skipping markers in hidden groups.
+    fn cycle_selection(&mut self, forward: bool) {
+        let markers = self.globe.markers();
+        let count = markers.len();
+        if count == 0 {
+            self.selected = None;
+            return;
+        }
+        // without a selection, start from the ends
+        let start = match self.selected.filter(|&i| i < count) {
+            Some(i) => i,
+            None if forward => count - 1,
+            None => 0,
+        };
+        self.selected = (1..=count)
+            .map(|step| {
+                if forward {
+                    (start + step) % count
+                } else {
+                    (start + count - step) % count
+                }
+            })
+            .find(|&i| !is_hidden(&self.globe, &markers[i]));
+    }
+
+    /// Removes the markers whose time is up, keeping the selection on the
+    /// same marker, or dropping it if that one expired.
+    fn age_markers(&mut self, dt: Duration) {
+        let expires = |marker: &Marker| marker.ttl.is_some_and(|ttl| ttl <= dt);
+        if let Some(index) = self.selected {
+            let markers = self.globe.markers();
+            self.selected = match markers.get(index) {
+                Some(marker) if !expires(marker) => {
+                    Some(index - markers[..index].iter().filter(|m| expires(m)).count())
+                }
+                _ => None,
+            };
+        }
+        self.globe.age_markers(dt);
+    }
+
+    /// Returns the selected marker, if it's still there.
+    fn selected_marker(&self) -> Option<&Marker> {
+        self.globe.markers().get(self.selected?)
+    }
+
+    /// Advances the scene by the given amount of time.
+    pub fn tick(&mut self, dt: Duration) {
+        match &self.wall {
+            // replaying from the start makes tiles started at different
+            // times agree on the state of the animation
+            Some(start) => {
+                self.controller = start.controller.clone();
+                self.globe.set_angle(start.angle);
+                if let Some(clouds) = self.globe.clouds_mut() {
+                    clouds.angle = start.cloud_angle;
+                }
+                let elapsed = wall::shared_time();
+                self.controller.advance(&mut self.globe, elapsed);
+                for series in self.globe.data_series_mut() {
+                    if !series.playing {
+                        continue;
+                    }
+                    // looping series are wound back by whole loops before
+                    // the time since the epoch loses its fraction of a step,
+                    // ones stopping at their last step play out from the top
+                    // of every hour, the only start all tiles agree on
+                    let speed = f64::from(series.speed);
+                    let secs = elapsed.as_secs_f64();
+                    let steps = if series.looping {
+                        (speed * secs).rem_euclid(series.step_count() as f64)
+                    } else {
+                        speed * (secs % 3600.)
+                    };
+                    series.seek(steps as Float);
+                }
+            }
+            None => {
+                self.controller.advance(&mut self.globe, dt);
+                self.globe.advance_data_series(dt);
+            }
+        }
+        self.age_markers(dt);
+        if self.real_sun {
+            self.globe.set_sun_from_time(local_time::unix_secs());
+        }
+        if let Some(iss) = &mut self.iss {
+            iss.update(&mut self.globe, local_time::unix_secs());
+        }
+        if let Some(flights) = &mut self.flights {
+            flights.tick(dt);
+        }
+        if let Mode::Stream(stream) = &mut self.mode {
+            if let Some(latest) = stream.update() {
+                stream.apply_markers(&mut self.globe, LISTING_MARKER, LISTING_MARKER_COLOR);
+                self.controller.focus_on(&self.globe, latest);
+            }
+        }
+        if let (Some(interval), Mode::Listing { .. }) = (self.auto_advance, &self.mode) {
+            self.shown_for += dt;
+            if self.shown_for >= interval {
+                self.next_location();
+            }
+        }
+
+        #[cfg(feature = "mqtt")]
+        if let Some(feed) = &mut self.feed {
+            if feed.update() {
+                feed.apply_markers(&mut self.globe);
+            }
+        }
+    }
+
+    /// Renders the scene and outputs it using the given backend.
+    pub fn draw<B: Backend>(&mut self, backend: &mut B) {
+        let mut colors = self.colors;
+        let schedule = self.schedule.as_ref();
+        if let Some(phase) = schedule.and_then(|s| s.phase_at(local_time::local_hour())) {
+            colors.fg = Some(colors.fg.map_or(phase.day, |fg| fg.tint(phase.day)));
+            if self.cell_colors {
+                self.globe.set_coloring(Some(phase.tint(self.theme_colors)));
+            }
+        }
+        backend.set_color(colors);
+
+        let term_size = backend.size();
+        if let Some(start) = &self.wall {
+            if term_size != self.canvas_term_size {
+                self.canvas = wall::tile_canvas(term_size);
+                self.canvas_term_size = term_size;
+            }
+            let (grid, offset) = start.wall.placement();
+            self.globe.render_tile(&mut self.canvas, grid, offset);
+            backend.draw(&self.canvas);
+            return;
+        }
+
+        if term_size.0 < MIN_TERM_SIZE.0 || term_size.1 < MIN_TERM_SIZE.1 {
+            backend.draw(&too_small_canvas(term_size));
+            return;
+        }
+
+        if term_size != self.canvas_term_size {
+            let canvas = Canvas::for_terminal(term_size, CANVAS_PRESET);
+            self.canvas = if self.cell_colors {
+                canvas.with_colors()
+            } else {
+                canvas
+            };
+            // the initial size doesn't count as resizing
+            if self.canvas_term_size != (0, 0) {
+                self.resized_at = Some(Instant::now());
+            }
+            self.canvas_term_size = term_size;
+        }
+
+        // erase overlays in case the canvas is only partially rendered
+        for rect in self.overlays.drain(..) {
+            self.globe.render_region(&mut self.canvas, rect);
+        }
+
+        // render globe on the canvas
+        if let Some(resized_at) = self.resized_at {
+            if resized_at.elapsed() < RESIZE_SETTLE_TIME {
+                // keep frames cheap until the terminal settles on a size
+                self.draw_preview();
+            } else {
+                self.resized_at = None;
+                self.canvas.clear();
+                self.globe.render_on(&mut self.canvas);
+            }
+        } else if let Some(budget) = self.frame_budget {
+            // rows left out keep their content from the previous frame
+            let report = self.globe.render_on_with_budget(&mut self.canvas, budget);
+            self.report_overrun(&report);
+        } else if self.interlace {
+            self.globe.render_on_interlaced(&mut self.canvas);
+        } else {
+            self.canvas.clear();
+            self.globe.render_on(&mut self.canvas);
+        }
+        if self.moon {
+            self.draw_moon();
+        }
+        self.draw_iss();
+        self.draw_planes();
+        self.draw_pointer(term_size);
+        self.draw_selection();
+        if self.legend {
+            self.draw_legend();
+        }
+        self.draw_notice();
+        let mut lines = Vec::new();
+        if self.info {
+            lines.extend(self.info_lines());
+        }
+        if let Some(iss) = &self.iss {
+            lines.extend(iss.lines(local_time::unix_secs()));
+        }
+        if let Some(rect) = overlay::draw_panel(&mut self.canvas, &lines) {
+            self.overlays.push(rect);
+        }
+        if let Some(rect) = self.draw_tooltip(term_size) {
+            self.overlays.push(rect);
+        }
+
+        // print canvas to the output
+        backend.draw(&self.canvas);
+    }
+
+    /// Shows the label of the marker the mouse points at, returning the
+    /// cells covered by the tooltip.
+    fn draw_tooltip(&mut self, term_size: (u16, u16)) -> Option<CellRect> {
+        let cell = self.pointer_cell(term_size)?;
+        let marker = self.globe.marker_near(&self.canvas, cell, POINTER_REACH)?;
+        let label = marker.label.as_deref()?;
+        let marker_cell = self.globe.marker_cell(&self.canvas, marker)?;
+        overlay::draw_tooltip(&mut self.canvas, marker_cell, label)
+    }
+
+    /// Shows the coordinates of the location under the mouse pointer in the
+    /// status bar, where the selection and notices take its place.
+    fn draw_pointer(&mut self, term_size: (u16, u16)) {
+        let cell = match self.pointer_cell(term_size) {
+            Some(cell) => cell,
+            None => return,
+        };
+        if let Some((lat, lon)) = self.globe.unproject(&self.canvas, cell.0, cell.1) {
+            let status = format!("{:.2}, {:.2}", lat, lon);
+            if let Some(rect) = overlay::draw_status(&mut self.canvas, &status) {
+                self.overlays.push(rect);
+            }
+        }
+    }
+
+    /// Returns the canvas cell the mouse points at, if it's over the canvas.
+    fn pointer_cell(&self, term_size: (u16, u16)) -> Option<(usize, usize)> {
+        let pointer = self.pointer?;
+        let origin = backend::frame_origin(&self.canvas, &term_size);
+        Some((
+            pointer.0.checked_sub(origin.0)? as usize,
+            pointer.1.checked_sub(origin.1)? as usize,
+        ))
+    }
+
+    /// Highlights the selected marker and shows its details in the status
+    /// bar.
+    fn draw_selection(&mut self) {
+        let (index, marker) = match self.selected.zip(self.selected_marker()) {
+            Some((index, marker)) => (index, marker.clone()),
+            None => return,
+        };
+        if let Some(cell) = self.globe.marker_cell(&self.canvas, &marker) {
+            let rect = overlay::draw_highlight(&mut self.canvas, cell);
+            self.overlays.push(rect);
+        }
+
+        let (lat, lon) = marker.coord.lat_lon();
+        let mut status = format!("{}/{}", index + 1, self.globe.markers().len());
+        if let Some(label) = &marker.label {
+            status.push_str(&format!(" {}", label));
+        }
+        status.push_str(&format!(" {:.2}, {:.2}", lat, lon));
+        if let Some(rect) = overlay::draw_status(&mut self.canvas, &status) {
+            self.overlays.push(rect);
+        }
+    }
+
+    /// Marks where the moon is overhead and shows its phase in the top right
+    /// corner.
+    fn draw_moon(&mut self) {
+        let now = local_time::unix_secs();
+        self.draw_mark(moon::sublunar_point(now), MOON_MARKER, MOON_COLOR);
+
+        let phase = moon::phase(now);
+        let mut lines = moon::glyph(phase, MOON_ROWS);
+        lines.push(moon::phase_name(phase).to_string());
+        let lit = moon::illuminated_fraction(now) * 100.;
+        lines.push(format!("{:.0}% lit", lit));
+        if let Some(rect) = overlay::draw_panel_right(&mut self.canvas, &lines) {
+            self.overlays.push(rect);
+        }
+    }
+
+    /// Marks where the ISS currently is, if shown.
+    fn draw_iss(&mut self) {
+        if let Some(iss) = &self.iss {
+            let position = iss.position(local_time::unix_secs());
+            self.draw_mark(position, iss::ISS_MARKER, iss::ISS_MARKER_COLOR);
+        }
+    }
+
+    /// Draws the planes flying along the routes, pointing the way they're
+    /// heading.
+    fn draw_planes(&mut self) {
+        let planes = match &self.flights {
+            Some(flights) => flights.planes(),
+            None => return,
+        };
+        for (position, ahead) in planes {
+            let ahead = self.project(ahead);
+            if let Some(cell) = self.project(position) {
+                // planes about to go over the horizon keep their last look
+                let ch = ahead.map_or(flights::PLANE_CHAR, |ahead| {
+                    flights::plane_char(cell, ahead)
+                });
+                self.draw_mark(position, ch, flights::PLANE_COLOR);
+            }
+        }
+    }
+
+    /// Returns the canvas cell the location is drawn at, if it's on the
+    /// visible side of the globe.
+    fn project(&self, coord: GeoCoord) -> Option<(usize, usize)> {
+        let (lat, lon) = coord.lat_lon();
+        self.globe.project(lat, lon, &self.canvas)
+    }
+
+    /// Draws a character over the location if it's on the visible side of
+    /// the globe, for things moving too often to be kept as markers.
+    fn draw_mark(&mut self, coord: GeoCoord, ch: char, color: Color) {
+        if let Some((x, y)) = self.project(coord) {
+            let color = CellColor {
+                fg: Some(color),
+                bg: None,
+            };
+            self.canvas.draw_colored_point(x, y, ch, color);
+            self.overlays.push(CellRect::new(x, y, 1, 1));
+        }
+    }
+
+    /// Returns facts about the location in the center of the view: whether
+    /// the sun is up there, its antipode and how far it is from home.
+    fn info_lines(&self) -> Vec<String> {
+        let center = self.globe.view_center();
+        let (lat, lon) = center.lat_lon();
+        let elevation = sun::elevation(center, local_time::unix_secs());
+        let time = if elevation > 0. { "day" } else { "night" };
+        let (antipode_lat, antipode_lon) = center.antipode().lat_lon();
+        vec![
+            format!("center {:.2}, {:.2} {}", lat, lon, time),
+            format!("antipode {:.2}, {:.2}", antipode_lat, antipode_lon),
+            format!("home {:.0} km", center.distance_km(self.controller.home)),
+        ]
+    }
+
+    /// Shows the scale of the grid of values drawn on top, the series being
+    /// drawn over the other grids.
+    fn draw_legend(&mut self) {
+        let globe = &self.globe;
+        let series = globe.data_series().last().map(|series| series.grid());
+        let grid = match series.or_else(|| globe.data_grids().last()) {
+            Some(grid) => grid,
+            None => return,
+        };
+        if let Some(rect) = overlay::draw_legend(&mut self.canvas, grid) {
+            self.overlays.push(rect);
+        }
+    }
+
+    /// Tells in the status bar that frames don't fit into the time budget,
+    /// unless another notice is shown.
+    fn report_overrun(&mut self, report: &FrameReport) {
+        let overrun = match report.overrun {
+            Some(overrun) => overrun,
+            None => return,
+        };
+        if self
+            .notice
+            .as_ref()
+            .is_some_and(|(_, posted)| posted.elapsed() < NOTICE_TIME)
+        {
+            return;
+        }
+        let text = format!(
+            "over frame budget by {} ms, {} of {} rows rendered",
+            overrun.as_millis(),
+            report.rows_rendered,
+            report.rows_total
+        );
+        self.notice = Some((text, Instant::now()));
+    }
+
+    /// Shows the latest notice in the status bar, until it expires.
+    fn draw_notice(&mut self) {
+        let text = match &self.notice {
+            Some((text, posted)) if posted.elapsed() < NOTICE_TIME => text,
+            _ => {
+                self.notice = None;
+                return;
+            }
+        };
+        if let Some(rect) = overlay::draw_status(&mut self.canvas, text) {
+            self.overlays.push(rect);
+        }
+    }
+
+    /// Renders the globe at a reduced resolution and scales it up to fill
+    /// the canvas.
+    fn draw_preview(&mut self) {
+        let (width, height) = self.canvas.get_size();
+        let mut preview = Canvas::new(
+            (width / PREVIEW_SCALE) as u16,
+            (height / PREVIEW_SCALE) as u16,
+            Some(self.canvas.char_pix()),
+        );
+        self.globe.render_on(&mut preview);
+
+        self.canvas.clear();
+        let (cols, rows) = self.canvas.grid_size();
+        for row in 0..rows {
+            for col in 0..cols {
+                if let Some(ch) = preview.char_at(col / PREVIEW_SCALE, row / PREVIEW_SCALE) {
+                    self.canvas.draw_point(col, row, ch);
+                }
+            }
+        }
+    }
+}
+
+/// Creates a canvas fitting the terminal of the given size with the
+/// placeholder centered on it, cut off where it doesn't fit.
+fn too_small_canvas(term_size: (u16, u16)) -> Canvas {
+    let mut canvas = Canvas::for_terminal(term_size, CANVAS_PRESET);
+    let (cols, rows) = canvas.grid_size();
+    let len = TOO_SMALL_TEXT.len().min(cols);
+    let start = (cols - len) / 2;
+    for (i, ch) in TOO_SMALL_TEXT.chars().take(len).enumerate() {
+        canvas.draw_point(start + i, rows / 2, ch);
+    }
+    canvas
+}
+
+/// Returns true if the marker is in a group that's hidden.
+fn is_hidden(globe: &Globe, marker: &Marker) -> bool {
+    let group = marker.group.as_deref();
+    group.is_some_and(|name| !globe.marker_group(name).visible)
+}
+
+/// Translates terminal events into controller input.
+fn to_input(event: Event) -> Option<Input> {
+    match event {
+        Event::Key(event) => match event.code {
+            KeyCode::Char(c) => Some(Input::Char(c)),
+            KeyCode::Up => Some(Input::Up),
+            KeyCode::Down => Some(Input::Down),
+            KeyCode::Left => Some(Input::Left),
+            KeyCode::Right => Some(Input::Right),
+            KeyCode::PageUp => Some(Input::PageUp),
+            KeyCode::PageDown => Some(Input::PageDown),
+            KeyCode::Enter => Some(Input::Enter),
+            _ => None,
+        },
+        Event::Mouse(event) => match event.kind {
+            MouseEventKind::Drag(_) => Some(Input::Drag(event.column, event.row)),
+            MouseEventKind::ScrollUp => Some(Input::ScrollUp),
+            MouseEventKind::ScrollDown => Some(Input::ScrollDown),
+            // hovering only moves the pointer
+            MouseEventKind::Moved => None,
+            _ => Some(Input::Release),
+        },
+        _ => None,
+    }
+}
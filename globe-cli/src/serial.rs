@@ -0,0 +1,88 @@
+//! Setting up serial lines for driving terminals and character displays
+//! attached to them.
+
+use std::fs::File;
+use std::io;
+
+/// Bits sent over the line per byte written, with 8 data bits, 1 start and
+/// 1 stop bit (8N1).
+const BITS_PER_BYTE: u32 = 10;
+
+/// Returns the number of bytes per second that can be sent at the given baud
+/// rate.
+pub fn line_rate(baud: u32) -> u64 {
+    (baud / BITS_PER_BYTE) as u64
+}
+
+/// Switches the line to raw 8N1 at the given baud rate.
+///
+/// Raw mode turns off output post-processing, so line endings get to the
+/// device exactly as written.
+#[cfg(unix)]
+pub fn configure(device: &File, baud: u32) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    let speed = baud_speed(baud).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("unsupported baud rate: {}", baud),
+        )
+    })?;
+    let fd = device.as_raw_fd();
+    // SAFETY: the descriptor stays open for the duration of the calls and
+    // `termios` is fully initialized by `tcgetattr` before being used
+    unsafe {
+        let mut termios: libc::termios = std::mem::zeroed();
+        if libc::tcgetattr(fd, &mut termios) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        libc::cfmakeraw(&mut termios);
+        termios.c_cflag |= libc::CLOCAL | libc::CREAD;
+        if libc::cfsetispeed(&mut termios, speed) != 0
+            || libc::cfsetospeed(&mut termios, speed) != 0
+            || libc::tcsetattr(fd, libc::TCSANOW, &termios) != 0
+        {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+/// Leaves the line settings as they are, the baud rate is only used for
+/// pacing the output.
+#[cfg(not(unix))]
+pub fn configure(_device: &File, _baud: u32) -> io::Result<()> {
+    Ok(())
+}
+
+/// Waits until everything written to the device was transmitted.
+#[cfg(unix)]
+pub fn drain(device: &File) {
+    use std::os::unix::io::AsRawFd;
+
+    // SAFETY: the descriptor stays open for the duration of the call
+    unsafe {
+        libc::tcdrain(device.as_raw_fd());
+    }
+}
+
+#[cfg(not(unix))]
+pub fn drain(_device: &File) {}
+
+#[cfg(unix)]
+fn baud_speed(baud: u32) -> Option<libc::speed_t> {
+    let speed = match baud {
+        300 => libc::B300,
+        1200 => libc::B1200,
+        2400 => libc::B2400,
+        4800 => libc::B4800,
+        9600 => libc::B9600,
+        19200 => libc::B19200,
+        38400 => libc::B38400,
+        57600 => libc::B57600,
+        115200 => libc::B115200,
+        230400 => libc::B230400,
+        _ => return None,
+    };
+    Some(speed)
+}
@@ -0,0 +1,85 @@
+//! Locations streamed in while the globe is shown, e.g. from a live feed.
+
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+use globe::{Color, GeoCoord, Globe, Marker};
+
+use crate::parse_lat_lon;
+
+/// Number of the latest locations kept pinned, so that a feed running for
+/// days doesn't bury the globe in markers.
+const MAX_PINNED: usize = 100;
+
+/// Locations read as `lat,lon` lines, in degrees, on a background thread.
+///
+/// Lines that don't parse are skipped. Call `update` every frame to take in
+/// the locations read since and `apply_markers` to pin them.
+pub struct CoordStream {
+    updates: Receiver<GeoCoord>,
+    /// Latest locations, oldest first
+    recent: VecDeque<GeoCoord>,
+    /// Markers added to the globe by the last `apply_markers` call
+    placed: Vec<Marker>,
+}
+
+impl CoordStream {
+    /// Starts reading lines from the reader, e.g. stdin fed by `tail -f`,
+    /// until it runs out.
+    pub fn spawn(reader: impl Read + Send + 'static) -> Self {
+        let (sender, updates) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(reader).lines() {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(_) => break,
+                };
+                let coord = match parse_lat_lon(&line) {
+                    Some(coord) => coord,
+                    None => continue,
+                };
+                // the stream was dropped
+                if sender.send(coord).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            updates,
+            recent: VecDeque::new(),
+            placed: Vec::new(),
+        }
+    }
+
+    /// Takes in the locations read since the last call, returning the
+    /// latest one if any arrived.
+    pub fn update(&mut self) -> Option<GeoCoord> {
+        let mut latest = None;
+        while let Ok(coord) = self.updates.try_recv() {
+            if self.recent.len() == MAX_PINNED {
+                self.recent.pop_front();
+            }
+            self.recent.push_back(coord);
+            latest = Some(coord);
+        }
+        latest
+    }
+
+    /// Replaces the markers placed by the previous call with markers of the
+    /// latest locations, leaving other markers on the globe alone.
+    pub fn apply_markers(&mut self, globe: &mut Globe, ch: char, color: Color) {
+        let placed = &self.placed;
+        globe.retain_markers(|marker| !placed.contains(marker));
+        self.placed = self
+            .recent
+            .iter()
+            .map(|&coord| Marker::new(coord, ch).with_color(color))
+            .collect();
+        for marker in &self.placed {
+            globe.add_marker(marker.clone());
+        }
+    }
+}
@@ -0,0 +1,54 @@
+//! Showing one giant globe on a wall of terminals.
+//!
+//! Every terminal runs its own instance, told the size of the whole wall and
+//! where on it the terminal sits. Instances don't talk to each other, they
+//! stay in sync by deriving the animation from the system clock, which is
+//! expected to be synchronized across the machines driving the wall.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use globe::{Canvas, Int};
+
+use crate::CANVAS_PRESET;
+
+/// Placement of this terminal within the wall.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Wall {
+    /// Size of the whole wall in characters
+    pub size: (u16, u16),
+    /// Position of this terminal's top left character on the wall
+    pub offset: (u16, u16),
+}
+
+impl Wall {
+    /// Returns the character grid the globe is rendered into, the largest
+    /// square fitting the wall and centered on it, along with the position
+    /// of this terminal's top left character within the grid.
+    pub fn placement(&self) -> ((usize, usize), (Int, Int)) {
+        let (char_x, char_y) = CANVAS_PRESET.char_pix();
+        let side = (self.size.0 as usize * char_x).min(self.size.1 as usize * char_y);
+        let grid = (side / char_x, side / char_y);
+        let margin = |wall: u16, grid: usize| (wall as Int - grid as Int) / 2;
+        let offset = (
+            self.offset.0 as Int - margin(self.size.0, grid.0),
+            self.offset.1 as Int - margin(self.size.1, grid.1),
+        );
+        (grid, offset)
+    }
+}
+
+/// Creates a canvas covering the whole terminal of the given size, which
+/// shows its tile of the wall.
+pub fn tile_canvas(term_size: (u16, u16)) -> Canvas {
+    let (char_x, char_y) = CANVAS_PRESET.char_pix();
+    let width = (term_size.0 as usize * char_x).min(u16::MAX as usize) as u16;
+    let height = (term_size.1 as usize * char_y).min(u16::MAX as usize) as u16;
+    Canvas::with_preset(width, height, CANVAS_PRESET)
+}
+
+/// Returns the time on the shared clock, the time since the Unix epoch.
+pub fn shared_time() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
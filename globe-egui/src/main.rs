@@ -0,0 +1,47 @@
+//! Displays a spinning globe inside an egui window.
+//!
+//! Run with `cargo run -p globe-egui`.
+
+use eframe::egui;
+use globe::egui_widget::CanvasWidget;
+use globe::{CameraConfig, Canvas, Globe, GlobeConfig, GlobeTemplate};
+
+struct GlobeApp {
+    globe: Globe,
+    canvas: Canvas,
+}
+
+impl eframe::App for GlobeApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // rotate the globe a bit every frame
+        self.globe.set_angle(self.globe.angle() + 0.01);
+        let camera = self.globe.camera_mut();
+        let (alpha, beta) = (camera.alpha(), camera.beta());
+        camera.update(2., alpha - 0.01, beta);
+
+        self.canvas.clear();
+        self.globe.render_on(&mut self.canvas);
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.add(CanvasWidget::new(&self.canvas).font_size(8.));
+        });
+        ctx.request_repaint();
+    }
+}
+
+fn main() -> eframe::Result<()> {
+    let app = GlobeApp {
+        globe: GlobeConfig::new()
+            .use_template(GlobeTemplate::Earth)
+            .with_camera(CameraConfig::default())
+            .display_night(true)
+            .build()
+            .expect("earth template is valid"),
+        canvas: Canvas::new(320, 320, None).with_colors(),
+    };
+    eframe::run_native(
+        "globe",
+        eframe::NativeOptions::default(),
+        Box::new(|_cc| Ok(Box::new(app))),
+    )
+}
@@ -0,0 +1,166 @@
+//! Node.js bindings for the globe renderer.
+//!
+//! Exposes a `Globe` class for rendering frames repeatedly and a one-shot
+//! `render` function, both returning plain text frames:
+//!
+//! ```js
+//! const { render } = require('globe-node');
+//! console.log(render({ width: 60, height: 30, x: 0.4, y: 0.6 }));
+//! ```
+
+use globe::{
+    CameraConfig, Canvas, CanvasPreset, Float, GeoCoord, Globe, GlobeConfig, GlobeTemplate, Marker,
+    RenderStyle,
+};
+use napi::{Error, Result};
+use napi_derive::napi;
+
+/// Character used for markers when none is given.
+const DEFAULT_MARKER: char = 'X';
+
+/// Options for creating a globe, all of them optional.
+#[napi(object)]
+#[derive(Default)]
+pub struct GlobeOptions {
+    /// Frame width in characters, defaults to 80
+    pub width: Option<u32>,
+    /// Frame height in characters, defaults to 40
+    pub height: Option<u32>,
+    /// Normalized horizontal texture coordinate to focus on
+    pub x: Option<f64>,
+    /// Normalized vertical texture coordinate to focus on
+    pub y: Option<f64>,
+    /// Distance from the camera to the globe's center, defaults to 1.7
+    pub zoom: Option<f64>,
+    /// Rotation angle of the globe
+    pub angle: Option<f64>,
+    /// Display the night side of the globe
+    pub night: Option<bool>,
+    /// Render style: "ascii", "braille", "half-block" or "quadrant"
+    pub style: Option<String>,
+    /// Built-in globe template: "earth", "moon", "mars" or "saturn"
+    pub template: Option<String>,
+}
+
+/// Globe along with the canvas it gets rendered on.
+#[napi(js_name = "Globe")]
+pub struct JsGlobe {
+    globe: Globe,
+    canvas: Canvas,
+}
+
+#[napi]
+impl JsGlobe {
+    #[napi(constructor)]
+    pub fn new(options: Option<GlobeOptions>) -> Result<Self> {
+        let options = options.unwrap_or_default();
+        let style = parse_style(options.style.as_deref())?;
+        let template = parse_template(options.template.as_deref())?;
+        let mut globe = GlobeConfig::new()
+            .use_template(template)
+            .with_camera(CameraConfig::new(
+                options.zoom.unwrap_or(1.7) as Float,
+                0.,
+                0.,
+            ))
+            .display_night(options.night.unwrap_or(false))
+            .with_render_style(style)
+            .build()
+            .map_err(|e| Error::from_reason(e.to_string()))?;
+        globe.set_angle(options.angle.unwrap_or(0.) as Float);
+        if let (Some(x), Some(y)) = (options.x, options.y) {
+            globe.focus(GeoCoord::new(x as Float, y as Float));
+        }
+        let canvas = new_canvas(options.width.unwrap_or(80), options.height.unwrap_or(40));
+        Ok(Self { globe, canvas })
+    }
+
+    /// Orients the camera so that it focuses on the given location.
+    #[napi]
+    pub fn focus(&mut self, x: f64, y: f64) {
+        self.globe.focus(GeoCoord::new(x as Float, y as Float));
+    }
+
+    /// Rotates the globe by the given angle.
+    #[napi]
+    pub fn rotate(&mut self, angle: f64) {
+        self.globe.set_angle(self.globe.angle() + angle as Float);
+    }
+
+    /// Positions the camera using spherical coordinates.
+    #[napi]
+    pub fn set_camera(&mut self, radius: f64, alpha: f64, beta: f64) {
+        self.globe
+            .camera_mut()
+            .update(radius as Float, alpha as Float, beta as Float);
+    }
+
+    /// Pins a marker at the given location.
+    #[napi]
+    pub fn add_marker(&mut self, x: f64, y: f64, ch: Option<String>) {
+        let ch = ch.and_then(|s| s.chars().next()).unwrap_or(DEFAULT_MARKER);
+        self.globe
+            .add_marker(Marker::new(GeoCoord::new(x as Float, y as Float), ch));
+    }
+
+    /// Removes all markers.
+    #[napi]
+    pub fn clear_markers(&mut self) {
+        self.globe.clear_markers();
+    }
+
+    /// Changes the size of rendered frames.
+    #[napi]
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.canvas = new_canvas(width, height);
+    }
+
+    /// Renders the globe, returning the frame as lines of text, each ending
+    /// with a newline.
+    #[napi]
+    pub fn render(&mut self) -> String {
+        let mut frame = self.globe.render_to_string(&mut self.canvas);
+        frame.push('\n');
+        frame
+    }
+}
+
+/// Renders a single frame with the given options.
+#[napi]
+pub fn render(options: Option<GlobeOptions>) -> Result<String> {
+    Ok(JsGlobe::new(options)?.render())
+}
+
+fn new_canvas(width: u32, height: u32) -> Canvas {
+    let size = (
+        width.min(u16::MAX as u32) as u16,
+        height.min(u16::MAX as u32) as u16,
+    );
+    Canvas::for_terminal(size, CanvasPreset::default())
+}
+
+fn parse_template(template: Option<&str>) -> Result<GlobeTemplate> {
+    match template {
+        None | Some("earth") => Ok(GlobeTemplate::Earth),
+        Some("moon") => Ok(GlobeTemplate::Moon),
+        Some("mars") => Ok(GlobeTemplate::Mars),
+        Some("saturn") => Ok(GlobeTemplate::Saturn),
+        Some(other) => Err(Error::from_reason(format!(
+            "unknown globe template: {}",
+            other
+        ))),
+    }
+}
+
+fn parse_style(style: Option<&str>) -> Result<RenderStyle> {
+    match style {
+        None | Some("ascii") => Ok(RenderStyle::Ascii),
+        Some("braille") => Ok(RenderStyle::Braille),
+        Some("half-block") => Ok(RenderStyle::HalfBlock),
+        Some("quadrant") => Ok(RenderStyle::Quadrant),
+        Some(other) => Err(Error::from_reason(format!(
+            "unknown render style: {}",
+            other
+        ))),
+    }
+}
@@ -0,0 +1,162 @@
+//! WebAssembly bindings for the globe renderer.
+//!
+//! Exposes a `Globe` class for rendering frames repeatedly and a one-shot
+//! `renderFrame` function, both returning plain text frames, e.g. to be
+//! shown in a `<pre>` element:
+//!
+//! ```js
+//! import init, { Globe } from './pkg/globe_wasm.js';
+//!
+//! await init();
+//! const globe = new Globe(80, 40);
+//! document.querySelector('pre').textContent = globe.render();
+//! ```
+
+use globe::{
+    CameraConfig, Canvas, CanvasPreset, Float, GeoCoord, Globe, GlobeConfig, GlobeTemplate, Marker,
+    RenderStyle,
+};
+use wasm_bindgen::prelude::*;
+
+/// Character used for markers when none is given.
+const DEFAULT_MARKER: char = 'X';
+/// Distance from the camera to the globe's center unless set otherwise.
+const DEFAULT_ZOOM: Float = 1.7;
+
+/// Globe along with the canvas it gets rendered on.
+#[wasm_bindgen(js_name = "Globe")]
+pub struct WasmGlobe {
+    globe: Globe,
+    canvas: Canvas,
+}
+
+#[wasm_bindgen(js_class = "Globe")]
+impl WasmGlobe {
+    /// Creates a globe rendering frames of the given size in characters.
+    ///
+    /// `template` is one of "earth" (the default), "moon", "mars" or "saturn", `style`
+    /// one of "ascii" (the default), "braille", "half-block" or "quadrant".
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        width: u32,
+        height: u32,
+        template: Option<String>,
+        style: Option<String>,
+    ) -> Result<WasmGlobe, JsError> {
+        let globe = GlobeConfig::new()
+            .use_template(parse_template(template.as_deref())?)
+            .with_camera(CameraConfig::new(DEFAULT_ZOOM, 0., 0.))
+            .with_render_style(parse_style(style.as_deref())?)
+            .build()?;
+        Ok(Self {
+            globe,
+            canvas: new_canvas(width, height),
+        })
+    }
+
+    /// Orients the camera so that it focuses on the given location, in
+    /// normalized texture coordinates.
+    pub fn focus(&mut self, x: f64, y: f64) {
+        self.globe.focus(GeoCoord::new(x as Float, y as Float));
+    }
+
+    /// Points the camera at the given latitude and longitude, in degrees.
+    #[wasm_bindgen(js_name = "focusLatLon")]
+    pub fn focus_lat_lon(&mut self, lat: f64, lon: f64) {
+        self.globe.focus_lat_lon(lat as Float, lon as Float);
+    }
+
+    /// Rotates the globe by the given angle.
+    pub fn rotate(&mut self, angle: f64) {
+        self.globe.set_angle(self.globe.angle() + angle as Float);
+    }
+
+    /// Sets the rotation angle of the globe.
+    #[wasm_bindgen(js_name = "setAngle")]
+    pub fn set_angle(&mut self, angle: f64) {
+        self.globe.set_angle(angle as Float);
+    }
+
+    /// Positions the camera using spherical coordinates.
+    #[wasm_bindgen(js_name = "setCamera")]
+    pub fn set_camera(&mut self, radius: f64, alpha: f64, beta: f64) {
+        self.globe
+            .camera_mut()
+            .update(radius as Float, alpha as Float, beta as Float);
+    }
+
+    /// Enables displaying the night side of the globe.
+    #[wasm_bindgen(js_name = "setNight")]
+    pub fn set_night(&mut self, night: bool) {
+        self.globe.set_display_night(night);
+    }
+
+    /// Pins a marker at the given latitude and longitude, in degrees.
+    #[wasm_bindgen(js_name = "addMarker")]
+    pub fn add_marker(&mut self, lat: f64, lon: f64, ch: Option<String>) {
+        let ch = ch.and_then(|s| s.chars().next()).unwrap_or(DEFAULT_MARKER);
+        let coord = GeoCoord::from_lat_lon(lat as Float, lon as Float);
+        self.globe.add_marker(Marker::new(coord, ch));
+    }
+
+    /// Removes all markers.
+    #[wasm_bindgen(js_name = "clearMarkers")]
+    pub fn clear_markers(&mut self) {
+        self.globe.clear_markers();
+    }
+
+    /// Changes the size of rendered frames.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.canvas = new_canvas(width, height);
+    }
+
+    /// Renders the globe, returning the frame as lines of text.
+    pub fn render(&mut self) -> String {
+        self.globe.render_to_string(&mut self.canvas)
+    }
+}
+
+/// Renders a single frame of the Earth of the given size, the camera placed
+/// using spherical coordinates and the globe rotated by `angle`.
+#[wasm_bindgen(js_name = "renderFrame")]
+pub fn render_frame(
+    width: u32,
+    height: u32,
+    radius: f64,
+    alpha: f64,
+    beta: f64,
+    angle: f64,
+) -> Result<String, JsError> {
+    let mut globe = WasmGlobe::new(width, height, None, None)?;
+    globe.set_camera(radius, alpha, beta);
+    globe.set_angle(angle);
+    Ok(globe.render())
+}
+
+fn new_canvas(width: u32, height: u32) -> Canvas {
+    let size = (
+        width.min(u16::MAX as u32) as u16,
+        height.min(u16::MAX as u32) as u16,
+    );
+    Canvas::for_terminal(size, CanvasPreset::default())
+}
+
+fn parse_template(template: Option<&str>) -> Result<GlobeTemplate, JsError> {
+    match template {
+        None | Some("earth") => Ok(GlobeTemplate::Earth),
+        Some("moon") => Ok(GlobeTemplate::Moon),
+        Some("mars") => Ok(GlobeTemplate::Mars),
+        Some("saturn") => Ok(GlobeTemplate::Saturn),
+        Some(other) => Err(JsError::new(&format!("unknown globe template: {}", other))),
+    }
+}
+
+fn parse_style(style: Option<&str>) -> Result<RenderStyle, JsError> {
+    match style {
+        None | Some("ascii") => Ok(RenderStyle::Ascii),
+        Some("braille") => Ok(RenderStyle::Braille),
+        Some("half-block") => Ok(RenderStyle::HalfBlock),
+        Some("quadrant") => Ok(RenderStyle::Quadrant),
+        Some(other) => Err(JsError::new(&format!("unknown render style: {}", other))),
+    }
+}
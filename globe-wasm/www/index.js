@@ -0,0 +1,25 @@
+// Spins the globe inside a <pre> element. Build the bindings first with
+// `wasm-pack build --target web` in the globe-wasm directory.
+import init, { Globe } from '../pkg/globe_wasm.js';
+
+// globe rotation in radians per second
+const ROTATION_SPEED = 0.3;
+
+async function main() {
+  await init();
+  const screen = document.getElementById('globe');
+  const globe = new Globe(80, 40);
+  globe.focusLatLon(20, 0);
+  globe.addMarker(51.5, -0.1, 'L');
+
+  let last = performance.now();
+  function frame(now) {
+    globe.rotate(ROTATION_SPEED * (now - last) / 1000);
+    last = now;
+    screen.textContent = globe.render();
+    requestAnimationFrame(frame);
+  }
+  requestAnimationFrame(frame);
+}
+
+main();
@@ -0,0 +1,100 @@
+//! Rendering benchmarks, run with `cargo bench -p globe`.
+//!
+//! Uses plain timing loops to avoid pulling in a benchmarking framework.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use globe::{CameraConfig, Canvas, CanvasPreset, GlobeConfig, GlobeTemplate, Texture};
+
+/// Runs the closure repeatedly for about a second, printing the average time
+/// of a single run.
+fn bench<F: FnMut()>(name: &str, mut f: F) {
+    // warm up
+    f();
+    let start = Instant::now();
+    let mut runs = 0;
+    while start.elapsed() < Duration::from_secs(1) {
+        f();
+        runs += 1;
+    }
+    println!("{:<48} {:>10.1?}/iter", name, start.elapsed() / runs);
+}
+
+/// Index of characters missing from the palette, as in the library.
+const NO_INDEX: u8 = u8::MAX;
+
+/// Finds the character on the palette by scanning it, the way textures
+/// translated their characters before the lookup table, kept as a baseline.
+fn palette_index(target: char, palette: &[char]) -> u8 {
+    palette
+        .iter()
+        .position(|&ch| ch == target)
+        .filter(|&i| i < NO_INDEX as usize)
+        .map_or(NO_INDEX, |i| i as u8)
+}
+
+/// Translates the day and night layers the way `Texture::new` did before
+/// the lookup table.
+fn linear_search_indices(day: Vec<Vec<char>>, night: Vec<Vec<char>>, palette: &[char]) {
+    let day: Vec<char> = day.into_iter().flatten().collect();
+    let day_indices: Vec<u8> = day.iter().map(|&ch| palette_index(ch, palette)).collect();
+    let night: Vec<u8> = night
+        .into_iter()
+        .flat_map(|row| row.into_iter().map(|ch| palette_index(ch, palette)))
+        .collect();
+    black_box((day, day_indices, night));
+}
+
+fn main() {
+    for &night in &[false, true] {
+        let mut globe = GlobeConfig::new()
+            .use_template(GlobeTemplate::Earth)
+            .with_camera(CameraConfig::new(1.7, 0., 0.))
+            .display_night(night)
+            .build()
+            .expect("earth template is valid");
+        let mut canvas = Canvas::for_terminal((200, 100), CanvasPreset::default());
+        let name = if night {
+            "render 200x100, night"
+        } else {
+            "render 200x100, day"
+        };
+        bench(name, || {
+            globe.set_angle(globe.angle() + 0.01);
+            canvas.clear();
+            globe.render_on(black_box(&mut canvas));
+        });
+    }
+
+    let palettes = [
+        (
+            "convert 1024x512, ascii ramp",
+            " .'`^\",:;Il!i><~+_-?][}{1)(|\\/tfjrxnuvczXYUJCLQ0OZmwqpdbkhao*#MW&8%B@$",
+        ),
+        ("convert 1024x512, block chars", " .:-=+*#%@░▒▓█"),
+    ];
+    // each palette is converted with the lookup table and, for comparison,
+    // with the linear search it replaced
+    for &(name, palette) in &palettes {
+        let palette: Vec<char> = palette.chars().collect();
+        let rows: Vec<Vec<char>> = (0..512)
+            .map(|y| {
+                (0..1024)
+                    .map(|x| palette[(x * 7 + y * 13) % palette.len()])
+                    .collect()
+            })
+            .collect();
+        bench(&format!("{}, lookup table", name), || {
+            let texture = Texture::new(
+                black_box(rows.clone()),
+                Some(rows.clone()),
+                Some(palette.clone()),
+            );
+            black_box(texture);
+        });
+        bench(&format!("{}, linear search", name), || {
+            linear_search_indices(black_box(rows.clone()), rows.clone(), &palette);
+        });
+    }
+}
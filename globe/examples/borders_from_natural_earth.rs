@@ -0,0 +1,41 @@
+//! Generates `data/borders.txt` from the Natural Earth 1:110m "admin 0
+//! boundary lines land" layer, downloaded as GeoJSON from
+//! https://github.com/nvkelso/natural-earth-vector/blob/master/geojson/ne_110m_admin_0_boundary_lines_land.geojson
+//!
+//! cargo run --example borders_from_natural_earth --features geojson -- \
+//!     ne_110m_admin_0_boundary_lines_land.geojson > data/borders.txt
+
+use std::{env, fs, process};
+
+use globe::{Float, LineLayer};
+
+/// Degrees corners may lie off the simplified borders, about a fifth of the
+/// width of the built-in Earth texture's cells.
+const TOLERANCE: Float = 0.25;
+
+fn main() -> globe::Result<()> {
+    let path = env::args().nth(1).unwrap_or_else(|| {
+        eprintln!("usage: borders_from_natural_earth <boundary lines geojson>");
+        process::exit(2);
+    });
+    let text = fs::read_to_string(&path)?;
+    let borders = LineLayer::from_geojson(&text)?.simplified(TOLERANCE);
+
+    println!("# Main land borders between countries from Natural Earth, 1:110m admin 0");
+    println!("# boundary lines (public domain, naturalearthdata.com), simplified to");
+    println!(
+        "# {} degrees. Generated with examples/borders_from_natural_earth.rs,",
+        TOLERANCE
+    );
+    println!("# don't edit by hand.");
+    println!("#");
+    println!("# One lat,lon corner per line in degrees, blank lines separating the borders.");
+    for line in borders.lines() {
+        println!();
+        for corner in line {
+            let (lat, lon) = corner.lat_lon();
+            println!("{:.2},{:.2}", lat, lon);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,50 @@
+//! Locations of major airports looked up by their IATA codes, e.g. for
+//! drawing flight routes.
+//!
+//! The table bundled with the library only holds the busiest airports of
+//! each continent, rounded to about a hundred meters.
+
+use crate::{Float, GeoCoord};
+
+/// Table of the bundled airports, one `code,lat,lon,name` line each.
+const AIRPORTS: &str = include_str!("../data/airports.txt");
+
+/// Airport found in the bundled table.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Airport {
+    /// Three letter IATA code, in upper case
+    pub code: &'static str,
+    /// Name of the airport, usually that of the city it serves
+    pub name: &'static str,
+    /// Location of the airport
+    pub coord: GeoCoord,
+}
+
+/// Returns the airport with the given IATA code, in any case, or `None` if
+/// it's not in the table.
+pub fn find(code: &str) -> Option<Airport> {
+    let code = code.trim();
+    all().find(|airport| airport.code.eq_ignore_ascii_case(code))
+}
+
+/// Returns all the airports in the table.
+pub fn all() -> impl Iterator<Item = Airport> {
+    AIRPORTS
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| parse_line(line).expect("bundled airports are well formed"))
+}
+
+fn parse_line(line: &'static str) -> Option<Airport> {
+    let mut fields = line.splitn(4, ',');
+    let code = fields.next()?;
+    let lat: Float = fields.next()?.parse().ok()?;
+    let lon: Float = fields.next()?.parse().ok()?;
+    let name = fields.next()?;
+    Some(Airport {
+        code,
+        name,
+        coord: GeoCoord::from_lat_lon(lat, lon),
+    })
+}
@@ -0,0 +1,159 @@
+//! Time based camera animations.
+
+use core::time::Duration;
+
+use crate::focus::{focus_angles, shortest_angle};
+use crate::{clamp, Camera, Float, GeoCoord, Globe};
+
+/// Curve shaping the progress of an animation over time.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Easing {
+    /// Constant speed.
+    #[default]
+    Linear,
+    /// Starts slow and speeds up.
+    EaseIn,
+    /// Starts fast and slows down.
+    EaseOut,
+    /// Starts and ends slow.
+    EaseInOut,
+}
+
+impl Easing {
+    /// Maps linear progress in the `0..=1` range onto the curve.
+    pub fn apply(self, t: Float) -> Float {
+        let t = clamp(t, 0., 1.);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1. - (1. - t) * (1. - t) * (1. - t),
+            Easing::EaseInOut => t * t * (3. - 2. * t),
+        }
+    }
+}
+
+/// Animation moving the camera between two positions over a fixed duration.
+///
+/// Created with `Camera::transition_to`, advanced with `Transition::step`
+/// every frame. Camera angle along the xy plane always takes the shorter way
+/// around the globe.
+#[derive(Clone, Debug)]
+pub struct Transition {
+    from: (Float, Float, Float),
+    to: (Float, Float, Float),
+    duration: Duration,
+    elapsed: Duration,
+    easing: Easing,
+    /// Location the camera ends up focused on, followed by `step_on`
+    location: Option<GeoCoord>,
+}
+
+impl Transition {
+    /// Sets the easing curve of the transition.
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Returns the progress of the transition in the `0..=1` range, before
+    /// easing is applied.
+    pub fn progress(&self) -> Float {
+        if self.duration.is_zero() {
+            return 1.;
+        }
+        clamp(
+            (self.elapsed.as_secs_f64() / self.duration.as_secs_f64()) as Float,
+            0.,
+            1.,
+        )
+    }
+
+    /// Returns true if the camera has reached the target position.
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    /// Advances the transition by the given amount of time and moves the
+    /// camera accordingly. Returns true once the transition is finished.
+    pub fn step(&mut self, camera: &mut Camera, dt: Duration) -> bool {
+        self.elapsed = (self.elapsed + dt).min(self.duration);
+        let t = self.easing.apply(self.progress());
+
+        let (radius, alpha, beta) = self.from;
+        let (to_radius, to_alpha, to_beta) = self.to;
+        camera.update(
+            radius + (to_radius - radius) * t,
+            alpha + shortest_angle(to_alpha - alpha) * t,
+            beta + (to_beta - beta) * t,
+        );
+
+        self.is_finished()
+    }
+
+    /// Advances the transition by the given amount of time and moves the
+    /// globe's camera accordingly, see `Transition::step`. Transitions
+    /// towards a location keep up with the globe spinning meanwhile, moving
+    /// over its surface as if it stood still and landing right on the
+    /// location.
+    pub fn step_on(&mut self, globe: &mut Globe, dt: Duration) -> bool {
+        let angle = globe.angle();
+        self.step_following(globe.camera_mut(), angle, dt)
+    }
+
+    /// Advances the transition like `step_on`, for the camera of a globe
+    /// rotated by `globe_angle`.
+    pub(crate) fn step_following(
+        &mut self,
+        camera: &mut Camera,
+        globe_angle: Float,
+        dt: Duration,
+    ) -> bool {
+        if let Some(coord) = self.location {
+            let (alpha, beta) = focus_angles(coord, globe_angle, camera.tilt);
+            // the starting point turns along with the globe
+            self.from.1 += shortest_angle(alpha - self.to.1);
+            self.to = (self.to.0, alpha, beta);
+        }
+        self.step(camera, dt)
+    }
+}
+
+impl Camera {
+    /// Creates a transition from the current camera position to the given
+    /// distance from the origin and angles.
+    pub fn transition_to(
+        &self,
+        radius: Float,
+        alpha: Float,
+        beta: Float,
+        duration: Duration,
+    ) -> Transition {
+        Transition {
+            from: (self.radius, self.alpha, self.beta),
+            to: (radius, alpha, beta),
+            duration,
+            elapsed: Duration::ZERO,
+            easing: Easing::default(),
+            location: None,
+        }
+    }
+
+    /// Creates a transition focusing the camera on the given location,
+    /// keeping its distance from the origin.
+    ///
+    /// `globe_angle` is the rotation angle of the globe. If it keeps
+    /// spinning during the transition, the target only follows it when the
+    /// transition is advanced with `Transition::step_on`.
+    pub fn transition_to_location(
+        &self,
+        coord: GeoCoord,
+        globe_angle: Float,
+        duration: Duration,
+    ) -> Transition {
+        let (alpha, beta) = focus_angles(coord, globe_angle, self.tilt);
+        Transition {
+            location: Some(coord),
+            ..self.transition_to(self.radius, alpha, beta, duration)
+        }
+    }
+}
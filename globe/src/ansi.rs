@@ -0,0 +1,70 @@
+//! Canvas text with the colors of its cells embedded as ANSI escape
+//! sequences, for writing to terminals or logs without a terminal library.
+
+use alloc::string::String;
+use core::fmt::Write;
+
+use crate::{Canvas, CellColor, Color};
+
+/// Sequence resetting the colors back to the terminal's own.
+const RESET: &str = "\x1b[0m";
+
+impl Canvas {
+    /// Returns the frame as text, rows separated by newlines, with the
+    /// colors of the cells set by 24-bit ANSI escape sequences.
+    ///
+    /// Sequences are only written where the colors change, and the colors
+    /// are reset at the end of every row, so the rows can be printed or
+    /// logged on their own too. Canvases without colors give the same text
+    /// as `to_string`.
+    pub fn to_ansi_string(&self) -> String {
+        let (cols, rows) = self.grid_size();
+        let mut out = String::with_capacity(cols * rows + rows);
+        for row in 0..rows {
+            if row > 0 {
+                out.push('\n');
+            }
+            let mut current = CellColor::default();
+            for col in 0..cols {
+                let cell = match self.cell(col, row) {
+                    Some(cell) => cell,
+                    None => continue,
+                };
+                let color = CellColor {
+                    fg: cell.fg,
+                    bg: cell.bg,
+                };
+                if color != current {
+                    push_colors(&mut out, current, color);
+                    current = color;
+                }
+                out.push(cell.ch);
+            }
+            if current != CellColor::default() {
+                out.push_str(RESET);
+            }
+        }
+        out
+    }
+}
+
+/// Writes the sequences switching from one pair of colors to another.
+fn push_colors(out: &mut String, mut from: CellColor, to: CellColor) {
+    // a single color can't be dropped, only both at once
+    if (from.fg.is_some() && to.fg.is_none()) || (from.bg.is_some() && to.bg.is_none()) {
+        out.push_str(RESET);
+        from = CellColor::default();
+    }
+    if let Some(fg) = to.fg.filter(|&fg| from.fg != Some(fg)) {
+        push_color(out, 38, fg);
+    }
+    if let Some(bg) = to.bg.filter(|&bg| from.bg != Some(bg)) {
+        push_color(out, 48, bg);
+    }
+}
+
+/// Writes the sequence setting the foreground (`38`) or background (`48`)
+/// color.
+fn push_color(out: &mut String, layer: u8, color: Color) {
+    let _ = write!(out, "\x1b[{};2;{};{};{}m", layer, color.r, color.g, color.b);
+}
@@ -0,0 +1,173 @@
+//! Great circle arcs drawn between two locations on the surface, along with
+//! the drawing of paths over the surface they share with other layers.
+
+use alloc::vec::Vec;
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::float::FloatExt;
+use crate::{CellColor, CellRect, Color, Float, GeoCoord, Globe, RenderTarget};
+
+/// Character arcs are drawn with unless set otherwise.
+const DEFAULT_CHAR: char = '+';
+
+/// Longest part of a path, in degrees, checked for visibility as a whole
+/// before being split further.
+const MAX_PIECE: Float = 10.;
+
+/// How many times a piece of a path is split in half at most, while looking
+/// for the cells between its ends.
+const MAX_SPLITS: u32 = 8;
+
+/// Shortest path between two locations on the surface, e.g. the route of a
+/// flight, drawn as a line of characters on the visible side of the globe.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GreatArc {
+    /// Location the arc starts at
+    pub from: GeoCoord,
+    /// Location the arc ends at
+    pub to: GeoCoord,
+    /// Character the arc is drawn with
+    pub ch: char,
+    /// Color the arc is drawn in, the globe's own if `None`
+    pub color: Option<Color>,
+}
+
+impl GreatArc {
+    /// Creates an arc between the two locations, drawn with `+`.
+    ///
+    /// The path between antipodes isn't defined, so the arc between them
+    /// stays at its start.
+    pub fn new(from: GeoCoord, to: GeoCoord) -> Self {
+        Self {
+            from,
+            to,
+            ch: DEFAULT_CHAR,
+            color: None,
+        }
+    }
+
+    /// Sets the character the arc is drawn with.
+    pub fn with_char(mut self, ch: char) -> Self {
+        self.ch = ch;
+        self
+    }
+
+    /// Sets the color the arc is drawn in on canvases with colors enabled.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Returns the location found at the given fraction of the way along
+    /// the arc, `0.` being its start and `1.` its end.
+    pub fn point_at(&self, fraction: Float) -> GeoCoord {
+        let angle = self.from.angle_to(self.to);
+        let sin = angle.sin();
+        if sin.abs() < Float::EPSILON {
+            return self.from;
+        }
+        let (a, b) = (self.from.unit_vector(), self.to.unit_vector());
+        let weight_a = ((1. - fraction) * angle).sin() / sin;
+        let weight_b = (fraction * angle).sin() / sin;
+        GeoCoord::from_unit_vector([0, 1, 2].map(|i| a[i] * weight_a + b[i] * weight_b))
+    }
+}
+
+impl Globe {
+    /// Adds a great circle arc to be drawn over the surface.
+    pub fn add_arc(&mut self, arc: GreatArc) {
+        self.arcs.push(arc);
+    }
+
+    /// Returns the arcs drawn over the surface.
+    pub fn arcs(&self) -> &[GreatArc] {
+        &self.arcs
+    }
+
+    /// Removes all the arcs.
+    pub fn clear_arcs(&mut self) {
+        self.arcs.clear();
+    }
+
+    /// Draws the parts of the arcs found on the visible side of the globe,
+    /// skipping the cells outside of `rect` if given.
+    pub(crate) fn render_arcs_within<S: RenderTarget>(
+        &self,
+        canvas: &mut S,
+        rect: Option<CellRect>,
+    ) {
+        for arc in &self.arcs {
+            let length = arc.from.angle_to(arc.to).to_degrees();
+            let path = |fraction| arc.point_at(fraction);
+            self.render_path_within(canvas, rect, path, length, arc.ch, arc.color);
+        }
+    }
+
+    /// Draws the parts of a path found on the visible side of the globe as a
+    /// line of characters, skipping the cells outside of `rect` if given.
+    ///
+    /// The path is given as the location found at any fraction of the way
+    /// along it, along with its length in degrees as seen from the center.
+    pub(crate) fn render_path_within<S: RenderTarget>(
+        &self,
+        canvas: &mut S,
+        rect: Option<CellRect>,
+        path: impl Fn(Float) -> GeoCoord,
+        length: Float,
+        ch: char,
+        color: Option<Color>,
+    ) {
+        let pieces = ((length / MAX_PIECE).ceil() as usize).max(1);
+        let mut cells = Vec::new();
+        let mut start = (0., self.coord_cell(canvas, path(0.)));
+        cells.extend(start.1);
+        for piece in 1..=pieces {
+            let fraction = piece as Float / pieces as Float;
+            let end = (fraction, self.coord_cell(canvas, path(fraction)));
+            cells.extend(end.1);
+            self.fill_path_piece(canvas, &path, start, end, MAX_SPLITS, &mut cells);
+            start = end;
+        }
+
+        let color = CellColor {
+            fg: color,
+            bg: None,
+        };
+        for &(x, y) in &cells {
+            if rect.is_some_and(|rect| !rect.contains(x, y)) {
+                continue;
+            }
+            canvas.draw_colored_point(x, y, ch, color);
+        }
+    }
+
+    /// Collects the cells between two points of the path, given as fractions
+    /// of the way along it along with the cells they're drawn at, splitting
+    /// the piece in half until its ends are next to each other.
+    fn fill_path_piece<S: RenderTarget>(
+        &self,
+        canvas: &S,
+        path: &impl Fn(Float) -> GeoCoord,
+        start: (Float, Option<(usize, usize)>),
+        end: (Float, Option<(usize, usize)>),
+        splits: u32,
+        cells: &mut Vec<(usize, usize)>,
+    ) {
+        let apart = match (start.1, end.1) {
+            (Some(a), Some(b)) => a.0.abs_diff(b.0).max(a.1.abs_diff(b.1)) > 1,
+            // the piece crosses the edge of the globe or of the view
+            (Some(_), None) | (None, Some(_)) => true,
+            // the middle might still show where the ends are hidden, but
+            // pieces are kept short enough for it not to matter
+            (None, None) => false,
+        };
+        if !apart || splits == 0 {
+            return;
+        }
+        let fraction = (start.0 + end.0) / 2.;
+        let middle = (fraction, self.coord_cell(canvas, path(fraction)));
+        cells.extend(middle.1);
+        self.fill_path_piece(canvas, path, start, middle, splits - 1, cells);
+        self.fill_path_piece(canvas, path, middle, end, splits - 1, cells);
+    }
+}
@@ -0,0 +1,116 @@
+//! Glow of the atmosphere around the edge of the globe.
+
+use alloc::vec::Vec;
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::float::FloatExt;
+use crate::math::Real;
+use crate::render::Sample;
+use crate::{dot, normalize, Color, Float, Globe};
+
+/// Halo drawn just outside the globe's silhouette, fading out with the
+/// distance from its surface.
+///
+/// Rays passing the globe within `width` of its surface are drawn with the
+/// `ramp` characters, the brightest ones hugging the surface. With the night
+/// side displayed, only the lit side of the globe glows. Render styles made
+/// of dots or blocks draw the halo like lit parts of the globe, set apart by
+/// its color only.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Atmosphere {
+    /// Thickness of the halo, relative to the radius of the globe
+    pub width: Float,
+    /// Characters the halo is drawn with, from its faint outer edge inwards
+    pub ramp: Vec<char>,
+    /// Color of the halo, fading towards black at its outer edge
+    pub color: Color,
+}
+
+impl Default for Atmosphere {
+    /// Thin pale blue halo.
+    fn default() -> Self {
+        Self {
+            width: 0.08,
+            ramp: alloc::vec!['.', ':', ';'],
+            color: Color::new(140, 190, 255),
+        }
+    }
+}
+
+impl Atmosphere {
+    /// Creates a halo of the given thickness relative to the radius of the
+    /// globe, drawn with the default characters and color.
+    pub fn new(width: Float) -> Self {
+        Self {
+            width,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the characters the halo is drawn with, from its faint outer edge
+    /// inwards.
+    pub fn with_ramp(mut self, ramp: &[char]) -> Self {
+        self.ramp = ramp.to_vec();
+        self
+    }
+
+    /// Sets the color of the halo.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+}
+
+impl Globe {
+    /// Returns the glow drawn around the globe, if enabled.
+    pub fn atmosphere(&self) -> Option<&Atmosphere> {
+        self.atmosphere.as_ref()
+    }
+
+    /// Enables or disables the glow drawn around the globe.
+    pub fn set_atmosphere(&mut self, atmosphere: Option<Atmosphere>) {
+        self.atmosphere = atmosphere;
+    }
+
+    /// Samples the atmosphere along a ray going in the direction of the unit
+    /// vector `u` that missed the globe, `None` if it passes outside the
+    /// halo or through its unlit part.
+    pub(crate) fn sample_atmosphere<R: Real>(&self, u: [R; 3]) -> Option<Sample> {
+        let atmosphere = self.atmosphere.as_ref()?;
+        let r = R::from_float;
+        let o = [r(self.camera.x), r(self.camera.y), r(self.camera.z)];
+        let dot_uo = dot(&u, &o);
+        // the ray points away from the globe
+        if dot_uo >= R::ZERO {
+            return None;
+        }
+
+        // distance between the center and the point the ray passes closest
+        let distance = (dot(&o, &o) - dot_uo * dot_uo).sqrt().to_float();
+        let height = (distance - self.radius) / (self.radius * atmosphere.width);
+        if !(0. ..1.).contains(&height) {
+            return None;
+        }
+        let mut strength = 1. - height;
+
+        if self.display_night {
+            let closest = [0, 1, 2].map(|axis| o[axis] - dot_uo * u[axis]);
+            let mut n = closest;
+            normalize(&mut n);
+            let l = self.light_vector(closest);
+            strength *= self.luminance(n, l).to_float();
+        }
+
+        let steps = atmosphere.ramp.len();
+        let step = (strength * steps as Float).ceil() as usize;
+        if step == 0 {
+            return None;
+        }
+        Some(Sample {
+            ch: atmosphere.ramp[step.min(steps) - 1],
+            index: None,
+            intensity: strength,
+            color: Some(atmosphere.color.scale(strength)),
+        })
+    }
+}
@@ -0,0 +1,110 @@
+//! Integration with the [Bevy](https://bevyengine.org) game engine.
+//!
+//! Enabled with the `bevy` feature. `GlobePlugin` renders the `GlobeScene`
+//! resource every update, using the `GlobeCamera` component to position the
+//! camera and entities with the `GlobeMarker` component as markers, and
+//! stores the result as text in the `GlobeFrame` resource.
+
+use bevy_app::{App, Plugin, Update};
+use bevy_ecs::prelude::*;
+
+use crate::{Canvas, CanvasPreset, Float, Globe, GlobeConfig, GlobeTemplate, Marker};
+
+/// Plugin rendering the globe scene into a text frame every update.
+pub struct GlobePlugin {
+    /// Size of the rendered frame in characters
+    pub frame_size: (u16, u16),
+    /// Character cell preset used for the canvas
+    pub preset: CanvasPreset,
+}
+
+impl Default for GlobePlugin {
+    fn default() -> Self {
+        Self {
+            frame_size: (80, 40),
+            preset: CanvasPreset::default(),
+        }
+    }
+}
+
+impl Plugin for GlobePlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world().contains_resource::<GlobeScene>() {
+            let globe = GlobeConfig::new()
+                .use_template(GlobeTemplate::Earth)
+                .build()
+                .expect("earth template is valid");
+            app.insert_resource(GlobeScene(globe));
+        }
+        app.insert_resource(GlobeCanvas(Canvas::for_terminal(
+            self.frame_size,
+            self.preset,
+        )))
+        .init_resource::<GlobeFrame>()
+        .add_systems(Update, render_globe);
+    }
+}
+
+/// Globe rendered by the plugin. Inserted with the Earth template if not
+/// present when the plugin is added.
+#[derive(Resource)]
+pub struct GlobeScene(pub Globe);
+
+/// Canvas the globe is rendered onto.
+#[derive(Resource)]
+pub struct GlobeCanvas(pub Canvas);
+
+/// Most recently rendered frame, one line of text per canvas row, each
+/// ending with a newline.
+#[derive(Resource, Default)]
+pub struct GlobeFrame(pub String);
+
+/// Camera looking at the globe. The first entity with this component
+/// positions the globe's camera.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct GlobeCamera {
+    /// Distance from the camera to the origin
+    pub radius: Float,
+    /// Camera's angle along the xy plane
+    pub alpha: Float,
+    /// Camera's angle along z axis
+    pub beta: Float,
+}
+
+impl Default for GlobeCamera {
+    fn default() -> Self {
+        Self {
+            radius: 2.,
+            alpha: 0.,
+            beta: 0.,
+        }
+    }
+}
+
+/// Marker displayed on the globe surface. Markers of the `GlobeScene` are
+/// replaced with the ones found on entities every update.
+#[derive(Component, Clone, Debug)]
+pub struct GlobeMarker(pub Marker);
+
+/// Renders the globe scene and stores the result in the `GlobeFrame`.
+fn render_globe(
+    mut scene: ResMut<GlobeScene>,
+    mut canvas: ResMut<GlobeCanvas>,
+    mut frame: ResMut<GlobeFrame>,
+    cameras: Query<&GlobeCamera>,
+    markers: Query<&GlobeMarker>,
+) {
+    let globe = &mut scene.0;
+    if let Some(camera) = cameras.iter().next() {
+        globe
+            .camera_mut()
+            .update(camera.radius, camera.alpha, camera.beta);
+    }
+    globe.clear_markers();
+    for marker in &markers {
+        globe.add_marker(marker.0.clone());
+    }
+
+    frame.0 = globe.render_to_string(&mut canvas.0);
+    frame.0.push('\n');
+}
@@ -0,0 +1,64 @@
+//! Rendering frames within a time budget.
+
+use std::time::{Duration, Instant};
+
+use crate::{Canvas, Globe};
+
+/// Outcome of rendering a frame within a time budget.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrameReport {
+    /// Number of canvas rows rendered this frame
+    pub rows_rendered: usize,
+    /// Number of canvas rows
+    pub rows_total: usize,
+    /// Time spent rendering
+    pub elapsed: Duration,
+    /// Time spent over the budget, if it was exceeded
+    pub overrun: Option<Duration>,
+}
+
+impl FrameReport {
+    /// Returns true if every row was rendered this frame.
+    pub fn is_complete(&self) -> bool {
+        self.rows_rendered == self.rows_total
+    }
+}
+
+impl Globe {
+    /// Renders the globe onto the canvas, stopping once the time budget is
+    /// exceeded.
+    ///
+    /// Rows that didn't get rendered keep the previous frame's content, so
+    /// the canvas shouldn't be cleared beforehand, apart from markers and
+    /// other overlays, which are erased from them and drawn where they are
+    /// now. The next frame continues from the first row left out, so every
+    /// row gets refreshed eventually. At least one row is rendered every
+    /// call.
+    pub fn render_on_with_budget(&self, canvas: &mut Canvas, budget: Duration) -> FrameReport {
+        let start = Instant::now();
+        let (_, rows) = canvas.grid_size();
+        let first = canvas.resume_row % rows.max(1);
+
+        let mut rendered = 0;
+        while rendered < rows {
+            let row = (first + rendered) % rows;
+            canvas.clear_row(row);
+            self.render_row(canvas, row);
+            rendered += 1;
+            if start.elapsed() > budget {
+                break;
+            }
+        }
+        canvas.resume_row = (first + rendered) % rows.max(1);
+        self.erase_stale_overlays(canvas, |row| (row + rows - first) % rows >= rendered);
+        self.render_markers(canvas);
+
+        let elapsed = start.elapsed();
+        FrameReport {
+            rows_rendered: rendered,
+            rows_total: rows,
+            elapsed,
+            overrun: elapsed.checked_sub(budget).filter(|d| !d.is_zero()),
+        }
+    }
+}
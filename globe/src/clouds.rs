@@ -0,0 +1,138 @@
+//! Layer of clouds drifting over the globe.
+
+use alloc::vec::Vec;
+
+use crate::error::{Error, Result};
+use crate::math::Real;
+use crate::render::Sample;
+use crate::{normalize, texture_coords, Color, Float, Globe, Layer, WrapMode};
+
+/// Color of clouds in full light.
+const CLOUD_COLOR: Color = Color::new(235, 235, 235);
+/// Light below which clouds on the night side aren't drawn, letting the
+/// lights of the night texture show through.
+const MIN_CLOUD_LIGHT: Float = 0.3;
+
+/// Texture of clouds wrapped around the globe on a shell slightly above its
+/// surface, turning at its own speed.
+///
+/// Cells of the texture holding the transparent character let the globe
+/// show through. The texture doesn't have to match the size of the globe's
+/// own one.
+#[derive(Clone, Debug)]
+pub struct Clouds {
+    texture: Layer<char>,
+    transparent: char,
+    /// Height of the shell above the surface, relative to the radius of the
+    /// globe
+    pub altitude: Float,
+    /// Rotation of the clouds relative to the surface
+    pub angle: Float,
+    /// Rotation speed relative to the surface, in movement per frame, see
+    /// `Globe::advance_clouds`
+    pub speed: Float,
+}
+
+impl Clouds {
+    /// Creates clouds from the given texture, laid out like the day texture
+    /// of the globe, with blanks being transparent.
+    ///
+    /// Fails if the texture is empty or its rows differ in length.
+    pub fn new(texture: &str) -> Result<Self> {
+        let rows: Vec<Vec<char>> = texture
+            .lines()
+            .map(|line| line.chars().rev().collect())
+            .collect();
+        let texture = Layer::from_rows(rows);
+        if texture.width == 0 {
+            return Err(Error::EmptyTexture);
+        }
+        if texture.uneven {
+            return Err(Error::UnevenTexture);
+        }
+        Ok(Self {
+            texture,
+            transparent: ' ',
+            altitude: 0.02,
+            angle: 0.,
+            speed: 0.,
+        })
+    }
+
+    /// Sets the character letting the globe show through.
+    pub fn with_transparent(mut self, ch: char) -> Self {
+        self.transparent = ch;
+        self
+    }
+
+    /// Sets the height of the shell above the surface, relative to the radius
+    /// of the globe.
+    pub fn with_altitude(mut self, altitude: Float) -> Self {
+        self.altitude = altitude;
+        self
+    }
+
+    /// Sets the rotation speed relative to the surface, in movement per
+    /// frame.
+    pub fn with_speed(mut self, speed: Float) -> Self {
+        self.speed = speed;
+        self
+    }
+}
+
+impl Globe {
+    /// Returns the clouds drifting over the globe, if any.
+    pub fn clouds(&self) -> Option<&Clouds> {
+        self.clouds.as_ref()
+    }
+
+    /// Returns the clouds drifting over the globe for changing them.
+    pub fn clouds_mut(&mut self) -> Option<&mut Clouds> {
+        self.clouds.as_mut()
+    }
+
+    /// Sets or removes the clouds drifting over the globe.
+    pub fn set_clouds(&mut self, clouds: Option<Clouds>) {
+        self.clouds = clouds;
+    }
+
+    /// Turns the clouds by their speed times the given number of frames.
+    pub fn advance_clouds(&mut self, frames: Float) {
+        if let Some(clouds) = &mut self.clouds {
+            clouds.angle += clouds.speed * frames;
+        }
+    }
+
+    /// Samples the clouds along a ray going in the direction of the unit
+    /// vector `u`, `None` if it passes through a gap or misses them.
+    pub(crate) fn sample_clouds<R: Real>(&self, u: [R; 3]) -> Option<Sample> {
+        let clouds = self.clouds.as_ref()?;
+        let inter = self.intersect(u, self.radius * (1. + clouds.altitude))?;
+        let (theta, phi) = texture_coords(inter, self.angle + clouds.angle);
+        let (width, height) = (clouds.texture.width, clouds.texture.height);
+        let x = (WrapMode::Repeat.apply(theta) * (width - 1) as Float) as usize;
+        let y = (phi * (height - 1) as Float) as usize;
+        let ch = *clouds.texture.get(x.min(width - 1), y.min(height - 1));
+        if ch == clouds.transparent {
+            return None;
+        }
+
+        let mut n = inter;
+        normalize(&mut n);
+        let luminance = self.luminance(n, self.light_vector(inter)).to_float();
+        let (intensity, color) = if self.display_night {
+            if luminance < MIN_CLOUD_LIGHT {
+                return None;
+            }
+            (luminance, CLOUD_COLOR.scale(luminance))
+        } else {
+            (1., CLOUD_COLOR)
+        };
+        Some(Sample {
+            ch,
+            index: None,
+            intensity,
+            color: Some(color),
+        })
+    }
+}
@@ -0,0 +1,78 @@
+//! Colors used for rendering colored globes.
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::float::FloatExt;
+use crate::{clamp, Float};
+
+/// RGB color.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    /// Creates a new color from its red, green and blue components.
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Linearly interpolates between this color and `other`, with `t` in the
+    /// `0..1` range.
+    pub fn lerp(self, other: Color, t: Float) -> Color {
+        let mix = |a: u8, b: u8| (a as Float + (b as Float - a as Float) * t).round() as u8;
+        Color::new(
+            mix(self.r, other.r),
+            mix(self.g, other.g),
+            mix(self.b, other.b),
+        )
+    }
+
+    /// Multiplies the brightness of the color by the given factor.
+    pub fn scale(self, factor: Float) -> Color {
+        let mul = |a: u8| clamp((a as Float * factor).round(), 0., 255.) as u8;
+        Color::new(mul(self.r), mul(self.g), mul(self.b))
+    }
+
+    /// Filters the color through `filter`, scaling each channel by the
+    /// filter's one, so that white leaves the color as it is.
+    pub fn tint(self, filter: Color) -> Color {
+        let mul = |a: u8, b: u8| (a as u16 * b as u16 / 255) as u8;
+        Color::new(
+            mul(self.r, filter.r),
+            mul(self.g, filter.g),
+            mul(self.b, filter.b),
+        )
+    }
+
+    /// Returns the perceived brightness of the color in the `0..1` range.
+    pub fn luminance(self) -> Float {
+        (0.2126 * self.r as Float + 0.7152 * self.g as Float + 0.0722 * self.b as Float) / 255.
+    }
+}
+
+/// Foreground and background color of a single canvas cell.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CellColor {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+}
+
+/// Canvas cell consisting of a character and its colors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+}
+
+/// Method of deriving colors when rendering the globe.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Coloring {
+    /// Colors are taken from the texture's color layer, dimmed on the night
+    /// side if night display is enabled.
+    Texture,
+    /// Colors are interpolated between `night` and `day` based on luminance.
+    Luminance { day: Color, night: Color },
+}
@@ -0,0 +1,210 @@
+//! Lines of equal value traced through data grids, like the isobars of a
+//! weather map.
+
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::vec::Vec;
+
+use crate::{DataGrid, Float, GeoCoord, LineLayer};
+
+/// Line between the centers of two neighboring cells, given by the row and
+/// column of the northern or western one and whether it runs east.
+type Edge = (usize, usize, bool);
+
+impl DataGrid {
+    /// Returns the lines along which the grid's values cross the given
+    /// level, each a list of corners.
+    ///
+    /// Lines are traced between the centers of the cells using marching
+    /// squares, closing into loops around highs and lows and ending where
+    /// the grid or its data does. Grids going all the way around the globe
+    /// are traced across the antimeridian too.
+    pub fn contours(&self, level: f32) -> Vec<Vec<GeoCoord>> {
+        let (width, height) = self.size();
+        let values = self.values();
+        // squares with their corners on the centers of four cells
+        let cols = if self.wraps_around() {
+            width
+        } else {
+            width.saturating_sub(1)
+        };
+
+        let mut crossings = BTreeMap::new();
+        let mut segments = Vec::new();
+        for row in 0..height.saturating_sub(1) {
+            for col in 0..cols {
+                let east = (col + 1) % width;
+                // corners go clockwise from the north west one, and each
+                // edge from the corner of the same index to the next one
+                let cells = [(row, col), (row, east), (row + 1, east), (row + 1, col)];
+                let corners = [
+                    self.cell_center(col, row),
+                    self.cell_center(col + 1, row),
+                    self.cell_center(col + 1, row + 1),
+                    self.cell_center(col, row + 1),
+                ];
+                let edges = [
+                    (row, col, true),
+                    (row, east, false),
+                    (row + 1, col, true),
+                    (row, col, false),
+                ];
+                let square = cells.map(|(row, col)| values[row * width + col]);
+                if square.iter().any(|value| value.is_nan()) {
+                    continue;
+                }
+                let above = square.map(|value| value >= level);
+
+                let mut crossed = [0; 4];
+                let mut count = 0;
+                for i in 0..4 {
+                    let next = (i + 1) % 4;
+                    if above[i] == above[next] {
+                        continue;
+                    }
+                    let t = ((level - square[i]) / (square[next] - square[i])) as Float;
+                    let (lat, lon) = corners[i];
+                    let (next_lat, next_lon) = corners[next];
+                    crossings.entry(edges[i]).or_insert_with(|| {
+                        GeoCoord::from_lat_lon(
+                            lat + (next_lat - lat) * t,
+                            lon + (next_lon - lon) * t,
+                        )
+                    });
+                    crossed[count] = i;
+                    count += 1;
+                }
+                match crossed[..count] {
+                    [a, b] => segments.push((edges[a], edges[b])),
+                    // saddles are split the way the average of the square
+                    // falls, cutting off the corners on the other side
+                    [_, _, _, _] => {
+                        let center = square.iter().sum::<f32>() / 4.;
+                        if (center >= level) == above[0] {
+                            segments.push((edges[0], edges[1]));
+                            segments.push((edges[2], edges[3]));
+                        } else {
+                            segments.push((edges[3], edges[0]));
+                            segments.push((edges[1], edges[2]));
+                        }
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        // join the segments meeting at the same edges into lines
+        let mut ends: BTreeMap<Edge, Vec<usize>> = BTreeMap::new();
+        for (i, &(a, b)) in segments.iter().enumerate() {
+            ends.entry(a).or_default().push(i);
+            ends.entry(b).or_default().push(i);
+        }
+        let mut used = alloc::vec![false; segments.len()];
+        let mut lines = Vec::new();
+        for start in 0..segments.len() {
+            if used[start] {
+                continue;
+            }
+            used[start] = true;
+            let (a, b) = segments[start];
+            let mut line = VecDeque::from(alloc::vec![a, b]);
+            while let Some(next) = follow(line[line.len() - 1], &segments, &ends, &mut used) {
+                line.push_back(next);
+            }
+            while let Some(next) = follow(line[0], &segments, &ends, &mut used) {
+                line.push_front(next);
+            }
+            lines.push(line.iter().map(|edge| crossings[edge]).collect());
+        }
+        lines
+    }
+
+    /// Returns `count` levels spread evenly between the ends of the grid's
+    /// scale, leaving the ends themselves out, e.g. for `contour_layer`.
+    pub fn contour_levels(&self, count: usize) -> Vec<f32> {
+        let (min, max) = match self.scale.or_else(|| self.range()) {
+            Some(scale) => scale,
+            None => return Vec::new(),
+        };
+        let step = (max - min) / (count + 1) as f32;
+        (1..=count).map(|i| min + step * i as f32).collect()
+    }
+
+    /// Returns a line layer of the contours at all the given levels, see
+    /// `contours`, to be drawn over the globe.
+    pub fn contour_layer(&self, levels: &[f32]) -> LineLayer {
+        levels
+            .iter()
+            .flat_map(|&level| self.contours(level))
+            .fold(LineLayer::new(), |layer, line| layer.with_line(&line))
+    }
+}
+
+/// Returns the far edge of a segment not yet joined into a line that meets
+/// the given edge, marking it as joined.
+fn follow(
+    edge: Edge,
+    segments: &[(Edge, Edge)],
+    ends: &BTreeMap<Edge, Vec<usize>>,
+    used: &mut [bool],
+) -> Option<Edge> {
+    let &i = ends.get(&edge)?.iter().find(|&&i| !used[i])?;
+    used[i] = true;
+    let (a, b) = segments[i];
+    Some(if a == edge { b } else { a })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a grid of 45° cells holding zero but for a one in the given
+    /// cell.
+    fn peak(row: usize, col: usize) -> DataGrid {
+        let mut values = alloc::vec![0.; 8 * 4];
+        values[row * 8 + col] = 1.;
+        DataGrid::new(8, 4, values).unwrap()
+    }
+
+    /// Returns true if the line has a corner at the given latitude and
+    /// longitude, in degrees.
+    fn passes_through(line: &[GeoCoord], lat: Float, lon: Float) -> bool {
+        line.iter().any(|corner| {
+            let (corner_lat, corner_lon) = corner.lat_lon();
+            let lon_diff = (corner_lon - lon + 180.).rem_euclid(360.) - 180.;
+            (corner_lat - lat).abs() < 1e-3 && lon_diff.abs() < 1e-3
+        })
+    }
+
+    #[test]
+    fn contours_close_around_peaks_halfway_to_the_neighbors() {
+        // the peak's cell is centered on 22.5° north and 22.5° west
+        let lines = peak(1, 3).contours(0.5);
+        assert_eq!(lines.len(), 1);
+        let line = &lines[0];
+        assert_eq!(line.len(), 5);
+        assert_eq!(line.first(), line.last());
+        // halfway from there to the centers of the four neighbors
+        for &(lat, lon) in &[(67.5, -22.5), (22.5, 22.5), (-22.5, -22.5), (22.5, -67.5)] {
+            assert!(passes_through(line, (lat + 22.5) / 2., (lon - 22.5) / 2.));
+        }
+    }
+
+    #[test]
+    fn contours_continue_across_the_antimeridian() {
+        // the peak's cell is centered on 157.5° west, its western neighbor
+        // on 157.5° east
+        let lines = peak(1, 0).contours(0.5);
+        assert_eq!(lines.len(), 1);
+        let line = &lines[0];
+        assert_eq!(line.len(), 5);
+        assert_eq!(line.first(), line.last());
+        assert!(passes_through(line, 22.5, 180.));
+        assert!(passes_through(line, 22.5, -135.));
+    }
+
+    #[test]
+    fn levels_outside_the_values_have_no_contours() {
+        assert!(peak(1, 3).contours(2.).is_empty());
+        assert!(peak(1, 3).contours(-1.).is_empty());
+    }
+}
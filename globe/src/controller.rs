@@ -0,0 +1,221 @@
+//! Interactive camera controller.
+//!
+//! Translates user input into globe and camera movement, so that
+//! applications can embed a fully controllable globe without reimplementing
+//! input handling. Input is described using the toolkit-agnostic `Input`
+//! enum, which applications build from their own key and mouse events.
+
+use core::f64::consts::TAU;
+use core::time::Duration;
+
+use crate::consts::PI;
+#[cfg(not(any(feature = "std", test)))]
+use crate::float::FloatExt;
+use crate::focus::focus_transition;
+use crate::{Float, GeoCoord, Globe, Transition};
+
+/// Maximum camera angle along the z axis reachable with manual controls.
+const MAX_CAM_Z: Float = 1.5;
+/// Minimum distance from the camera to the origin.
+const MIN_CAM_ZOOM: Float = 1.0;
+/// Frames per second rotation speeds are given for unless set otherwise.
+const DEFAULT_REFRESH_RATE: Float = 60.;
+
+/// User input understood by the `Controller`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Input {
+    /// Character key press.
+    Char(char),
+    Up,
+    Down,
+    Left,
+    Right,
+    PageUp,
+    PageDown,
+    Enter,
+    /// Mouse dragged to the given cell.
+    Drag(u16, u16),
+    /// Mouse button released or moved without dragging.
+    Release,
+    ScrollUp,
+    ScrollDown,
+}
+
+/// Key/mouse driven camera state machine.
+///
+/// Controls are:
+/// - arrows or `hjkl` to pan the camera, mouse drag also works
+/// - `+` and `-` to control globe rotation speed
+/// - `,` and `.` to control camera rotation speed
+/// - `PgUp` and `PgDown` or mouse scroll to control camera zoom
+/// - `n` to toggle displaying globe's night side
+/// - `Enter` to focus back on the home location
+#[derive(Clone, Debug)]
+pub struct Controller {
+    cam_zoom: Float,
+    cam_xy: Float,
+    cam_z: Float,
+    /// Globe rotation speed, in movement per frame
+    pub globe_rot_speed: Float,
+    /// Camera rotation speed, in movement per frame
+    pub cam_rot_speed: Float,
+    /// Frames per second the rotation speeds are given for, 0 stopping
+    /// the globe and the camera from rotating
+    pub refresh_rate: Float,
+    /// Location the camera returns to on `Enter`
+    pub home: GeoCoord,
+    /// Speed of the focusing transitions, as a multiple of the default one
+    pub focus_speed: Float,
+    focus: Option<Transition>,
+    last_drag_pos: Option<(u16, u16)>,
+}
+
+impl Controller {
+    /// Creates a new controller starting from the current state of the
+    /// globe's camera.
+    pub fn new(globe: &Globe) -> Self {
+        Self {
+            cam_zoom: globe.camera().radius(),
+            cam_xy: globe.camera().alpha(),
+            cam_z: globe.camera().beta(),
+            globe_rot_speed: 0.,
+            cam_rot_speed: 0.,
+            refresh_rate: DEFAULT_REFRESH_RATE,
+            home: GeoCoord::default(),
+            focus_speed: 1.,
+            focus: None,
+            last_drag_pos: None,
+        }
+    }
+
+    /// Returns true if a focusing transition is in progress.
+    pub fn is_focusing(&self) -> bool {
+        self.focus.is_some()
+    }
+
+    /// Immediately focuses the camera on the given location.
+    pub fn focus(&mut self, globe: &mut Globe, target: GeoCoord) {
+        self.focus = None;
+        globe.focus(target);
+        self.sync_camera(globe);
+    }
+
+    /// Starts moving the camera towards the given location, taking longer
+    /// the further it has to turn.
+    pub fn focus_on(&mut self, globe: &Globe, target: GeoCoord) {
+        let transition = focus_transition(globe.camera(), target, globe.angle(), self.focus_speed);
+        self.focus = Some(transition);
+    }
+
+    /// Reacts to user input. Returns false if the input is not handled by the
+    /// controller.
+    pub fn handle_input(&mut self, globe: &mut Globe, input: Input) -> bool {
+        match input {
+            Input::Char('-') => self.globe_rot_speed -= 0.005,
+            Input::Char('+') => self.globe_rot_speed += 0.005,
+            Input::Char(',') => self.cam_rot_speed -= 0.005,
+            Input::Char('.') => self.cam_rot_speed += 0.005,
+            Input::Char('n') => globe.set_display_night(!globe.display_night()),
+            // vim-style navigation with hjkl
+            Input::Char('h') | Input::Left => self.cam_xy += 0.1,
+            Input::Char('l') | Input::Right => self.cam_xy -= 0.1,
+            Input::Char('k') | Input::Up => {
+                if self.cam_z < MAX_CAM_Z {
+                    self.cam_z += 0.1;
+                }
+            }
+            Input::Char('j') | Input::Down => {
+                if self.cam_z > -MAX_CAM_Z {
+                    self.cam_z -= 0.1;
+                }
+            }
+            Input::Char(_) => return false,
+            Input::PageUp => self.cam_zoom += 0.1,
+            Input::PageDown => self.cam_zoom -= 0.1,
+            Input::Enter => self.focus(globe, self.home),
+            Input::Drag(x, y) => {
+                if let Some((x_last, y_last)) = self.last_drag_pos {
+                    let x_diff = x as Float - x_last as Float;
+                    let y_diff = y as Float - y_last as Float;
+
+                    if y_diff > 0. && self.cam_z < MAX_CAM_Z {
+                        self.cam_z += 0.1;
+                    } else if y_diff < 0. && self.cam_z > -MAX_CAM_Z {
+                        self.cam_z -= 0.1;
+                    }
+
+                    self.cam_xy += x_diff * PI / 30.;
+                    self.cam_xy += y_diff * PI / 30.;
+                }
+                self.last_drag_pos = Some((x, y))
+            }
+            Input::Release => self.last_drag_pos = None,
+            Input::ScrollUp => self.cam_zoom -= 0.1,
+            Input::ScrollDown => self.cam_zoom += 0.1,
+        }
+        true
+    }
+
+    /// Advances globe and camera movement by the given amount of time.
+    ///
+    /// Whole turns are left out of the rotation, so that even a time as long
+    /// as the one since the Unix epoch lands exactly where advancing frame by
+    /// frame would.
+    pub fn advance(&mut self, globe: &mut Globe, dt: Duration) {
+        // turns are summed up in f64 whichever precision `Float` has
+        #[allow(clippy::unnecessary_cast)]
+        let frames = dt.as_secs_f64() * self.refresh_rate as f64;
+        #[allow(clippy::unnecessary_cast)]
+        let turn = |speed: Float| (speed as f64 * frames).rem_euclid(TAU) as Float;
+
+        // apply globe rotation
+        globe.set_angle(globe.angle() + turn(self.globe_rot_speed));
+        self.cam_xy -= turn(self.globe_rot_speed);
+
+        // apply camera rotation
+        self.cam_xy -= turn(self.cam_rot_speed);
+
+        if let Some(clouds) = globe.clouds_mut() {
+            clouds.angle += turn(clouds.speed);
+        }
+
+        // clip camera zoom
+        if self.cam_zoom < MIN_CAM_ZOOM {
+            self.cam_zoom = MIN_CAM_ZOOM;
+        }
+
+        globe
+            .camera_mut()
+            .update(self.cam_zoom, self.cam_xy, self.cam_z);
+
+        if let Some(transition) = &mut self.focus {
+            if transition.step_on(globe, dt) {
+                self.focus = None;
+            }
+            self.sync_camera(globe);
+        }
+    }
+
+    /// Advances globe and camera movement by the given number of frames at
+    /// `refresh_rate`, or at 60 frames per second while it's 0.
+    #[deprecated(
+        since = "0.3.0",
+        note = "use `Controller::advance` with the time elapsed instead"
+    )]
+    pub fn tick(&mut self, globe: &mut Globe, frames: Float) {
+        let rate = if self.refresh_rate > 0. {
+            self.refresh_rate
+        } else {
+            DEFAULT_REFRESH_RATE
+        };
+        #[allow(clippy::unnecessary_cast)]
+        let secs = (frames / rate).max(0.) as f64;
+        self.advance(globe, Duration::from_secs_f64(secs));
+    }
+
+    /// Reads back camera angles after they were changed on the globe.
+    fn sync_camera(&mut self, globe: &Globe) {
+        self.cam_xy = globe.camera().alpha();
+        self.cam_z = globe.camera().beta();
+    }
+}
@@ -0,0 +1,467 @@
+//! Values laid over the surface on a grid of latitudes and longitudes, e.g.
+//! a raster dataset drawn over the texture, and the shading of land by one
+//! of them.
+
+use alloc::vec::Vec;
+
+use crate::error::{Error, Result};
+#[cfg(not(any(feature = "std", test)))]
+use crate::float::FloatExt;
+use crate::{Color, DataSeries, Float, GeoCoord, Globe};
+
+/// Rough density of population, see `population_density`.
+#[cfg(feature = "population")]
+const POPULATION: &str = include_str!("../data/population.txt");
+
+/// Palette index the characters of land without anything on it are thinned
+/// out to, above the faintest ones that the sea is drawn with on the Earth.
+const DENSITY_FLOOR: usize = 2;
+
+/// Characters grids are drawn with unless set otherwise, from the lowest
+/// values to the highest.
+const DEFAULT_RAMP: &str = ".:-=+*#%@";
+
+/// Colors grids are drawn in unless set otherwise, going from blue through
+/// white to red like a map of temperature anomalies.
+const DEFAULT_COLORS: [Color; 3] = [
+    Color::new(50, 90, 220),
+    Color::new(235, 235, 235),
+    Color::new(220, 50, 40),
+];
+
+/// How a data grid is combined with the texture underneath.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Blend {
+    /// Characters and colors both come from the grid's ramps.
+    #[default]
+    Replace,
+    /// The texture keeps its characters and takes the grid's colors, so the
+    /// shapes of the land stay readable.
+    Tint,
+    /// The texture keeps its characters, its colors moved towards the
+    /// grid's by the given weight in the `0..=1` range.
+    Mix(Float),
+}
+
+/// Values laid over the surface on an evenly spaced grid of latitudes and
+/// longitudes, e.g. temperature anomalies or vegetation indices.
+///
+/// Added to a globe, cells are drawn with a character and a color picked
+/// from the ramps by where their value falls on the scale. Cells without
+/// data leave the texture as it is.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DataGrid {
+    width: usize,
+    height: usize,
+    values: Vec<f32>,
+    /// Lowest and highest value, kept for drawing without a fixed scale
+    range: Option<(f32, f32)>,
+    /// Longitude of the west edge of the grid in degrees
+    west: Float,
+    /// Latitude of the north edge of the grid in degrees
+    north: Float,
+    /// Size of the cells in degrees of longitude and latitude
+    cell_size: (Float, Float),
+    /// Characters the cells are drawn with, from the lowest values to the
+    /// highest
+    pub ramp: Vec<char>,
+    /// Colors the cells are drawn in, from the lowest values to the highest
+    /// and blended in between, the texture's own if empty
+    pub colors: Vec<Color>,
+    /// Values put at the two ends of the ramps, the lowest and the highest
+    /// of the grid if `None`
+    pub scale: Option<(f32, f32)>,
+    /// How the grid is combined with the texture
+    pub blend: Blend,
+}
+
+impl DataGrid {
+    /// Creates a grid covering the whole surface from its values, row by
+    /// row from the north pole to the south one, each row running eastwards from 180
+    /// degrees west. Cells without data hold NaN.
+    ///
+    /// Fails if the grid is empty or the number of values doesn't match its
+    /// size.
+    pub fn new(width: usize, height: usize, values: Vec<f32>) -> Result<Self> {
+        if width == 0 || height == 0 || values.len() != width * height {
+            return Err(Error::InvalidDataGrid);
+        }
+        Ok(Self {
+            width,
+            height,
+            range: value_range(&values),
+            values,
+            west: -180.,
+            north: 90.,
+            cell_size: (360. / width as Float, 180. / height as Float),
+            ramp: DEFAULT_RAMP.chars().collect(),
+            colors: DEFAULT_COLORS.to_vec(),
+            scale: None,
+            blend: Blend::default(),
+        })
+    }
+
+    /// Parses a grid covering the whole surface from CSV, one row of the
+    /// grid per line from the north, see `new`. Empty fields and `NaN` mark
+    /// cells without data, and lines starting with `#` are skipped.
+    ///
+    /// Fails if a value can't be parsed or the rows differ in length.
+    pub fn from_csv(text: &str) -> Result<Self> {
+        let mut width = None;
+        let mut values = Vec::new();
+        for row in text.lines().map(str::trim) {
+            if row.is_empty() || row.starts_with('#') {
+                continue;
+            }
+            let before = values.len();
+            for field in row.split(',') {
+                values.push(parse_value(field.trim(), None)?);
+            }
+            let row_width = values.len() - before;
+            if *width.get_or_insert(row_width) != row_width {
+                return Err(Error::InvalidDataGrid);
+            }
+        }
+        let width = width.unwrap_or(0);
+        Self::new(width, values.len() / width.max(1), values)
+    }
+
+    /// Parses a grid in the ESRI ASCII raster format: a header giving
+    /// `ncols`, `nrows`, `xllcorner` or `xllcenter`, `yllcorner` or
+    /// `yllcenter`, `cellsize` and optionally `NODATA_value`, followed by
+    /// the values row by row from the north. Coordinates are taken as
+    /// longitudes and latitudes in degrees, so the grid may cover just a
+    /// part of the surface.
+    ///
+    /// Fails if the header is incomplete or the values don't fill the grid.
+    pub fn from_ascii_grid(text: &str) -> Result<Self> {
+        let mut tokens = text.split_whitespace().peekable();
+        let (mut cols, mut rows, mut cell, mut no_data) = (None, None, None, None);
+        let (mut x, mut y) = (None, None);
+        let mut centered = (false, false);
+        while let Some(key) = tokens.next_if(|token| token.starts_with(char::is_alphabetic)) {
+            let value = tokens.next().ok_or(Error::InvalidDataGrid)?;
+            let number = || value.parse::<Float>().map_err(|_| Error::InvalidDataGrid);
+            match key.to_ascii_lowercase().as_str() {
+                "ncols" => cols = value.parse::<usize>().ok(),
+                "nrows" => rows = value.parse::<usize>().ok(),
+                "xllcorner" => x = Some(number()?),
+                "xllcenter" => {
+                    x = Some(number()?);
+                    centered.0 = true;
+                }
+                "yllcorner" => y = Some(number()?),
+                "yllcenter" => {
+                    y = Some(number()?);
+                    centered.1 = true;
+                }
+                "cellsize" => cell = Some(number()?),
+                "nodata_value" => {
+                    no_data = Some(value.parse::<f32>().map_err(|_| Error::InvalidDataGrid)?)
+                }
+                _ => return Err(Error::InvalidDataGrid),
+            }
+        }
+        let (cols, rows, mut x, mut y, cell) = match (cols, rows, x, y, cell) {
+            (Some(cols), Some(rows), Some(x), Some(y), Some(cell)) if cell > 0. => {
+                (cols, rows, x, y, cell)
+            }
+            _ => return Err(Error::InvalidDataGrid),
+        };
+        // centers are given for the south west cell rather than its corner
+        if centered.0 {
+            x -= cell / 2.;
+        }
+        if centered.1 {
+            y -= cell / 2.;
+        }
+        let values = tokens
+            .map(|token| parse_value(token, no_data))
+            .collect::<Result<Vec<_>>>()?;
+        let mut grid = Self::new(cols, rows, values)?;
+        grid.west = x;
+        grid.north = y + rows as Float * cell;
+        grid.cell_size = (cell, cell);
+        Ok(grid)
+    }
+
+    /// Creates a grid of the rough density of population bundled with the
+    /// library, in two degree cells.
+    ///
+    /// Values run from `0.` for fewer than one person per square kilometer
+    /// to `1.` for a thousand and more on a log scale, and cells over the
+    /// sea hold no data. It's spread out from the sizes of major cities
+    /// rather than counted, good for showing where people live but not for
+    /// telling how many do.
+    #[cfg(feature = "population")]
+    pub fn population_density() -> Self {
+        let mut width = 0;
+        let mut values = Vec::new();
+        for row in POPULATION.lines().map(str::trim) {
+            if row.is_empty() || row.starts_with('#') {
+                continue;
+            }
+            width = row.len();
+            values.extend(row.chars().map(|ch| match ch.to_digit(10) {
+                Some(digit) => digit as f32 / 9.,
+                None => f32::NAN,
+            }));
+        }
+        let height = values.len() / width.max(1);
+        Self::new(width, height, values)
+            .expect("bundled population grid is well formed")
+            .with_scale(0., 1.)
+    }
+
+    /// Sets the characters the cells are drawn with, from the lowest values
+    /// to the highest.
+    pub fn with_ramp(mut self, ramp: &str) -> Self {
+        self.ramp = ramp.chars().collect();
+        self
+    }
+
+    /// Sets the colors the cells are drawn in on canvases with colors
+    /// enabled, from the lowest values to the highest. Values in between
+    /// get colors blended from the two nearest, and no colors at all keep
+    /// the texture's.
+    pub fn with_colors(mut self, colors: &[Color]) -> Self {
+        self.colors = colors.to_vec();
+        self
+    }
+
+    /// Sets the values put at the two ends of the ramps, values beyond them
+    /// getting the ends.
+    pub fn with_scale(mut self, min: f32, max: f32) -> Self {
+        self.scale = Some((min, max));
+        self
+    }
+
+    /// Sets how the grid is combined with the texture.
+    pub fn with_blend(mut self, blend: Blend) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    /// Returns the number of columns and rows of the grid.
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Returns the values of the grid, row by row from the north.
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+
+    /// Returns the value of the cell the location falls in, `None` if it
+    /// holds no data or the grid doesn't reach the location.
+    pub fn value_at(&self, coord: GeoCoord) -> Option<f32> {
+        let (lat, lon) = coord.lat_lon();
+        let col = (lon - self.west).rem_euclid(360.) / self.cell_size.0;
+        let row = (self.north - lat) / self.cell_size.1;
+        // locations right on the south and east edges fall in the last cells
+        if row < 0. || row > self.height as Float || col > self.width as Float {
+            return None;
+        }
+        let col = (col as usize).min(self.width - 1);
+        let row = (row as usize).min(self.height - 1);
+        let value = self.values[row * self.width + col];
+        Some(value).filter(|value| !value.is_nan())
+    }
+
+    /// Returns the lowest and the highest value of the grid, `None` if no
+    /// cell holds data.
+    pub fn range(&self) -> Option<(f32, f32)> {
+        self.range
+    }
+
+    /// Returns a copy of the grid holding the given values instead, which
+    /// must be as many as before.
+    pub(crate) fn with_values(&self, values: Vec<f32>) -> Self {
+        debug_assert_eq!(values.len(), self.values.len());
+        Self {
+            range: value_range(&values),
+            values,
+            ramp: self.ramp.clone(),
+            colors: self.colors.clone(),
+            ..*self
+        }
+    }
+
+    /// Returns the latitude and longitude in degrees of the center of the
+    /// given cell.
+    pub(crate) fn cell_center(&self, col: usize, row: usize) -> (Float, Float) {
+        (
+            self.north - (row as Float + 0.5) * self.cell_size.1,
+            self.west + (col as Float + 0.5) * self.cell_size.0,
+        )
+    }
+
+    /// Returns true if the grid goes all the way around the globe, its east
+    /// edge meeting the west one.
+    pub(crate) fn wraps_around(&self) -> bool {
+        (self.width as Float * self.cell_size.0 - 360.).abs() < self.cell_size.0 / 2.
+    }
+
+    /// Returns where the value falls on the scale, from `0.` at its low end
+    /// to `1.` at its high one.
+    pub fn scale_position(&self, value: f32) -> Float {
+        match self.scale.or(self.range) {
+            Some((min, max)) if max > min => ((value - min) / (max - min)).clamp(0., 1.) as Float,
+            _ => 0.,
+        }
+    }
+
+    /// Returns the character of the ramp at the given position on the
+    /// scale, `None` if the ramp is empty.
+    pub fn char_for(&self, position: Float) -> Option<char> {
+        let last = self.ramp.len().checked_sub(1)?;
+        let step = (position.clamp(0., 1.) * last as Float).round() as usize;
+        Some(self.ramp[step])
+    }
+
+    /// Returns the color blended from the ramp at the given position on the
+    /// scale, `None` if there are no colors.
+    pub fn color_for(&self, position: Float) -> Option<Color> {
+        let last = self.colors.len().checked_sub(1)?;
+        let step = position.clamp(0., 1.) * last as Float;
+        let below = (step.floor() as usize).min(last);
+        let above = (below + 1).min(last);
+        Some(self.colors[below].lerp(self.colors[above], step - below as Float))
+    }
+}
+
+/// Returns the lowest and the highest of the values, skipping the cells
+/// without data.
+fn value_range(values: &[f32]) -> Option<(f32, f32)> {
+    values
+        .iter()
+        .filter(|value| !value.is_nan())
+        .fold(None, |range, &value| match range {
+            Some((min, max)) => Some((value.min(min), value.max(max))),
+            None => Some((value, value)),
+        })
+}
+
+/// Parses a single value of a grid, taking empty fields, `NaN` and the given
+/// placeholder as missing data.
+fn parse_value(field: &str, no_data: Option<f32>) -> Result<f32> {
+    if field.is_empty() || field.eq_ignore_ascii_case("nan") {
+        return Ok(f32::NAN);
+    }
+    match field.parse::<f32>() {
+        Ok(value) if Some(value) == no_data => Ok(f32::NAN),
+        Ok(value) if value.is_finite() => Ok(value),
+        _ => Err(Error::InvalidDataGrid),
+    }
+}
+
+/// Returns the index of the cell the location falls in, on a grid of the
+/// given size laid over the surface with rows running from the north pole.
+pub(crate) fn grid_index(coord: GeoCoord, width: usize, height: usize) -> usize {
+    let col = (coord.x.rem_euclid(1.) * width as Float) as usize;
+    let row = ((1. - coord.y) * height as Float).max(0.) as usize;
+    row.min(height - 1) * width + col.min(width - 1)
+}
+
+/// Look a data grid gives a point of the surface, see
+/// `Globe::data_style_at`.
+#[derive(Clone, Copy)]
+pub(crate) struct DataStyle {
+    /// Character replacing the texture's, if any
+    pub ch: Option<char>,
+    /// Color blended over the texture's along with its weight, if any
+    pub color: Option<(Color, Float)>,
+}
+
+impl Globe {
+    /// Adds a data grid to be drawn over the texture, on top of the ones
+    /// added before it.
+    pub fn add_data_grid(&mut self, grid: DataGrid) {
+        self.data_grids.push(grid);
+    }
+
+    /// Returns the data grids drawn over the texture.
+    pub fn data_grids(&self) -> &[DataGrid] {
+        &self.data_grids
+    }
+
+    /// Returns the data grids drawn over the texture, e.g. for changing
+    /// their scales.
+    pub fn data_grids_mut(&mut self) -> &mut [DataGrid] {
+        &mut self.data_grids
+    }
+
+    /// Removes all the data grids.
+    pub fn clear_data_grids(&mut self) {
+        self.data_grids.clear();
+    }
+
+    /// Returns the grid thinning out the characters of land, if any.
+    pub fn density(&self) -> Option<&DataGrid> {
+        self.density.as_ref()
+    }
+
+    /// Sets or removes the grid thinning out the characters of land, e.g.
+    /// `DataGrid::population_density` for showing where people live. Its
+    /// ramps and blending aren't used.
+    ///
+    /// Values below `1.` step the characters down the palette, as far as its
+    /// third faintest for `0.`, which keeps empty land apart from the sea.
+    /// Shiny parts of the texture, like the sea, and cells without data are
+    /// left as they are. Textures without a palette aren't affected.
+    pub fn set_density(&mut self, density: Option<DataGrid>) {
+        self.density = density;
+    }
+
+    /// Returns the look the topmost data grid holding data at the given
+    /// texture coordinates, see `Hit`, gives them.
+    pub(crate) fn data_style_at(&self, theta: Float, phi: Float) -> Option<DataStyle> {
+        if self.data_grids.is_empty() && self.data_series.is_empty() {
+            return None;
+        }
+        // texture rows are stored reversed, running from east to west
+        let coord = GeoCoord::new((1. - theta).rem_euclid(1.), 1. - phi);
+        let series = self.data_series.iter().filter(|series| series.visible);
+        let grids = self.data_grids.iter().chain(series.map(DataSeries::grid));
+        grids.rev().find_map(|grid| {
+            let position = grid.scale_position(grid.value_at(coord)?);
+            let (ch, weight) = match grid.blend {
+                Blend::Replace => (grid.char_for(position), 1.),
+                Blend::Tint => (None, 1.),
+                Blend::Mix(weight) => (None, weight.clamp(0., 1.)),
+            };
+            Some(DataStyle {
+                ch,
+                color: grid.color_for(position).map(|color| (color, weight)),
+            })
+        })
+    }
+
+    /// Returns the palette index the character at the given texture
+    /// coordinates, see `Hit`, is thinned out to by the density grid.
+    pub(crate) fn thinned_index(
+        &self,
+        index: usize,
+        (theta, phi): (Float, Float),
+        (earth_x, earth_y): (usize, usize),
+    ) -> usize {
+        let density = match &self.density {
+            Some(density) if index > DENSITY_FLOOR => density,
+            _ => return index,
+        };
+        if let Some(mask) = &self.texture.specular {
+            if *mask.get(earth_x, earth_y) {
+                return index;
+            }
+        }
+        // texture rows are stored reversed, running from east to west
+        let coord = GeoCoord::new((1. - theta).rem_euclid(1.), 1. - phi);
+        match density.value_at(coord) {
+            Some(value) => {
+                let value = value.clamp(0., 1.) as Float;
+                DENSITY_FLOOR + ((index - DENSITY_FLOOR) as Float * value).round() as usize
+            }
+            None => index,
+        }
+    }
+}
@@ -0,0 +1,206 @@
+//! Data grids changing over time, played back over the globe like the frames
+//! of an animation.
+
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use crate::error::{Error, Result};
+#[cfg(not(any(feature = "std", test)))]
+use crate::float::FloatExt;
+use crate::{DataGrid, Float, Globe};
+
+/// Sequence of data grids over the same cells, one per timestep, e.g. a
+/// month of daily temperatures or hourly cloud cover.
+///
+/// Added to a globe, the grid at the current time is drawn like any other
+/// data grid, on top of them. Time is counted in steps, and between two of
+/// them the values are blended from both unless interpolation is turned
+/// off. The look of the drawn grid, its ramps, scale and blending, comes
+/// from the first step.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DataSeries {
+    steps: Vec<DataGrid>,
+    /// Grid drawn at the current time
+    frame: DataGrid,
+    /// Current time in steps from the first one
+    time: Float,
+    interpolate: bool,
+    /// Playback speed in steps per second, negative going backwards
+    pub speed: Float,
+    /// Whether the time moves on when the series is advanced
+    pub playing: bool,
+    /// Whether playback starts over from the first step after the last one,
+    /// rather than stopping there
+    pub looping: bool,
+    /// Whether the series is drawn
+    pub visible: bool,
+}
+
+impl DataSeries {
+    /// Creates a series out of its steps in order, playing at one step per
+    /// second and looping.
+    ///
+    /// Unless the first step has a scale set, the lowest and highest values
+    /// of all the steps are put at the ends of the ramps, so the same value
+    /// looks the same throughout.
+    ///
+    /// Fails if there are no steps or they differ in size.
+    pub fn new(mut steps: Vec<DataGrid>) -> Result<Self> {
+        let first = steps.first().ok_or(Error::InvalidDataGrid)?;
+        if steps.iter().any(|step| step.size() != first.size()) {
+            return Err(Error::InvalidDataGrid);
+        }
+        if first.scale.is_none() {
+            let scale = steps
+                .iter()
+                .filter_map(DataGrid::range)
+                .reduce(|(min, max), (low, high)| (min.min(low), max.max(high)));
+            steps[0].scale = scale;
+        }
+        Ok(Self {
+            frame: steps[0].clone(),
+            steps,
+            time: 0.,
+            interpolate: true,
+            speed: 1.,
+            playing: true,
+            looping: true,
+            visible: true,
+        })
+    }
+
+    /// Sets the playback speed in steps per second.
+    pub fn with_speed(mut self, speed: Float) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Sets whether playback starts over after the last step.
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// Sets whether values between two steps are blended from both, rather
+    /// than jumping from one step to the next.
+    pub fn with_interpolation(mut self, interpolate: bool) -> Self {
+        self.interpolate = interpolate;
+        self.seek(self.time);
+        self
+    }
+
+    /// Returns the steps of the series.
+    pub fn steps(&self) -> &[DataGrid] {
+        &self.steps
+    }
+
+    /// Returns the number of steps of the series.
+    pub fn step_count(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Returns the current time in steps from the first one.
+    pub fn time(&self) -> Float {
+        self.time
+    }
+
+    /// Returns the grid drawn at the current time.
+    pub fn grid(&self) -> &DataGrid {
+        &self.frame
+    }
+
+    /// Jumps to the given time in steps from the first one. Times beyond the
+    /// ends wrap around when looping, the last step blending back into the
+    /// first, and stop at the ends otherwise.
+    pub fn seek(&mut self, time: Float) {
+        let count = self.steps.len() as Float;
+        self.time = if self.looping {
+            time.rem_euclid(count)
+        } else {
+            time.clamp(0., count - 1.)
+        };
+        self.update_frame();
+    }
+
+    /// Moves the time by the given number of steps, backwards if negative,
+    /// e.g. for scrubbing through the series by hand.
+    pub fn scrub(&mut self, steps: Float) {
+        self.seek(self.time + steps);
+    }
+
+    /// Moves the time on by the speed times the given amount of time, if the
+    /// series is playing. Without looping, playback stops at the ends.
+    pub fn advance(&mut self, dt: Duration) {
+        if !self.playing || self.speed == 0. {
+            return;
+        }
+        self.scrub(self.speed * dt.as_secs_f64() as Float);
+        let end = (self.steps.len() - 1) as Float;
+        if !self.looping && (self.time <= 0. || self.time >= end) {
+            self.playing = false;
+        }
+    }
+
+    /// Rebuilds the grid drawn at the current time from the steps around
+    /// it.
+    fn update_frame(&mut self) {
+        let count = self.steps.len();
+        let below = (self.time.floor() as usize).min(count - 1);
+        let above = if self.looping {
+            (below + 1) % count
+        } else {
+            (below + 1).min(count - 1)
+        };
+        // grids hold f32 values whichever precision `Float` has
+        #[allow(clippy::unnecessary_cast)]
+        let t = (self.time - below as Float) as f32;
+        let values = if self.interpolate && below != above {
+            let (from, to) = (self.steps[below].values(), self.steps[above].values());
+            from.iter()
+                .zip(to)
+                .map(|(&a, &b)| match (a.is_nan(), b.is_nan()) {
+                    (false, false) => a + (b - a) * t,
+                    // cells holding data at just one of the steps take the
+                    // nearer one's
+                    _ if t < 0.5 => a,
+                    _ => b,
+                })
+                .collect()
+        } else {
+            self.steps[below].values().to_vec()
+        };
+        self.frame = self.steps[0].with_values(values);
+    }
+}
+
+impl Globe {
+    /// Adds a series of data grids to be played back over the texture, on
+    /// top of the data grids and the series added before it.
+    pub fn add_data_series(&mut self, series: DataSeries) {
+        self.data_series.push(series);
+    }
+
+    /// Returns the series of data grids played back over the texture.
+    pub fn data_series(&self) -> &[DataSeries] {
+        &self.data_series
+    }
+
+    /// Returns the series of data grids played back over the texture, e.g.
+    /// for pausing or scrubbing through them.
+    pub fn data_series_mut(&mut self) -> &mut [DataSeries] {
+        &mut self.data_series
+    }
+
+    /// Removes all the series of data grids.
+    pub fn clear_data_series(&mut self) {
+        self.data_series.clear();
+    }
+
+    /// Moves all the playing series of data grids on by the given amount of
+    /// time, see `DataSeries::advance`.
+    pub fn advance_data_series(&mut self, dt: Duration) {
+        for series in &mut self.data_series {
+            series.advance(dt);
+        }
+    }
+}
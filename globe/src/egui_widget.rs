@@ -0,0 +1,74 @@
+//! Integration with the [egui](https://github.com/emilk/egui) GUI library.
+//!
+//! Enabled with the `egui` feature. See `globe-egui` for a complete
+//! application.
+
+use egui::text::{LayoutJob, TextFormat};
+use egui::{Color32, FontId, Response, Ui, Widget};
+
+use crate::{Canvas, Color};
+
+/// Widget displaying a rendered canvas as monospace text.
+///
+/// Colors stored on the canvas are used for the text, cells without colors
+/// use the current text color of the ui style.
+pub struct CanvasWidget<'a> {
+    canvas: &'a Canvas,
+    font_size: f32,
+}
+
+impl<'a> CanvasWidget<'a> {
+    /// Creates a new widget displaying the given canvas.
+    pub fn new(canvas: &'a Canvas) -> Self {
+        Self {
+            canvas,
+            font_size: 10.,
+        }
+    }
+
+    /// Sets the size of the monospace font.
+    pub fn font_size(mut self, size: f32) -> Self {
+        self.font_size = size;
+        self
+    }
+}
+
+impl Widget for CanvasWidget<'_> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let default_color = ui.visuals().text_color();
+        let font_id = FontId::monospace(self.font_size);
+        let format = |color: Option<Color>| TextFormat {
+            font_id: font_id.clone(),
+            color: color.map(to_color32).unwrap_or(default_color),
+            ..Default::default()
+        };
+
+        let (cols, rows) = self.canvas.grid_size();
+        let mut job = LayoutJob::default();
+        for i in 0..rows {
+            // group consecutive cells of the same color into a single section
+            let mut run = String::new();
+            let mut run_color = None;
+            for j in 0..cols {
+                let cell = match self.canvas.cell(j, i) {
+                    Some(cell) => cell,
+                    None => continue,
+                };
+                if cell.fg != run_color && !run.is_empty() {
+                    job.append(&run, 0., format(run_color));
+                    run.clear();
+                }
+                run_color = cell.fg;
+                run.push(cell.ch);
+            }
+            run.push('\n');
+            job.append(&run, 0., format(run_color));
+        }
+
+        ui.label(job)
+    }
+}
+
+fn to_color32(color: Color) -> Color32 {
+    Color32::from_rgb(color.r, color.g, color.b)
+}
@@ -0,0 +1,102 @@
+//! Errors returned when configuring and building globes.
+
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+
+/// Convenience result type used throughout the library.
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// Error returned when a globe can't be built from the given configuration,
+/// or when saving a rendered image, driving a display or subscribing to a
+/// data feed fails.
+#[derive(Debug)]
+pub enum Error {
+    /// Failed reading a texture file
+    #[cfg(feature = "std")]
+    Io(io::Error),
+    /// Failed decoding a texture image
+    #[cfg(feature = "image")]
+    Image(image::ImageError),
+    /// Failed encoding or writing an image of the canvas
+    #[cfg(feature = "image")]
+    SaveImage(image::ImageError),
+    /// Failed setting up or writing to a display
+    #[cfg(feature = "led")]
+    Output(io::Error),
+    /// Failed subscribing to an MQTT topic
+    #[cfg(feature = "mqtt")]
+    Mqtt(rumqttc::ClientError),
+    /// Neither a texture nor a template was provided
+    MissingTexture,
+    /// Texture has no rows or contains an empty row
+    EmptyTexture,
+    /// Texture rows are not all of the same length
+    UnevenTexture,
+    /// Night or color layer dimensions differ from the day texture
+    TextureSizeMismatch,
+    /// Palette contains no characters, or more than 255
+    InvalidPalette,
+    /// Geofence can't be parsed or has an outline with fewer than three
+    /// corners
+    InvalidGeofence,
+    /// Line layer can't be parsed or has a line with a single corner
+    InvalidLineLayer,
+    /// Two-line element set is missing a line or has a malformed field
+    InvalidTle,
+    /// Data grid is empty or its values don't fill it
+    InvalidDataGrid,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            Error::Io(e) => write!(f, "failed reading texture: {}", e),
+            #[cfg(feature = "image")]
+            Error::Image(e) => write!(f, "failed decoding texture image: {}", e),
+            #[cfg(feature = "image")]
+            Error::SaveImage(e) => write!(f, "failed saving image: {}", e),
+            #[cfg(feature = "led")]
+            Error::Output(e) => write!(f, "failed writing to display: {}", e),
+            #[cfg(feature = "mqtt")]
+            Error::Mqtt(e) => write!(f, "failed subscribing to MQTT topic: {}", e),
+            Error::MissingTexture => write!(f, "texture not provided"),
+            Error::EmptyTexture => write!(f, "texture is empty"),
+            Error::UnevenTexture => write!(f, "texture rows differ in length"),
+            Error::TextureSizeMismatch => {
+                write!(f, "texture layers differ in size from the day texture")
+            }
+            Error::InvalidPalette => write!(f, "palette is empty or too long"),
+            Error::InvalidGeofence => write!(f, "geofence is malformed or has too few corners"),
+            Error::InvalidLineLayer => {
+                write!(f, "line layer is malformed or has a single corner line")
+            }
+            Error::InvalidTle => write!(f, "two-line element set is malformed"),
+            Error::InvalidDataGrid => write!(f, "data grid is empty or doesn't match its size"),
+        }
+    }
+}
+
+impl core::error::Error for Error {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            Error::Io(e) => Some(e),
+            #[cfg(feature = "led")]
+            Error::Output(e) => Some(e),
+            #[cfg(feature = "mqtt")]
+            Error::Mqtt(e) => Some(e),
+            #[cfg(feature = "image")]
+            Error::Image(e) | Error::SaveImage(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
@@ -0,0 +1,75 @@
+//! Stack allocated canvas of a fixed size.
+
+use crate::RenderTarget;
+
+/// Canvas of `W` columns by `H` rows of characters, stored inline.
+///
+/// Cells are taken to be square unless set otherwise with `with_char_pix`,
+/// matching the LED matrices and small displays it's meant for. Rendering
+/// onto it with `Globe::render_on_fixed` doesn't allocate, which makes it
+/// suitable for microcontrollers and status bars. Colors are not stored.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FixedCanvas<const W: usize, const H: usize> {
+    cells: [[char; W]; H],
+    char_pix: (usize, usize),
+}
+
+impl<const W: usize, const H: usize> FixedCanvas<W, H> {
+    /// Creates a new blank canvas.
+    pub const fn new() -> Self {
+        Self {
+            cells: [[' '; W]; H],
+            char_pix: (1, 1),
+        }
+    }
+    /// Sets how many pixels make up a cell, horizontally and vertically,
+    /// e.g. `(1, 2)` for a terminal's status bar.
+    pub const fn with_char_pix(mut self, char_pix: (usize, usize)) -> Self {
+        self.char_pix = char_pix;
+        self
+    }
+    /// Returns the number of character columns and rows.
+    pub const fn grid_size(&self) -> (usize, usize) {
+        (W, H)
+    }
+    /// Returns the character at the given position.
+    pub fn char_at(&self, x: usize, y: usize) -> Option<char> {
+        self.cells.get(y)?.get(x).copied()
+    }
+    /// Returns how many pixels make up a cell, horizontally and vertically.
+    pub const fn char_pix(&self) -> (usize, usize) {
+        self.char_pix
+    }
+    /// Returns the rows of characters.
+    pub fn rows(&self) -> &[[char; W]; H] {
+        &self.cells
+    }
+    pub fn clear(&mut self) {
+        self.cells = [[' '; W]; H];
+    }
+}
+
+impl<const W: usize, const H: usize> Default for FixedCanvas<W, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const W: usize, const H: usize> RenderTarget for FixedCanvas<W, H> {
+    fn grid_size(&self) -> (usize, usize) {
+        (W, H)
+    }
+    fn char_pix(&self) -> (usize, usize) {
+        self.char_pix
+    }
+    fn draw_point(&mut self, col: usize, row: usize, ch: char) {
+        if col < W && row < H {
+            self.cells[row][col] = ch;
+        }
+    }
+    fn clear_row(&mut self, row: usize) {
+        if let Some(row) = self.cells.get_mut(row) {
+            *row = [' '; W];
+        }
+    }
+}
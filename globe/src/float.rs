@@ -0,0 +1,112 @@
+//! Float functions for builds without `std`, where core leaves them out.
+//!
+//! Modules doing float math import `FloatExt` when the `std` feature is
+//! disabled, the calls then resolve to `libm` instead of the inherent methods.
+//! Test builds always link std, which brings the inherent methods back.
+
+/// Float methods of `std` missing from `core`.
+pub(crate) trait FloatExt: Sized {
+    fn sqrt(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn tan(self) -> Self;
+    fn asin(self) -> Self;
+    fn acos(self) -> Self;
+    fn atan(self) -> Self;
+    fn atan2(self, x: Self) -> Self;
+    fn sin_cos(self) -> (Self, Self);
+    fn hypot(self, other: Self) -> Self;
+    fn powf(self, n: Self) -> Self;
+    fn powi(self, n: i32) -> Self;
+    fn exp(self) -> Self;
+    fn ln(self) -> Self;
+    fn floor(self) -> Self;
+    fn ceil(self) -> Self;
+    fn round(self) -> Self;
+    fn trunc(self) -> Self;
+    fn fract(self) -> Self;
+    fn rem_euclid(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_float_ext {
+    ($t:ty, $sqrt:ident, $sin:ident, $cos:ident, $tan:ident, $asin:ident, $acos:ident,
+     $atan:ident, $atan2:ident, $hypot:ident, $pow:ident, $exp:ident, $log:ident,
+     $floor:ident, $ceil:ident, $round:ident, $trunc:ident) => {
+        impl FloatExt for $t {
+            fn sqrt(self) -> Self {
+                libm::$sqrt(self)
+            }
+            fn sin(self) -> Self {
+                libm::$sin(self)
+            }
+            fn cos(self) -> Self {
+                libm::$cos(self)
+            }
+            fn tan(self) -> Self {
+                libm::$tan(self)
+            }
+            fn asin(self) -> Self {
+                libm::$asin(self)
+            }
+            fn acos(self) -> Self {
+                libm::$acos(self)
+            }
+            fn atan(self) -> Self {
+                libm::$atan(self)
+            }
+            fn atan2(self, x: Self) -> Self {
+                libm::$atan2(self, x)
+            }
+            fn sin_cos(self) -> (Self, Self) {
+                (libm::$sin(self), libm::$cos(self))
+            }
+            fn hypot(self, other: Self) -> Self {
+                libm::$hypot(self, other)
+            }
+            fn powf(self, n: Self) -> Self {
+                libm::$pow(self, n)
+            }
+            fn powi(self, n: i32) -> Self {
+                libm::$pow(self, n as $t)
+            }
+            fn exp(self) -> Self {
+                libm::$exp(self)
+            }
+            fn ln(self) -> Self {
+                libm::$log(self)
+            }
+            fn floor(self) -> Self {
+                libm::$floor(self)
+            }
+            fn ceil(self) -> Self {
+                libm::$ceil(self)
+            }
+            fn round(self) -> Self {
+                libm::$round(self)
+            }
+            fn trunc(self) -> Self {
+                libm::$trunc(self)
+            }
+            fn fract(self) -> Self {
+                self - libm::$trunc(self)
+            }
+            fn rem_euclid(self, rhs: Self) -> Self {
+                let r = self % rhs;
+                if r < 0. {
+                    r + rhs.abs()
+                } else {
+                    r
+                }
+            }
+        }
+    };
+}
+
+impl_float_ext!(
+    f32, sqrtf, sinf, cosf, tanf, asinf, acosf, atanf, atan2f, hypotf, powf, expf, logf, floorf,
+    ceilf, roundf, truncf
+);
+impl_float_ext!(
+    f64, sqrt, sin, cos, tan, asin, acos, atan, atan2, hypot, pow, exp, log, floor, ceil, round,
+    trunc
+);
@@ -0,0 +1,139 @@
+//! Focusing the camera on locations on the globe surface.
+
+use core::time::Duration;
+
+use crate::consts::PI;
+#[cfg(not(any(feature = "std", test)))]
+use crate::float::FloatExt;
+use crate::{rotate_x, Camera, Easing, Float, GeoCoord, Transition};
+
+/// Seconds a focusing transition takes at the default speed, no matter how
+/// far the camera turns.
+const FOCUS_BASE_SECS: Float = 0.4;
+/// Seconds a focusing transition takes at the default speed for every
+/// radian the camera turns.
+const FOCUS_SECS_PER_RADIAN: Float = 0.5;
+/// Slowest focusing speed, keeping transitions from taking forever.
+const MIN_FOCUS_SPEED: Float = 0.01;
+/// Time `FocusAnimation::step` advances by, a frame at 60 frames per second.
+const FOCUS_FRAME_TIME: Duration = Duration::from_nanos(16_666_667);
+
+/// Computes camera angles `(alpha, beta)` that put the given location in the
+/// center of the view, taking the current globe angle and the axial tilt of
+/// the globe (in radians) into account.
+pub(crate) fn focus_angles(coord: GeoCoord, globe_angle: Float, tilt: Float) -> (Float, Float) {
+    if tilt == 0. {
+        return coord.sphere_angles(globe_angle);
+    }
+    let mut point = coord.sphere_point(globe_angle, 1.);
+    rotate_x(&mut point, tilt);
+    let [x, y, z] = point;
+    (y.atan2(x), z.atan2((x * x + y * y).sqrt()))
+}
+
+/// Creates a transition focusing the camera on the given location, taking
+/// longer the further it has to turn and less the higher `speed` is.
+pub(crate) fn focus_transition(
+    camera: &Camera,
+    coord: GeoCoord,
+    globe_angle: Float,
+    speed: Float,
+) -> Transition {
+    let (alpha, beta) = focus_angles(coord, globe_angle, camera.tilt);
+    let alpha_turn = shortest_angle(alpha - camera.alpha);
+    let beta_turn = beta - camera.beta;
+    let turn = (alpha_turn * alpha_turn + beta_turn * beta_turn).sqrt();
+    let secs = (FOCUS_BASE_SECS + FOCUS_SECS_PER_RADIAN * turn) / speed.max(MIN_FOCUS_SPEED);
+    camera
+        .transition_to_location(coord, globe_angle, Duration::from_secs_f64(secs as f64))
+        .with_easing(Easing::EaseInOut)
+}
+
+/// Wraps an angle difference into the `-PI..PI` range so that the camera
+/// always takes the shorter way around the globe.
+pub(crate) fn shortest_angle(diff: Float) -> Float {
+    let diff = diff % (2. * PI);
+    if diff > PI {
+        diff - 2. * PI
+    } else if diff < -PI {
+        diff + 2. * PI
+    } else {
+        diff
+    }
+}
+
+impl Camera {
+    /// Orients the camera so that it focuses on the given location.
+    ///
+    /// `globe_angle` is the current rotation angle of the globe.
+    pub fn focus(&mut self, coord: GeoCoord, globe_angle: Float) {
+        let (alpha, beta) = focus_angles(coord, globe_angle, self.tilt);
+        self.update(self.radius, alpha, beta);
+    }
+
+    /// Orients the camera so that it focuses on the given latitude and
+    /// longitude, in degrees.
+    ///
+    /// `globe_angle` is the current rotation angle of the globe.
+    pub fn look_at_lat_lon(&mut self, lat: Float, lon: Float, globe_angle: Float) {
+        self.focus(GeoCoord::from_lat_lon(lat, lon), globe_angle);
+    }
+
+    /// Creates an animation that gradually rotates the camera towards the
+    /// given location.
+    #[deprecated(
+        since = "0.3.0",
+        note = "use `Camera::transition_to_location` and `Transition::step_on` instead"
+    )]
+    #[allow(deprecated)]
+    pub fn animate_focus(&self, coord: GeoCoord, speed: Float) -> FocusAnimation {
+        FocusAnimation {
+            target: coord,
+            speed,
+            transition: None,
+        }
+    }
+}
+
+/// Animation rotating the camera towards a target location.
+///
+/// Created with `Camera::animate_focus`, advanced with `FocusAnimation::step`
+/// once per frame of 60 frames per second. Follows the target while the globe
+/// spins, landing exactly on it, like the transition it's a wrapper over.
+#[deprecated(
+    since = "0.3.0",
+    note = "use `Camera::transition_to_location` and `Transition::step_on` instead"
+)]
+#[derive(Clone, Debug)]
+pub struct FocusAnimation {
+    target: GeoCoord,
+    speed: Float,
+    /// Transition started on the first step, once the globe angle is known
+    transition: Option<Transition>,
+}
+
+#[allow(deprecated)]
+impl FocusAnimation {
+    /// Returns the target location.
+    pub fn target(&self) -> GeoCoord {
+        self.target
+    }
+
+    /// Returns true if the camera has reached the target.
+    pub fn is_finished(&self) -> bool {
+        self.transition
+            .as_ref()
+            .is_some_and(Transition::is_finished)
+    }
+
+    /// Moves the camera one step closer to the target. Returns true once the
+    /// target is reached.
+    ///
+    /// `globe_angle` is the current rotation angle of the globe.
+    pub fn step(&mut self, camera: &mut Camera, globe_angle: Float) -> bool {
+        let (target, speed) = (self.target, self.speed);
+        self.transition
+            .get_or_insert_with(|| focus_transition(camera, target, globe_angle, speed))
+            .step_following(camera, globe_angle, FOCUS_FRAME_TIME)
+    }
+}
@@ -0,0 +1,69 @@
+//! Iterating over rendered animation frames.
+
+use alloc::string::{String, ToString};
+
+use crate::{Canvas, CanvasPreset, Float, Globe};
+
+/// Iterator rendering consecutive frames of a spinning globe.
+///
+/// Created with `Globe::frames`. Every frame is rendered onto a new canvas,
+/// after which the globe is rotated by the step angle. The iterator never
+/// ends, use `Iterator::take` to limit the number of frames.
+pub struct Frames<'a> {
+    globe: &'a mut Globe,
+    size: (u16, u16),
+    preset: CanvasPreset,
+    colors: bool,
+    step: Float,
+}
+
+impl<'a> Frames<'a> {
+    /// Sets the character size of the rendered canvases.
+    pub fn with_preset(mut self, preset: CanvasPreset) -> Self {
+        self.preset = preset;
+        self
+    }
+
+    /// Enables storing colors on the rendered canvases.
+    pub fn with_colors(mut self) -> Self {
+        self.colors = true;
+        self
+    }
+
+    /// Turns the iterator into one yielding frames as text, with canvas rows
+    /// separated by newlines.
+    pub fn strings(self) -> impl Iterator<Item = String> + 'a {
+        self.map(|canvas| canvas.to_string())
+    }
+}
+
+impl Iterator for Frames<'_> {
+    type Item = Canvas;
+
+    fn next(&mut self) -> Option<Canvas> {
+        let mut canvas = Canvas::with_preset(self.size.0, self.size.1, self.preset);
+        if self.colors {
+            canvas = canvas.with_colors();
+        }
+        self.globe.render_on(&mut canvas);
+        self.globe.set_angle(self.globe.angle() + self.step);
+        Some(canvas)
+    }
+}
+
+impl Globe {
+    /// Returns an iterator rendering frames of the globe spinning by `step`
+    /// radians per frame, starting from its current angle.
+    ///
+    /// `canvas_size` is the size of every frame's canvas, same as in
+    /// `Canvas::new`.
+    pub fn frames(&mut self, canvas_size: (u16, u16), step: Float) -> Frames<'_> {
+        Frames {
+            globe: self,
+            size: canvas_size,
+            preset: CanvasPreset::default(),
+            colors: false,
+            step,
+        }
+    }
+}
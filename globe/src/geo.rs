@@ -0,0 +1,157 @@
+//! Geographic coordinates on the globe surface.
+
+use alloc::vec::Vec;
+
+use crate::consts::PI;
+#[cfg(not(any(feature = "std", test)))]
+use crate::float::FloatExt;
+use crate::{clamp, cross, dot, Float};
+
+/// Mean radius of the Earth in kilometers.
+pub const EARTH_RADIUS_KM: Float = 6371.;
+
+/// Location on the globe surface expressed in normalized texture
+/// coordinates.
+///
+/// `x` runs along the texture columns (longitude) and `y` runs along the
+/// latitude from the south pole (`0.`) to the north pole (`1.`). Both values
+/// are expected to be in the `0..1` range.
+///
+/// Textures are expected to start at longitude -180°, so geographic
+/// coordinates can be converted with `GeoCoord::from_lat_lon` and
+/// `GeoCoord::lat_lon`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GeoCoord {
+    pub x: Float,
+    pub y: Float,
+}
+
+impl GeoCoord {
+    /// Creates a new `GeoCoord` from normalized coordinates.
+    pub fn new(x: Float, y: Float) -> Self {
+        Self { x, y }
+    }
+
+    /// Creates a new `GeoCoord` from latitude and longitude in degrees.
+    ///
+    /// Latitude is clamped to the `-90..=90` range, longitude wraps around.
+    pub fn from_lat_lon(lat: Float, lon: Float) -> Self {
+        Self::from_lat_lon_radians(lat.to_radians(), lon.to_radians())
+    }
+
+    /// Creates a new `GeoCoord` from latitude and longitude in radians.
+    pub fn from_lat_lon_radians(lat: Float, lon: Float) -> Self {
+        Self {
+            x: ((lon + PI) / (2. * PI)).rem_euclid(1.),
+            y: clamp(lat / PI + 0.5, 0., 1.),
+        }
+    }
+
+    /// Returns the latitude and longitude of the location in degrees.
+    pub fn lat_lon(self) -> (Float, Float) {
+        let (lat, lon) = self.lat_lon_radians();
+        (lat.to_degrees(), lon.to_degrees())
+    }
+
+    /// Returns the latitude and longitude of the location in radians.
+    pub fn lat_lon_radians(self) -> (Float, Float) {
+        ((self.y - 0.5) * PI, self.x * 2. * PI - PI)
+    }
+
+    /// Returns the location on the opposite side of the globe.
+    pub fn antipode(self) -> Self {
+        Self {
+            x: (self.x + 0.5).rem_euclid(1.),
+            y: 1. - self.y,
+        }
+    }
+
+    /// Returns the angle in radians between the two locations as seen from
+    /// the center of the globe, which is the length of the shortest path
+    /// between them on a globe of radius one.
+    pub fn angle_to(self, other: GeoCoord) -> Float {
+        let (a, b) = (self.unit_vector(), other.unit_vector());
+        let mut normal = [0.; 3];
+        cross(&mut normal, a, b);
+        dot(&normal, &normal).sqrt().atan2(dot(&a, &b))
+    }
+
+    /// Returns the length in kilometers of the shortest path between the two
+    /// locations on the surface of the Earth.
+    pub fn distance_km(self, other: GeoCoord) -> Float {
+        self.angle_to(other) * EARTH_RADIUS_KM
+    }
+
+    /// Returns true if the location lies within the polygon whose corners
+    /// are the given locations, connected by great circle arcs.
+    ///
+    /// Polygons are closed between their last and first corners and can be
+    /// listed in either direction. Polygons with fewer than three corners
+    /// contain nothing.
+    pub fn is_inside(self, polygon: &[GeoCoord]) -> bool {
+        let corners: Vec<_> = polygon.iter().map(|c| c.unit_vector()).collect();
+        winds_around(&corners, self.unit_vector())
+    }
+
+    /// Returns the unit vector pointing from the center of the unrotated
+    /// globe towards the location.
+    pub(crate) fn unit_vector(self) -> [Float; 3] {
+        let (lat, lon) = self.lat_lon_radians();
+        [lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin()]
+    }
+
+    /// Returns the location the unit vector pointing from the center of the
+    /// unrotated globe points at.
+    pub(crate) fn from_unit_vector(v: [Float; 3]) -> Self {
+        let lat = v[2].atan2((v[0] * v[0] + v[1] * v[1]).sqrt());
+        Self::from_lat_lon_radians(lat, v[1].atan2(v[0]))
+    }
+
+    /// Returns the angles at which the location is found on the globe
+    /// rotated by `globe_angle`, as a pair of angle along the xy plane and
+    /// angle along z axis.
+    pub(crate) fn sphere_angles(self, globe_angle: Float) -> (Float, Float) {
+        let (elevation, longitude) = self.lat_lon_radians();
+        (-longitude - globe_angle, elevation)
+    }
+
+    /// Returns the point on the surface of the globe of the given radius,
+    /// rotated by `globe_angle`, where the location is found.
+    pub(crate) fn sphere_point(self, globe_angle: Float, radius: Float) -> [Float; 3] {
+        let (azimuth, elevation) = self.sphere_angles(globe_angle);
+        [
+            radius * azimuth.cos() * elevation.cos(),
+            radius * azimuth.sin() * elevation.cos(),
+            radius * elevation.sin(),
+        ]
+    }
+}
+
+impl From<(Float, Float)> for GeoCoord {
+    fn from((x, y): (Float, Float)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+/// Returns true if the polygon with the given unit vector corners, connected
+/// by great circle arcs, winds around the unit vector `point`.
+///
+/// Adds up the angles the edges subtend as seen from the point, which come
+/// to a full turn for points inside and cancel out for points outside.
+pub(crate) fn winds_around(corners: &[[Float; 3]], point: [Float; 3]) -> bool {
+    if corners.len() < 3 {
+        return false;
+    }
+    let mut total: Float = 0.;
+    for (i, a) in corners.iter().enumerate() {
+        let b = &corners[(i + 1) % corners.len()];
+        let mut normal = [0.; 3];
+        cross(&mut normal, *a, *b);
+        // both corners projected onto the plane touching the sphere at the
+        // point, their cross product lying along the point
+        let y = dot(&point, &normal);
+        let x = dot(a, b) - dot(a, &point) * dot(b, &point);
+        total += y.atan2(x);
+    }
+    total.abs() > PI
+}
@@ -0,0 +1,341 @@
+//! Regions of the surface drawn over with their own character and color.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[cfg(feature = "geojson")]
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+#[cfg(not(any(feature = "std", test)))]
+use crate::float::FloatExt;
+use crate::geo::winds_around;
+use crate::{dot, Color, Float, GeoCoord, Globe};
+
+/// Character geofences are filled with unless set otherwise.
+const DEFAULT_FILL: char = '#';
+
+/// Closed outline of a geofence, along with a circle around it used for
+/// quickly ruling out locations far away.
+#[derive(Clone, Debug, PartialEq)]
+struct Outline {
+    corners: Vec<GeoCoord>,
+    /// Corners as unit vectors
+    points: Vec<[Float; 3]>,
+    /// Unit vector pointing at the center of the circle
+    center: [Float; 3],
+    /// Cosine of the angular radius of the circle, `None` for outlines
+    /// spread over more than a hemisphere, which the circle can't rule out
+    /// anything for
+    reach: Option<Float>,
+}
+
+impl Outline {
+    fn new(corners: &[GeoCoord]) -> Self {
+        let points: Vec<_> = corners.iter().map(|c| c.unit_vector()).collect();
+        let sum = points.iter().fold([0.; 3], |sum, p| {
+            [sum[0] + p[0], sum[1] + p[1], sum[2] + p[2]]
+        });
+        let length = dot(&sum, &sum).sqrt();
+        let (center, reach) = if length > 0. {
+            let center = sum.map(|c| c / length);
+            let reach = points.iter().map(|p| dot(p, &center)).fold(1., Float::min);
+            // smaller circles hold the arcs between their corners too
+            (center, Some(reach).filter(|&reach| reach > 0.))
+        } else {
+            (sum, None)
+        };
+        Self {
+            corners: corners.to_vec(),
+            points,
+            center,
+            reach,
+        }
+    }
+
+    fn contains(&self, point: [Float; 3]) -> bool {
+        if self
+            .reach
+            .is_some_and(|reach| dot(&point, &self.center) < reach)
+        {
+            return false;
+        }
+        winds_around(&self.points, point)
+    }
+}
+
+/// Region of the surface drawn over with its own character and color, e.g.
+/// to highlight a country or the area a service covers.
+///
+/// Made up of one or more outlines whose corners are connected by great
+/// circle arcs. Locations inside an odd number of outlines are part of the
+/// region, so outlines within others cut holes in them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Geofence {
+    outlines: Vec<Outline>,
+    /// Character the region is filled with, `None` keeping the texture's
+    pub fill: Option<char>,
+    /// Color the region is drawn in, the globe's own if `None`
+    pub color: Option<Color>,
+}
+
+impl Geofence {
+    /// Creates a geofence with the given outline, filled with `#`.
+    pub fn new(corners: &[GeoCoord]) -> Self {
+        Self {
+            outlines: Vec::new(),
+            fill: Some(DEFAULT_FILL),
+            color: None,
+        }
+        .with_outline(corners)
+    }
+
+    /// Parses outlines given one corner per line as `lat,lon`, in degrees,
+    /// with blank lines separating the outlines.
+    pub fn from_lat_lon_list(text: &str) -> Result<Self> {
+        let mut outlines = vec![Vec::new()];
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() {
+                outlines.push(Vec::new());
+                continue;
+            }
+            let (lat, lon) = line.split_once(',').ok_or(Error::InvalidGeofence)?;
+            let degrees = |value: &str| {
+                value
+                    .trim()
+                    .parse::<Float>()
+                    .ok()
+                    .filter(|value| value.is_finite())
+                    .ok_or(Error::InvalidGeofence)
+            };
+            let corner = GeoCoord::from_lat_lon(degrees(lat)?, degrees(lon)?);
+            if let Some(outline) = outlines.last_mut() {
+                outline.push(corner);
+            }
+        }
+        outlines.retain(|outline| !outline.is_empty());
+        Self::from_outlines(&outlines)
+    }
+
+    /// Parses the polygons and multipolygons of a GeoJSON document, found
+    /// either on their own or in features and collections. Other geometries
+    /// are skipped.
+    #[cfg(feature = "geojson")]
+    pub fn from_geojson(text: &str) -> Result<Self> {
+        let value: Value = serde_json::from_str(text).map_err(|_| Error::InvalidGeofence)?;
+        let mut outlines = Vec::new();
+        collect_geojson(&value, &mut outlines).ok_or(Error::InvalidGeofence)?;
+        Self::from_outlines(&outlines)
+    }
+
+    /// Creates a geofence out of outlines read from a file, which all need
+    /// to have at least three corners.
+    fn from_outlines(outlines: &[Vec<GeoCoord>]) -> Result<Self> {
+        let (first, rest) = outlines.split_first().ok_or(Error::InvalidGeofence)?;
+        if outlines.iter().any(|outline| outline.len() < 3) {
+            return Err(Error::InvalidGeofence);
+        }
+        Ok(rest.iter().fold(Self::new(first), |fence, outline| {
+            fence.with_outline(outline)
+        }))
+    }
+
+    /// Adds another outline, cutting a hole where it lies within the others.
+    pub fn with_outline(mut self, corners: &[GeoCoord]) -> Self {
+        self.outlines.push(Outline::new(corners));
+        self
+    }
+
+    /// Sets the character the region is filled with, `None` keeping the
+    /// texture's and only changing the color.
+    pub fn with_fill(mut self, fill: Option<char>) -> Self {
+        self.fill = fill;
+        self
+    }
+
+    /// Sets the color the region is drawn in on canvases with colors
+    /// enabled.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Returns the corners of the outlines.
+    pub fn outlines(&self) -> impl Iterator<Item = &[GeoCoord]> {
+        self.outlines
+            .iter()
+            .map(|outline| outline.corners.as_slice())
+    }
+
+    /// Returns true if the location is part of the region.
+    pub fn contains(&self, coord: GeoCoord) -> bool {
+        self.contains_point(coord.unit_vector())
+    }
+
+    /// Returns true if the location the unit vector points at is part of
+    /// the region.
+    fn contains_point(&self, point: [Float; 3]) -> bool {
+        let inside = self.outlines.iter().filter(|o| o.contains(point)).count();
+        inside % 2 == 1
+    }
+}
+
+impl Globe {
+    /// Adds a geofence to be drawn over the surface, on top of the ones
+    /// added before.
+    pub fn add_geofence(&mut self, geofence: Geofence) {
+        self.geofences.push(geofence);
+    }
+
+    /// Returns the geofences drawn over the surface.
+    pub fn geofences(&self) -> &[Geofence] {
+        &self.geofences
+    }
+
+    /// Removes all the geofences.
+    pub fn clear_geofences(&mut self) {
+        self.geofences.clear();
+    }
+
+    /// Returns the topmost geofence covering the given texture coordinates,
+    /// see `Hit`.
+    pub(crate) fn geofence_at(&self, theta: Float, phi: Float) -> Option<&Geofence> {
+        if self.geofences.is_empty() {
+            return None;
+        }
+        // texture rows are stored reversed, running from east to west
+        let point = GeoCoord::new((1. - theta).rem_euclid(1.), 1. - phi).unit_vector();
+        self.geofences
+            .iter()
+            .rev()
+            .find(|fence| fence.contains_point(point))
+    }
+}
+
+/// Collects the outlines of all the polygons found in the GeoJSON object,
+/// returning `None` if it's malformed.
+#[cfg(feature = "geojson")]
+fn collect_geojson(value: &Value, outlines: &mut Vec<Vec<GeoCoord>>) -> Option<()> {
+    match value.get("type")?.as_str()? {
+        "FeatureCollection" => {
+            for feature in value.get("features")?.as_array()? {
+                collect_geojson(feature, outlines)?;
+            }
+        }
+        "Feature" => match value.get("geometry")? {
+            // features without a location
+            Value::Null => {}
+            geometry => collect_geojson(geometry, outlines)?,
+        },
+        "GeometryCollection" => {
+            for geometry in value.get("geometries")?.as_array()? {
+                collect_geojson(geometry, outlines)?;
+            }
+        }
+        "Polygon" => collect_polygon(value.get("coordinates")?, outlines)?,
+        "MultiPolygon" => {
+            for polygon in value.get("coordinates")?.as_array()? {
+                collect_polygon(polygon, outlines)?;
+            }
+        }
+        // points and lines enclose nothing
+        _ => {}
+    }
+    Some(())
+}
+
+/// Collects the rings of a GeoJSON polygon, given as arrays of
+/// `[lon, lat]` positions.
+#[cfg(feature = "geojson")]
+fn collect_polygon(coordinates: &Value, outlines: &mut Vec<Vec<GeoCoord>>) -> Option<()> {
+    for ring in coordinates.as_array()? {
+        let mut corners = ring
+            .as_array()?
+            .iter()
+            .map(|position| {
+                let position = position.as_array()?;
+                let lon = position.first()?.as_f64()?;
+                let lat = position.get(1)?.as_f64()?;
+                Some(GeoCoord::from_lat_lon(lat as Float, lon as Float))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        // rings end where they start
+        if corners.len() > 1 && corners.first() == corners.last() {
+            corners.pop();
+        }
+        outlines.push(corners);
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corners(lat_lon: &[(Float, Float)]) -> Vec<GeoCoord> {
+        lat_lon
+            .iter()
+            .map(|&(lat, lon)| GeoCoord::from_lat_lon(lat, lon))
+            .collect()
+    }
+
+    #[test]
+    fn regions_across_the_antimeridian_contain_both_sides() {
+        // around Fiji, from 175° east across the antimeridian to 178° west
+        let fence = Geofence::new(&corners(&[
+            (-15., 175.),
+            (-15., -178.),
+            (-20., -178.),
+            (-20., 175.),
+        ]));
+        for &(lat, lon) in &[(-17., 177.), (-17., 180.), (-17., -180.), (-17., -179.)] {
+            assert!(
+                fence.contains(GeoCoord::from_lat_lon(lat, lon)),
+                "{} {}",
+                lat,
+                lon
+            );
+        }
+        for &(lat, lon) in &[(-17., 170.), (-17., -175.), (-17., 0.), (-10., 179.)] {
+            assert!(
+                !fence.contains(GeoCoord::from_lat_lon(lat, lon)),
+                "{} {}",
+                lat,
+                lon
+            );
+        }
+    }
+
+    #[test]
+    fn corner_order_does_not_matter() {
+        let mut outline = corners(&[(10., 10.), (10., 20.), (0., 20.), (0., 10.)]);
+        let clockwise = Geofence::new(&outline);
+        outline.reverse();
+        let counterclockwise = Geofence::new(&outline);
+        for coord in corners(&[(5., 15.), (5., 25.), (-5., 15.)]) {
+            assert_eq!(clockwise.contains(coord), counterclockwise.contains(coord));
+        }
+        assert!(clockwise.contains(GeoCoord::from_lat_lon(5., 15.)));
+    }
+
+    #[test]
+    fn inner_outlines_cut_holes() {
+        let fence = Geofence::new(&corners(&[
+            (10., -10.),
+            (10., 10.),
+            (-10., 10.),
+            (-10., -10.),
+        ]))
+        .with_outline(&corners(&[(2., -2.), (2., 2.), (-2., 2.), (-2., -2.)]));
+        assert!(fence.contains(GeoCoord::from_lat_lon(5., 5.)));
+        assert!(!fence.contains(GeoCoord::from_lat_lon(0., 0.)));
+    }
+
+    #[test]
+    fn lists_need_three_corners_per_outline() {
+        let fence = Geofence::from_lat_lon_list("0,0\n0,10\n10,10\n\n20,20\n20,30\n30,30").unwrap();
+        assert_eq!(fence.outlines().count(), 2);
+        assert!(Geofence::from_lat_lon_list("0,0\n0,10").is_err());
+        assert!(Geofence::from_lat_lon_list("0,0\n0,ten\n10,10").is_err());
+    }
+}
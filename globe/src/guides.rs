@@ -0,0 +1,121 @@
+//! Reference marks for finding one's way around the globe: the poles, the
+//! equator and the tropics.
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::float::FloatExt;
+use crate::{magnetic, CellColor, CellRect, Color, Float, GeoCoord, Globe, RenderTarget};
+
+/// Latitude of the tropics in degrees, matching the tilt of the Earth's
+/// axis.
+const TROPIC_LAT: Float = 23.44;
+
+/// Reference marks drawn over the surface, each of which can be left out.
+///
+/// The lines are drawn under arcs and markers, and like them only on the
+/// side of the globe facing the camera.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Guides {
+    /// Mark the geographic poles
+    pub poles: bool,
+    /// Draw the equator
+    pub equator: bool,
+    /// Draw the tropics of Cancer and Capricorn
+    pub tropics: bool,
+    /// Year to mark the magnetic poles for, see `magnetic`, `None` leaving
+    /// them out
+    pub magnetic_year: Option<Float>,
+    /// Character the lines are drawn with
+    pub line_ch: char,
+    /// Character the geographic poles are marked with
+    pub pole_ch: char,
+    /// Character the magnetic poles are marked with
+    pub magnetic_ch: char,
+    /// Color of the guides, the globe's own if `None`
+    pub color: Option<Color>,
+}
+
+impl Default for Guides {
+    /// Geographic poles, equator and tropics, without the magnetic poles.
+    fn default() -> Self {
+        Self {
+            poles: true,
+            equator: true,
+            tropics: true,
+            magnetic_year: None,
+            line_ch: '-',
+            pole_ch: '+',
+            magnetic_ch: 'M',
+            color: Some(Color::new(120, 200, 255)),
+        }
+    }
+}
+
+impl Guides {
+    /// Marks the magnetic poles where they were in the given year too.
+    pub fn with_magnetic_poles(mut self, year: Float) -> Self {
+        self.magnetic_year = Some(year);
+        self
+    }
+
+    /// Sets the color of the guides, `None` keeping the globe's.
+    pub fn with_color(mut self, color: Option<Color>) -> Self {
+        self.color = color;
+        self
+    }
+}
+
+impl Globe {
+    /// Returns the reference marks drawn over the surface, if any.
+    pub fn guides(&self) -> Option<&Guides> {
+        self.guides.as_ref()
+    }
+
+    /// Sets or removes the reference marks drawn over the surface.
+    pub fn set_guides(&mut self, guides: Option<Guides>) {
+        self.guides = guides;
+    }
+
+    /// Draws the guides found on the visible side of the globe, skipping the
+    /// cells outside of `rect` if given.
+    pub(crate) fn render_guides_within<S: RenderTarget>(
+        &self,
+        canvas: &mut S,
+        rect: Option<CellRect>,
+    ) {
+        let guides = match &self.guides {
+            Some(guides) => guides,
+            None => return,
+        };
+        let equator = guides.equator.then_some(0.);
+        let tropics = guides.tropics.then_some([TROPIC_LAT, -TROPIC_LAT]);
+        for lat in equator.into_iter().chain(tropics.into_iter().flatten()) {
+            let path = |fraction: Float| GeoCoord::from_lat_lon(lat, fraction * 360. - 180.);
+            let length = 360. * lat.to_radians().cos();
+            self.render_path_within(canvas, rect, path, length, guides.line_ch, guides.color);
+        }
+
+        let mut marks = [None; 4];
+        if guides.poles {
+            marks[0] = Some((GeoCoord::new(0.5, 1.), guides.pole_ch));
+            marks[1] = Some((GeoCoord::new(0.5, 0.), guides.pole_ch));
+        }
+        if let Some(year) = guides.magnetic_year {
+            marks[2] = Some((magnetic::north_pole(year), guides.magnetic_ch));
+            marks[3] = Some((magnetic::south_pole(year), guides.magnetic_ch));
+        }
+        let color = CellColor {
+            fg: guides.color,
+            bg: None,
+        };
+        for &(coord, ch) in marks.iter().flatten() {
+            let (x, y) = match self.coord_cell(canvas, coord) {
+                Some(cell) => cell,
+                None => continue,
+            };
+            if rect.is_some_and(|rect| !rect.contains(x, y)) {
+                continue;
+            }
+            canvas.draw_colored_point(x, y, ch, color);
+        }
+    }
+}
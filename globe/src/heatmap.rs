@@ -0,0 +1,134 @@
+//! Density of samples over the surface drawn as a ramp of characters.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::data_grid::grid_index;
+#[cfg(not(any(feature = "std", test)))]
+use crate::float::FloatExt;
+use crate::{Color, Float, GeoCoord, Globe, Texture};
+
+/// Characters heatmaps are drawn with unless set otherwise, from the fewest
+/// samples to the most.
+const DEFAULT_RAMP: &str = ":-=+*#%@";
+
+/// Color heatmaps are drawn in unless set otherwise.
+const DEFAULT_COLOR: Color = Color::new(255, 70, 40);
+
+/// Counts of samples, e.g. locations of logins or earthquakes, binned over a
+/// grid laid over the surface like a texture.
+///
+/// Cells holding samples are drawn over the texture with a character picked
+/// from the ramp by how their count compares to the busiest cell's, so even
+/// a single sample shows up.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Heatmap {
+    width: usize,
+    height: usize,
+    counts: Vec<u32>,
+    /// Highest count of any cell
+    max: u32,
+    /// Characters the cells are drawn with, from the fewest samples to the
+    /// most
+    pub ramp: Vec<char>,
+    /// Color the cells are drawn in, the globe's own if `None`
+    pub color: Option<Color>,
+}
+
+impl Heatmap {
+    /// Creates an empty heatmap binning samples over a grid of the given
+    /// size, rows running from the north pole to the south one.
+    pub fn new(width: usize, height: usize) -> Self {
+        let (width, height) = (width.max(1), height.max(1));
+        Self {
+            width,
+            height,
+            counts: vec![0; width * height],
+            max: 0,
+            ramp: DEFAULT_RAMP.chars().collect(),
+            color: Some(DEFAULT_COLOR),
+        }
+    }
+
+    /// Creates an empty heatmap with one bin for every cell of the texture.
+    pub fn for_texture(texture: &Texture) -> Self {
+        let (width, height) = texture.get_size();
+        Self::new(width + 1, height + 1)
+    }
+
+    /// Sets the characters the cells are drawn with, from the fewest samples
+    /// to the most.
+    pub fn with_ramp(mut self, ramp: &str) -> Self {
+        self.ramp = ramp.chars().collect();
+        self
+    }
+
+    /// Sets the color the cells are drawn in on canvases with colors
+    /// enabled, `None` keeping the globe's.
+    pub fn with_color(mut self, color: Option<Color>) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Adds a sample at the given location.
+    pub fn add(&mut self, coord: GeoCoord) {
+        let bin = self.bin(coord);
+        self.counts[bin] = self.counts[bin].saturating_add(1);
+        self.max = self.max.max(self.counts[bin]);
+    }
+
+    /// Removes all the samples.
+    pub fn clear(&mut self) {
+        self.counts.fill(0);
+        self.max = 0;
+    }
+
+    /// Returns the number of samples in the cell the location falls in.
+    pub fn count_at(&self, coord: GeoCoord) -> u32 {
+        self.counts[self.bin(coord)]
+    }
+
+    /// Returns the character the cell the location falls in is drawn with,
+    /// `None` if it holds no samples.
+    pub(crate) fn char_at(&self, coord: GeoCoord) -> Option<char> {
+        let count = self.count_at(coord);
+        if count == 0 || self.ramp.is_empty() {
+            return None;
+        }
+        let steps = self.ramp.len() as u64;
+        let step = (count as u64 - 1) * steps / self.max as u64;
+        Some(self.ramp[step as usize])
+    }
+
+    /// Returns the index of the cell the location falls in.
+    fn bin(&self, coord: GeoCoord) -> usize {
+        grid_index(coord, self.width, self.height)
+    }
+}
+
+impl Globe {
+    /// Returns the heatmap drawn over the surface, if any.
+    pub fn heatmap(&self) -> Option<&Heatmap> {
+        self.heatmap.as_ref()
+    }
+
+    /// Returns the heatmap drawn over the surface for adding samples to it,
+    /// if any.
+    pub fn heatmap_mut(&mut self) -> Option<&mut Heatmap> {
+        self.heatmap.as_mut()
+    }
+
+    /// Sets or removes the heatmap drawn over the surface.
+    pub fn set_heatmap(&mut self, heatmap: Option<Heatmap>) {
+        self.heatmap = heatmap;
+    }
+
+    /// Returns the heatmap character to draw at the given texture
+    /// coordinates, see `Hit`, along with the heatmap.
+    pub(crate) fn heatmap_at(&self, theta: Float, phi: Float) -> Option<(char, &Heatmap)> {
+        let heatmap = self.heatmap.as_ref()?;
+        // texture rows are stored reversed, running from east to west
+        let coord = GeoCoord::new((1. - theta).rem_euclid(1.), 1. - phi);
+        Some((heatmap.char_at(coord)?, heatmap))
+    }
+}
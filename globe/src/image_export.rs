@@ -0,0 +1,183 @@
+//! Saving rendered canvases as images.
+//!
+//! Enabled with the `image` feature.
+
+use std::path::Path;
+
+use image::{Rgb, RgbImage};
+
+use crate::font::{self, GLYPH_HEIGHT, GLYPH_WIDTH};
+use crate::render::{BRAILLE_BLANK, BRAILLE_DOTS, QUADRANTS};
+use crate::{Canvas, Cell, Color, Error, Result};
+
+/// Color of characters without a foreground color.
+const DEFAULT_FG: Color = Color::new(255, 255, 255);
+/// Color of cells without a background color.
+const DEFAULT_BG: Color = Color::new(0, 0, 0);
+
+/// Size of a single character cell when rasterizing a canvas.
+///
+/// Text characters are drawn using a built-in 5x7 bitmap font, scaled by
+/// whole pixels to fit the cell, so cells should be at least 6 by 8 pixels
+/// large. Braille and block characters fill the cell exactly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FontMetrics {
+    /// Width of a cell in pixels
+    pub cell_width: u32,
+    /// Height of a cell in pixels
+    pub cell_height: u32,
+}
+
+impl Default for FontMetrics {
+    fn default() -> Self {
+        Self::new(8, 16)
+    }
+}
+
+impl FontMetrics {
+    /// Creates metrics with cells of the given size in pixels.
+    pub fn new(cell_width: u32, cell_height: u32) -> Self {
+        Self {
+            cell_width,
+            cell_height,
+        }
+    }
+
+    /// Creates metrics keeping the proportions of the canvas' characters,
+    /// each canvas pixel becoming `scale` by `scale` image pixels.
+    pub fn for_canvas(canvas: &Canvas, scale: u32) -> Self {
+        let (char_x, char_y) = canvas.char_pix();
+        Self::new(char_x as u32 * scale, char_y as u32 * scale)
+    }
+}
+
+impl Canvas {
+    /// Rasterizes the character grid into an image, drawing white characters
+    /// on black unless the canvas stores colors.
+    pub fn to_image(&self, metrics: FontMetrics) -> RgbImage {
+        rasterize(self.grid_size(), |col, row| self.cell(col, row), metrics)
+    }
+
+    /// Rasterizes the character grid and saves it as a PNG image, see
+    /// `Canvas::to_image`.
+    pub fn save_png<P: AsRef<Path>>(&self, path: P, metrics: FontMetrics) -> Result<()> {
+        self.to_image(metrics)
+            .save_with_format(path, image::ImageFormat::Png)
+            .map_err(Error::SaveImage)
+    }
+}
+
+/// Rasterizes a grid of `cols` by `rows` cells, as returned by `cell`.
+pub(crate) fn rasterize<F>((cols, rows): (usize, usize), cell: F, metrics: FontMetrics) -> RgbImage
+where
+    F: Fn(usize, usize) -> Option<Cell>,
+{
+    let (cell_w, cell_h) = (metrics.cell_width, metrics.cell_height);
+    let mut image = RgbImage::new(cols as u32 * cell_w, rows as u32 * cell_h);
+    for row in 0..rows {
+        for col in 0..cols {
+            let cell = match cell(col, row) {
+                Some(cell) => cell,
+                None => continue,
+            };
+            let fg = to_rgb(cell.fg.unwrap_or(DEFAULT_FG));
+            let bg = to_rgb(cell.bg.unwrap_or(DEFAULT_BG));
+            let shape = Shape::of(cell.ch, metrics);
+            for y in 0..cell_h {
+                for x in 0..cell_w {
+                    let color = if shape.covers(x, y) { fg } else { bg };
+                    image.put_pixel(col as u32 * cell_w + x, row as u32 * cell_h + y, color);
+                }
+            }
+        }
+    }
+    image
+}
+
+/// Pixels covered by a character within its cell.
+enum Shape {
+    /// Glyph of the bitmap font, scaled and placed within the cell
+    Glyph {
+        rows: [u8; 7],
+        scale: (u32, u32),
+        offset: (i64, i64),
+    },
+    /// Quadrants of the cell, filled where the bits are set, see
+    /// `QUADRANTS`
+    Quadrants { bits: u32, metrics: FontMetrics },
+    /// Braille dots, laid out in 2 columns of 4
+    Braille { bits: u32, metrics: FontMetrics },
+}
+
+impl Shape {
+    fn of(ch: char, metrics: FontMetrics) -> Shape {
+        if let Some(bits) = QUADRANTS.iter().position(|&q| q == ch) {
+            return Shape::Quadrants {
+                bits: bits as u32,
+                metrics,
+            };
+        }
+        let code = ch as u32;
+        if (BRAILLE_BLANK..BRAILLE_BLANK + 0x100).contains(&code) {
+            return Shape::Braille {
+                bits: code - BRAILLE_BLANK,
+                metrics,
+            };
+        }
+
+        // characters the font doesn't cover are drawn as question marks
+        let rows = font::glyph(ch)
+            .or_else(|| font::glyph('?'))
+            .unwrap_or_default();
+        // glyphs keep a pixel of spacing on the right and at the bottom
+        let scale = (
+            (metrics.cell_width / (GLYPH_WIDTH + 1)).max(1),
+            (metrics.cell_height / (GLYPH_HEIGHT + 1)).max(1),
+        );
+        let offset = (
+            (metrics.cell_width as i64 - (GLYPH_WIDTH * scale.0) as i64) / 2,
+            (metrics.cell_height as i64 - (GLYPH_HEIGHT * scale.1) as i64) / 2,
+        );
+        Shape::Glyph {
+            rows,
+            scale,
+            offset,
+        }
+    }
+
+    /// Returns true if the pixel at the given position within the cell is
+    /// covered.
+    fn covers(&self, x: u32, y: u32) -> bool {
+        match self {
+            Shape::Glyph {
+                rows,
+                scale,
+                offset,
+            } => {
+                let gx = x as i64 - offset.0;
+                let gy = y as i64 - offset.1;
+                gx >= 0 && gy >= 0 && font::is_set(rows, gx as u32 / scale.0, gy as u32 / scale.1)
+            }
+            Shape::Quadrants { bits, metrics } => {
+                let qx = x * 2 / metrics.cell_width;
+                let qy = y * 2 / metrics.cell_height;
+                bits >> (qy * 2 + qx) & 1 == 1
+            }
+            Shape::Braille { bits, metrics } => {
+                // position within the cell, in dot cells
+                let dx = x * 2 / metrics.cell_width;
+                let dy = y * 4 / metrics.cell_height;
+                // dots take up the middle half of their dot cell
+                let fx = (x * 2 % metrics.cell_width) * 4 / metrics.cell_width;
+                let fy = (y * 4 % metrics.cell_height) * 4 / metrics.cell_height;
+                (1..3).contains(&fx)
+                    && (1..3).contains(&fy)
+                    && bits & BRAILLE_DOTS[dy as usize][dx as usize] != 0
+            }
+        }
+    }
+}
+
+fn to_rgb(color: Color) -> Rgb<u8> {
+    Rgb([color.r, color.g, color.b])
+}
@@ -0,0 +1,63 @@
+//! Loading textures from image files.
+//!
+//! Enabled with the `image` feature.
+
+use std::path::Path;
+
+use image::imageops::FilterType;
+
+use crate::{Color, Error, Float, Result, Texture};
+
+impl Texture {
+    /// Loads a texture from a PNG or JPEG image holding an equirectangular
+    /// map of the globe.
+    ///
+    /// The image is scaled down to `size` characters (columns and rows) and
+    /// every pixel's luminance is mapped onto the `palette`, ordered from
+    /// darkest to brightest. The image colors are kept as the texture's color
+    /// layer.
+    pub fn from_image_path<P: AsRef<Path>>(
+        path: P,
+        palette: &[char],
+        size: (u32, u32),
+    ) -> Result<Self> {
+        if palette.is_empty() {
+            return Err(Error::InvalidPalette);
+        }
+        let (width, height) = size;
+        if width == 0 || height == 0 {
+            return Err(Error::EmptyTexture);
+        }
+
+        let image = image::open(path)?
+            .resize_exact(width, height, FilterType::Triangle)
+            .to_rgb8();
+
+        let max_index = (palette.len() - 1) as Float;
+        let mut day = Vec::with_capacity(height as usize);
+        let mut colors = Vec::with_capacity(height as usize);
+        for row in image.rows() {
+            // texture rows are stored mirrored, same as the text textures
+            let row: Vec<Color> = row
+                .rev()
+                .map(|pixel| Color::new(pixel[0], pixel[1], pixel[2]))
+                .collect();
+            day.push(
+                row.iter()
+                    .map(|color| palette[(color.luminance() * max_index).round() as usize])
+                    .collect(),
+            );
+            colors.push(row);
+        }
+
+        let mut texture = Texture::new(day, None, Some(palette.to_vec()));
+        texture.set_colors(Some(colors));
+        Ok(texture)
+    }
+}
+
+impl From<image::ImageError> for Error {
+    fn from(e: image::ImageError) -> Self {
+        Error::Image(e)
+    }
+}
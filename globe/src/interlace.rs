@@ -0,0 +1,26 @@
+//! Rendering every other canvas row per frame.
+
+use crate::{Canvas, Globe};
+
+impl Globe {
+    /// Renders only every other row of the canvas, alternating between even
+    /// and odd rows on consecutive calls.
+    ///
+    /// Rows that are skipped keep the previous frame's content, so the canvas
+    /// shouldn't be cleared beforehand, apart from markers and other
+    /// overlays, which are erased from them and drawn where they are now.
+    /// Halves the rendering cost of a frame at the price of some combing on
+    /// fast moving parts of the image. On a fresh canvas the first call fills
+    /// in only the even rows.
+    pub fn render_on_interlaced(&self, canvas: &mut Canvas) {
+        let (_, rows) = canvas.grid_size();
+        let first = canvas.odd_field as usize;
+        for row in (first..rows).step_by(2) {
+            canvas.clear_row(row);
+            self.render_row(canvas, row);
+        }
+        canvas.odd_field = !canvas.odd_field;
+        self.erase_stale_overlays(canvas, |row| row % 2 != first);
+        self.render_markers(canvas);
+    }
+}
@@ -0,0 +1,145 @@
+//! Text written next to locations on the surface, e.g. the names of cities.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::{CellColor, CellRect, Color, Float, GeoCoord, Globe, RenderTarget};
+
+/// Cells left between a location and its label.
+const GAP: usize = 1;
+
+/// Text written on the canvas next to where a location is drawn, as long as
+/// the location is on the visible side of the globe.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Label {
+    /// Location the label belongs to
+    pub coord: GeoCoord,
+    /// Text of the label
+    pub text: String,
+    /// Color the label is written in, the globe's own if `None`
+    pub color: Option<Color>,
+}
+
+impl Label {
+    /// Creates a label with the text for the location.
+    pub fn new(coord: GeoCoord, text: &str) -> Self {
+        Self {
+            coord,
+            text: text.to_string(),
+            color: None,
+        }
+    }
+
+    /// Sets the color the label is written in on canvases with colors
+    /// enabled.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+impl Globe {
+    /// Adds a label with the text for the location given as latitude and
+    /// longitude in degrees.
+    pub fn add_label(&mut self, lat: Float, lon: Float, text: &str) {
+        self.labels
+            .push(Label::new(GeoCoord::from_lat_lon(lat, lon), text));
+    }
+
+    /// Returns the labels written over the globe.
+    pub fn labels(&self) -> &[Label] {
+        &self.labels
+    }
+
+    /// Returns the labels written over the globe, e.g. for changing their
+    /// colors.
+    pub fn labels_mut(&mut self) -> &mut [Label] {
+        &mut self.labels
+    }
+
+    /// Removes all the labels.
+    pub fn clear_labels(&mut self) {
+        self.labels.clear();
+    }
+
+    /// Writes the labels of the locations found on the visible side of the
+    /// globe, skipping the cells outside of `rect` if given.
+    ///
+    /// Each label goes on the right of its location, or on its left, above
+    /// or below it if that keeps more of it over the globe rather than
+    /// running off its edge. Labels that would cover ones written before
+    /// them are left out.
+    pub(crate) fn render_labels_within<S: RenderTarget>(
+        &self,
+        canvas: &mut S,
+        rect: Option<CellRect>,
+    ) {
+        let mut taken: Vec<CellRect> = Vec::new();
+        for label in &self.labels {
+            let placed = self
+                .coord_cell(canvas, label.coord)
+                .and_then(|cell| self.place_label(canvas, cell, &label.text, &taken));
+            let placed = match placed {
+                Some(placed) => placed,
+                None => continue,
+            };
+            taken.push(placed);
+
+            let color = CellColor {
+                fg: label.color,
+                bg: None,
+            };
+            for (col, ch) in label.text.chars().enumerate() {
+                let (x, y) = (placed.x + col, placed.y);
+                if rect.is_some_and(|rect| !rect.contains(x, y)) {
+                    continue;
+                }
+                canvas.draw_colored_point(x, y, ch, color);
+            }
+        }
+    }
+
+    /// Picks the cells the text is written over next to the given cell,
+    /// `None` if it fits nowhere on the canvas without covering the cells
+    /// already taken.
+    fn place_label<S: RenderTarget>(
+        &self,
+        canvas: &S,
+        (x, y): (usize, usize),
+        text: &str,
+        taken: &[CellRect],
+    ) -> Option<CellRect> {
+        let (cols, rows) = canvas.grid_size();
+        let width = text.chars().count();
+        if width == 0 || width > cols {
+            return None;
+        }
+        let centered = x.saturating_sub(width / 2).min(cols - width);
+        let candidates = [
+            Some((x + 1 + GAP, y)),
+            x.checked_sub(GAP + width).map(|left| (left, y)),
+            y.checked_sub(1).map(|above| (centered, above)),
+            Some((centered, y + 1)),
+        ];
+        let mut best: Option<(usize, CellRect)> = None;
+        for &(left, row) in candidates.iter().flatten() {
+            if left + width > cols || row >= rows {
+                continue;
+            }
+            let placed = CellRect::new(left, row, width, 1);
+            if taken.iter().any(|other| other.overlaps(&placed)) {
+                continue;
+            }
+            let covered = (left..left + width)
+                .filter(|&col| self.covers_cell(canvas, (col, row)))
+                .count();
+            if covered == width {
+                return Some(placed);
+            }
+            if best.is_none_or(|(most, _)| covered > most) {
+                best = Some((covered, placed));
+            }
+        }
+        best.map(|(_, placed)| placed)
+    }
+}
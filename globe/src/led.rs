@@ -0,0 +1,232 @@
+//! Driving physical displays, like LED matrices or a Linux framebuffer, with
+//! the globe.
+//!
+//! Enabled with the `led` feature.
+
+#[cfg(target_os = "linux")]
+use std::fs::{self, File, OpenOptions};
+#[cfg(target_os = "linux")]
+use std::io;
+#[cfg(target_os = "linux")]
+use std::path::Path;
+
+use crate::render::Sample;
+#[cfg(target_os = "linux")]
+use crate::Error;
+use crate::{Color, Float, Globe, Int, Result};
+
+/// Display made up of individually colored pixels.
+///
+/// Implement it for the canvas of an LED matrix driver, e.g. the one from
+/// `rpi-led-matrix`, setting pixels on an offscreen canvas and swapping it
+/// in on `present`.
+pub trait PixelSink {
+    /// Returns the size of the display in pixels.
+    fn size(&self) -> (usize, usize);
+
+    /// Sets the color of a pixel, `(0, 0)` being the top left corner.
+    fn set_pixel(&mut self, x: usize, y: usize, color: Color);
+
+    /// Shows all the pixels set since the last call.
+    fn present(&mut self) -> Result<()>;
+}
+
+/// Renders the globe straight onto a pixel display, one ray per pixel.
+///
+/// Pixels get their brightness from the position of the texture character
+/// on the palette, which can be remapped with `with_levels`, and are lit in
+/// the tint color. Colored textures keep their colors. The globe is drawn
+/// in the largest square fitting the display, with markers left out.
+#[derive(Clone, Debug)]
+pub struct LedAdapter {
+    /// Brightness of each palette index
+    levels: Option<Vec<u8>>,
+    tint: Color,
+    max_brightness: u8,
+}
+
+impl Default for LedAdapter {
+    fn default() -> Self {
+        Self {
+            levels: None,
+            tint: Color::new(255, 255, 255),
+            max_brightness: 255,
+        }
+    }
+}
+
+impl LedAdapter {
+    /// Creates an adapter lighting pixels white, brighter the further along
+    /// the palette their character is.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the brightness of every palette index, `levels[i]` being used
+    /// for the `i`-th palette character. Characters past the end of the list
+    /// keep their brightness from the palette position.
+    pub fn with_levels(mut self, levels: Vec<u8>) -> Self {
+        self.levels = Some(levels);
+        self
+    }
+
+    /// Sets the color of pixels at full brightness.
+    pub fn with_tint(mut self, color: Color) -> Self {
+        self.tint = color;
+        self
+    }
+
+    /// Caps the brightness of all pixels, e.g. to keep an LED matrix from
+    /// drawing too much current.
+    pub fn with_max_brightness(mut self, brightness: u8) -> Self {
+        self.max_brightness = brightness;
+        self
+    }
+
+    /// Renders a frame of the globe onto the display and shows it.
+    pub fn render<S: PixelSink + ?Sized>(&self, globe: &Globe, sink: &mut S) -> Result<()> {
+        let (width, height) = sink.size();
+        let side = width.min(height);
+        let offset = ((width - side) / 2, (height - side) / 2);
+        let half = (side / 2) as Int;
+
+        let black = Color::new(0, 0, 0);
+        for y in 0..height {
+            for x in 0..width {
+                let (xi, yi) = (x.wrapping_sub(offset.0), y.wrapping_sub(offset.1));
+                if half == 0 || xi >= side || yi >= side {
+                    sink.set_pixel(x, y, black);
+                    continue;
+                }
+                let ray_x = -((xi as Int - half) as Float + 0.5) / half as Float;
+                let ray_y = ((yi as Int - half) as Float + 0.5) / half as Float;
+                let color = globe
+                    .sample(ray_x, ray_y)
+                    .map_or(black, |sample| self.pixel(&sample));
+                sink.set_pixel(x, y, color);
+            }
+        }
+        sink.present()
+    }
+
+    /// Returns the color of the pixel showing the sample.
+    fn pixel(&self, sample: &Sample) -> Color {
+        let max = self.max_brightness as Float / 255.;
+        if let Some(color) = sample.color {
+            return color.scale(max);
+        }
+        let level = match (&self.levels, sample.index) {
+            (Some(levels), Some(index)) if index < levels.len() => levels[index] as Float / 255.,
+            _ => sample.intensity,
+        };
+        self.tint.scale(level * max)
+    }
+}
+
+/// Linux framebuffer device, e.g. `/dev/fb0`.
+///
+/// Pixels are kept in memory and written out to the device on `present`.
+/// 16 bit (RGB565), 24 and 32 bit pixel formats are supported.
+#[cfg(target_os = "linux")]
+pub struct Framebuffer {
+    device: File,
+    size: (usize, usize),
+    bytes_per_pixel: usize,
+    /// Length of a line in bytes, including any padding
+    stride: usize,
+    buffer: Vec<u8>,
+}
+
+#[cfg(target_os = "linux")]
+impl Framebuffer {
+    /// Opens the framebuffer device, reading its geometry from sysfs.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let name = path.file_name().unwrap_or_default();
+        let sysfs = Path::new("/sys/class/graphics").join(name);
+        let read = |attr: &str| -> Result<String> {
+            fs::read_to_string(sysfs.join(attr))
+                .map(|value| value.trim().to_string())
+                .map_err(Error::Output)
+        };
+
+        let virtual_size = read("virtual_size")?;
+        let (width, height) = virtual_size
+            .split_once(',')
+            .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+            .ok_or_else(|| invalid_data("malformed framebuffer size"))?;
+        let bits_per_pixel = read("bits_per_pixel")?
+            .parse()
+            .map_err(|_| invalid_data("malformed framebuffer pixel depth"))?;
+        let stride = read("stride")?
+            .parse()
+            .map_err(|_| invalid_data("malformed framebuffer stride"))?;
+        Self::with_geometry(path, (width, height), bits_per_pixel, stride)
+    }
+
+    /// Opens the framebuffer device with the given geometry, for when it
+    /// can't be read from sysfs.
+    pub fn with_geometry<P: AsRef<Path>>(
+        path: P,
+        size: (usize, usize),
+        bits_per_pixel: usize,
+        stride: usize,
+    ) -> Result<Self> {
+        let bytes_per_pixel = match bits_per_pixel {
+            16 | 24 | 32 => bits_per_pixel / 8,
+            _ => return Err(invalid_data("unsupported framebuffer pixel depth")),
+        };
+        if stride < size.0 * bytes_per_pixel {
+            return Err(invalid_data("framebuffer stride shorter than a line"));
+        }
+        let device = OpenOptions::new()
+            .write(true)
+            .open(path)
+            .map_err(Error::Output)?;
+        Ok(Self {
+            device,
+            size,
+            bytes_per_pixel,
+            stride,
+            buffer: vec![0; stride * size.1],
+        })
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl PixelSink for Framebuffer {
+    fn size(&self) -> (usize, usize) {
+        self.size
+    }
+
+    fn set_pixel(&mut self, x: usize, y: usize, color: Color) {
+        if x >= self.size.0 || y >= self.size.1 {
+            return;
+        }
+        let offset = y * self.stride + x * self.bytes_per_pixel;
+        let pixel = &mut self.buffer[offset..offset + self.bytes_per_pixel];
+        match pixel {
+            [lo, hi] => {
+                let rgb565 =
+                    (color.r as u16 >> 3) << 11 | (color.g as u16 >> 2) << 5 | color.b as u16 >> 3;
+                *lo = rgb565 as u8;
+                *hi = (rgb565 >> 8) as u8;
+            }
+            // pixels are stored in little-endian order, blue first
+            _ => pixel[..3].copy_from_slice(&[color.b, color.g, color.r]),
+        }
+    }
+
+    fn present(&mut self) -> Result<()> {
+        use std::os::unix::fs::FileExt;
+
+        self.device
+            .write_all_at(&self.buffer, 0)
+            .map_err(Error::Output)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn invalid_data(message: &str) -> Error {
+    Error::Output(io::Error::new(io::ErrorKind::InvalidData, message))
+}
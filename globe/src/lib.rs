@@ -2,23 +2,252 @@
 //!
 //! Based on [C++ code by DinoZ1729](https://github.com/DinoZ1729/Earth).
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![allow(dead_code)]
 
-use std::f32::consts::PI;
+extern crate alloc;
+
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+use core::mem;
+use core::ops::Range;
+#[cfg(feature = "std")]
 use std::fs::File;
+#[cfg(feature = "std")]
 use std::io::Read;
 
+#[cfg(feature = "airports")]
+pub mod airports;
+mod animation;
+mod ansi;
+mod arc;
+mod atmosphere;
+#[cfg(feature = "bevy")]
+pub mod bevy_plugin;
+#[cfg(feature = "std")]
+mod budget;
+mod clouds;
+pub mod color;
+mod contour;
+#[cfg(feature = "controller")]
+pub mod controller;
+mod data_grid;
+mod data_series;
+#[cfg(feature = "egui")]
+pub mod egui_widget;
+pub mod error;
+mod fixed;
+#[cfg(not(any(feature = "std", test)))]
+mod float;
+mod focus;
+#[cfg(feature = "image")]
+mod font;
+mod frames;
+pub mod geo;
+mod geofence;
+mod guides;
+mod heatmap;
+#[cfg(feature = "image")]
+mod image_export;
+#[cfg(feature = "image")]
+mod image_texture;
+mod interlace;
+mod label;
+#[cfg(feature = "led")]
+pub mod led;
+mod light;
+mod line_layer;
+pub mod magnetic;
+mod marker;
+pub mod math;
+pub mod moon;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+pub mod orbit;
+mod projection;
+mod ray_cache;
+#[cfg(feature = "image")]
+mod recorder;
+mod region;
+mod render;
+mod rings;
+mod schedule;
+#[cfg(all(feature = "simd", not(feature = "fixed-point"), not(feature = "f64")))]
+mod simd;
+mod smoothing;
+mod snapshot;
+pub mod sun;
+pub mod svg;
+mod target;
+#[cfg(feature = "test-support")]
+pub mod test_support;
+mod theme;
+mod tile;
+mod vector_field;
+
+pub use animation::{Easing, Transition};
+pub use arc::GreatArc;
+pub use atmosphere::Atmosphere;
+#[cfg(feature = "std")]
+pub use budget::FrameReport;
+pub use clouds::Clouds;
+pub use color::{Cell, CellColor, Color, Coloring};
+pub use data_grid::{Blend, DataGrid};
+pub use data_series::DataSeries;
+pub use error::{Error, Result};
+pub use fixed::FixedCanvas;
+#[cfg(not(any(feature = "std", test)))]
+use float::FloatExt;
+#[allow(deprecated)]
+pub use focus::FocusAnimation;
+pub use frames::Frames;
+pub use geo::GeoCoord;
+pub use geofence::Geofence;
+pub use guides::Guides;
+pub use heatmap::Heatmap;
+#[cfg(feature = "image")]
+pub use image_export::FontMetrics;
+pub use label::Label;
+pub use light::{Light, LightSource, Specular, Terminator};
+pub use line_layer::LineLayer;
+pub use marker::{Marker, MarkerGroup};
+use math::Real;
+pub use ray_cache::RayCache;
+#[cfg(feature = "image")]
+pub use recorder::{AnimationFormat, Recorder};
+pub use region::CellRect;
+pub use render::RenderStyle;
+use render::Sample;
+pub use rings::Rings;
+pub use schedule::{ColorSchedule, DayPhase};
+pub use smoothing::SmoothingHistory;
+pub use snapshot::{render_snapshot, SnapshotOptions};
+pub use target::RenderTarget;
+use target::WithoutHistory;
+pub use theme::{MarkerStyle, Theme, COLORBLIND_SAFE_COLORING, COLORBLIND_SAFE_COLORS};
+pub use vector_field::VectorField;
+
 pub type Int = isize;
+/// Floating point type used throughout the crate, `f64` with the `f64`
+/// feature enabled.
+#[cfg(not(feature = "f64"))]
 pub type Float = f32;
+#[cfg(feature = "f64")]
+pub type Float = f64;
+
+use consts::PI;
+/// Mathematical constants of the `Float` type.
+#[cfg(not(feature = "f64"))]
+pub(crate) use core::f32::consts;
+#[cfg(feature = "f64")]
+pub(crate) use core::f64::consts;
+
+/// Number type used for tracing rays.
+#[cfg(not(feature = "fixed-point"))]
+type Scalar = Float;
+#[cfg(feature = "fixed-point")]
+type Scalar = math::Fixed;
+
+/// Point a ray hit on the globe, see `Globe::trace`.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Hit {
+    /// Horizontal texture coordinate, left unwrapped, see `WrapMode`
+    theta: Float,
+    /// Vertical texture coordinate
+    phi: Float,
+    /// Light the point receives, in the `0..1` range
+    luminance: Float,
+    /// Cosine of the angle between the surface normal and the direction
+    /// towards the light, negative on the night side
+    light_cos: Float,
+    /// Strength of the specular highlight, zero where there's none
+    glint: Float,
+}
+
+/// Maximum number of rays traced together, see `Globe::sample_batch`.
+const BATCH: usize = 8;
 
 static EARTH_TEXTURE: &str = include_str!("../textures/earth.txt");
 static EARTH_NIGHT_TEXTURE: &str = include_str!("../textures/earth_night.txt");
+static MOON_TEXTURE: &str = include_str!("../textures/moon.txt");
+static MARS_TEXTURE: &str = include_str!("../textures/mars.txt");
+static SATURN_TEXTURE: &str = include_str!("../textures/saturn.txt");
+
+const EARTH_PALETTE: [char; 18] = [
+    ' ', '.', ':', ';', '\'', ',', 'w', 'i', 'o', 'g', 'O', 'L', 'X', 'H', 'W', 'Y', 'V', '@',
+];
+const ROCKY_PALETTE: [char; 10] = [' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'];
+
+const EARTH_OCEAN: Color = Color::new(40, 90, 200);
+const EARTH_LAND: Color = Color::new(60, 170, 70);
+const MOON_MARE: Color = Color::new(105, 105, 110);
+const MOON_HIGHLAND: Color = Color::new(175, 175, 170);
+const MOON_BRIGHT: Color = Color::new(225, 225, 215);
+const MARS_DARK: Color = Color::new(125, 65, 45);
+const MARS_DUST: Color = Color::new(195, 100, 60);
+const MARS_ICE: Color = Color::new(235, 235, 240);
+const SATURN_DARK: Color = Color::new(170, 130, 85);
+const SATURN_BAND: Color = Color::new(215, 185, 135);
+const SATURN_LIGHT: Color = Color::new(240, 225, 185);
+/// Radius of the `Saturn` template, small enough for the rings to fit into
+/// the view.
+const SATURN_RADIUS: Float = 0.42;
+/// Axial tilt of Saturn in degrees.
+const SATURN_TILT: Float = 26.7;
+/// Color specular highlights blend towards.
+const GLINT_COLOR: Color = Color::new(255, 255, 240);
+
+/// Way of mapping horizontal texture coordinates that fall outside of the
+/// texture back onto it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WrapMode {
+    /// Texture repeats itself.
+    #[default]
+    Repeat,
+    /// Edge columns of the texture are stretched.
+    Clamp,
+    /// Texture repeats itself, mirrored every other time.
+    Mirror,
+}
+
+impl WrapMode {
+    /// Maps the coordinate into the `0..=1` range.
+    fn apply(self, t: Float) -> Float {
+        match self {
+            WrapMode::Repeat => t - t.floor(),
+            WrapMode::Clamp => clamp(t, 0., 1.),
+            WrapMode::Mirror => {
+                let t = t.rem_euclid(2.);
+                if t > 1. {
+                    2. - t
+                } else {
+                    t
+                }
+            }
+        }
+    }
+}
+
+/// Palette index of texture characters missing from the palette.
+const NO_INDEX: u8 = u8::MAX;
 
 /// Globe texture.
+///
+/// Layers are stored row by row in contiguous memory, with the characters
+/// already translated into palette indices.
 pub struct Texture {
-    day: Vec<Vec<char>>,
-    night: Option<Vec<Vec<char>>>,
+    day: Layer<char>,
+    // palette indices of the day characters, empty without a palette
+    day_indices: Vec<u8>,
+    night: Option<Layer<u8>>,
     palette: Option<Vec<char>>,
+    colors: Option<Layer<Color>>,
+    // cells reflecting light, see `Specular`
+    specular: Option<Layer<bool>>,
+    wrap: WrapMode,
 }
 
 impl Texture {
@@ -27,23 +256,213 @@ impl Texture {
         night: Option<Vec<Vec<char>>>,
         palette: Option<Vec<char>>,
     ) -> Self {
-        Texture {
-            day,
-            night,
+        let mut texture = Texture {
+            day: Layer::from_rows(Vec::new()),
+            day_indices: Vec::new(),
+            night: None,
             palette,
-        }
+            colors: None,
+            specular: None,
+            wrap: WrapMode::default(),
+        };
+        texture.set_day(day);
+        texture.set_night(night);
+        texture
     }
     pub fn get_size(&self) -> (usize, usize) {
-        (self.day[0].len() - 1, self.day.len() - 1)
+        (self.day.width - 1, self.day.height - 1)
+    }
+    /// Sets the color layer of the texture, laid out the same way as the day
+    /// texture.
+    pub fn set_colors(&mut self, colors: Option<Vec<Vec<Color>>>) {
+        self.colors = colors.map(Layer::from_rows);
+    }
+    /// Returns true if the texture has a color layer.
+    pub fn has_colors(&self) -> bool {
+        self.colors.is_some()
+    }
+    /// Sets the cells reflecting light, laid out the same way as the day
+    /// texture, see `Specular`.
+    pub fn set_specular_mask(&mut self, mask: Option<Vec<Vec<bool>>>) {
+        self.specular = mask.map(Layer::from_rows);
+    }
+    /// Makes the cells drawn with any of the given characters reflect light,
+    /// replacing the mask set before.
+    pub fn set_specular_chars(&mut self, chars: &[char]) {
+        self.specular = Some(Layer {
+            cells: self.day.cells.iter().map(|ch| chars.contains(ch)).collect(),
+            width: self.day.width,
+            height: self.day.height,
+            uneven: self.day.uneven,
+        });
+    }
+    /// Returns true if the texture has cells reflecting light.
+    pub fn has_specular_mask(&self) -> bool {
+        self.specular.is_some()
+    }
+    /// Sets how the texture wraps horizontally.
+    pub fn set_wrap(&mut self, wrap: WrapMode) {
+        self.wrap = wrap;
+    }
+    /// Returns how the texture wraps horizontally.
+    pub fn wrap(&self) -> WrapMode {
+        self.wrap
+    }
+    /// Swaps the palette characters for the given ones, ordered from dark to
+    /// bright like the palette and spread evenly over it. Textures without
+    /// a palette are left as they are.
+    pub fn remap_palette(&mut self, chars: &[char]) {
+        let palette = match &mut self.palette {
+            Some(palette) if !chars.is_empty() => palette,
+            _ => return,
+        };
+        let steps = palette.len().saturating_sub(1).max(1) as Float;
+        let last = (chars.len() - 1) as Float;
+        for (i, ch) in palette.iter_mut().enumerate() {
+            *ch = chars[(i as Float / steps * last).round() as usize];
+        }
+        // day characters are drawn as they are, swap them along
+        for (ch, &index) in self.day.cells.iter_mut().zip(&self.day_indices) {
+            if let Some(&mapped) = palette.get(index as usize) {
+                *ch = mapped;
+            }
+        }
+    }
+    fn set_day(&mut self, day: Vec<Vec<char>>) {
+        self.day = Layer::from_rows(day);
+        self.day_indices = match &self.palette {
+            Some(palette) => {
+                let lut = PaletteLut::new(palette);
+                self.day.cells.iter().map(|&ch| lut.index(ch)).collect()
+            }
+            None => Vec::new(),
+        };
+    }
+    fn set_night(&mut self, night: Option<Vec<Vec<char>>>) {
+        self.night = night.map(|night| {
+            let lut = PaletteLut::new(self.palette.as_deref().unwrap_or_default());
+            Layer::from_rows(
+                night
+                    .into_iter()
+                    .map(|row| row.into_iter().map(|ch| lut.index(ch)).collect())
+                    .collect(),
+            )
+        });
+    }
+    /// Checks that the texture can be safely sampled when rendering.
+    fn validate(&self) -> Result<()> {
+        if self.day.width == 0 {
+            return Err(Error::EmptyTexture);
+        }
+        if self.day.uneven {
+            return Err(Error::UnevenTexture);
+        }
+        let (width, height) = (self.day.width, self.day.height);
+        let night_matches = self
+            .night
+            .as_ref()
+            .is_none_or(|night| night.has_size(width, height));
+        let colors_match = self
+            .colors
+            .as_ref()
+            .is_none_or(|colors| colors.has_size(width, height));
+        let specular_matches = self
+            .specular
+            .as_ref()
+            .is_none_or(|mask| mask.has_size(width, height));
+        if !night_matches || !colors_match || !specular_matches {
+            return Err(Error::TextureSizeMismatch);
+        }
+        if let Some(palette) = &self.palette {
+            if palette.is_empty() || palette.len() > NO_INDEX as usize {
+                return Err(Error::InvalidPalette);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Single layer of a texture, stored row by row in one buffer.
+#[derive(Clone, Debug)]
+struct Layer<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+    // source rows differed in length, the layer can't be sampled
+    uneven: bool,
+}
+
+impl<T> Layer<T> {
+    fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let width = rows.first().map_or(0, Vec::len);
+        let height = rows.len();
+        let uneven = rows.iter().any(|row| row.len() != width);
+        Layer {
+            cells: rows.into_iter().flatten().collect(),
+            width,
+            height,
+            uneven,
+        }
+    }
+    /// Returns the position of the given cell in the buffer.
+    fn offset(&self, x: usize, y: usize) -> usize {
+        y * self.width + x
+    }
+    fn get(&self, x: usize, y: usize) -> &T {
+        &self.cells[self.offset(x, y)]
+    }
+    /// Checks whether the layer has the given dimensions.
+    fn has_size(&self, width: usize, height: usize) -> bool {
+        !self.uneven && self.width == width && self.height == height
+    }
+}
+
+/// Character cell aspect presets for the `Canvas`.
+///
+/// Each preset describes how many canvas pixels make up a single printed
+/// character, horizontally and vertically.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CanvasPreset {
+    /// Single pixel per character.
+    Square,
+    /// Regular terminal character cell, 4 pixels wide and 8 pixels tall.
+    #[default]
+    Terminal4x8,
+    /// Braille character cell, 2 pixels wide and 4 pixels tall.
+    Braille2x4,
+}
+
+impl CanvasPreset {
+    /// Returns the character size in pixels.
+    pub fn char_pix(&self) -> (usize, usize) {
+        match self {
+            CanvasPreset::Square => (1, 1),
+            CanvasPreset::Terminal4x8 => (4, 8),
+            CanvasPreset::Braille2x4 => (2, 4),
+        }
     }
 }
 
 /// Canvas that will be used to render the globe onto.
 pub struct Canvas {
-    pub matrix: Vec<Vec<char>>,
+    matrix: Vec<Vec<char>>,
+    colors: Option<Vec<Vec<CellColor>>>,
     size: (usize, usize),
+    // character and colors empty cells are filled with
+    background: char,
+    background_color: CellColor,
     // character size
-    pub char_pix: (usize, usize),
+    char_pix: (usize, usize),
+    // row to continue from when rendering within a time budget
+    resume_row: usize,
+    // whether odd rows are next when rendering interlaced
+    odd_field: bool,
+    // palette index history of every cell, kept across frames
+    history: SmoothingHistory,
+    // directions of the rays shot through the canvas, kept across frames
+    rays: RayCache,
+    // cells overlays were drawn on in the last frame
+    overlay_cells: Vec<(usize, usize)>,
 }
 
 impl Canvas {
@@ -56,125 +475,808 @@ impl Canvas {
         Self {
             size: (x, y),
             matrix,
-            char_pix: cp.unwrap_or((4, 8)),
+            colors: None,
+            background: ' ',
+            background_color: CellColor::default(),
+            char_pix: cp.unwrap_or_else(|| CanvasPreset::default().char_pix()),
+            resume_row: 0,
+            odd_field: false,
+            history: SmoothingHistory::new(),
+            rays: RayCache::new(),
+            overlay_cells: Vec::new(),
         }
     }
+    /// Creates a new canvas using character size from the given preset.
+    pub fn with_preset(x: u16, y: u16, preset: CanvasPreset) -> Self {
+        Self::new(x, y, Some(preset.char_pix()))
+    }
+    /// Creates the largest square canvas that fits into a terminal of the
+    /// given size (in characters).
+    pub fn for_terminal(term_size: (u16, u16), preset: CanvasPreset) -> Self {
+        let (char_x, char_y) = preset.char_pix();
+        let side = (term_size.0 as usize * char_x).min(term_size.1 as usize * char_y);
+        let side = side.min(u16::MAX as usize) as u16;
+        Self::with_preset(side, side, preset)
+    }
+    /// Enables storing colors alongside the characters.
+    pub fn with_colors(mut self) -> Self {
+        self.colors = Some(vec![vec![self.background_color; self.size.0]; self.size.1]);
+        self
+    }
+    /// Fills the cells the globe doesn't cover with the given character
+    /// instead of a space, e.g. for compositing the canvas over other text.
+    pub fn with_background(mut self, ch: char) -> Self {
+        self.set_background(ch);
+        self
+    }
+    /// Fills the cells the globe doesn't cover with the given colors, on
+    /// canvases storing colors.
+    pub fn with_background_color(mut self, color: CellColor) -> Self {
+        self.set_background_color(color);
+        self
+    }
+    /// Returns the character empty cells are filled with.
+    pub fn background(&self) -> char {
+        self.background
+    }
+    /// Returns the colors empty cells are filled with.
+    pub fn background_color(&self) -> CellColor {
+        self.background_color
+    }
+    /// Sets the character empty cells are filled with, refilling the
+    /// canvas.
+    pub fn set_background(&mut self, ch: char) {
+        self.background = ch;
+        self.clear();
+    }
+    /// Sets the colors empty cells are filled with, refilling the canvas.
+    pub fn set_background_color(&mut self, color: CellColor) {
+        self.background_color = color;
+        self.clear();
+    }
+    /// Returns true if the canvas stores colors.
+    pub fn has_colors(&self) -> bool {
+        self.colors.is_some()
+    }
     pub fn get_size(&self) -> (usize, usize) {
         self.size
     }
+    /// Returns how many canvas pixels make up a single character,
+    /// horizontally and vertically.
+    pub fn char_pix(&self) -> (usize, usize) {
+        self.char_pix
+    }
+    /// Returns the number of character columns and rows the globe is
+    /// rendered into.
+    pub fn grid_size(&self) -> (usize, usize) {
+        (self.size.0 / self.char_pix.0, self.size.1 / self.char_pix.1)
+    }
+    /// Returns the character at the given position.
+    pub fn char_at(&self, x: usize, y: usize) -> Option<char> {
+        self.matrix.get(y)?.get(x).copied()
+    }
+    /// Returns an iterator over the rendered rows of characters, limited to
+    /// the character grid.
+    pub fn rows(&self) -> impl Iterator<Item = &[char]> {
+        let (cols, rows) = self.grid_size();
+        self.matrix.iter().take(rows).map(move |row| &row[..cols])
+    }
+    /// Returns the underlying character matrix.
+    #[deprecated(
+        since = "0.3.0",
+        note = "use `Canvas::rows` or `Canvas::char_at` instead"
+    )]
+    pub fn matrix(&self) -> &[Vec<char>] {
+        &self.matrix
+    }
+    /// Returns the cell at the given position, including its colors.
+    pub fn cell(&self, x: usize, y: usize) -> Option<Cell> {
+        let ch = *self.matrix.get(y)?.get(x)?;
+        let color = self
+            .colors
+            .as_ref()
+            .map(|colors| colors[y][x])
+            .unwrap_or_default();
+        Some(Cell {
+            ch,
+            fg: color.fg,
+            bg: color.bg,
+        })
+    }
     pub fn clear(&mut self) {
         for i in self.matrix.iter_mut().flatten() {
-            *i = ' ';
+            *i = self.background;
+        }
+        if let Some(colors) = &mut self.colors {
+            for color in colors.iter_mut().flatten() {
+                *color = self.background_color;
+            }
+        }
+    }
+    fn clear_row(&mut self, row: usize) {
+        let (background, background_color) = (self.background, self.background_color);
+        if let Some(chars) = self.matrix.get_mut(row) {
+            chars.iter_mut().for_each(|c| *c = background);
+        }
+        if let Some(colors) = self.colors.as_mut().and_then(|colors| colors.get_mut(row)) {
+            colors.iter_mut().for_each(|c| *c = background_color);
         }
     }
+    fn clear_cell(&mut self, col: usize, row: usize) {
+        self.draw_colored_point(col, row, self.background, self.background_color);
+    }
     fn draw_point(&mut self, a: usize, b: usize, c: char) {
         if a >= self.size.0 || b >= self.size.1 {
             return;
         }
         self.matrix[b][a] = c;
     }
+    fn draw_colored_point(&mut self, a: usize, b: usize, c: char, color: CellColor) {
+        if a >= self.size.0 || b >= self.size.1 {
+            return;
+        }
+        self.matrix[b][a] = c;
+        if let Some(colors) = &mut self.colors {
+            colors[b][a] = color;
+        }
+    }
+}
+
+impl fmt::Display for Canvas {
+    /// Writes out the character grid, rows separated by newlines.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                f.write_char('\n')?;
+            }
+            for &ch in row {
+                f.write_char(ch)?;
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Main globe abstraction.
 pub struct Globe {
-    pub camera: Camera,
-    pub radius: Float,
-    pub angle: Float,
-    pub texture: Texture,
-    pub display_night: bool,
+    camera: Camera,
+    radius: Float,
+    angle: Float,
+    texture: Texture,
+    display_night: bool,
+    coloring: Option<Coloring>,
+    render_style: RenderStyle,
+    temporal_smoothing: Float,
+    markers: Vec<Marker>,
+    /// Look and visibility of marker groups, by name
+    marker_groups: BTreeMap<String, MarkerGroup>,
+    /// Location the sun is overhead, lighting the globe in place of the
+    /// light's own source if set
+    sun: Option<GeoCoord>,
+    light: Light,
+    /// Highlight reflected off shiny parts of the texture, if enabled
+    specular: Option<Specular>,
+    /// Line drawn between the day and night sides, if enabled
+    terminator: Option<Terminator>,
+    /// Glow drawn around the globe, if enabled
+    atmosphere: Option<Atmosphere>,
+    /// Clouds drifting over the surface
+    clouds: Option<Clouds>,
+    /// Flat rings around the equator
+    rings: Option<Rings>,
+    /// Regions drawn over the surface, in the order they were added
+    geofences: Vec<Geofence>,
+    /// Layers of lines drawn over the surface, in the order they were added
+    line_layers: Vec<LineLayer>,
+    /// Great circle arcs drawn over the surface
+    arcs: Vec<GreatArc>,
+    /// Text written next to locations, in the order they were added
+    labels: Vec<Label>,
+    /// Density of samples drawn over the surface, if any
+    heatmap: Option<Heatmap>,
+    /// Grid thinning out the characters of land, if any
+    density: Option<DataGrid>,
+    /// Grids of values laid over the texture, in the order they were added
+    data_grids: Vec<DataGrid>,
+    /// Series of grids played back over the texture, drawn over the grids
+    data_series: Vec<DataSeries>,
+    /// Fields of vectors drawn as arrows, in the order they were added
+    vector_fields: Vec<VectorField>,
+    /// Reference marks drawn over the surface, if any
+    guides: Option<Guides>,
+    /// Unit vector pointing towards the light, updated along with the angle,
+    /// `None` for lights with a position
+    light_direction: Option<[Float; 3]>,
+    /// Position of the light turned along with the globe's axis, `None` for
+    /// lights coming from a direction
+    light_position: Option<[Float; 3]>,
 }
 
 impl Globe {
-    pub fn render_on(&self, canvas: &mut Canvas) {
-        // let there be light
-        let light: [Float; 3] = [0., 999999., 0.];
-        // shoot the ray through every pixel
-        let (size_x, size_y) = canvas.get_size();
-        for yi in 0..size_y {
-            let yif = yi as Int;
-            for xi in 0..size_x {
+    /// Returns the camera looking at the globe.
+    pub fn camera(&self) -> &Camera {
+        &self.camera
+    }
+
+    /// Returns the camera for changing its position.
+    pub fn camera_mut(&mut self) -> &mut Camera {
+        &mut self.camera
+    }
+
+    /// Returns the globe radius.
+    pub fn radius(&self) -> Float {
+        self.radius
+    }
+
+    /// Sets the globe radius.
+    pub fn set_radius(&mut self, radius: Float) {
+        self.radius = radius;
+    }
+
+    /// Returns the rotation angle of the globe around its axis.
+    pub fn angle(&self) -> Float {
+        self.angle
+    }
+
+    /// Sets the rotation angle of the globe around its axis.
+    pub fn set_angle(&mut self, angle: Float) {
+        self.angle = angle;
+        self.update_light();
+    }
+
+    /// Returns the angle in degrees the axis of the globe is tilted by.
+    pub fn axial_tilt(&self) -> Float {
+        self.camera.tilt.to_degrees()
+    }
+
+    /// Tilts the axis the globe spins around by the given angle in degrees,
+    /// leaning its north pole towards the negative y axis.
+    ///
+    /// The camera keeps its angles, which stay relative to the untilted
+    /// axis.
+    pub fn set_axial_tilt(&mut self, degrees: Float) {
+        self.camera.set_tilt(degrees.to_radians());
+        self.update_light();
+    }
+
+    /// Returns the location the sun is overhead, if the globe is lit by it.
+    pub fn sun(&self) -> Option<GeoCoord> {
+        self.sun
+    }
+
+    /// Lights the globe from the sun standing overhead the given location,
+    /// turning with the globe as it spins, or from the light's own source if
+    /// `None`.
+    pub fn set_sun(&mut self, sun: Option<GeoCoord>) {
+        self.sun = sun;
+        self.update_light();
+    }
+
+    /// Lights the globe from where the sun is at the given Unix time in
+    /// seconds, see `sun::subsolar_point`.
+    pub fn set_sun_from_time(&mut self, unix_secs: i64) {
+        self.set_sun(Some(sun::subsolar_point(unix_secs)));
+    }
+
+    /// Returns the texture displayed on the globe.
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    /// Replaces the texture displayed on the globe, failing the same way
+    /// building a globe with it would.
+    pub fn set_texture(&mut self, texture: Texture) -> Result<()> {
+        texture.validate()?;
+        self.texture = texture;
+        Ok(())
+    }
+
+    /// Returns true if the night side of the globe is displayed.
+    pub fn display_night(&self) -> bool {
+        self.display_night
+    }
+
+    /// Toggles displaying the night side of the globe.
+    pub fn set_display_night(&mut self, b: bool) {
+        self.display_night = b;
+    }
+
+    /// Returns the method of deriving colors, if any.
+    pub fn coloring(&self) -> Option<Coloring> {
+        self.coloring
+    }
+
+    /// Sets the method of deriving colors, `None` disables colors.
+    pub fn set_coloring(&mut self, coloring: Option<Coloring>) {
+        self.coloring = coloring;
+    }
+
+    /// Returns the style used for drawing the globe.
+    pub fn render_style(&self) -> RenderStyle {
+        self.render_style
+    }
+
+    /// Sets the style used for drawing the globe.
+    pub fn set_render_style(&mut self, style: RenderStyle) {
+        self.render_style = style;
+    }
+
+    /// Returns the strength of smoothing between consecutive frames.
+    pub fn temporal_smoothing(&self) -> Float {
+        self.temporal_smoothing
+    }
+
+    /// Sets the strength of smoothing between consecutive frames, see
+    /// `GlobeConfig::with_temporal_smoothing`.
+    pub fn set_temporal_smoothing(&mut self, strength: Float) {
+        self.temporal_smoothing = clamp(strength, 0., 1.);
+    }
+
+    /// Orients the camera so that it focuses on the given location.
+    pub fn focus(&mut self, coord: GeoCoord) {
+        self.camera.focus(coord, self.angle);
+    }
+
+    /// Points the camera at the given latitude and longitude, in degrees.
+    pub fn focus_lat_lon(&mut self, lat: Float, lon: Float) {
+        self.camera.look_at_lat_lon(lat, lon, self.angle);
+    }
+
+    /// Returns the location at the center of the view, right below the
+    /// camera.
+    pub fn view_center(&self) -> GeoCoord {
+        let camera = [self.camera.x, self.camera.y, self.camera.z];
+        let (theta, phi) = texture_coords(camera, self.angle);
+        // texture rows are stored reversed, running from east to west
+        GeoCoord::new((1. - theta).rem_euclid(1.), 1. - phi)
+    }
+
+    /// Renders the globe onto the canvas, or any other render target, using
+    /// the selected render style.
+    pub fn render_on<T: RenderTarget>(&self, target: &mut T) {
+        let (_, rows) = target.grid_size();
+        for row in 0..rows {
+            self.render_row(target, row);
+        }
+        self.render_markers(target);
+    }
+
+    /// Clears the canvas, renders the globe onto it and returns the frame as
+    /// text, rows separated by newlines.
+    pub fn render_to_string(&self, canvas: &mut Canvas) -> String {
+        canvas.clear();
+        self.render_on(canvas);
+        canvas.to_string()
+    }
+
+    /// Renders the globe onto the fixed size canvas using the selected render
+    /// style, without allocating.
+    pub fn render_on_fixed<const W: usize, const H: usize>(&self, canvas: &mut FixedCanvas<W, H>) {
+        self.render_on(canvas);
+    }
+
+    /// Renders a single row of character cells, drawing only the cells the
+    /// globe covers.
+    fn render_row<S: RenderTarget>(&self, canvas: &mut S, row: usize) {
+        let (cols, _) = canvas.grid_size();
+        self.render_row_span(canvas, row, 0..cols);
+    }
+
+    /// Renders the globe again under the cells overlays were drawn on in the
+    /// last frame, on the rows `stale` tells were left out of this one, so
+    /// that markers that moved don't linger on them.
+    fn erase_stale_overlays(&self, canvas: &mut Canvas, stale: impl Fn(usize) -> bool) {
+        let (cols, rows) = canvas.grid_size();
+        let cells = mem::take(&mut canvas.overlay_cells);
+        // the rest of the row isn't moving on to the next frame either
+        let mut target = WithoutHistory(&mut *canvas);
+        let erased = cells
+            .iter()
+            .filter(|&&(col, row)| col < cols && row < rows && stale(row));
+        for &(col, row) in erased {
+            target.clear_cell(col, row);
+            self.render_row_span(&mut target, row, col..col + 1);
+        }
+        canvas.overlay_cells = cells;
+    }
+
+    /// Renders the given columns of a single row of character cells.
+    fn render_row_span<S: RenderTarget>(&self, canvas: &mut S, row: usize, span: Range<usize>) {
+        match self.render_style {
+            RenderStyle::Ascii => self.render_ascii_row(canvas, row, span),
+            RenderStyle::Braille => self.render_braille_row(canvas, row, span),
+            RenderStyle::HalfBlock => self.render_half_block_row(canvas, row, span),
+            RenderStyle::Quadrant => self.render_quadrant_row(canvas, row, span),
+        }
+    }
+
+    /// Renders a row of the globe drawing a single texture character per
+    /// canvas cell.
+    fn render_ascii_row<S: RenderTarget>(&self, canvas: &mut S, yi: usize, span: Range<usize>) {
+        // shoot the ray through every character cell
+        let (cols, rows) = canvas.grid_size();
+        let half_x = (cols / 2) as Int;
+        let half_y = (rows / 2) as Int;
+        let extent = view_extent((cols, rows), canvas.char_pix());
+        let yif = yi as Int;
+        ray_cache::validate(canvas, &self.camera, (cols, rows));
+        for first in span.clone().step_by(BATCH) {
+            let batch = first..(first + BATCH).min(span.end);
+            let mut dirs = [[Scalar::ZERO; 3]; BATCH];
+            for (dir, xi) in dirs.iter_mut().zip(batch.clone()) {
                 let xif = xi as Int;
-                // coordinates of the camera, origin of the ray
-                let o: [Float; 3] = [self.camera.x, self.camera.y, self.camera.z];
-                // u is unit vector, direction of the ray
-                let mut u: [Float; 3] = [
-                    -((xif - (size_x / canvas.char_pix.0 / 2) as Int) as Float + 0.5)
-                        / (size_x / canvas.char_pix.0 / 2) as Float,
-                    ((yif - (size_y / canvas.char_pix.1 / 2) as Int) as Float + 0.5)
-                        / (size_y / canvas.char_pix.1 / 2) as Float,
-                    -1.,
-                ];
-                transform_vector(&mut u, self.camera.matrix);
-                u[0] -= self.camera.x;
-                u[1] -= self.camera.y;
-                u[2] -= self.camera.z;
-                normalize(&mut u);
-                let dot_uo = dot(&u, &o);
-                let discriminant: Float = dot_uo * dot_uo - dot(&o, &o) + self.radius * self.radius;
-
-                // ray doesn't hit the sphere
-                if discriminant < 0. {
-                    continue;
+                let ray_x = -((xif - half_x) as Float + 0.5) / half_x as Float * extent.0;
+                let ray_y = ((yif - half_y) as Float + 0.5) / half_y as Float * extent.1;
+                *dir = ray_cache::direction(canvas, (xi, yi), || self.ray_direction(ray_x, ray_y));
+            }
+            let samples = self.sample_batch(&dirs[..batch.len()]);
+
+            for (xi, sample) in batch.zip(samples) {
+                let sample = match sample {
+                    Some(sample) => sample,
+                    // ray doesn't hit the sphere
+                    None => {
+                        if let Some(history) = smoothing::cell_history(canvas, xi, yi) {
+                            *history = None;
+                        }
+                        continue;
+                    }
+                };
+                let ch = self.smoothed_char(canvas, xi, yi, &sample);
+
+                if canvas.has_colors() {
+                    let color = CellColor {
+                        fg: sample.color,
+                        bg: None,
+                    };
+                    canvas.draw_colored_point(xi, yi, ch, color);
+                } else {
+                    canvas.draw_point(xi, yi, ch);
                 }
+            }
+        }
+    }
 
-                let distance: Float = -discriminant.sqrt() - dot_uo;
-
-                // intersection point
-                let inter: [Float; 3] = [
-                    o[0] + distance * u[0],
-                    o[1] + distance * u[1],
-                    o[2] + distance * u[2],
-                ];
-
-                // surface normal
-                let mut n: [Float; 3] = [
-                    o[0] + distance * u[0],
-                    o[1] + distance * u[1],
-                    o[2] + distance * u[2],
-                ];
-                normalize(&mut n);
-
-                // unit vector pointing from intersection to light source
-                let mut l: [Float; 3] = [0.; 3];
-                vector(&mut l, &inter, &light);
-                normalize(&mut l);
-                let luminance: Float = clamp(5. * (dot(&n, &l)) + 0.5, 0., 1.);
-                let mut temp: [Float; 3] = [inter[0], inter[1], inter[2]];
-                rotate_x(&mut temp, -PI * 2. * 0. / 360.);
-
-                // computing coordinates for the sphere
-                let phi: Float = -temp[2] / self.radius / 2. + 0.5;
-                let mut theta: Float = (temp[1] / temp[0]).atan() / PI + 0.5 + self.angle / 2. / PI;
-                // let mut theta: Float = (temp[1] / temp[0]).atan() / PI + self.angle / 2. / PI * 20.;
-                theta -= theta.floor();
-                let (tex_x, tex_y) = self.texture.get_size();
-                let earth_x = (theta * tex_x as Float) as usize;
-                let earth_y = (phi * tex_y as Float) as usize;
-
-                // if night texture and palette are available, draw the night side
-                if self.display_night
-                    && self.texture.night.is_some()
-                    && self.texture.palette.is_some()
-                {
-                    let palette = self.texture.palette.as_ref().unwrap();
-                    let day = find_index(self.texture.day[earth_y][earth_x], palette);
-                    let night = find_index(
-                        self.texture.night.as_ref().unwrap()[earth_y][earth_x],
-                        palette,
-                    );
-
-                    let mut index =
-                        ((1.0 - luminance) * night as Float + luminance * day as Float) as usize;
-                    if index >= palette.len() {
-                        index = 0;
+    /// Picks the character for the cell, blending the sample's palette index
+    /// with the ones drawn there in previous frames if smoothing is enabled.
+    fn smoothed_char<S: RenderTarget>(
+        &self,
+        canvas: &mut S,
+        col: usize,
+        row: usize,
+        sample: &Sample,
+    ) -> char {
+        if self.temporal_smoothing <= 0. {
+            return sample.ch;
+        }
+        let history = match smoothing::cell_history(canvas, col, row) {
+            Some(history) => history,
+            None => return sample.ch,
+        };
+        match (sample.index, &self.texture.palette) {
+            (Some(index), Some(palette)) => {
+                let index = smoothing::smooth(history, index, self.temporal_smoothing);
+                palette.get(index).copied().unwrap_or(sample.ch)
+            }
+            _ => {
+                // characters off the palette can't be blended
+                *history = None;
+                sample.ch
+            }
+        }
+    }
+
+    /// Shoots a single ray at the globe and samples the texture where it hits
+    /// the sphere.
+    ///
+    /// `ray_x` and `ray_y` are screen coordinates of the ray, with the
+    /// `-1..1` range covering the view.
+    fn sample(&self, ray_x: Float, ray_y: Float) -> Option<Sample> {
+        self.sample_direction(self.ray_direction(ray_x, ray_y))
+    }
+
+    /// Shoots a ray going in the given direction from the camera, see
+    /// `Globe::sample`. Rings in front of the globe and clouds cover the
+    /// surface where present, rays missing the globe can still sample its
+    /// atmosphere.
+    fn sample_direction(&self, u: [Scalar; 3]) -> Option<Sample> {
+        if let Some(sample) = self.sample_rings(u).or_else(|| self.sample_clouds(u)) {
+            return Some(sample);
+        }
+        match self.trace(u) {
+            Some(hit) => Some(self.sample_hit(hit)),
+            None => self.sample_atmosphere(u),
+        }
+    }
+
+    /// Shoots rays going in the given directions, up to `BATCH` of them,
+    /// returning their samples in the same order.
+    fn sample_batch(&self, dirs: &[[Scalar; 3]]) -> [Option<Sample>; BATCH] {
+        let hits = self.trace_batch(dirs);
+        let mut samples = [None; BATCH];
+        for ((sample, hit), &u) in samples.iter_mut().zip(hits).zip(dirs) {
+            let cover = self.sample_rings(u).or_else(|| self.sample_clouds(u));
+            *sample = match (cover, hit) {
+                (Some(cloud), _) => Some(cloud),
+                (None, Some(hit)) => Some(self.sample_hit(hit)),
+                (None, None) => self.sample_atmosphere(u),
+            };
+        }
+        samples
+    }
+
+    /// Traces the rays one by one, see `Globe::trace`.
+    #[cfg(not(all(feature = "simd", not(feature = "fixed-point"), not(feature = "f64"))))]
+    fn trace_batch(&self, dirs: &[[Scalar; 3]]) -> [Option<Hit>; BATCH] {
+        let mut hits = [None; BATCH];
+        for (hit, &u) in hits.iter_mut().zip(dirs) {
+            *hit = self.trace(u);
+        }
+        hits
+    }
+
+    /// Samples the texture at the point a ray hit.
+    fn sample_hit(&self, hit: Hit) -> Sample {
+        let Hit {
+            theta,
+            phi,
+            luminance,
+            light_cos,
+            glint,
+        } = hit;
+        let fence = self.geofence_at(theta, phi);
+        let heat = self.heatmap_at(theta, phi);
+        let data = self.data_style_at(theta, phi);
+        // the wrap mode applies to the texture's own longitude, spinning the
+        // globe only moves that around
+        let spin = self.angle / 2. / PI;
+        let theta = WrapMode::Repeat.apply(self.texture.wrap.apply(theta - spin) + spin);
+        let (tex_x, tex_y) = self.texture.get_size();
+        // rounding errors may push the coordinates slightly out of bounds
+        let earth_x = ((theta * tex_x as Float) as usize).min(tex_x);
+        let earth_y = ((phi * tex_y as Float) as usize).min(tex_y);
+
+        let offset = self.texture.day.offset(earth_x, earth_y);
+        let day_ch = self.texture.day.cells[offset];
+        let palette = self.texture.palette.as_ref();
+        let day_index = palette.map(|_| self.texture.day_indices[offset]);
+
+        // if night texture and palette are available, draw the night side
+        let (mut ch, mut index) = if let (true, Some(night), Some(palette), Some(day)) =
+            (self.display_night, &self.texture.night, palette, day_index)
+        {
+            let day = index_value(day);
+            let night = index_value(*night.get(earth_x, earth_y));
+
+            let mut index = ((1.0 - luminance) * night + luminance * day) as usize;
+            if index >= palette.len() {
+                index = 0;
+            }
+            (palette[index], Some(index))
+        }
+        // else just draw the day texture without considering luminance
+        else {
+            (
+                day_ch,
+                day_index.filter(|i| *i != NO_INDEX).map(usize::from),
+            )
+        };
+
+        // sparsely filled land steps down the palette
+        if let (Some(i), Some(palette)) = (index, palette) {
+            let thinned = self.thinned_index(i, (theta, phi), (earth_x, earth_y));
+            if thinned != i {
+                ch = palette[thinned];
+                index = Some(thinned);
+            }
+        }
+
+        // shiny cells light up towards the brightest character
+        let glint = match &self.texture.specular {
+            Some(mask) if *mask.get(earth_x, earth_y) => glint,
+            _ => 0.,
+        };
+        if let (Some(i), Some(palette), true) = (index, palette, glint > 0.) {
+            let brightest = palette.len() - 1;
+            let i = (i + ((brightest - i) as Float * glint).round() as usize).min(brightest);
+            ch = palette[i];
+            index = Some(i);
+        }
+
+        // data grids are laid over the texture
+        if let Some(data_ch) = data.and_then(|data| data.ch) {
+            ch = data_ch;
+            index = palette.and_then(|palette| palette.iter().position(|&c| c == ch));
+        }
+        // geofences are drawn over it
+        if let Some(fill) = fence.and_then(|fence| fence.fill) {
+            ch = fill;
+            index = palette.and_then(|palette| palette.iter().position(|&c| c == fill));
+        }
+        // followed by the heatmap
+        if let Some((heat_ch, _)) = heat {
+            ch = heat_ch;
+            index = palette.and_then(|palette| palette.iter().position(|&c| c == ch));
+        }
+        // and the terminator over everything
+        let twilight = self.twilight(light_cos);
+        if let Some((terminator, _)) = twilight.filter(|&(_, weight)| weight >= 1.) {
+            ch = terminator.ch;
+            index = palette.and_then(|palette| palette.iter().position(|&c| c == ch));
+        }
+
+        // how bright the character is, based on its position on the palette
+        let intensity = match (index, palette) {
+            (Some(index), Some(palette)) if palette.len() > 1 => {
+                index as Float / (palette.len() - 1) as Float
+            }
+            _ if ch.is_whitespace() => 0.,
+            _ => 1.,
+        };
+
+        let overlay_color = heat
+            .and_then(|(_, heatmap)| heatmap.color)
+            .or(fence.and_then(|fence| fence.color));
+        let mut color = match overlay_color {
+            Some(color) => Some(self.night_shaded(color, luminance)),
+            None => {
+                let surface = self
+                    .color_at(earth_x, earth_y, luminance)
+                    .map(|color| color.lerp(GLINT_COLOR, glint));
+                match (surface, data.and_then(|data| data.color)) {
+                    (Some(surface), Some((tint, weight))) => {
+                        Some(surface.lerp(self.night_shaded(tint, luminance), weight))
                     }
-                    canvas.draw_point(xi, yi, palette[index]);
-                }
-                // else just draw the day texture without considering luminance
-                else {
-                    canvas.draw_point(xi, yi, self.texture.day[earth_y][earth_x]);
+                    (None, Some((tint, _))) => Some(self.night_shaded(tint, luminance)),
+                    (surface, None) => surface,
                 }
             }
+        };
+        if let Some((terminator, weight)) = twilight {
+            color = match (color, terminator.color) {
+                (Some(surface), Some(line)) => Some(surface.lerp(line, weight)),
+                (None, line) if weight >= 1. => line,
+                (color, _) => color,
+            };
+        }
+
+        Sample {
+            ch,
+            index,
+            intensity,
+            color,
+        }
+    }
+
+    /// Returns the unit vector pointing from the camera through the given
+    /// screen coordinates.
+    ///
+    /// Only depends on the camera, so it stays the same while the globe
+    /// spins, see `RayCache`.
+    fn ray_direction<R: Real>(&self, ray_x: Float, ray_y: Float) -> [R; 3] {
+        let r = R::from_float;
+        let o = [r(self.camera.x), r(self.camera.y), r(self.camera.z)];
+        let mut u = [r(ray_x), r(ray_y), -R::ONE];
+        transform_vector(&mut u, self.camera.matrix.map(r));
+        u = [u[0] - o[0], u[1] - o[1], u[2] - o[2]];
+        normalize(&mut u);
+        u
+    }
+
+    /// Shoots a ray from the camera in the direction of the unit vector `u`,
+    /// returning the texture coordinates of the point it hits along with how
+    /// much light the point receives.
+    fn trace<R: Real>(&self, u: [R; 3]) -> Option<Hit> {
+        let inter = self.intersect(u, self.radius)?;
+
+        // surface normal
+        let mut n = inter;
+        normalize(&mut n);
+
+        // let there be light
+        let l = self.light_vector(inter);
+        let luminance = self.luminance(n, l);
+        let glint = self.glint(n, l, u);
+
+        let (theta, phi) = texture_coords(inter, self.angle);
+        Some(Hit {
+            theta,
+            phi,
+            luminance: luminance.to_float(),
+            light_cos: dot(&n, &l).to_float(),
+            glint,
+        })
+    }
+
+    /// Returns the point where a ray from the camera in the direction of the
+    /// unit vector `u` first hits a sphere of the given radius, centered
+    /// where the globe is.
+    fn intersect<R: Real>(&self, u: [R; 3], radius: Float) -> Option<[R; 3]> {
+        let r = R::from_float;
+        // coordinates of the camera, origin of the ray
+        let o = [r(self.camera.x), r(self.camera.y), r(self.camera.z)];
+        let radius = r(radius);
+        let dot_uo = dot(&u, &o);
+        let discriminant = dot_uo * dot_uo - dot(&o, &o) + radius * radius;
+
+        // ray doesn't hit the sphere
+        if discriminant < R::ZERO {
+            return None;
+        }
+
+        let distance = -discriminant.sqrt() - dot_uo;
+
+        // intersection point
+        Some([
+            o[0] + distance * u[0],
+            o[1] + distance * u[1],
+            o[2] + distance * u[2],
+        ])
+    }
+
+    /// Returns how much light a surface with the normal `n` receives from the
+    /// direction `l`, in the `0..1` range.
+    fn luminance<R: Real>(&self, n: [R; 3], l: [R; 3]) -> R {
+        let r = R::from_float;
+        clamp(
+            r(self.light.intensity) * dot(&n, &l) + r(self.light.ambient),
+            R::ZERO,
+            R::ONE,
+        )
+    }
+
+    /// Returns the unit vector pointing from the given point towards the
+    /// light.
+    fn light_vector<R: Real>(&self, point: [R; 3]) -> [R; 3] {
+        let r = R::from_float;
+        match (self.light_direction, self.light_position) {
+            (Some(direction), _) => direction.map(r),
+            (None, Some(p)) => {
+                let mut l = [r(p[0]) - point[0], r(p[1]) - point[1], r(p[2]) - point[2]];
+                normalize(&mut l);
+                l
+            }
+            // direction without a length, only ambient light is left
+            (None, None) => [R::ZERO; 3],
+        }
+    }
+
+    /// Returns the strength of the specular highlight at a point with the
+    /// surface normal `n`, lit from the direction `l` and seen along the ray
+    /// `u`, using the Blinn-Phong model.
+    fn glint<R: Real>(&self, n: [R; 3], l: [R; 3], u: [R; 3]) -> Float {
+        let specular = match self.specular {
+            Some(specular) if self.texture.specular.is_some() => specular,
+            _ => return 0.,
+        };
+        if dot(&n, &l) <= R::ZERO {
+            return 0.;
+        }
+        // halfway between the directions towards the light and the camera
+        let mut h = [l[0] - u[0], l[1] - u[1], l[2] - u[2]];
+        normalize(&mut h);
+        specular.highlight(dot(&n, &h).to_float())
+    }
+
+    /// Computes the color of the given texture position based on the selected
+    /// coloring method.
+    fn color_at(&self, tex_x: usize, tex_y: usize, luminance: Float) -> Option<Color> {
+        match self.coloring? {
+            Coloring::Texture => {
+                let color = *self.texture.colors.as_ref()?.get(tex_x, tex_y);
+                Some(self.night_shaded(color, luminance))
+            }
+            Coloring::Luminance { day, night } => Some(night.lerp(day, luminance)),
+        }
+    }
+
+    /// Darkens the color of a surface receiving the given amount of light if
+    /// the night side is displayed.
+    fn night_shaded(&self, color: Color, luminance: Float) -> Color {
+        if self.display_night {
+            color.scale(0.3 + 0.7 * luminance)
+        } else {
+            color
         }
     }
 }
@@ -188,6 +1290,18 @@ pub struct GlobeConfig {
     template: Option<GlobeTemplate>,
     texture: Option<Texture>,
     display_night: bool,
+    canvas_preset: CanvasPreset,
+    coloring: Option<Coloring>,
+    render_style: RenderStyle,
+    temporal_smoothing: Float,
+    sun: Option<GeoCoord>,
+    light: Light,
+    specular: Option<Specular>,
+    terminator: Option<Terminator>,
+    atmosphere: Option<Atmosphere>,
+    clouds: Option<Clouds>,
+    rings: Option<Rings>,
+    axial_tilt: Option<Float>,
 }
 
 impl GlobeConfig {
@@ -208,6 +1322,13 @@ impl GlobeConfig {
         self
     }
 
+    /// Tilts the axis the globe spins around by the given angle in degrees,
+    /// see `Globe::set_axial_tilt`.
+    pub fn with_axial_tilt(mut self, degrees: Float) -> Self {
+        self.axial_tilt = Some(degrees);
+        self
+    }
+
     /// Selects a template to be used by the builder.
     pub fn use_template(mut self, t: GlobeTemplate) -> Self {
         self.template = Some(t);
@@ -223,7 +1344,7 @@ impl GlobeConfig {
             day.push(row);
         }
         if let Some(texture) = &mut self.texture {
-            texture.day = day;
+            texture.set_day(day);
         } else {
             self.texture = Some(Texture::new(day, None, palette));
         }
@@ -240,7 +1361,7 @@ impl GlobeConfig {
         }
 
         if let Some(texture) = &mut self.texture {
-            texture.night = Some(night);
+            texture.set_night(Some(night));
         } else {
             self.texture = Some(Texture::new(night.clone(), Some(night), palette));
         }
@@ -248,12 +1369,164 @@ impl GlobeConfig {
         self
     }
 
+    /// Sets the texture to be displayed on the globe, replacing any texture
+    /// set before.
+    pub fn with_texture_data(mut self, texture: Texture) -> Self {
+        self.texture = Some(texture);
+        self
+    }
+
+    /// Sets the color layer of the texture.
+    ///
+    /// Every character of the given color texture is mapped to a color using
+    /// the `legend`, characters missing from the legend get the `default`
+    /// color. Must be called after the day texture is set.
+    pub fn with_color_texture(
+        mut self,
+        texture: &str,
+        legend: &[(char, Color)],
+        default: Color,
+    ) -> Self {
+        let colors = texture
+            .lines()
+            .map(|line| {
+                line.chars()
+                    .rev()
+                    .map(|c| {
+                        legend
+                            .iter()
+                            .find(|(ch, _)| *ch == c)
+                            .map(|(_, color)| *color)
+                            .unwrap_or(default)
+                    })
+                    .collect()
+            })
+            .collect();
+        if let Some(texture) = &mut self.texture {
+            texture.set_colors(Some(colors));
+        }
+        self
+    }
+
+    /// Sets the method of deriving colors when rendering onto a canvas with
+    /// colors enabled.
+    pub fn with_coloring(mut self, coloring: Coloring) -> Self {
+        self.coloring = Some(coloring);
+        self
+    }
+
+    /// Sets the style used for drawing the globe onto the canvas.
+    pub fn with_render_style(mut self, style: RenderStyle) -> Self {
+        self.render_style = style;
+        self
+    }
+
+    /// Enables smoothing of texture characters between consecutive frames,
+    /// which suppresses twinkling while the globe spins quickly, at the cost
+    /// of blurring moving features.
+    ///
+    /// `strength` in the `0..1` range is the weight given to the previous
+    /// frame, `0.` (the default) disables smoothing. Only applies to the
+    /// ASCII render style on canvases kept between frames, and only to
+    /// textures with a palette.
+    pub fn with_temporal_smoothing(mut self, strength: Float) -> Self {
+        self.temporal_smoothing = clamp(strength, 0., 1.);
+        self
+    }
+
+    /// Sets where the light falling on the globe comes from, how hard the
+    /// terminator falls off and how bright the night side gets, see
+    /// `Light`. By default light comes from far along the y axis.
+    pub fn with_light(mut self, source: LightSource, intensity: Float, ambient: Float) -> Self {
+        self.light = Light::new(source, intensity, ambient);
+        self
+    }
+
+    /// Enables highlights reflected off the shiny parts of the texture, e.g.
+    /// a glint on the ocean, see `Specular`. Which parts are shiny is set with
+    /// `GlobeConfig::with_specular_texture` or
+    /// `GlobeConfig::with_specular_chars`, the Earth template marks its oceans.
+    pub fn with_specular(mut self, strength: Float, shininess: Float) -> Self {
+        self.specular = Some(Specular::new(strength, shininess));
+        self
+    }
+
+    /// Draws the line between the day and night sides while the night side
+    /// is displayed, see `Terminator`.
+    pub fn with_terminator(mut self, terminator: Terminator) -> Self {
+        self.terminator = Some(terminator);
+        self
+    }
+
+    /// Sets the parts of the texture reflecting light, laid out the same way
+    /// as the day texture. Cells holding anything but whitespace are shiny.
+    /// Must be called after the day texture is set.
+    pub fn with_specular_texture(mut self, texture: &str) -> Self {
+        let mask = texture
+            .lines()
+            .map(|line| line.chars().rev().map(|c| !c.is_whitespace()).collect())
+            .collect();
+        if let Some(texture) = &mut self.texture {
+            texture.set_specular_mask(Some(mask));
+        }
+        self
+    }
+
+    /// Makes the cells of the day texture drawn with any of the given
+    /// characters shiny, e.g. the ones standing for water. Must be called
+    /// after the day texture is set.
+    pub fn with_specular_chars(mut self, chars: &[char]) -> Self {
+        if let Some(texture) = &mut self.texture {
+            texture.set_specular_chars(chars);
+        }
+        self
+    }
+
+    /// Covers the globe with a layer of clouds, see `Clouds`.
+    pub fn with_clouds(mut self, clouds: Clouds) -> Self {
+        self.clouds = Some(clouds);
+        self
+    }
+
+    /// Surrounds the globe with flat rings, see `Rings`.
+    pub fn with_rings(mut self, rings: Rings) -> Self {
+        self.rings = Some(rings);
+        self
+    }
+
+    /// Draws a glow of the atmosphere around the globe, see `Atmosphere`.
+    pub fn with_atmosphere(mut self, atmosphere: Atmosphere) -> Self {
+        self.atmosphere = Some(atmosphere);
+        self
+    }
+
+    /// Lights the globe from where the sun is at the given Unix time in
+    /// seconds, so that the night side matches reality. Takes the place of
+    /// the light's source.
+    pub fn with_sun_from_time(mut self, unix_secs: i64) -> Self {
+        self.sun = Some(sun::subsolar_point(unix_secs));
+        self
+    }
+
     /// Sets the day texture to be loaded from the given path.
-    pub fn with_texture_at(self, path: &str, palette: Option<Vec<char>>) -> Self {
-        let mut file = File::open(path).unwrap();
+    #[cfg(feature = "std")]
+    pub fn with_texture_at(self, path: &str, palette: Option<Vec<char>>) -> Result<Self> {
+        let mut file = File::open(path)?;
         let mut out_string = String::new();
-        file.read_to_string(&mut out_string).unwrap();
-        self.with_texture(&out_string, palette)
+        file.read_to_string(&mut out_string)?;
+        Ok(self.with_texture(&out_string, palette))
+    }
+
+    /// Sets the canvas preset used when creating recommended canvases.
+    pub fn with_canvas_preset(mut self, preset: CanvasPreset) -> Self {
+        self.canvas_preset = preset;
+        self
+    }
+
+    /// Creates a canvas best suited for displaying the globe in a terminal
+    /// of the given size (in characters).
+    pub fn recommended_canvas(&self, term_size: (u16, u16)) -> Canvas {
+        Canvas::for_terminal(term_size, self.canvas_preset)
     }
 
     /// Sets the night display toggle to the given value.
@@ -263,40 +1536,142 @@ impl GlobeConfig {
     }
 
     /// Builds new `Globe` from the collected configuration settings.
-    pub fn build(mut self) -> Globe {
+    ///
+    /// Fails if no texture was provided or the texture is malformed.
+    pub fn build(mut self) -> Result<Globe> {
         if let Some(template) = &self.template {
             match template {
                 GlobeTemplate::Earth => {
-                    let palette = vec![
-                        ' ', '.', ':', ';', '\'', ',', 'w', 'i', 'o', 'g', 'O', 'L', 'X', 'H', 'W',
-                        'Y', 'V', '@',
-                    ];
+                    let palette = template.palette().to_vec();
                     self = self
                         .with_texture(EARTH_TEXTURE, Some(palette.clone()))
                         .with_night_texture(EARTH_NIGHT_TEXTURE, Some(palette))
+                        .with_color_texture(EARTH_TEXTURE, &[('.', EARTH_OCEAN)], EARTH_LAND)
+                        .with_specular_chars(&['.'])
+                }
+                GlobeTemplate::Moon => {
+                    let palette = template.palette().to_vec();
+                    // the moon has no lights of its own, night side stays dark
+                    let night = blank_texture(MOON_TEXTURE);
+                    self = self
+                        .with_texture(MOON_TEXTURE, Some(palette.clone()))
+                        .with_night_texture(&night, Some(palette))
+                        .with_color_texture(
+                            MOON_TEXTURE,
+                            &[
+                                (':', MOON_MARE),
+                                ('-', MOON_MARE),
+                                ('%', MOON_BRIGHT),
+                                ('@', MOON_BRIGHT),
+                            ],
+                            MOON_HIGHLAND,
+                        )
+                }
+                GlobeTemplate::Mars => {
+                    let palette = template.palette().to_vec();
+                    let night = blank_texture(MARS_TEXTURE);
+                    self = self
+                        .with_texture(MARS_TEXTURE, Some(palette.clone()))
+                        .with_night_texture(&night, Some(palette))
+                        .with_color_texture(
+                            MARS_TEXTURE,
+                            &[
+                                (':', MARS_DARK),
+                                ('-', MARS_DARK),
+                                ('=', MARS_DARK),
+                                ('@', MARS_ICE),
+                            ],
+                            MARS_DUST,
+                        )
+                }
+                GlobeTemplate::Saturn => {
+                    let palette = template.palette().to_vec();
+                    let night = blank_texture(SATURN_TEXTURE);
+                    self.radius.get_or_insert(SATURN_RADIUS);
+                    self.axial_tilt.get_or_insert(SATURN_TILT);
+                    self.rings.get_or_insert_with(Rings::default);
+                    self = self
+                        .with_texture(SATURN_TEXTURE, Some(palette.clone()))
+                        .with_night_texture(&night, Some(palette))
+                        .with_color_texture(
+                            SATURN_TEXTURE,
+                            &[
+                                (':', SATURN_DARK),
+                                ('-', SATURN_DARK),
+                                ('*', SATURN_LIGHT),
+                                ('#', SATURN_LIGHT),
+                                ('%', SATURN_LIGHT),
+                                ('@', SATURN_LIGHT),
+                            ],
+                            SATURN_BAND,
+                        )
                 }
             }
         }
-        let texture = self.texture.expect("texture not provided");
-        let camera = self
-            .camera_cfg
-            .unwrap_or_else(CameraConfig::default)
-            .build();
-        Globe {
+        let texture = self.texture.ok_or(Error::MissingTexture)?;
+        texture.validate()?;
+        // use the color layer if the texture comes with one
+        let coloring = self
+            .coloring
+            .or_else(|| texture.has_colors().then_some(Coloring::Texture));
+        let mut camera = self.camera_cfg.unwrap_or_default().build();
+        camera.set_tilt(self.axial_tilt.unwrap_or(0.).to_radians());
+        let mut globe = Globe {
             camera,
             radius: self.radius.unwrap_or(1.),
             angle: self.angle.unwrap_or(0.),
             texture,
             display_night: self.display_night,
-        }
+            coloring,
+            render_style: self.render_style,
+            temporal_smoothing: self.temporal_smoothing,
+            markers: Vec::new(),
+            marker_groups: BTreeMap::new(),
+            sun: self.sun,
+            light: self.light,
+            specular: self.specular,
+            terminator: self.terminator,
+            atmosphere: self.atmosphere,
+            clouds: self.clouds,
+            rings: self.rings,
+            geofences: Vec::new(),
+            line_layers: Vec::new(),
+            arcs: Vec::new(),
+            labels: Vec::new(),
+            heatmap: None,
+            density: None,
+            data_grids: Vec::new(),
+            data_series: Vec::new(),
+            vector_fields: Vec::new(),
+            guides: None,
+            light_direction: None,
+            light_position: None,
+        };
+        globe.update_light();
+        Ok(globe)
     }
 }
 
 /// Built-in globe template enumeration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum GlobeTemplate {
     Earth,
-    // Moon,
-    // Mars,
+    Moon,
+    Mars,
+    /// Banded gas giant with rings, its radius shrunk and its axis tilted
+    /// unless set, see `Rings`.
+    Saturn,
+}
+
+impl GlobeTemplate {
+    /// Returns the characters the template's texture is drawn with, ordered
+    /// from dark to bright.
+    pub fn palette(&self) -> &'static [char] {
+        match self {
+            GlobeTemplate::Earth => &EARTH_PALETTE,
+            GlobeTemplate::Moon | GlobeTemplate::Mars | GlobeTemplate::Saturn => &ROCKY_PALETTE,
+        }
+    }
 }
 
 /// Camera configuration struct implementing the builder pattern.
@@ -306,6 +1681,17 @@ pub struct CameraConfig {
     beta: Float,
 }
 
+impl Default for CameraConfig {
+    /// Creates a new `CameraConfig` using default values.
+    fn default() -> Self {
+        Self {
+            radius: 2.,
+            alpha: 0.,
+            beta: 0.,
+        }
+    }
+}
+
 impl CameraConfig {
     /// Creates a new `CameraConfig`.
     ///
@@ -322,15 +1708,6 @@ impl CameraConfig {
         }
     }
 
-    /// Creates a new `CameraConfig` using default values.
-    pub fn default() -> Self {
-        Self {
-            radius: 2.,
-            alpha: 0.,
-            beta: 0.,
-        }
-    }
-
     /// Builds a camera from the collected config information.
     pub fn build(&self) -> Camera {
         let mut camera = Camera::default();
@@ -341,6 +1718,14 @@ impl CameraConfig {
 
 #[derive(Default)]
 pub struct Camera {
+    radius: Float,
+    alpha: Float,
+    beta: Float,
+    /// Axial tilt of the globe in radians. The camera is placed by its
+    /// angles around the untilted axis, its position and orientation are
+    /// then turned into the frame of the tilted globe, so that rendering
+    /// doesn't need to know about the tilt.
+    tilt: Float,
     x: Float,
     y: Float,
     z: Float,
@@ -349,6 +1734,28 @@ pub struct Camera {
 }
 
 impl Camera {
+    /// Returns the distance from the camera to the origin.
+    pub fn radius(&self) -> Float {
+        self.radius
+    }
+
+    /// Returns camera's angle along the xy plane.
+    pub fn alpha(&self) -> Float {
+        self.alpha
+    }
+
+    /// Returns camera's angle along z axis.
+    pub fn beta(&self) -> Float {
+        self.beta
+    }
+
+    /// Sets the axial tilt of the globe in radians, keeping the camera's
+    /// angles.
+    pub(crate) fn set_tilt(&mut self, tilt: Float) {
+        self.tilt = tilt;
+        self.update(self.radius, self.alpha, self.beta);
+    }
+
     /// Updates the camera using new data.
     pub fn update(&mut self, r: Float, alpha: Float, beta: Float) {
         let sin_a = alpha.sin();
@@ -384,9 +1791,21 @@ impl Camera {
         matrix[13] = y;
         matrix[14] = z;
 
+        if self.tilt != 0. {
+            for axis in [0, 4, 8, 12] {
+                let mut v = [matrix[axis], matrix[axis + 1], matrix[axis + 2]];
+                rotate_x(&mut v, -self.tilt);
+                matrix[axis..axis + 3].copy_from_slice(&v);
+            }
+        }
+        let (x, y, z) = (matrix[12], matrix[13], matrix[14]);
+
         let mut inv = [0.; 16];
         invert(&mut inv, matrix);
 
+        self.radius = r;
+        self.alpha = alpha;
+        self.beta = beta;
         self.x = x;
         self.y = y;
         self.z = z;
@@ -395,20 +1814,83 @@ impl Camera {
     }
 }
 
-/// Get index of the given character on the palette.
-fn find_index(target: char, palette: &[char]) -> Int {
-    for (i, &ch) in palette.iter().enumerate() {
-        if target == ch {
-            return i as Int;
+/// Creates a texture of the same dimensions as the given one, filled with
+/// blank characters.
+fn blank_texture(texture: &str) -> String {
+    texture
+        .lines()
+        .map(|line| " ".repeat(line.chars().count()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Lookup table translating characters into their palette indices.
+struct PaletteLut {
+    ascii: [u8; 128],
+    // palettes hold few characters outside of ASCII, scanning them is
+    // faster than hashing
+    other: Vec<(char, u8)>,
+}
+
+impl PaletteLut {
+    fn new(palette: &[char]) -> Self {
+        let mut lut = PaletteLut {
+            ascii: [NO_INDEX; 128],
+            other: Vec::new(),
+        };
+        for (i, &ch) in palette.iter().enumerate().take(NO_INDEX as usize) {
+            if !ch.is_ascii() {
+                lut.other.push((ch, i as u8));
+            } else if lut.ascii[ch as usize] == NO_INDEX {
+                lut.ascii[ch as usize] = i as u8;
+            }
         }
+        lut
     }
-    -1
+
+    /// Get index of the given character on the palette.
+    fn index(&self, ch: char) -> u8 {
+        if ch.is_ascii() {
+            self.ascii[ch as usize]
+        } else {
+            self.other
+                .iter()
+                .find(|(other, _)| *other == ch)
+                .map_or(NO_INDEX, |(_, i)| *i)
+        }
+    }
+}
+
+/// Returns how far the rays shot through a grid of the given size, with
+/// cells of `char_pix` pixels, reach to the sides and up and down, relative
+/// to its shorter side.
+///
+/// The globe fills the shorter side and stays round, the longer one showing
+/// more of the space around it.
+fn view_extent((cols, rows): (usize, usize), char_pix: (usize, usize)) -> (Float, Float) {
+    let width = (cols * char_pix.0) as Float;
+    let height = (rows * char_pix.1) as Float;
+    let side = width.min(height);
+    if side <= 0. {
+        return (1., 1.);
+    }
+    (width / side, height / side)
 }
 
-fn transform_vector(vec: &mut [Float; 3], m: [Float; 16]) {
-    let tx: Float = vec[0] * m[0] + vec[1] * m[4] + vec[2] * m[8] + m[12];
-    let ty: Float = vec[0] * m[1] + vec[1] * m[5] + vec[2] * m[9] + m[13];
-    let tz: Float = vec[0] * m[2] + vec[1] * m[6] + vec[2] * m[10] + m[14];
+/// Converts a palette index for blending, characters missing from the palette
+/// count as one step below the first palette entry.
+fn index_value(index: u8) -> Float {
+    if index == NO_INDEX {
+        -1.
+    } else {
+        index as Float
+    }
+}
+
+fn transform_vector<R: Real>(vec: &mut [R; 3], m: [R; 16]) {
+    let tx = vec[0] * m[0] + vec[1] * m[4] + vec[2] * m[8] + m[12];
+    let ty = vec[0] * m[1] + vec[1] * m[5] + vec[2] * m[9] + m[13];
+    let tz = vec[0] * m[2] + vec[1] * m[6] + vec[2] * m[10] + m[14];
     vec[0] = tx;
     vec[1] = ty;
     vec[2] = tz;
@@ -543,31 +2025,45 @@ fn cross(r: &mut [Float; 3], a: [Float; 3], b: [Float; 3]) {
     r[2] = a[0] * b[1] - a[1] * b[0];
 }
 
-fn magnitude(r: &[Float; 3]) -> Float {
+/// Returns the coordinates on an equirectangular texture of the given point
+/// on a sphere rotated by `angle`, the horizontal one left unwrapped, see
+/// `WrapMode`.
+fn texture_coords<R: Real>(point: [R; 3], angle: Float) -> (Float, Float) {
+    let r = R::from_float;
+    let [x, y, z] = point;
+    let longitude = y.atan2(x);
+    let latitude = z.atan2((x * x + y * y).sqrt());
+    let theta = longitude / r(2. * PI) + r(0.5) + r(angle / 2. / PI);
+    let phi = r(0.5) - latitude / r(PI);
+    (theta.to_float(), phi.to_float())
+}
+
+fn magnitude<R: Real>(r: &[R; 3]) -> R {
     dot(r, r).sqrt()
 }
 
-fn normalize(r: &mut [Float; 3]) {
-    let len: Float = magnitude(r);
-    r[0] /= len;
-    r[1] /= len;
-    r[2] /= len;
+fn normalize<R: Real>(r: &mut [R; 3]) {
+    let len = magnitude(r);
+    r[0] = r[0] / len;
+    r[1] = r[1] / len;
+    r[2] = r[2] / len;
 }
 
-fn dot(a: &[Float; 3], b: &[Float; 3]) -> Float {
+fn dot<R: Real>(a: &[R; 3], b: &[R; 3]) -> R {
     a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
 }
 
-fn vector(a: &mut [Float; 3], b: &[Float; 3], c: &[Float; 3]) {
+fn vector<R: Real>(a: &mut [R; 3], b: &[R; 3], c: &[R; 3]) {
     a[0] = b[0] - c[0];
     a[1] = b[1] - c[1];
     a[2] = b[2] - c[2];
 }
 
 fn transform_vector2(vec: &mut [Float; 3], m: &[Float; 9]) {
-    vec[0] = m[0] * vec[0] + m[1] * vec[1] + m[2] * vec[2];
-    vec[1] = m[3] * vec[0] + m[4] * vec[1] + m[5] * vec[2];
-    vec[2] = m[6] * vec[0] + m[7] * vec[1] + m[8] * vec[2];
+    let [x, y, z] = *vec;
+    vec[0] = m[0] * x + m[1] * y + m[2] * z;
+    vec[1] = m[3] * x + m[4] * y + m[5] * z;
+    vec[2] = m[6] * x + m[7] * y + m[8] * z;
 }
 
 fn rotate_x(vec: &mut [Float; 3], theta: Float) {
@@ -591,7 +2087,7 @@ fn rotate_z(vec: &mut [Float; 3], theta: Float) {
     transform_vector2(vec, &m);
 }
 
-fn clamp(mut x: Float, min: Float, max: Float) -> Float {
+fn clamp<R: Real>(mut x: R, min: R, max: R) -> R {
     if x < min {
         x = min;
     } else if x > max {
@@ -0,0 +1,238 @@
+//! Light falling on the globe, setting apart its day and night sides.
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::float::FloatExt;
+use crate::{clamp, rotate_x, Color, Float, Globe};
+
+/// Place the light comes from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LightSource {
+    /// Light coming from far away, the vector pointing towards it, so that
+    /// all of the globe is lit from the same direction
+    Direction([Float; 3]),
+    /// Light shining from the given point, e.g. a lamp close to the globe
+    Position([Float; 3]),
+}
+
+/// Light falling on the globe.
+///
+/// How much light a point receives is `intensity` times the cosine of the
+/// angle between the surface and the light, plus `ambient`, clamped to the
+/// `0..1` range. Higher intensities make the terminator fall off harder,
+/// ambient light brightens the whole globe.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Light {
+    pub source: LightSource,
+    pub intensity: Float,
+    pub ambient: Float,
+}
+
+impl Default for Light {
+    /// Light coming from far along the y axis, with a sharp terminator.
+    fn default() -> Self {
+        Self {
+            source: LightSource::Direction([0., -1., 0.]),
+            intensity: 5.,
+            ambient: 0.5,
+        }
+    }
+}
+
+impl Light {
+    /// Creates a light coming from the given source.
+    pub fn new(source: LightSource, intensity: Float, ambient: Float) -> Self {
+        Self {
+            source,
+            intensity,
+            ambient,
+        }
+    }
+}
+
+/// Highlight of light reflected off shiny parts of the texture towards the
+/// camera, like the sun glinting on the ocean.
+///
+/// The highlight brightens cells up to `strength` times the way towards the
+/// brightest palette character, and their colors towards white. Higher
+/// `shininess` narrows it down to a smaller spot.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Specular {
+    pub strength: Float,
+    pub shininess: Float,
+}
+
+impl Default for Specular {
+    /// Bright highlight spreading over a few characters on a typical globe.
+    fn default() -> Self {
+        Self {
+            strength: 1.,
+            shininess: 30.,
+        }
+    }
+}
+
+impl Specular {
+    /// Creates a highlight of the given strength and shininess.
+    pub fn new(strength: Float, shininess: Float) -> Self {
+        Self {
+            strength,
+            shininess,
+        }
+    }
+
+    /// Returns the strength of the highlight given the cosine of the angle
+    /// between the surface normal and the halfway vector.
+    pub(crate) fn highlight(&self, cos: Float) -> Float {
+        if cos <= 0. {
+            return 0.;
+        }
+        (self.strength * cos.powf(self.shininess)).min(1.)
+    }
+}
+
+/// Line drawn along the boundary between the day and night sides while the
+/// night side is displayed, making it stand out on textures with little
+/// contrast.
+///
+/// Points where the light stands within `width` degrees of the horizon are
+/// drawn with `ch` in `color`. Over `softness` more degrees on either side,
+/// the color keeps fading into the surface's, like twilight does.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Terminator {
+    pub ch: char,
+    /// Color of the line, blended into the surface's around it
+    pub color: Option<Color>,
+    /// Half the width of the line, in degrees
+    pub width: Float,
+    /// Width of the twilight on either side of the line, in degrees
+    pub softness: Float,
+}
+
+impl Default for Terminator {
+    /// Thin orange line with a wide twilight around it.
+    fn default() -> Self {
+        Self {
+            ch: '*',
+            color: Some(Color::new(255, 150, 60)),
+            width: 2.,
+            softness: 10.,
+        }
+    }
+}
+
+impl Terminator {
+    /// Creates a terminator line of the given half width and twilight
+    /// softness in degrees, drawn with the default character and color.
+    pub fn new(width: Float, softness: Float) -> Self {
+        Self {
+            width,
+            softness,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the character the line is drawn with.
+    pub fn with_char(mut self, ch: char) -> Self {
+        self.ch = ch;
+        self
+    }
+
+    /// Sets the color of the line.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Returns how strongly a point shows the terminator, given the cosine
+    /// of the angle between its surface normal and the light: `1.` on the
+    /// line, falling off to `0.` at the far edge of the twilight.
+    pub(crate) fn weight(&self, light_cos: Float) -> Float {
+        let elevation = clamp(light_cos, -1., 1.).asin().to_degrees().abs();
+        if elevation <= self.width {
+            1.
+        } else if self.softness > 0. {
+            (1. - (elevation - self.width) / self.softness).max(0.)
+        } else {
+            0.
+        }
+    }
+}
+
+impl Globe {
+    /// Returns the light falling on the globe.
+    pub fn light(&self) -> Light {
+        self.light
+    }
+
+    /// Sets the light falling on the globe. While lit by the sun, see
+    /// `Globe::set_sun`, only the intensity and ambient light are used.
+    pub fn set_light(&mut self, light: Light) {
+        self.light = light;
+        self.update_light();
+    }
+
+    /// Returns the highlight reflected off shiny parts of the texture, if
+    /// enabled.
+    pub fn specular(&self) -> Option<Specular> {
+        self.specular
+    }
+
+    /// Enables or disables the highlight reflected off shiny parts of the
+    /// texture, see `Texture::set_specular_mask`.
+    pub fn set_specular(&mut self, specular: Option<Specular>) {
+        self.specular = specular;
+    }
+
+    /// Returns the line drawn between the day and night sides, if enabled.
+    pub fn terminator(&self) -> Option<Terminator> {
+        self.terminator
+    }
+
+    /// Enables or disables the line drawn between the day and night sides,
+    /// shown while the night side is displayed.
+    pub fn set_terminator(&mut self, terminator: Option<Terminator>) {
+        self.terminator = terminator;
+    }
+
+    /// Returns the terminator along with how strongly it shows at a point
+    /// lit at the given cosine, `None` where it doesn't show at all.
+    pub(crate) fn twilight(&self, light_cos: Float) -> Option<(Terminator, Float)> {
+        let terminator = self.terminator.filter(|_| self.display_night)?;
+        // without a direction the light has no horizon
+        if self.light_direction.is_none() && self.light_position.is_none() {
+            return None;
+        }
+        let weight = terminator.weight(light_cos);
+        (weight > 0.).then_some((terminator, weight))
+    }
+
+    /// Updates the direction of the light, pointing it towards the sun if
+    /// placed, which moves along as the globe spins.
+    ///
+    /// Rendering happens in the frame of the globe, so the light's own source
+    /// is turned against its axial tilt, see `Camera::set_tilt`. The sun is
+    /// placed on the globe and needs no turning.
+    pub(crate) fn update_light(&mut self) {
+        let tilt = self.camera.tilt;
+        let to_globe_frame = |mut v: [Float; 3]| {
+            rotate_x(&mut v, -tilt);
+            v
+        };
+        let direction = match (self.sun, self.light.source) {
+            (Some(sun), _) => Some(sun.sphere_point(self.angle, 1.)),
+            (None, LightSource::Direction(direction)) => Some(to_globe_frame(direction)),
+            (None, LightSource::Position(_)) => None,
+        };
+        self.light_direction = direction.and_then(unit_vector);
+        self.light_position = match (self.sun, self.light.source) {
+            (None, LightSource::Position(position)) => Some(to_globe_frame(position)),
+            _ => None,
+        };
+    }
+}
+
+/// Scales the vector to unit length, `None` if it has no length.
+fn unit_vector(v: [Float; 3]) -> Option<[Float; 3]> {
+    let len = crate::magnitude(&v);
+    (len > 0.).then(|| v.map(|c| c / len))
+}
@@ -0,0 +1,428 @@
+//! Lines drawn over the surface as a layer that can be shown or hidden, e.g.
+//! the borders between countries.
+
+use alloc::vec::Vec;
+
+#[cfg(feature = "geojson")]
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+#[cfg(not(any(feature = "std", test)))]
+use crate::float::FloatExt;
+use crate::{
+    clamp, cross, dot, normalize, CellRect, Color, Float, GeoCoord, Globe, GreatArc, RenderTarget,
+    Texture,
+};
+
+/// Character line layers are drawn with unless set otherwise.
+const DEFAULT_CHAR: char = '*';
+
+/// Outline of the main land borders between countries, see `country_borders`.
+#[cfg(feature = "borders")]
+const BORDERS: &str = include_str!("../data/borders.txt");
+
+/// Color the country borders are drawn in.
+#[cfg(feature = "borders")]
+const BORDERS_COLOR: Color = Color::new(230, 200, 90);
+
+/// Lines drawn over the surface with a single character and color, whose
+/// corners are connected by great circle arcs.
+///
+/// Layers are drawn over any texture, under arcs and markers, and can be
+/// hidden without removing them from the globe.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LineLayer {
+    lines: Vec<Vec<GeoCoord>>,
+    /// Character the lines are drawn with
+    pub ch: char,
+    /// Color the lines are drawn in, the globe's own if `None`
+    pub color: Option<Color>,
+    /// Whether the layer is drawn
+    pub visible: bool,
+}
+
+impl Default for LineLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LineLayer {
+    /// Creates an empty, visible layer drawn with `*`.
+    pub fn new() -> Self {
+        Self {
+            lines: Vec::new(),
+            ch: DEFAULT_CHAR,
+            color: None,
+            visible: true,
+        }
+    }
+
+    /// Parses lines given as `lat,lon` corners in degrees, one per line of
+    /// text. Blank lines separate the lines of the layer and lines starting
+    /// with `#` are skipped.
+    ///
+    /// Fails if a corner can't be parsed or a line has a single corner.
+    pub fn from_lat_lon_list(text: &str) -> Result<Self> {
+        let mut lines = Vec::new();
+        let mut corners = Vec::new();
+        for line in text.lines().map(str::trim) {
+            if line.starts_with('#') {
+                continue;
+            }
+            if line.is_empty() {
+                lines.push(core::mem::take(&mut corners));
+                continue;
+            }
+            let (lat, lon) = line.split_once(',').ok_or(Error::InvalidLineLayer)?;
+            let degrees = |value: &str| {
+                value
+                    .trim()
+                    .parse::<Float>()
+                    .ok()
+                    .filter(|value| value.is_finite())
+                    .ok_or(Error::InvalidLineLayer)
+            };
+            corners.push(GeoCoord::from_lat_lon(degrees(lat)?, degrees(lon)?));
+        }
+        lines.push(corners);
+        lines.retain(|line| !line.is_empty());
+        Self::from_lines(lines.iter())
+    }
+
+    /// Parses lines from CSV rows of `line,lat,lon`, with the corners in
+    /// degrees. Consecutive rows naming the same line make up its corners,
+    /// and a first row that isn't a corner is taken for a header.
+    ///
+    /// Fails if a row can't be parsed or a line has a single corner.
+    pub fn from_csv(text: &str) -> Result<Self> {
+        let mut lines: Vec<(&str, Vec<GeoCoord>)> = Vec::new();
+        let rows = text.lines().map(str::trim).filter(|row| !row.is_empty());
+        for (index, row) in rows.enumerate() {
+            let corner = parse_csv_row(row);
+            let (name, coord) = match corner {
+                Some(corner) => corner,
+                None if index == 0 => continue,
+                None => return Err(Error::InvalidLineLayer),
+            };
+            match lines.last_mut() {
+                Some((last, corners)) if *last == name => corners.push(coord),
+                _ => lines.push((name, alloc::vec![coord])),
+            }
+        }
+        Self::from_lines(lines.iter().map(|(_, corners)| corners))
+    }
+
+    /// Parses the lines found in a GeoJSON document, taken from its
+    /// `LineString` and `MultiLineString` geometries, within features or
+    /// not. Other geometries are skipped.
+    ///
+    /// Fails if the document is malformed or a line has a single corner.
+    #[cfg(feature = "geojson")]
+    pub fn from_geojson(text: &str) -> Result<Self> {
+        let value: Value = serde_json::from_str(text).map_err(|_| Error::InvalidLineLayer)?;
+        let mut lines = Vec::new();
+        collect_geojson(&value, &mut lines).ok_or(Error::InvalidLineLayer)?;
+        Self::from_lines(lines.iter())
+    }
+
+    /// Creates a layer outlining the main land borders between countries,
+    /// from a set bundled with the library.
+    ///
+    /// The set stands in for the Natural Earth 1:110m boundary lines, traced
+    /// by hand to about a degree until it's regenerated from them with the
+    /// `borders_from_natural_earth` example. Either is enough for the cells
+    /// of a globe drawn in a terminal but not for telling which side of a
+    /// border a town lies on. Borders running through lakes and along coasts are
+    /// left out, and disputed ones follow the line held on the ground.
+    #[cfg(feature = "borders")]
+    pub fn country_borders() -> Self {
+        Self::from_lat_lon_list(BORDERS)
+            .expect("bundled borders are well formed")
+            .with_color(Some(BORDERS_COLOR))
+    }
+
+    /// Adds a line going through the given corners, skipping lines with
+    /// fewer than two.
+    pub fn with_line(mut self, corners: &[GeoCoord]) -> Self {
+        if corners.len() >= 2 {
+            self.lines.push(corners.to_vec());
+        }
+        self
+    }
+
+    /// Sets the character the lines are drawn with.
+    pub fn with_char(mut self, ch: char) -> Self {
+        self.ch = ch;
+        self
+    }
+
+    /// Sets the color the lines are drawn in, `None` keeping the globe's.
+    pub fn with_color(mut self, color: Option<Color>) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Drops the corners of each line lying within `tolerance` degrees of
+    /// the path left without them, keeping the ends of every line.
+    ///
+    /// Dense datasets drawn on a character grid pile many corners into each
+    /// cell, which only blurs the lines and slows down drawing them.
+    pub fn simplified(mut self, tolerance: Float) -> Self {
+        let tolerance = tolerance.to_radians();
+        for line in &mut self.lines {
+            *line = simplify_line(line, tolerance);
+        }
+        self
+    }
+
+    /// Simplifies the lines down to the cells of the texture, see
+    /// `simplified`. Corners closer than half a cell to the rest of the line
+    /// wouldn't change the cells it's drawn over.
+    pub fn simplified_for(self, texture: &Texture) -> Self {
+        let (width, height) = texture.get_size();
+        let cell = (360. / width.max(1) as Float).max(180. / height.max(1) as Float);
+        self.simplified(cell / 2.)
+    }
+
+    /// Returns the corners of each line of the layer.
+    pub fn lines(&self) -> &[Vec<GeoCoord>] {
+        &self.lines
+    }
+
+    /// Creates a layer holding the lines, failing if one of them has a
+    /// single corner.
+    fn from_lines<'a>(lines: impl Iterator<Item = &'a Vec<GeoCoord>>) -> Result<Self> {
+        let mut layer = Self::new();
+        for line in lines {
+            if line.len() < 2 {
+                return Err(Error::InvalidLineLayer);
+            }
+            layer = layer.with_line(line);
+        }
+        Ok(layer)
+    }
+}
+
+impl Globe {
+    /// Adds a layer of lines to be drawn over the surface, on top of the
+    /// ones added before it.
+    pub fn add_line_layer(&mut self, layer: LineLayer) {
+        self.line_layers.push(layer);
+    }
+
+    /// Returns the layers of lines drawn over the surface.
+    pub fn line_layers(&self) -> &[LineLayer] {
+        &self.line_layers
+    }
+
+    /// Returns the layers of lines drawn over the surface, e.g. for showing
+    /// or hiding them.
+    pub fn line_layers_mut(&mut self) -> &mut [LineLayer] {
+        &mut self.line_layers
+    }
+
+    /// Removes all the layers of lines.
+    pub fn clear_line_layers(&mut self) {
+        self.line_layers.clear();
+    }
+
+    /// Draws the parts of the visible layers found on the visible side of the
+    /// globe, skipping the cells outside of `rect` if given.
+    pub(crate) fn render_line_layers_within<S: RenderTarget>(
+        &self,
+        canvas: &mut S,
+        rect: Option<CellRect>,
+    ) {
+        for layer in self.line_layers.iter().filter(|layer| layer.visible) {
+            for line in &layer.lines {
+                for pair in line.windows(2) {
+                    let arc = GreatArc::new(pair[0], pair[1]);
+                    let length = arc.from.angle_to(arc.to).to_degrees();
+                    let path = |fraction| arc.point_at(fraction);
+                    self.render_path_within(canvas, rect, path, length, layer.ch, layer.color);
+                }
+            }
+        }
+    }
+}
+
+/// Parses a `line,lat,lon` CSV row, `None` if it's not a corner.
+fn parse_csv_row(row: &str) -> Option<(&str, GeoCoord)> {
+    let mut fields = row.split(',').map(str::trim);
+    let name = fields.next()?;
+    let mut degrees = || {
+        fields
+            .next()?
+            .parse::<Float>()
+            .ok()
+            .filter(|value| value.is_finite())
+    };
+    let (lat, lon) = (degrees()?, degrees()?);
+    Some((name, GeoCoord::from_lat_lon(lat, lon)))
+}
+
+/// Keeps the corners of the line that lie further than `tolerance` radians
+/// from the path between the corners kept around them, splitting the line
+/// at the furthest corner until none are left out of place
+/// (Ramer-Douglas-Peucker).
+fn simplify_line(line: &[GeoCoord], tolerance: Float) -> Vec<GeoCoord> {
+    if line.len() < 3 {
+        return line.to_vec();
+    }
+    let points: Vec<[Float; 3]> = line.iter().map(|coord| coord.unit_vector()).collect();
+    let mut keep = alloc::vec![false; line.len()];
+    keep[0] = true;
+    keep[line.len() - 1] = true;
+    let mut spans = alloc::vec![(0, line.len() - 1)];
+    while let Some((start, end)) = spans.pop() {
+        let furthest = (start + 1..end)
+            .map(|i| (i, distance_to_path(points[i], points[start], points[end])))
+            .fold(
+                None,
+                |best: Option<(usize, Float)>, (i, distance)| match best {
+                    Some((_, most)) if most >= distance => best,
+                    _ => Some((i, distance)),
+                },
+            );
+        if let Some((i, distance)) = furthest {
+            if distance > tolerance {
+                keep[i] = true;
+                spans.push((start, i));
+                spans.push((i, end));
+            }
+        }
+    }
+    line.iter()
+        .zip(keep)
+        .filter(|(_, keep)| *keep)
+        .map(|(&coord, _)| coord)
+        .collect()
+}
+
+/// Returns the angle in radians between the point and the shorter great
+/// circle arc from `a` to `b`, or `a` itself if they're the same point.
+///
+/// Points off to the side of the arc are measured to the nearer of its ends,
+/// so corners doubling back past an end aren't taken as lying on the path.
+fn distance_to_path(point: [Float; 3], a: [Float; 3], b: [Float; 3]) -> Float {
+    let angle_to = |end: [Float; 3]| clamp(dot(&point, &end), -1., 1.).acos();
+    let mut normal = [0.; 3];
+    cross(&mut normal, a, b);
+    if dot(&normal, &normal) < Float::EPSILON {
+        return angle_to(a);
+    }
+    // the point projects onto the arc if it's on the inner side of the
+    // planes through each end and the circle's axis
+    let (mut after_a, mut before_b) = ([0.; 3], [0.; 3]);
+    cross(&mut after_a, a, point);
+    cross(&mut before_b, point, b);
+    if dot(&after_a, &normal) < 0. || dot(&before_b, &normal) < 0. {
+        return angle_to(a).min(angle_to(b));
+    }
+    normalize(&mut normal);
+    clamp(dot(&point, &normal), -1., 1.).abs().asin()
+}
+
+/// Collects the corners of all the lines found in the GeoJSON object,
+/// returning `None` if it's malformed.
+#[cfg(feature = "geojson")]
+fn collect_geojson(value: &Value, lines: &mut Vec<Vec<GeoCoord>>) -> Option<()> {
+    match value.get("type")?.as_str()? {
+        "FeatureCollection" => {
+            for feature in value.get("features")?.as_array()? {
+                collect_geojson(feature, lines)?;
+            }
+        }
+        "Feature" => match value.get("geometry")? {
+            // features without a location
+            Value::Null => {}
+            geometry => collect_geojson(geometry, lines)?,
+        },
+        "GeometryCollection" => {
+            for geometry in value.get("geometries")?.as_array()? {
+                collect_geojson(geometry, lines)?;
+            }
+        }
+        "LineString" => lines.push(parse_line_string(value.get("coordinates")?)?),
+        "MultiLineString" => {
+            for line in value.get("coordinates")?.as_array()? {
+                lines.push(parse_line_string(line)?);
+            }
+        }
+        // points and polygons aren't lines
+        _ => {}
+    }
+    Some(())
+}
+
+/// Parses the corners of a GeoJSON line, given as `[lon, lat]` positions.
+#[cfg(feature = "geojson")]
+fn parse_line_string(coordinates: &Value) -> Option<Vec<GeoCoord>> {
+    coordinates
+        .as_array()?
+        .iter()
+        .map(|position| {
+            let position = position.as_array()?;
+            let lon = position.first()?.as_f64()?;
+            let lat = position.get(1)?.as_f64()?;
+            Some(GeoCoord::from_lat_lon(lat as Float, lon as Float))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(lat_lon: impl Iterator<Item = (Float, Float)>) -> Vec<GeoCoord> {
+        lat_lon
+            .map(|(lat, lon)| GeoCoord::from_lat_lon(lat, lon))
+            .collect()
+    }
+
+    #[test]
+    fn simplified_lines_stay_within_the_tolerance() {
+        let tolerance = 0.25;
+        let wave = line((-60..=60).map(|lon| {
+            let lon = lon as Float;
+            (5. * (lon / 20.).sin(), lon)
+        }));
+        let layer = LineLayer::new().with_line(&wave).simplified(tolerance);
+        let simplified = &layer.lines()[0];
+        assert!(
+            simplified.len() < wave.len() / 4,
+            "{} corners",
+            simplified.len()
+        );
+        assert_eq!(simplified.first(), wave.first());
+        assert_eq!(simplified.last(), wave.last());
+        for coord in &wave {
+            let distance = simplified
+                .windows(2)
+                .map(|pair| {
+                    distance_to_path(
+                        coord.unit_vector(),
+                        pair[0].unit_vector(),
+                        pair[1].unit_vector(),
+                    )
+                })
+                .fold(Float::MAX, Float::min);
+            assert!(distance.to_degrees() <= tolerance + 1e-3, "{:?}", coord);
+        }
+    }
+
+    #[test]
+    fn straight_runs_keep_only_their_ends() {
+        let straight = line((0..=20).map(|lon| (0., lon as Float)));
+        let layer = LineLayer::new().with_line(&straight).simplified(0.1);
+        assert_eq!(layer.lines()[0], [straight[0], straight[20]]);
+    }
+
+    #[test]
+    fn corners_doubling_back_are_kept() {
+        let back = line([(0., 0.), (0., 10.), (0., 5.)].iter().copied());
+        let layer = LineLayer::new().with_line(&back).simplified(1.);
+        assert_eq!(layer.lines()[0], back);
+    }
+}
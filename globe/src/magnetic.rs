@@ -0,0 +1,58 @@
+//! Approximate location of the Earth's magnetic poles over the years.
+//!
+//! Interpolates a table of the dip poles, where the field points straight
+//! down, given by the International Geomagnetic Reference Field every five
+//! years. Close enough to mark them on a character grid, but not to navigate
+//! by, and held at the ends of the table for years outside of it.
+
+use crate::{Float, GeoCoord, GreatArc};
+
+/// Year the tables start at.
+const FIRST_YEAR: Float = 2000.;
+/// Number of years between the entries of the tables.
+const STEP_YEARS: Float = 5.;
+/// Latitude and longitude of the north dip pole in degrees, every
+/// `STEP_YEARS` from `FIRST_YEAR`.
+const NORTH: [(Float, Float); 6] = [
+    (81.0, -109.6),
+    (83.2, -118.2),
+    (85.0, -132.8),
+    (86.3, -159.2),
+    (86.5, 162.9),
+    (85.8, 139.3),
+];
+/// Latitude and longitude of the south dip pole in degrees, every
+/// `STEP_YEARS` from `FIRST_YEAR`.
+const SOUTH: [(Float, Float); 6] = [
+    (-64.7, 138.3),
+    (-64.5, 137.9),
+    (-64.4, 137.3),
+    (-64.3, 136.6),
+    (-64.1, 135.9),
+    (-63.8, 135.0),
+];
+
+/// Returns the north magnetic pole in the given year, e.g. `2024.5` for the
+/// middle of 2024.
+pub fn north_pole(year: Float) -> GeoCoord {
+    interpolate(&NORTH, year)
+}
+
+/// Returns the south magnetic pole in the given year, see `north_pole`.
+pub fn south_pole(year: Float) -> GeoCoord {
+    interpolate(&SOUTH, year)
+}
+
+/// Returns the location the pole moved to by the given year, going along
+/// the shortest path between the entries of the table around it.
+fn interpolate(table: &[(Float, Float)], year: Float) -> GeoCoord {
+    let last = table.len() - 1;
+    let position = ((year - FIRST_YEAR) / STEP_YEARS).clamp(0., last as Float);
+    let index = (position as usize).min(last - 1);
+    let (from, to) = (table[index], table[index + 1]);
+    GreatArc::new(
+        GeoCoord::from_lat_lon(from.0, from.1),
+        GeoCoord::from_lat_lon(to.0, to.1),
+    )
+    .point_at(position - index as Float)
+}
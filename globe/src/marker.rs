@@ -0,0 +1,326 @@
+//! Markers pinning locations on the globe.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::mem;
+use core::time::Duration;
+
+use crate::target::sealed::{FrameState, Token};
+use crate::target::{overlay_cells, ray_cache};
+
+use crate::{
+    CellColor, CellRect, Color, GeoCoord, Globe, MarkerStyle, RayCache, RenderTarget, ROCKY_PALETTE,
+};
+
+/// Point of interest displayed on the globe surface.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Marker {
+    pub coord: GeoCoord,
+    pub ch: char,
+    pub color: Option<Color>,
+    /// Text describing the marker, e.g. shown when it's pointed at
+    pub label: Option<String>,
+    /// Name of the group the marker belongs to, see `MarkerGroup`
+    pub group: Option<String>,
+    /// Time left until the marker is removed, see `Globe::age_markers`,
+    /// `None` for markers staying until they're removed by hand
+    pub ttl: Option<Duration>,
+    /// Time before being removed over which the marker fades out
+    pub fade: Duration,
+}
+
+/// Look and visibility shared by all the markers of a group, e.g. the ones
+/// coming from the same data source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MarkerGroup {
+    /// Style the markers of the group are drawn in, overriding their own
+    pub style: MarkerStyle,
+    /// Whether the markers of the group are shown
+    pub visible: bool,
+}
+
+impl Default for MarkerGroup {
+    /// Visible group drawing markers as they are.
+    fn default() -> Self {
+        Self {
+            style: MarkerStyle::default(),
+            visible: true,
+        }
+    }
+}
+
+impl Marker {
+    /// Creates a new marker drawn with the given character.
+    pub fn new(coord: GeoCoord, ch: char) -> Self {
+        Self {
+            coord,
+            ch,
+            color: None,
+            label: None,
+            group: None,
+            ttl: None,
+            fade: Duration::ZERO,
+        }
+    }
+
+    /// Sets the color used to draw the marker on canvases with colors
+    /// enabled.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Sets the text describing the marker.
+    pub fn with_label(mut self, label: &str) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Puts the marker in the named group.
+    pub fn with_group(mut self, group: &str) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+
+    /// Makes the marker expire after `ttl`, fading out over the last `fade`
+    /// of it by stepping down the texture palette from its own character,
+    /// or from the brightest one if it's not on the palette.
+    pub fn with_ttl(mut self, ttl: Duration, fade: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self.fade = fade;
+        self
+    }
+}
+
+impl Globe {
+    /// Adds a marker to be displayed on the globe.
+    pub fn add_marker(&mut self, marker: Marker) {
+        self.markers.push(marker);
+    }
+
+    /// Returns the markers displayed on the globe.
+    pub fn markers(&self) -> &[Marker] {
+        &self.markers
+    }
+
+    /// Removes all the markers.
+    pub fn clear_markers(&mut self) {
+        self.markers.clear();
+    }
+
+    /// Keeps only the markers the predicate returns true for, e.g. dropping
+    /// the ones placed by a feed while leaving the others alone.
+    pub fn retain_markers(&mut self, keep: impl FnMut(&Marker) -> bool) {
+        self.markers.retain(keep);
+    }
+
+    /// Counts down the time left of expiring markers by `dt`, removing the
+    /// ones whose time is up.
+    pub fn age_markers(&mut self, dt: Duration) {
+        self.markers.retain_mut(|marker| match marker.ttl {
+            Some(ttl) if ttl > dt => {
+                marker.ttl = Some(ttl - dt);
+                true
+            }
+            Some(_) => false,
+            None => true,
+        });
+    }
+
+    /// Returns the names of the groups markers on the globe belong to, in
+    /// the order their first markers were added.
+    pub fn marker_group_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = Vec::new();
+        for name in self.markers.iter().filter_map(|m| m.group.as_deref()) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    /// Returns the look and visibility of the named group, the default for
+    /// groups never set.
+    pub fn marker_group(&self, name: &str) -> MarkerGroup {
+        self.marker_groups.get(name).copied().unwrap_or_default()
+    }
+
+    /// Sets the look and visibility of the named group, applying to markers
+    /// added to it before and after.
+    pub fn set_marker_group(&mut self, name: &str, group: MarkerGroup) {
+        self.marker_groups.insert(name.into(), group);
+    }
+
+    /// Shows or hides the markers of the named group.
+    pub fn set_marker_group_visible(&mut self, name: &str, visible: bool) {
+        let group = self.marker_group(name);
+        self.set_marker_group(name, MarkerGroup { visible, ..group });
+    }
+
+    /// Returns the group settings applying to the marker, if it's in one.
+    fn group_of(&self, marker: &Marker) -> Option<&MarkerGroup> {
+        self.marker_groups.get(marker.group.as_deref()?)
+    }
+
+    /// Returns the canvas cell (column and row) the marker is drawn at, or
+    /// `None` if it's hidden on the far side of the globe, out of view or in
+    /// a hidden group.
+    pub fn marker_cell<T: RenderTarget>(
+        &self,
+        target: &T,
+        marker: &Marker,
+    ) -> Option<(usize, usize)> {
+        if self.group_of(marker).is_some_and(|group| !group.visible) {
+            return None;
+        }
+        self.coord_cell(target, marker.coord)
+    }
+
+    /// Returns the visible marker drawn closest to the given canvas cell,
+    /// at most `max_distance` cells away from it in either direction.
+    ///
+    /// Markers drawn equally close are resolved in favor of the one added
+    /// last, as it's the one drawn on top.
+    pub fn marker_near<T: RenderTarget>(
+        &self,
+        target: &T,
+        cell: (usize, usize),
+        max_distance: usize,
+    ) -> Option<&Marker> {
+        self.markers
+            .iter()
+            .rev()
+            .filter_map(|marker| {
+                let (x, y) = self.marker_cell(target, marker)?;
+                let distance = x.abs_diff(cell.0).max(y.abs_diff(cell.1));
+                (distance <= max_distance).then_some((distance, marker))
+            })
+            .min_by_key(|&(distance, _)| distance)
+            .map(|(_, marker)| marker)
+    }
+
+    /// Draws guides, line layers, arcs, markers and labels found on the
+    /// visible side of the globe.
+    pub(crate) fn render_markers<S: RenderTarget>(&self, canvas: &mut S) {
+        self.render_markers_within(canvas, None);
+    }
+
+    /// Draws guides, line layers, arcs, markers and labels found on the
+    /// visible side of the globe, skipping the ones outside of `rect` if
+    /// given, and notes down the cells they cover on targets keeping them.
+    pub(crate) fn render_markers_within<S: RenderTarget>(
+        &self,
+        canvas: &mut S,
+        rect: Option<CellRect>,
+    ) {
+        let mut cells = match overlay_cells(canvas) {
+            Some(cells) => mem::take(cells),
+            None => return self.draw_overlays_within(canvas, rect),
+        };
+        // cells within the rectangle were rendered again, the others keep
+        // what was drawn on them
+        match rect {
+            Some(rect) => cells.retain(|&(col, row)| !rect.contains(col, row)),
+            None => cells.clear(),
+        }
+        let mut recording = Recording {
+            inner: &mut *canvas,
+            cells,
+        };
+        self.draw_overlays_within(&mut recording, rect);
+        let cells = recording.cells;
+        if let Some(overlay_cells) = overlay_cells(canvas) {
+            *overlay_cells = cells;
+        }
+    }
+
+    /// Draws the overlays, see `Globe::render_markers_within`.
+    fn draw_overlays_within<S: RenderTarget>(&self, canvas: &mut S, rect: Option<CellRect>) {
+        // markers stand out on top of the lines around them
+        self.render_guides_within(canvas, rect);
+        self.render_line_layers_within(canvas, rect);
+        self.render_vector_fields_within(canvas, rect);
+        self.render_arcs_within(canvas, rect);
+        for marker in &self.markers {
+            let (x, y) = match self.marker_cell(canvas, marker) {
+                Some(cell) => cell,
+                None => continue,
+            };
+            if rect.is_some_and(|rect| !rect.contains(x, y)) {
+                continue;
+            }
+            let style = self.group_of(marker).map(|group| group.style);
+            let style = style.unwrap_or_default();
+            let ch = self.faded_char(marker, style.ch.unwrap_or(marker.ch));
+            // faded all the way out
+            if ch == ' ' {
+                continue;
+            }
+            let color = CellColor {
+                fg: style.color.or(marker.color),
+                bg: None,
+            };
+            canvas.draw_colored_point(x, y, ch, color);
+        }
+        self.render_labels_within(canvas, rect);
+    }
+
+    /// Returns the character the marker is drawn with, `ch` stepped down the
+    /// palette in proportion to how far it has faded out.
+    fn faded_char(&self, marker: &Marker, ch: char) -> char {
+        let ttl = match marker.ttl {
+            Some(ttl) if ttl < marker.fade => ttl,
+            _ => return ch,
+        };
+        let palette = match self.texture.palette.as_deref() {
+            Some(palette) if palette.len() > 1 => palette,
+            _ => &ROCKY_PALETTE,
+        };
+        let start = palette
+            .iter()
+            .position(|&c| c == ch)
+            .unwrap_or(palette.len() - 1);
+        // rounded up, so the dimmest step is only reached when the time is up
+        let step = (ttl.as_nanos() * start as u128).div_ceil(marker.fade.as_nanos());
+        palette[step as usize]
+    }
+}
+
+/// Render target noting down the cells drawn on, for finding the overlays
+/// again when the next frame is rendered.
+struct Recording<'a, T> {
+    inner: &'a mut T,
+    cells: Vec<(usize, usize)>,
+}
+
+impl<T: RenderTarget> RenderTarget for Recording<'_, T> {
+    fn grid_size(&self) -> (usize, usize) {
+        self.inner.grid_size()
+    }
+    fn char_pix(&self) -> (usize, usize) {
+        self.inner.char_pix()
+    }
+    fn draw_point(&mut self, col: usize, row: usize, ch: char) {
+        self.cells.push((col, row));
+        self.inner.draw_point(col, row, ch);
+    }
+    fn has_colors(&self) -> bool {
+        self.inner.has_colors()
+    }
+    fn draw_colored_point(&mut self, col: usize, row: usize, ch: char, color: CellColor) {
+        self.cells.push((col, row));
+        self.inner.draw_colored_point(col, row, ch, color);
+    }
+    fn clear_cell(&mut self, col: usize, row: usize) {
+        self.inner.clear_cell(col, row);
+    }
+    fn frame_state(&mut self, _: Token) -> Option<&mut dyn FrameState> {
+        Some(self)
+    }
+}
+
+impl<T: RenderTarget> FrameState for Recording<'_, T> {
+    fn ray_cache(&mut self) -> Option<&mut RayCache> {
+        ray_cache(self.inner)
+    }
+}
@@ -0,0 +1,200 @@
+//! Numeric types used for tracing rays.
+//!
+//! Ray tracing is generic over the `Real` trait. By default it runs on
+//! `Float`, which is `f64` with the `f64` feature enabled and `f32` otherwise.
+//! With the `fixed-point` feature enabled it runs on `Fixed` instead, finding
+//! the points rays hit and the light they receive with integer arithmetic.
+//! The camera setup feeding it and the shading of the points hit still run
+//! on `Float`.
+
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::float::FloatExt;
+use crate::Float;
+
+/// Number type the renderer can trace rays with.
+pub trait Real:
+    Copy
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn from_float(v: Float) -> Self;
+    fn to_float(self) -> Float;
+    fn sqrt(self) -> Self;
+    /// Four quadrant arctangent of `self` (y) and `x`.
+    fn atan2(self, x: Self) -> Self;
+}
+
+impl Real for Float {
+    const ZERO: Self = 0.;
+    const ONE: Self = 1.;
+
+    fn from_float(v: Float) -> Self {
+        v
+    }
+    fn to_float(self) -> Float {
+        self
+    }
+    #[cfg(any(feature = "std", test))]
+    fn sqrt(self) -> Self {
+        Float::sqrt(self)
+    }
+    #[cfg(not(any(feature = "std", test)))]
+    fn sqrt(self) -> Self {
+        FloatExt::sqrt(self)
+    }
+    #[cfg(any(feature = "std", test))]
+    fn atan2(self, x: Self) -> Self {
+        Float::atan2(self, x)
+    }
+    #[cfg(not(any(feature = "std", test)))]
+    fn atan2(self, x: Self) -> Self {
+        FloatExt::atan2(self, x)
+    }
+}
+
+/// Number of fractional bits of `Fixed`.
+const FRAC_BITS: u32 = 16;
+
+/// Signed fixed-point number with 16 integer and 16 fractional bits.
+///
+/// Arithmetic saturates instead of overflowing, division by zero saturates
+/// towards the sign of the dividend.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fixed(i32);
+
+impl Fixed {
+    const HALF_PI: Fixed = Fixed(102_944);
+    const PI: Fixed = Fixed(205_887);
+    // minimax polynomial coefficients of atan on [-1, 1]
+    const ATAN: [Fixed; 5] = [
+        Fixed(65_527),
+        Fixed(-21_647),
+        Fixed(11_806),
+        Fixed(-5_579),
+        Fixed(1_365),
+    ];
+
+    /// Creates a number from its raw bit representation.
+    pub const fn from_bits(bits: i32) -> Self {
+        Fixed(bits)
+    }
+    /// Returns the raw bit representation.
+    pub const fn to_bits(self) -> i32 {
+        self.0
+    }
+
+    fn saturate(v: i64) -> Self {
+        Fixed(v.clamp(i32::MIN as i64, i32::MAX as i64) as i32)
+    }
+
+    fn atan(self) -> Self {
+        // reduce to [-1, 1] using atan(z) = ±pi/2 - atan(1/z)
+        let inverted = self > Fixed::ONE || self < -Fixed::ONE;
+        let z = if inverted { Fixed::ONE / self } else { self };
+        let z2 = z * z;
+        let [c1, c3, c5, c7, c9] = Fixed::ATAN;
+        let atan = z * (c1 + z2 * (c3 + z2 * (c5 + z2 * (c7 + z2 * c9))));
+        match (inverted, self.0 < 0) {
+            (false, _) => atan,
+            (true, false) => Fixed::HALF_PI - atan,
+            (true, true) => -Fixed::HALF_PI - atan,
+        }
+    }
+}
+
+impl Add for Fixed {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Fixed(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Fixed(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl Mul for Fixed {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Fixed::saturate((self.0 as i64 * rhs.0 as i64) >> FRAC_BITS)
+    }
+}
+
+impl Div for Fixed {
+    type Output = Self;
+    fn div(self, rhs: Self) -> Self {
+        if rhs.0 == 0 {
+            return if self.0 < 0 {
+                Fixed(i32::MIN)
+            } else {
+                Fixed(i32::MAX)
+            };
+        }
+        Fixed::saturate(((self.0 as i64) << FRAC_BITS) / rhs.0 as i64)
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Fixed(self.0.saturating_neg())
+    }
+}
+
+impl Real for Fixed {
+    const ZERO: Self = Fixed(0);
+    const ONE: Self = Fixed(1 << FRAC_BITS);
+
+    fn from_float(v: Float) -> Self {
+        // float to int casts saturate
+        Fixed((v * (1 << FRAC_BITS) as Float).round() as i32)
+    }
+    fn to_float(self) -> Float {
+        self.0 as Float / (1 << FRAC_BITS) as Float
+    }
+    fn sqrt(self) -> Self {
+        if self.0 <= 0 {
+            return Fixed::ZERO;
+        }
+        // integer square root of the value scaled up by another 16 bits
+        let n = (self.0 as u64) << FRAC_BITS;
+        let mut x = n;
+        let mut y = x.div_ceil(2);
+        while y < x {
+            x = y;
+            y = (x + n / x) / 2;
+        }
+        Fixed(x as i32)
+    }
+    fn atan2(self, x: Self) -> Self {
+        let y = self;
+        if x.0 > 0 {
+            (y / x).atan()
+        } else if x.0 < 0 {
+            let atan = (y / x).atan();
+            if y.0 < 0 {
+                atan - Fixed::PI
+            } else {
+                atan + Fixed::PI
+            }
+        } else if y.0 > 0 {
+            Fixed::HALF_PI
+        } else if y.0 < 0 {
+            -Fixed::HALF_PI
+        } else {
+            Fixed::ZERO
+        }
+    }
+}
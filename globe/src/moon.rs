@@ -0,0 +1,134 @@
+//! Position and phase of the moon at a given time.
+//!
+//! Uses the low precision series of the Astronomical Almanac, good to about
+//! a third of a degree in position, which still puts the sublunar point in
+//! the right cell of a character grid. The moon is seen from the center of
+//! the Earth, so the phase is the same everywhere.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::consts::PI;
+#[cfg(not(any(feature = "std", test)))]
+use crate::float::FloatExt;
+use crate::{sun, Float, GeoCoord};
+
+/// Number of days in a century, the unit the series counts time in.
+const CENTURY_DAYS: f64 = 36_525.;
+
+/// Character the lit part of the moon is drawn with in `glyph`.
+const LIT_CHAR: char = '@';
+/// Character the dark part of the moon is drawn with in `glyph`.
+const DARK_CHAR: char = '.';
+
+/// Returns the sublunar point, where the moon is directly overhead, at the
+/// given Unix time in seconds.
+pub fn sublunar_point(unix_secs: i64) -> GeoCoord {
+    let (lat, lon) = sublunar_lat_lon(unix_secs);
+    GeoCoord::from_lat_lon(lat as Float, lon as Float)
+}
+
+/// Returns the latitude and longitude of the sublunar point in degrees, see
+/// `sublunar_point`.
+pub fn sublunar_lat_lon(unix_secs: i64) -> (f64, f64) {
+    let d = sun::days_since_j2000(unix_secs);
+    let (lon, lat) = ecliptic_lon_lat(d);
+    let (l, b) = (lon.to_radians(), lat.to_radians());
+    let e = sun::obliquity(d).to_radians();
+
+    let x = b.cos() * l.cos();
+    let y = e.cos() * b.cos() * l.sin() - e.sin() * b.sin();
+    let z = e.sin() * b.cos() * l.sin() + e.cos() * b.sin();
+    let right_ascension = y.atan2(x).to_degrees();
+    let declination = z.asin().to_degrees();
+    (declination, sun::longitude_below(right_ascension, d))
+}
+
+/// Returns how far through its cycle of phases the moon is at the given
+/// Unix time in seconds, from `0.` at new moon through `0.5` at full moon
+/// back to `1.` at the next new moon.
+pub fn phase(unix_secs: i64) -> Float {
+    let d = sun::days_since_j2000(unix_secs);
+    let (lon, _) = ecliptic_lon_lat(d);
+    ((lon - sun::ecliptic_longitude(d)).rem_euclid(360.) / 360.) as Float
+}
+
+/// Returns the fraction of the moon's disc lit by the sun at the given Unix
+/// time in seconds, `0.` at new moon and `1.` at full moon.
+pub fn illuminated_fraction(unix_secs: i64) -> Float {
+    let elongation = sublunar_point(unix_secs).angle_to(sun::subsolar_point(unix_secs));
+    (1. - elongation.cos()) / 2.
+}
+
+/// Returns the name of the given phase, see `phase`, e.g. "waxing
+/// crescent".
+pub fn phase_name(phase: Float) -> &'static str {
+    const NAMES: [&str; 8] = [
+        "new moon",
+        "waxing crescent",
+        "first quarter",
+        "waxing gibbous",
+        "full moon",
+        "waning gibbous",
+        "last quarter",
+        "waning crescent",
+    ];
+    // each name covers an eighth of the cycle centered on its phase
+    let eighth = (phase.rem_euclid(1.) * 8. + 0.5) as usize % NAMES.len();
+    NAMES[eighth]
+}
+
+/// Draws the moon in the given phase, see `phase`, as a disc of `rows` lines
+/// of text, lit on the right while waxing and on the left while waning as
+/// seen from the northern hemisphere.
+///
+/// Lines are twice as wide as there are rows, for terminal cells about
+/// twice as tall as they're wide.
+pub fn glyph(phase: Float, rows: usize) -> Vec<String> {
+    let cols = rows * 2;
+    // position of the terminator relative to the edge of the disc, from `1.`
+    // on the right at new moon to `-1.` on the left at full moon
+    let terminator = (phase * 2. * PI).cos();
+    let waxing = phase.rem_euclid(1.) < 0.5;
+    (0..rows)
+        .map(|row| {
+            let y = (row as Float + 0.5) / rows as Float * 2. - 1.;
+            let half_width = (1. - y * y).max(0.).sqrt();
+            (0..cols)
+                .map(|col| {
+                    let x = (col as Float + 0.5) / cols as Float * 2. - 1.;
+                    if x.abs() > half_width {
+                        ' '
+                    } else if (waxing && x > half_width * terminator)
+                        || (!waxing && x < -half_width * terminator)
+                    {
+                        LIT_CHAR
+                    } else {
+                        DARK_CHAR
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns the ecliptic longitude and latitude of the moon in degrees, `d`
+/// days from J2000.0.
+fn ecliptic_lon_lat(d: f64) -> (f64, f64) {
+    let t = d / CENTURY_DAYS;
+    let term =
+        |amplitude: f64, base: f64, rate: f64| amplitude * (base + rate * t).to_radians().sin();
+    let lon = 218.32
+        + 481_267.881 * t
+        + term(6.29, 135.0, 477_198.87)
+        + term(-1.27, 259.3, -413_335.36)
+        + term(0.66, 235.7, 890_534.22)
+        + term(0.21, 269.9, 954_397.74)
+        + term(-0.19, 357.5, 35_999.05)
+        + term(-0.11, 186.5, 966_404.03);
+    let lat = term(5.13, 93.3, 483_202.02)
+        + term(0.28, 228.2, 960_400.89)
+        + term(-0.28, 318.3, 6_003.15)
+        + term(-0.17, 217.6, -407_332.21);
+    (lon, lat)
+}
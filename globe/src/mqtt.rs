@@ -0,0 +1,175 @@
+//! Streaming device positions from an MQTT broker onto the globe.
+//!
+//! Enabled with the `mqtt` feature.
+
+use std::collections::BTreeMap;
+use std::process;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+use rumqttc::{Client, Event, MqttOptions, Packet, QoS};
+use serde_json::Value;
+
+use crate::{Color, Error, Float, GeoCoord, Globe, Marker, Result};
+
+/// Time between connection attempts while the broker can't be reached.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+/// Number of requests to the broker that can be queued up.
+const REQUEST_CAPACITY: usize = 10;
+/// Character of markers of devices without a label.
+const DEFAULT_MARKER: char = 'o';
+
+/// Last known position of a device.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Device {
+    /// Identifier from the payload's `id` field, or the topic it was
+    /// published on
+    pub id: String,
+    /// Name from the payload's `label` field, or the identifier
+    pub label: String,
+    pub coord: GeoCoord,
+    pub color: Option<Color>,
+    /// Character the device is marked with
+    pub marker: char,
+    /// Marker group from the payload's `group` field
+    pub group: Option<String>,
+}
+
+impl Device {
+    /// Parses a JSON payload such as
+    /// `{"id": "truck-1", "lat": 51.5, "lon": -0.1}`.
+    ///
+    /// Besides the required latitude and longitude, in degrees, payloads can
+    /// carry a `label`, a `color` given as `#rrggbb`, a `marker`
+    /// character, which defaults to the first character of the label, and
+    /// the name of a marker `group`.
+    pub fn from_payload(topic: &str, payload: &[u8]) -> Option<Self> {
+        let value: Value = serde_json::from_slice(payload).ok()?;
+        let lat = value.get("lat")?.as_f64()?;
+        let lon = value.get("lon")?.as_f64()?;
+        let string = |key: &str| value.get(key).and_then(Value::as_str);
+
+        let id = string("id").unwrap_or(topic).to_string();
+        let label = string("label").map_or_else(|| id.clone(), String::from);
+        let marker = string("marker")
+            .and_then(|s| s.chars().next())
+            .or_else(|| label.chars().next())
+            .unwrap_or(DEFAULT_MARKER);
+        Some(Self {
+            id,
+            label,
+            coord: GeoCoord::from_lat_lon(lat as Float, lon as Float),
+            color: string("color").and_then(parse_hex_color),
+            marker,
+            group: string("group").map(String::from),
+        })
+    }
+
+    /// Returns the marker showing the device on the globe.
+    pub fn to_marker(&self) -> Marker {
+        let mut marker = Marker::new(self.coord, self.marker).with_label(&self.label);
+        marker.color = self.color;
+        marker.group = self.group.clone();
+        marker
+    }
+}
+
+/// Subscription to a topic carrying device positions as JSON payloads.
+///
+/// Messages are received on a background thread, which keeps reconnecting
+/// whenever the connection to the broker drops. Call `update` every frame to
+/// take in the latest positions and `apply_markers` to show them.
+pub struct MqttFeed {
+    client: Client,
+    updates: Receiver<Device>,
+    /// Devices by their identifier
+    devices: BTreeMap<String, Device>,
+    /// Markers added to the globe by the last `apply_markers` call
+    placed: Vec<Marker>,
+}
+
+impl MqttFeed {
+    /// Connects to the broker and subscribes to the topic, which may
+    /// contain wildcards, e.g. `fleet/+/position`.
+    pub fn connect(host: &str, port: u16, topic: &str) -> Result<Self> {
+        let mut options = MqttOptions::new(format!("globe-{}", process::id()), host, port);
+        options.set_keep_alive(Duration::from_secs(30));
+        let (client, mut connection) = Client::new(options, REQUEST_CAPACITY);
+        // subscriptions are queued until the connection is up
+        client
+            .subscribe(topic, QoS::AtMostOnce)
+            .map_err(Error::Mqtt)?;
+
+        let (sender, updates) = mpsc::channel();
+        thread::spawn(move || {
+            for event in connection.iter() {
+                let publish = match event {
+                    Ok(Event::Incoming(Packet::Publish(publish))) => publish,
+                    Ok(_) => continue,
+                    Err(_) => {
+                        thread::sleep(RECONNECT_DELAY);
+                        continue;
+                    }
+                };
+                let device = match Device::from_payload(&publish.topic, &publish.payload) {
+                    Some(device) => device,
+                    // malformed payloads are skipped
+                    None => continue,
+                };
+                // the feed was dropped
+                if sender.send(device).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Self {
+            client,
+            updates,
+            devices: BTreeMap::new(),
+            placed: Vec::new(),
+        })
+    }
+
+    /// Takes in the positions received since the last call. Returns true if
+    /// any device moved or showed up.
+    pub fn update(&mut self) -> bool {
+        let mut changed = false;
+        while let Ok(device) = self.updates.try_recv() {
+            let previous = self.devices.insert(device.id.clone(), device.clone());
+            changed |= previous.as_ref() != Some(&device);
+        }
+        changed
+    }
+
+    /// Returns the devices seen so far, ordered by their identifier.
+    pub fn devices(&self) -> impl Iterator<Item = &Device> {
+        self.devices.values()
+    }
+
+    /// Replaces the markers placed by the previous call with markers of the
+    /// current device positions, leaving other markers on the globe alone.
+    pub fn apply_markers(&mut self, globe: &mut Globe) {
+        let placed = &self.placed;
+        globe.markers.retain(|marker| !placed.contains(marker));
+        self.placed = self.devices.values().map(Device::to_marker).collect();
+        globe.markers.extend(self.placed.iter().cloned());
+    }
+}
+
+impl Drop for MqttFeed {
+    fn drop(&mut self) {
+        let _ = self.client.disconnect();
+    }
+}
+
+/// Parses a color given as `#rrggbb`.
+fn parse_hex_color(s: &str) -> Option<Color> {
+    let hex = s.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(Color::new(channel(0)?, channel(2)?, channel(4)?))
+}
@@ -0,0 +1,371 @@
+//! Position of satellites in low orbits, e.g. the International Space
+//! Station, propagated from two-line element sets.
+//!
+//! Orbits are propagated as ellipses turned by the Earth's flattening and
+//! shrunk by drag, leaving out the smaller terms of the SGP4 model the
+//! element sets are fitted with. The position drifts by a few tens of
+//! kilometers a day from the epoch, so element sets are best kept to a few
+//! days old, but passes still come out within a minute or so.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::error::{Error, Result};
+#[cfg(not(any(feature = "std", test)))]
+use crate::float::FloatExt;
+use crate::{sun, Float, GeoCoord};
+
+/// Gravitational parameter of the Earth, in km³/s².
+const MU: f64 = 398_600.441_8;
+/// Second zonal harmonic of the Earth's gravity, from its flattening.
+const J2: f64 = 1.082_63e-3;
+/// Equatorial radius of the Earth the harmonic is given for, in kilometers.
+const EQUATORIAL_RADIUS_KM: f64 = 6_378.137;
+/// Mean radius of the Earth in kilometers, the sphere locations are taken to
+/// lie on, see `geo::EARTH_RADIUS_KM`.
+const MEAN_RADIUS_KM: f64 = 6_371.;
+/// Number of seconds in a day.
+const DAY_SECS: f64 = 86_400.;
+/// Seconds between the samples passes are looked for at, short enough not
+/// to miss the lowest ones.
+const PASS_STEP: i64 = 30;
+/// Elevation of the sun in degrees below which the sky is dark enough for a
+/// sunlit satellite to be seen.
+const DARK_SUN_ELEVATION: Float = -6.;
+
+/// Orbit of a satellite as given by a two-line element set, the format
+/// NORAD and CelesTrak publish them in.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tle {
+    /// Name of the satellite, from the line before the elements if given
+    pub name: Option<String>,
+    /// Unix time in seconds the elements were given for
+    pub epoch: f64,
+    /// Inclination of the orbit in degrees
+    pub inclination: f64,
+    /// Right ascension of the ascending node in degrees
+    pub raan: f64,
+    /// Eccentricity of the orbit
+    pub eccentricity: f64,
+    /// Argument of perigee in degrees
+    pub arg_perigee: f64,
+    /// Mean anomaly in degrees
+    pub mean_anomaly: f64,
+    /// Mean motion in revolutions per day
+    pub mean_motion: f64,
+    /// Half the rate the mean motion changes at, in revolutions per day
+    /// squared, taking drag into account
+    pub mean_motion_dot: f64,
+}
+
+/// Time the satellite spends above the horizon of a location.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pass {
+    /// Unix time in seconds the satellite rises at
+    pub rise: i64,
+    /// Unix time in seconds the satellite is highest at
+    pub culmination: i64,
+    /// Unix time in seconds the satellite sets at
+    pub set: i64,
+    /// Highest elevation above the horizon in degrees
+    pub max_elevation: Float,
+    /// Whether the satellite is lit by the sun against a dark sky during
+    /// part of the pass, so it can be seen with the naked eye
+    pub visible: bool,
+}
+
+impl Tle {
+    /// Parses an element set given as its two lines, optionally preceded by
+    /// a line with the satellite's name.
+    ///
+    /// Checksums aren't verified, but fails if the lines are missing or a
+    /// field can't be parsed.
+    pub fn parse(text: &str) -> Result<Self> {
+        let lines: Vec<&str> = text
+            .lines()
+            .map(str::trim_end)
+            .filter(|line| !line.is_empty())
+            .collect();
+        let first = lines
+            .iter()
+            .position(|line| line.starts_with("1 "))
+            .ok_or(Error::InvalidTle)?;
+        let (line1, line2) = match lines.get(first + 1) {
+            Some(line2) if line2.starts_with("2 ") => (lines[first], *line2),
+            _ => return Err(Error::InvalidTle),
+        };
+        let name = first
+            .checked_sub(1)
+            .map(|i| lines[i].trim_start_matches("0 ").trim().to_string());
+
+        let field = |line: &str, from: usize, to: usize| -> Result<f64> {
+            line.get(from..to)
+                .and_then(|field| field.trim().parse().ok())
+                .ok_or(Error::InvalidTle)
+        };
+        let epoch_year = field(line1, 18, 20)? as i32;
+        let epoch_day = field(line1, 20, 32)?;
+        // two digit years wrap around at the launch of the first satellite
+        let year = if epoch_year < 57 {
+            2000 + epoch_year
+        } else {
+            1900 + epoch_year
+        };
+        let epoch = sun::year_start(year) as f64 + (epoch_day - 1.) * DAY_SECS;
+        let eccentricity = line2
+            .get(26..33)
+            .and_then(|digits| ["0.", digits.trim()].concat().parse().ok())
+            .ok_or(Error::InvalidTle)?;
+
+        Ok(Self {
+            name,
+            epoch,
+            inclination: field(line2, 8, 16)?,
+            raan: field(line2, 17, 25)?,
+            eccentricity,
+            arg_perigee: field(line2, 34, 42)?,
+            mean_anomaly: field(line2, 43, 51)?,
+            mean_motion: field(line2, 52, 63)?,
+            mean_motion_dot: field(line1, 33, 43)?,
+        })
+    }
+
+    /// Returns the location the satellite is over at the given Unix time in
+    /// seconds.
+    pub fn subpoint(&self, unix_secs: i64) -> GeoCoord {
+        let [x, y, z] = self.position(unix_secs);
+        let lat = (z / (x * x + y * y + z * z).sqrt()).asin().to_degrees();
+        let lon = y.atan2(x).to_degrees();
+        GeoCoord::from_lat_lon(lat as Float, lon as Float)
+    }
+
+    /// Returns the height of the satellite above the surface in kilometers,
+    /// at the given Unix time in seconds.
+    pub fn altitude_km(&self, unix_secs: i64) -> Float {
+        let [x, y, z] = self.position(unix_secs);
+        ((x * x + y * y + z * z).sqrt() - MEAN_RADIUS_KM) as Float
+    }
+
+    /// Returns the locations the satellite is over from `from` up to `to`,
+    /// Unix times in seconds, one every `step` seconds.
+    pub fn ground_track(&self, from: i64, to: i64, step: i64) -> Vec<GeoCoord> {
+        (from..to)
+            .step_by(step.max(1) as usize)
+            .map(|t| self.subpoint(t))
+            .collect()
+    }
+
+    /// Returns the elevation of the satellite above the horizon of the given
+    /// location in degrees, negative while it's below it.
+    pub fn elevation(&self, coord: GeoCoord, unix_secs: i64) -> Float {
+        let up = unit_vector(coord);
+        let observer = up.map(|c| c * MEAN_RADIUS_KM);
+        let satellite = self.position(unix_secs);
+        let to = [0, 1, 2].map(|i| satellite[i] - observer[i]);
+        let distance = dot(&to, &to).sqrt();
+        (dot(&to, &up) / distance).asin().to_degrees() as Float
+    }
+
+    /// Returns whether the satellite is lit by the sun at the given Unix time
+    /// in seconds, rather than in the Earth's shadow.
+    pub fn is_sunlit(&self, unix_secs: i64) -> bool {
+        let (lat, lon) = sun::subsolar_lat_lon(unix_secs);
+        let sun = unit_vector(GeoCoord::from_lat_lon(lat as Float, lon as Float));
+        let satellite = self.position(unix_secs);
+        let along = dot(&satellite, &sun);
+        if along >= 0. {
+            return true;
+        }
+        // distance from the line through the Earth's center towards the sun,
+        // the shadow taken as a cylinder
+        let off = [0, 1, 2].map(|i| satellite[i] - sun[i] * along);
+        dot(&off, &off).sqrt() > MEAN_RADIUS_KM
+    }
+
+    /// Returns the passes of the satellite over the given location between
+    /// `from` and `to`, Unix times in seconds, in the order they happen.
+    ///
+    /// A pass under way at `from` starts there, and one still under way at
+    /// `to` is left out.
+    pub fn passes(&self, coord: GeoCoord, from: i64, to: i64) -> Vec<Pass> {
+        let mut passes = Vec::new();
+        let mut current: Option<Pass> = None;
+        let mut previous = (from, self.elevation(coord, from));
+        if previous.1 > 0. {
+            current = Some(self.pass_from(from, previous.1));
+        }
+        let mut t = from;
+        while t < to {
+            t = (t + PASS_STEP).min(to);
+            let elevation = self.elevation(coord, t);
+            if elevation > 0. && previous.1 <= 0. {
+                let rise = self.horizon_crossing(coord, previous.0, t);
+                current = Some(self.pass_from(rise, self.elevation(coord, rise)));
+            }
+            if let Some(pass) = &mut current {
+                if elevation <= 0. {
+                    pass.set = self.horizon_crossing(coord, previous.0, t);
+                    passes.push(*pass);
+                    current = None;
+                } else {
+                    if elevation > pass.max_elevation {
+                        pass.max_elevation = elevation;
+                        pass.culmination = t;
+                    }
+                    pass.visible |=
+                        self.is_sunlit(t) && sun::elevation(coord, t) < DARK_SUN_ELEVATION;
+                }
+            }
+            previous = (t, elevation);
+        }
+        passes
+    }
+
+    /// Starts a pass rising at the given time.
+    fn pass_from(&self, rise: i64, elevation: Float) -> Pass {
+        Pass {
+            rise,
+            culmination: rise,
+            set: rise,
+            max_elevation: elevation,
+            visible: false,
+        }
+    }
+
+    /// Returns the second the satellite crosses the horizon at between two
+    /// times it's on opposite sides of it at.
+    fn horizon_crossing(&self, coord: GeoCoord, mut from: i64, mut to: i64) -> i64 {
+        let above = self.elevation(coord, to) > 0.;
+        while to - from > 1 {
+            let middle = (from + to) / 2;
+            if (self.elevation(coord, middle) > 0.) == above {
+                to = middle;
+            } else {
+                from = middle;
+            }
+        }
+        to
+    }
+
+    /// Returns the position of the satellite in kilometers, in axes turning
+    /// with the Earth: the first towards longitude 0 on the equator, the
+    /// second towards 90° east and the third towards the north pole.
+    fn position(&self, unix_secs: i64) -> [f64; 3] {
+        let dt = unix_secs as f64 - self.epoch;
+        let days = dt / DAY_SECS;
+        let e = self.eccentricity;
+        let i = self.inclination.to_radians();
+
+        // mean motion in radians per second and the size of the orbit
+        let n = self.mean_motion * 2. * core::f64::consts::PI / DAY_SECS;
+        let a = (MU / (n * n)).powf(1. / 3.);
+        let p = a * (1. - e * e);
+        // the equatorial bulge turns the orbit around the axis and within
+        // its plane
+        let k = 1.5 * J2 * (EQUATORIAL_RADIUS_KM / p).powi(2) * n;
+        let raan = self.raan.to_radians() - k * i.cos() * dt;
+        let arg_perigee =
+            self.arg_perigee.to_radians() + 0.5 * k * (5. * i.cos().powi(2) - 1.) * dt;
+        let revolutions = self.mean_motion * days + self.mean_motion_dot * days * days;
+        let mean_anomaly =
+            self.mean_anomaly.to_radians() + revolutions * 2. * core::f64::consts::PI;
+
+        // eccentric anomaly, solving Kepler's equation
+        let mut anomaly = mean_anomaly;
+        for _ in 0..10 {
+            anomaly -= (anomaly - e * anomaly.sin() - mean_anomaly) / (1. - e * anomaly.cos());
+        }
+        let x = a * (anomaly.cos() - e);
+        let y = a * (1. - e * e).sqrt() * anomaly.sin();
+
+        let (sin_raan, cos_raan) = raan.sin_cos();
+        let (sin_arg, cos_arg) = arg_perigee.sin_cos();
+        let (sin_i, cos_i) = i.sin_cos();
+        let inertial = [
+            x * (cos_raan * cos_arg - sin_raan * sin_arg * cos_i)
+                - y * (cos_raan * sin_arg + sin_raan * cos_arg * cos_i),
+            x * (sin_raan * cos_arg + cos_raan * sin_arg * cos_i)
+                + y * (cos_raan * cos_arg * cos_i - sin_raan * sin_arg),
+            x * sin_arg * sin_i + y * cos_arg * sin_i,
+        ];
+
+        let sidereal = sun::sidereal_time(sun::days_since_j2000(unix_secs)).to_radians();
+        let (sin_s, cos_s) = sidereal.sin_cos();
+        [
+            inertial[0] * cos_s + inertial[1] * sin_s,
+            inertial[1] * cos_s - inertial[0] * sin_s,
+            inertial[2],
+        ]
+    }
+}
+
+/// Returns the unit vector pointing at the location, in the axes of
+/// `Tle::position`.
+fn unit_vector(coord: GeoCoord) -> [f64; 3] {
+    let (lat, lon) = coord.lat_lon();
+    let [lat, lon] = [lat, lon].map(f64::from).map(f64::to_radians);
+    [lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin()]
+}
+
+fn dot(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Element set of the ISS from the 20th of September 2008, the example
+    /// the two-line element format is usually documented with.
+    const ISS: &str = "ISS (ZARYA)
+1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927
+2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537";
+    /// Unix time of the element set's epoch, 12:25:40 UTC, cut to seconds.
+    const EPOCH: i64 = 1_221_913_540;
+
+    #[test]
+    fn parses_the_elements() {
+        let tle = Tle::parse(ISS).unwrap();
+        assert_eq!(tle.name.as_deref(), Some("ISS (ZARYA)"));
+        assert!((tle.epoch - 1_221_913_540.104).abs() < 0.01);
+        assert_eq!(tle.inclination, 51.6416);
+        assert_eq!(tle.raan, 247.4627);
+        assert_eq!(tle.eccentricity, 0.0006703);
+        assert_eq!(tle.mean_motion, 15.72125391);
+        assert_eq!(tle.mean_motion_dot, -0.00002182);
+        assert!(Tle::parse("1 25544U").is_err());
+    }
+
+    #[test]
+    fn station_is_where_its_elements_put_it_at_the_epoch() {
+        let tle = Tle::parse(ISS).unwrap();
+        // 95.6° past the ascending node, with the sidereal time at 186.2°
+        let (lat, lon) = tle.subpoint(EPOCH).lat_lon();
+        assert!((lat - 51.31).abs() < 0.05, "latitude {}", lat);
+        assert!((lon - 160.13).abs() < 0.05, "longitude {}", lon);
+        let altitude = tle.altitude_km(EPOCH);
+        assert!((340. ..380.).contains(&altitude), "altitude {}", altitude);
+    }
+
+    #[test]
+    fn ground_track_stays_within_the_inclination() {
+        let tle = Tle::parse(ISS).unwrap();
+        for coord in tle.ground_track(EPOCH, EPOCH + 86_400, 60) {
+            assert!(coord.lat_lon().0.abs() < 51.7);
+        }
+    }
+
+    #[test]
+    fn passes_overhead_of_the_place_it_is_over() {
+        let tle = Tle::parse(ISS).unwrap();
+        let below = tle.subpoint(EPOCH);
+        let passes = tle.passes(below, EPOCH - 900, EPOCH + 900);
+        assert_eq!(passes.len(), 1);
+        let pass = passes[0];
+        assert!((pass.culmination - EPOCH).abs() <= PASS_STEP);
+        assert!(pass.max_elevation > 85., "elevation {}", pass.max_elevation);
+        // about ten minutes from horizon to horizon at this height
+        assert!((540..660).contains(&(pass.set - pass.rise)));
+        assert!(pass.rise < pass.culmination && pass.culmination < pass.set);
+    }
+}
@@ -0,0 +1,207 @@
+//! Mapping points on the globe surface to canvas cells.
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::float::FloatExt;
+use crate::{dot, texture_coords, view_extent, Float, GeoCoord, Globe, Int, RenderTarget};
+
+impl Globe {
+    /// Returns true if the given point on the surface of the globe is facing
+    /// the camera.
+    pub(crate) fn is_point_visible(&self, point: [Float; 3]) -> bool {
+        let to_camera = [
+            self.camera.x - point[0],
+            self.camera.y - point[1],
+            self.camera.z - point[2],
+        ];
+        dot(&point, &to_camera) > 0.
+    }
+
+    /// Returns true if the location given as latitude and longitude in
+    /// degrees is on the side of the globe facing the camera, whether or
+    /// not it fits in the view.
+    pub fn is_visible(&self, lat: Float, lon: Float) -> bool {
+        let coord = GeoCoord::from_lat_lon(lat, lon);
+        self.is_point_visible(coord.sphere_point(self.angle, self.radius))
+    }
+
+    /// Returns the canvas cell (column and row) the given point is displayed
+    /// at, or `None` if it's outside of the view.
+    pub(crate) fn project_point<S: RenderTarget>(
+        &self,
+        point: [Float; 3],
+        canvas: &S,
+    ) -> Option<(usize, usize)> {
+        let (x, y) = self.screen_position(point, canvas)?;
+        let (xi, yi) = (x.round() as Int, y.round() as Int);
+        let (cols, rows) = canvas.grid_size();
+        if xi < 0 || yi < 0 || xi >= cols as Int || yi >= rows as Int {
+            return None;
+        }
+        Some((xi as usize, yi as usize))
+    }
+
+    /// Returns where the given point is displayed on the canvas, in columns
+    /// and rows from the center of the top left cell and not rounded to a
+    /// cell, or `None` if it's behind the camera.
+    pub(crate) fn screen_position<S: RenderTarget>(
+        &self,
+        point: [Float; 3],
+        canvas: &S,
+    ) -> Option<(Float, Float)> {
+        let m = &self.camera.matrix;
+        // direction from the camera to the point, in camera space
+        let d = [
+            point[0] - self.camera.x,
+            point[1] - self.camera.y,
+            point[2] - self.camera.z,
+        ];
+        let dx = dot(&d, &[m[0], m[1], m[2]]);
+        let dy = dot(&d, &[m[4], m[5], m[6]]);
+        let dz = dot(&d, &[m[8], m[9], m[10]]);
+        // point is behind the camera
+        if dz >= 0. {
+            return None;
+        }
+        let ray_x = dx / -dz;
+        let ray_y = dy / -dz;
+
+        // invert the ray setup used when rendering
+        let (cols, rows) = canvas.grid_size();
+        let half_x = (cols / 2) as Float;
+        let half_y = (rows / 2) as Float;
+        let extent = view_extent((cols, rows), canvas.char_pix());
+        Some((
+            half_x - 0.5 - ray_x / extent.0 * half_x,
+            half_y - 0.5 + ray_y / extent.1 * half_y,
+        ))
+    }
+
+    /// Returns true if the ray shot through the center of the given canvas
+    /// cell hits the globe, the way it's shot when rendering.
+    pub(crate) fn covers_cell<S: RenderTarget>(&self, canvas: &S, cell: (usize, usize)) -> bool {
+        self.intersect(self.cell_ray(canvas, cell), self.radius)
+            .is_some()
+    }
+
+    /// Returns the latitude and longitude in degrees of the location shown
+    /// at the given canvas cell (column and row), or `None` if the cell is
+    /// off the globe.
+    ///
+    /// The ray is shot through the center of the cell the same way it's
+    /// shot when rendering, so the location is the one the cell's character
+    /// was picked from.
+    pub fn unproject<S: RenderTarget>(
+        &self,
+        canvas: &S,
+        x: usize,
+        y: usize,
+    ) -> Option<(Float, Float)> {
+        let (cols, rows) = canvas.grid_size();
+        if x >= cols || y >= rows {
+            return None;
+        }
+        let point = self.intersect(self.cell_ray(canvas, (x, y)), self.radius)?;
+        let (theta, phi) = texture_coords(point, self.angle);
+        // texture rows are stored reversed, running from east to west
+        Some(GeoCoord::new((1. - theta).rem_euclid(1.), 1. - phi).lat_lon())
+    }
+
+    /// Returns the direction of the ray shot from the camera through the
+    /// center of the given canvas cell.
+    fn cell_ray<S: RenderTarget>(&self, canvas: &S, (x, y): (usize, usize)) -> [Float; 3] {
+        let (cols, rows) = canvas.grid_size();
+        let half_x = (cols / 2) as Int;
+        let half_y = (rows / 2) as Int;
+        let extent = view_extent((cols, rows), canvas.char_pix());
+        let ray_x = -((x as Int - half_x) as Float + 0.5) / half_x as Float * extent.0;
+        let ray_y = ((y as Int - half_y) as Float + 0.5) / half_y as Float * extent.1;
+        self.ray_direction(ray_x, ray_y)
+    }
+
+    /// Returns the canvas cell (column and row) the location given as
+    /// latitude and longitude in degrees is drawn at, or `None` if it's on
+    /// the far side of the globe or out of view.
+    ///
+    /// Markers land on the same cells, so anything drawn there afterwards
+    /// stays in place over the surface.
+    pub fn project<S: RenderTarget>(
+        &self,
+        lat: Float,
+        lon: Float,
+        canvas: &S,
+    ) -> Option<(usize, usize)> {
+        self.coord_cell(canvas, GeoCoord::from_lat_lon(lat, lon))
+    }
+
+    /// Returns the canvas cell the location is drawn at, `None` if it's on
+    /// the far side of the globe or out of view.
+    pub(crate) fn coord_cell<S: RenderTarget>(
+        &self,
+        canvas: &S,
+        coord: GeoCoord,
+    ) -> Option<(usize, usize)> {
+        let point = coord.sphere_point(self.angle, self.radius);
+        if !self.is_point_visible(point) {
+            return None;
+        }
+        self.project_point(point, canvas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CameraConfig, Canvas, Globe, GlobeConfig, GlobeTemplate};
+
+    fn earth() -> Globe {
+        let mut globe = GlobeConfig::new()
+            .use_template(GlobeTemplate::Earth)
+            .with_camera(CameraConfig::new(1.7, 0.4, 0.3))
+            .build()
+            .unwrap();
+        globe.set_angle(1.2);
+        globe
+    }
+
+    #[test]
+    fn cells_unproject_to_locations_projecting_back_onto_them() {
+        let globe = earth();
+        let canvas = Canvas::new(320, 192, None);
+        let (cols, rows) = canvas.grid_size();
+        let mut on_globe = 0;
+        for y in 0..rows {
+            for x in 0..cols {
+                if let Some((lat, lon)) = globe.unproject(&canvas, x, y) {
+                    on_globe += 1;
+                    assert_eq!(globe.project(lat, lon, &canvas), Some((x, y)));
+                }
+            }
+        }
+        // the globe fills a good part of the view, so plenty of cells are checked
+        assert!(on_globe > cols * rows / 8);
+    }
+
+    #[test]
+    fn locations_project_onto_cells_unprojecting_close_to_them() {
+        let globe = earth();
+        let canvas = Canvas::new(320, 192, None);
+        let (cols, rows) = canvas.grid_size();
+        let (lat, lon) = globe.unproject(&canvas, cols / 2, rows / 2).unwrap();
+        // half a degree off lands on the same cell or a neighboring one
+        let (x, y) = globe.project(lat + 0.5, lon - 0.5, &canvas).unwrap();
+        assert!(x.abs_diff(cols / 2) <= 1 && y.abs_diff(rows / 2) <= 1);
+        let (back_lat, back_lon) = globe.unproject(&canvas, x, y).unwrap();
+        assert!((back_lat - lat).abs() < 3., "latitude {}", back_lat);
+        assert!((back_lon - lon).abs() < 3., "longitude {}", back_lon);
+    }
+
+    #[test]
+    fn far_side_and_cells_off_the_canvas_have_no_location() {
+        let globe = earth();
+        let canvas = Canvas::new(320, 192, None);
+        let (cols, rows) = canvas.grid_size();
+        let (lat, lon) = globe.unproject(&canvas, cols / 2, rows / 2).unwrap();
+        assert_eq!(globe.project(-lat, lon + 180., &canvas), None);
+        assert_eq!(globe.unproject(&canvas, 0, 0), None);
+        assert_eq!(globe.unproject(&canvas, cols, rows / 2), None);
+    }
+}
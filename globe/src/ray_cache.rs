@@ -0,0 +1,81 @@
+//! Caching ray directions between frames.
+
+use alloc::vec::Vec;
+
+use crate::{target, Camera, Float, RenderTarget, Scalar};
+
+/// Directions of the rays shot through the samples of a canvas, kept between
+/// frames.
+///
+/// As long as the camera and the canvas stay the same, e.g. while only the
+/// globe spins, every frame shoots the same rays and only their hits on the
+/// sphere need computing. Kept by `Canvas`, other render targets shoot every
+/// ray each frame.
+#[derive(Clone, Debug, Default)]
+pub struct RayCache {
+    key: Option<CacheKey>,
+    directions: Vec<Option<[Scalar; 3]>>,
+}
+
+/// Everything the ray directions depend on.
+#[derive(Clone, Debug, PartialEq)]
+struct CacheKey {
+    position: [Float; 3],
+    matrix: [Float; 16],
+    /// Number of samples across and down the canvas
+    grid: (usize, usize),
+}
+
+impl RayCache {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forgets all the cached directions.
+    pub fn reset(&mut self) {
+        self.key = None;
+        self.directions.clear();
+    }
+
+    /// Starts over unless the camera and the sample grid are the same as the
+    /// ones the directions were cached for.
+    fn validate(&mut self, camera: &Camera, grid: (usize, usize)) {
+        let key = CacheKey {
+            position: [camera.x, camera.y, camera.z],
+            matrix: camera.matrix,
+            grid,
+        };
+        if self.key.as_ref() != Some(&key) {
+            self.directions.clear();
+            self.directions.resize(grid.0 * grid.1, None);
+            self.key = Some(key);
+        }
+    }
+}
+
+/// Invalidates the target's cache if the camera or the sample grid changed.
+/// Called before shooting rays through the grid.
+pub(crate) fn validate<T: RenderTarget>(target: &mut T, camera: &Camera, grid: (usize, usize)) {
+    if let Some(cache) = target::ray_cache(target) {
+        cache.validate(camera, grid);
+    }
+}
+
+/// Returns the direction of the ray through the given sample, computing and
+/// caching it on first use.
+pub(crate) fn direction<T, F>(target: &mut T, (x, y): (usize, usize), compute: F) -> [Scalar; 3]
+where
+    T: RenderTarget,
+    F: FnOnce() -> [Scalar; 3],
+{
+    let cache = match target::ray_cache(target) {
+        Some(cache) => cache,
+        None => return compute(),
+    };
+    let slot = match &cache.key {
+        Some(key) if x < key.grid.0 && y < key.grid.1 => &mut cache.directions[y * key.grid.0 + x],
+        _ => return compute(),
+    };
+    *slot.get_or_insert_with(compute)
+}
@@ -0,0 +1,187 @@
+//! Recording rendered canvases into animated GIF or PNG images.
+//!
+//! Enabled with the `image` feature.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::error::{EncodingError, ImageFormatHint, UnsupportedError, UnsupportedErrorKind};
+use image::{imageops, Delay, ImageError, ImageFormat, RgbImage};
+
+use crate::image_export::rasterize;
+use crate::{Canvas, Cell, Error, FontMetrics, Result};
+
+/// Speed of the GIF color quantization, from 1 (best quality) to 30.
+const GIF_QUANTIZATION_SPEED: i32 = 10;
+
+/// Format of a recorded animation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnimationFormat {
+    Gif,
+    /// Animated PNG
+    Apng,
+}
+
+impl AnimationFormat {
+    /// Guesses the format from the file extension, `.gif` for GIF and
+    /// `.png` or `.apng` for APNG.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let ext = path.as_ref().extension()?.to_str()?.to_lowercase();
+        match ext.as_str() {
+            "gif" => Some(AnimationFormat::Gif),
+            "png" | "apng" => Some(AnimationFormat::Apng),
+            _ => None,
+        }
+    }
+}
+
+/// Characters and colors of a recorded canvas.
+struct Frame {
+    grid_size: (usize, usize),
+    cells: Vec<Cell>,
+    delay: Duration,
+}
+
+/// Collects successive canvases and writes them out as an animation.
+///
+/// Only the cells are kept until the animation is written out, rasterized
+/// using the built-in bitmap font, see `FontMetrics`. All the frames take
+/// the size of the first one, larger ones get cropped.
+pub struct Recorder {
+    metrics: FontMetrics,
+    frames: Vec<Frame>,
+}
+
+impl Recorder {
+    /// Creates an empty recorder drawing frames with the given metrics.
+    pub fn new(metrics: FontMetrics) -> Self {
+        Self {
+            metrics,
+            frames: Vec::new(),
+        }
+    }
+
+    /// Adds the canvas as the next frame, shown for the given time.
+    pub fn add_frame(&mut self, canvas: &Canvas, delay: Duration) {
+        let (cols, rows) = canvas.grid_size();
+        let mut cells = Vec::with_capacity(cols * rows);
+        for row in 0..rows {
+            for col in 0..cols {
+                cells.extend(canvas.cell(col, row));
+            }
+        }
+        self.frames.push(Frame {
+            grid_size: (cols, rows),
+            cells,
+            delay,
+        });
+    }
+
+    /// Returns the number of frames recorded so far.
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Removes all the recorded frames.
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    /// Writes the animation to a file, in the format matching its extension.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let format = AnimationFormat::from_path(path).ok_or_else(|| {
+            let hint = ImageFormatHint::PathExtension(path.to_path_buf());
+            Error::SaveImage(ImageError::Unsupported(
+                UnsupportedError::from_format_and_kind(
+                    hint.clone(),
+                    UnsupportedErrorKind::Format(hint),
+                ),
+            ))
+        })?;
+        let io_error = |e| Error::SaveImage(ImageError::IoError(e));
+        let mut file = BufWriter::new(File::create(path).map_err(io_error)?);
+        match format {
+            AnimationFormat::Gif => self.write_gif(&mut file)?,
+            AnimationFormat::Apng => self.write_apng(&mut file)?,
+        }
+        file.flush().map_err(io_error)
+    }
+
+    /// Writes the animation as a GIF looping forever.
+    pub fn write_gif<W: Write>(&self, w: W) -> Result<()> {
+        let mut encoder = GifEncoder::new_with_speed(w, GIF_QUANTIZATION_SPEED);
+        encoder
+            .set_repeat(Repeat::Infinite)
+            .map_err(Error::SaveImage)?;
+        for (image, delay) in self.images() {
+            let frame = image::Frame::from_parts(
+                image::DynamicImage::ImageRgb8(image).into_rgba8(),
+                0,
+                0,
+                Delay::from_saturating_duration(delay),
+            );
+            encoder.encode_frame(frame).map_err(Error::SaveImage)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the animation as an animated PNG looping forever.
+    pub fn write_apng<W: Write>(&self, w: W) -> Result<()> {
+        let (width, height) = self.image_size();
+        let mut encoder = png::Encoder::new(w, width, height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .set_animated(self.frames.len() as u32, 0)
+            .map_err(png_error)?;
+        let mut writer = encoder.write_header().map_err(png_error)?;
+        for (image, delay) in self.images() {
+            let millis = delay.as_millis().min(u16::MAX as u128) as u16;
+            writer.set_frame_delay(millis, 1000).map_err(png_error)?;
+            writer.write_image_data(&image).map_err(png_error)?;
+        }
+        writer.finish().map_err(png_error)
+    }
+
+    /// Returns the size of the animation in pixels, taken from the first
+    /// frame.
+    fn image_size(&self) -> (u32, u32) {
+        let (cols, rows) = self.frames.first().map_or((0, 0), |f| f.grid_size);
+        (
+            cols as u32 * self.metrics.cell_width,
+            rows as u32 * self.metrics.cell_height,
+        )
+    }
+
+    /// Rasterizes the frames one by one, fitting them to the animation size.
+    fn images(&self) -> impl Iterator<Item = (RgbImage, Duration)> + '_ {
+        let (width, height) = self.image_size();
+        self.frames.iter().map(move |frame| {
+            let (cols, _) = frame.grid_size;
+            let cell = |col, row| frame.cells.get(row * cols + col).copied();
+            let image = rasterize(frame.grid_size, cell, self.metrics);
+            if image.dimensions() == (width, height) {
+                return (image, frame.delay);
+            }
+            let mut fitted = RgbImage::new(width, height);
+            imageops::replace(&mut fitted, &image, 0, 0);
+            (fitted, frame.delay)
+        })
+    }
+}
+
+/// Wraps errors of the PNG encoder.
+fn png_error(e: png::EncodingError) -> Error {
+    let e = match e {
+        png::EncodingError::IoError(e) => ImageError::IoError(e),
+        e => ImageError::Encoding(EncodingError::new(
+            ImageFormatHint::Exact(ImageFormat::Png),
+            e,
+        )),
+    };
+    Error::SaveImage(e)
+}
@@ -0,0 +1,85 @@
+//! Re-rendering selected parts of the canvas.
+
+use crate::{Globe, RenderTarget, WithoutHistory};
+
+/// Rectangle of canvas cells.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CellRect {
+    /// Column of the left edge
+    pub x: usize,
+    /// Row of the top edge
+    pub y: usize,
+    /// Number of columns
+    pub width: usize,
+    /// Number of rows
+    pub height: usize,
+}
+
+impl CellRect {
+    /// Creates a new rectangle from its top left cell and size.
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Creates a rectangle centered on the given cell (column and row),
+    /// extending `margin` cells in every direction.
+    pub fn around(cell: (usize, usize), margin: usize) -> Self {
+        let x = cell.0.saturating_sub(margin);
+        let y = cell.1.saturating_sub(margin);
+        Self::new(x, y, cell.0 + margin + 1 - x, cell.1 + margin + 1 - y)
+    }
+
+    /// Returns true if the given cell lies within the rectangle.
+    pub fn contains(&self, col: usize, row: usize) -> bool {
+        col >= self.x && col - self.x < self.width && row >= self.y && row - self.y < self.height
+    }
+
+    /// Returns true if the two rectangles share any cell.
+    pub fn overlaps(&self, other: &CellRect) -> bool {
+        self.x < other.x + other.width
+            && other.x < self.x + self.width
+            && self.y < other.y + other.height
+            && other.y < self.y + self.height
+    }
+}
+
+impl Globe {
+    /// Re-renders only the canvas cells within the given rectangle, leaving
+    /// the rest of the canvas untouched.
+    ///
+    /// Meant for updating a few markers while the camera and the globe stay
+    /// still, e.g. with rectangles around the old and new position of a
+    /// marker, see `Globe::marker_cell`. Markers are redrawn only where they
+    /// fall within the rectangle. The cells don't count as another frame for
+    /// temporal smoothing, leaving how the next whole frame is smoothed as
+    /// it was.
+    pub fn render_region<T: RenderTarget>(&self, canvas: &mut T, rect: CellRect) {
+        self.render_cells(&mut WithoutHistory(canvas), rect);
+    }
+
+    /// Renders the cells within the rectangle, see `Globe::render_region`,
+    /// moving their smoothing history on like a whole frame does.
+    pub(crate) fn render_cells<T: RenderTarget>(&self, canvas: &mut T, rect: CellRect) {
+        let (cols, rows) = canvas.grid_size();
+        let span = rect.x.min(cols)..rect.x.saturating_add(rect.width).min(cols);
+        for row in rect.y.min(rows)..rect.y.saturating_add(rect.height).min(rows) {
+            for col in span.clone() {
+                canvas.clear_cell(col, row);
+            }
+            self.render_row_span(canvas, row, span.clone());
+        }
+        self.render_markers_within(canvas, Some(rect));
+    }
+
+    /// Re-renders the canvas cells within every one of the given rectangles.
+    pub fn render_regions<T: RenderTarget>(&self, canvas: &mut T, rects: &[CellRect]) {
+        for &rect in rects {
+            self.render_region(canvas, rect);
+        }
+    }
+}
@@ -0,0 +1,268 @@
+//! Render styles determining how samples are drawn onto the canvas.
+
+use core::ops::Range;
+
+use crate::math::Real;
+use crate::{
+    ray_cache, view_extent, CellColor, Color, Float, Globe, Int, RenderTarget, Scalar, BATCH,
+};
+
+/// First character of the unicode braille patterns block.
+pub(crate) const BRAILLE_BLANK: u32 = 0x2800;
+/// Braille dot bits, indexed by dot row and column.
+pub(crate) const BRAILLE_DOTS: [[u32; 2]; 4] =
+    [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+/// Quadrant block characters, indexed by bits set for the upper left (1),
+/// upper right (2), lower left (4) and lower right (8) quadrants.
+pub(crate) const QUADRANTS: [char; 16] = [
+    ' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛', '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█',
+];
+/// Minimum sample intensity for a dot or block to be drawn.
+const INTENSITY_THRESHOLD: Float = 0.1;
+/// Samples across and down a braille character cell.
+const BRAILLE_SAMPLES: (usize, usize) = (2, 4);
+/// Samples across and down a half block character cell.
+const HALF_BLOCK_SAMPLES: (usize, usize) = (1, 2);
+/// Samples across and down a quadrant block character cell.
+const QUADRANT_SAMPLES: (usize, usize) = (2, 2);
+
+/// Style used for drawing the globe onto the canvas.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RenderStyle {
+    /// Single texture character per canvas cell.
+    #[default]
+    Ascii,
+    /// Unicode braille characters, each cell packing 2x4 dots that are
+    /// raised where the texture is bright enough.
+    Braille,
+    /// Half block characters, each cell packing 2 samples stacked
+    /// vertically. With colors enabled the upper sample is drawn using the
+    /// foreground color and the lower one using the background color.
+    HalfBlock,
+    /// Quadrant block characters, each cell packing 2x2 samples.
+    Quadrant,
+}
+
+/// Result of shooting a single ray at the globe.
+#[derive(Clone, Copy)]
+pub(crate) struct Sample {
+    /// Texture character
+    pub ch: char,
+    /// Position of the character on the texture palette
+    pub index: Option<usize>,
+    /// Brightness of the character in the `0..1` range
+    pub intensity: Float,
+    pub color: Option<Color>,
+}
+
+impl Sample {
+    fn is_lit(&self) -> bool {
+        self.intensity >= INTENSITY_THRESHOLD
+    }
+}
+
+impl Globe {
+    /// Shoots `sub_x` by `sub_y` rays through the canvas cell at the given
+    /// column and row, calling `f` with the position of each sub-sample
+    /// within the cell and its result.
+    fn sample_cell<S, F>(&self, canvas: &mut S, cell: (usize, usize), sub: (usize, usize), mut f: F)
+    where
+        S: RenderTarget,
+        F: FnMut(usize, usize, Option<Sample>),
+    {
+        let (cols, rows) = canvas.grid_size();
+        // sub-sample grid dimensions
+        let half_x = (cols * sub.0 / 2) as Int;
+        let half_y = (rows * sub.1 / 2) as Int;
+        let extent = view_extent((cols, rows), canvas.char_pix());
+
+        // sub-samples are numbered row by row and traced in batches
+        let count = sub.0 * sub.1;
+        for first in (0..count).step_by(BATCH) {
+            let batch = first..(first + BATCH).min(count);
+            let mut dirs = [[Scalar::ZERO; 3]; BATCH];
+            for (dir, i) in dirs.iter_mut().zip(batch.clone()) {
+                let xi = (cell.0 * sub.0 + i % sub.0) as Int;
+                let yi = (cell.1 * sub.1 + i / sub.0) as Int;
+                let ray_x = -((xi - half_x) as Float + 0.5) / half_x as Float * extent.0;
+                let ray_y = ((yi - half_y) as Float + 0.5) / half_y as Float * extent.1;
+                let pos = (xi as usize, yi as usize);
+                *dir = ray_cache::direction(canvas, pos, || self.ray_direction(ray_x, ray_y));
+            }
+            let samples = self.sample_batch(&dirs[..batch.len()]);
+            for (i, sample) in batch.zip(samples) {
+                f(i % sub.0, i / sub.0, sample);
+            }
+        }
+    }
+
+    /// Invalidates the canvas' cached rays if the camera or the canvas
+    /// changed, with `sub` samples per character cell.
+    fn validate_rays<S: RenderTarget>(&self, canvas: &mut S, sub: (usize, usize)) {
+        let (cols, rows) = canvas.grid_size();
+        ray_cache::validate(canvas, &self.camera, (cols * sub.0, rows * sub.1));
+    }
+
+    /// Renders a row of the globe packing 2x4 samples into every braille
+    /// character.
+    pub(crate) fn render_braille_row<S: RenderTarget>(
+        &self,
+        canvas: &mut S,
+        row: usize,
+        span: Range<usize>,
+    ) {
+        self.validate_rays(canvas, BRAILLE_SAMPLES);
+        for col in span {
+            let mut bits = 0;
+            let mut hit = false;
+            let mut color = None;
+            self.sample_cell(canvas, (col, row), BRAILLE_SAMPLES, |x, y, sample| {
+                if let Some(sample) = sample {
+                    hit = true;
+                    if sample.is_lit() {
+                        bits |= BRAILLE_DOTS[y][x];
+                        color = color.or(sample.color);
+                    }
+                }
+            });
+
+            // ray doesn't hit the sphere anywhere within the cell
+            if !hit {
+                continue;
+            }
+
+            let ch = core::char::from_u32(BRAILLE_BLANK + bits).unwrap_or(' ');
+            draw_cell(
+                canvas,
+                col,
+                row,
+                ch,
+                CellColor {
+                    fg: color,
+                    bg: None,
+                },
+            );
+        }
+    }
+
+    /// Renders a row of the globe packing 2 vertically stacked samples into
+    /// every half block character.
+    pub(crate) fn render_half_block_row<S: RenderTarget>(
+        &self,
+        canvas: &mut S,
+        row: usize,
+        span: Range<usize>,
+    ) {
+        self.validate_rays(canvas, HALF_BLOCK_SAMPLES);
+        for col in span {
+            let mut halves: [Option<Sample>; 2] = [None, None];
+            self.sample_cell(canvas, (col, row), HALF_BLOCK_SAMPLES, |_, y, sample| {
+                halves[y] = sample;
+            });
+            let [upper, lower] = halves;
+
+            // ray doesn't hit the sphere anywhere within the cell
+            if upper.is_none() && lower.is_none() {
+                continue;
+            }
+
+            if canvas.has_colors() {
+                // each half gets its own color
+                let color = CellColor {
+                    fg: upper.as_ref().and_then(|s| s.color),
+                    bg: lower.as_ref().and_then(|s| s.color),
+                };
+                draw_cell(canvas, col, row, '▀', color);
+            } else {
+                let lit = |s: &Option<Sample>| s.as_ref().is_some_and(Sample::is_lit);
+                let ch = match (lit(&upper), lit(&lower)) {
+                    (true, true) => '█',
+                    (true, false) => '▀',
+                    (false, true) => '▄',
+                    (false, false) => ' ',
+                };
+                canvas.draw_point(col, row, ch);
+            }
+        }
+    }
+
+    /// Renders a row of the globe packing 2x2 samples into every quadrant
+    /// block character.
+    pub(crate) fn render_quadrant_row<S: RenderTarget>(
+        &self,
+        canvas: &mut S,
+        row: usize,
+        span: Range<usize>,
+    ) {
+        self.validate_rays(canvas, QUADRANT_SAMPLES);
+        for col in span {
+            let mut bits = 0;
+            let mut hit = false;
+            let mut lit_colors = ColorSum::default();
+            let mut unlit_colors = ColorSum::default();
+            self.sample_cell(canvas, (col, row), QUADRANT_SAMPLES, |x, y, sample| {
+                if let Some(sample) = sample {
+                    hit = true;
+                    if sample.is_lit() {
+                        bits |= 1 << (y * 2 + x);
+                        lit_colors.add(sample.color);
+                    } else {
+                        unlit_colors.add(sample.color);
+                    }
+                }
+            });
+
+            // ray doesn't hit the sphere anywhere within the cell
+            if !hit {
+                continue;
+            }
+
+            let color = CellColor {
+                fg: lit_colors.average(),
+                bg: unlit_colors.average(),
+            };
+            draw_cell(canvas, col, row, QUADRANTS[bits], color);
+        }
+    }
+}
+
+/// Draws the character, along with the color if the canvas supports it.
+fn draw_cell<S: RenderTarget>(canvas: &mut S, col: usize, row: usize, ch: char, color: CellColor) {
+    if canvas.has_colors() {
+        canvas.draw_colored_point(col, row, ch, color);
+    } else {
+        canvas.draw_point(col, row, ch);
+    }
+}
+
+/// Running sum of colors, used for averaging without allocating.
+#[derive(Default)]
+struct ColorSum {
+    r: u32,
+    g: u32,
+    b: u32,
+    n: u32,
+}
+
+impl ColorSum {
+    fn add(&mut self, color: Option<Color>) {
+        if let Some(c) = color {
+            self.r += c.r as u32;
+            self.g += c.g as u32;
+            self.b += c.b as u32;
+            self.n += 1;
+        }
+    }
+
+    /// Averages the added colors.
+    fn average(&self) -> Option<Color> {
+        if self.n == 0 {
+            return None;
+        }
+        Some(Color::new(
+            (self.r / self.n) as u8,
+            (self.g / self.n) as u8,
+            (self.b / self.n) as u8,
+        ))
+    }
+}
@@ -0,0 +1,149 @@
+//! Flat ring system around the equator of the globe.
+
+use alloc::vec::Vec;
+
+use crate::math::Real;
+use crate::render::Sample;
+use crate::{dot, Color, Float, Globe};
+
+/// Flat disc with a hole, lying in the plane of the globe's equator.
+///
+/// The `profile` characters are laid out from the inner edge outwards, each
+/// covering an equal part of the width of the rings, with spaces leaving
+/// gaps. The globe hides the part of the rings behind it and the part in
+/// front of it hides the globe. With the night side displayed, the part of
+/// the rings in the globe's shadow isn't drawn.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rings {
+    /// Distance of the inner edge from the center, relative to the radius of
+    /// the globe
+    pub inner: Float,
+    /// Distance of the outer edge from the center, relative to the radius of
+    /// the globe
+    pub outer: Float,
+    /// Characters the rings are drawn with, from the inner edge outwards
+    pub profile: Vec<char>,
+    /// Color of the rings
+    pub color: Color,
+}
+
+impl Default for Rings {
+    /// Rings of Saturn, from the faint C ring to the A ring, with the Cassini
+    /// division in between.
+    fn default() -> Self {
+        Self {
+            inner: 1.24,
+            outer: 2.27,
+            profile: "..::::=##%%##= ++*+-".chars().collect(),
+            color: Color::new(210, 190, 150),
+        }
+    }
+}
+
+impl Rings {
+    /// Creates rings spanning the given distances from the center, relative
+    /// to the radius of the globe, drawn with the default profile and color.
+    pub fn new(inner: Float, outer: Float) -> Self {
+        Self {
+            inner,
+            outer,
+            ..Self::default()
+        }
+    }
+
+    /// Sets the characters the rings are drawn with, from the inner edge
+    /// outwards, spaces leaving gaps.
+    pub fn with_profile(mut self, profile: &str) -> Self {
+        self.profile = profile.chars().collect();
+        self
+    }
+
+    /// Sets the color of the rings.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+}
+
+impl Globe {
+    /// Returns the rings around the globe, if any.
+    pub fn rings(&self) -> Option<&Rings> {
+        self.rings.as_ref()
+    }
+
+    /// Sets or removes the rings around the globe.
+    pub fn set_rings(&mut self, rings: Option<Rings>) {
+        self.rings = rings;
+    }
+
+    /// Samples the rings along a ray going in the direction of the unit
+    /// vector `u`, `None` if it passes through a gap, misses them or they're
+    /// hidden where it crosses them.
+    pub(crate) fn sample_rings<R: Real>(&self, u: [R; 3]) -> Option<Sample> {
+        let rings = self.rings.as_ref()?;
+        let r = R::from_float;
+        let o = [r(self.camera.x), r(self.camera.y), r(self.camera.z)];
+        // rays parallel to the rings never cross them
+        if u[2] == R::ZERO {
+            return None;
+        }
+        let distance = -o[2] / u[2];
+        if distance <= R::ZERO {
+            return None;
+        }
+        let point = [0, 1, 2].map(|axis| o[axis] + distance * u[axis]);
+
+        let from_center = dot(&point, &point).sqrt().to_float() / self.radius;
+        if !(rings.inner..rings.outer).contains(&from_center) || rings.profile.is_empty() {
+            return None;
+        }
+        let position = (from_center - rings.inner) / (rings.outer - rings.inner);
+        let steps = rings.profile.len();
+        let ch = rings.profile[((position * steps as Float) as usize).min(steps - 1)];
+        if ch == ' ' {
+            return None;
+        }
+
+        // the globe hides what's behind it
+        if let Some(inter) = self.intersect(u, self.radius) {
+            let to_globe = [0, 1, 2].map(|axis| inter[axis] - o[axis]);
+            if dot(&to_globe, &u) < distance {
+                return None;
+            }
+        }
+        if self.display_night && self.in_shadow(point) {
+            return None;
+        }
+
+        // how bright the character is, based on its position on the palette
+        let intensity = self
+            .texture
+            .palette
+            .as_ref()
+            .filter(|palette| palette.len() > 1)
+            .and_then(|palette| {
+                let index = palette.iter().position(|&c| c == ch)?;
+                Some(index as Float / (palette.len() - 1) as Float)
+            })
+            .unwrap_or(1.);
+        Some(Sample {
+            ch,
+            index: None,
+            intensity,
+            color: Some(rings.color),
+        })
+    }
+
+    /// Returns true if the globe stands between the given point and the
+    /// light.
+    fn in_shadow<R: Real>(&self, point: [R; 3]) -> bool {
+        let l = self.light_vector(point);
+        let along = dot(&point, &l);
+        // the globe is behind the point, as seen from the light
+        if along >= R::ZERO {
+            return false;
+        }
+        let radius = R::from_float(self.radius);
+        dot(&point, &point) - along * along < radius * radius
+    }
+}
@@ -0,0 +1,124 @@
+//! Colors changing with the time of day.
+
+use alloc::vec::Vec;
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::float::FloatExt;
+use crate::{Color, Coloring, Float};
+
+/// Number of hours in a day.
+const DAY_HOURS: Float = 24.;
+
+/// Colors shown at one time of the day.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DayPhase {
+    /// Hour of the day, in the `0..24` range, at which the colors are shown
+    /// unblended
+    pub hour: Float,
+    /// Color of the lit parts of the globe
+    pub day: Color,
+    /// Color of the unlit parts of the globe
+    pub night: Color,
+}
+
+impl DayPhase {
+    /// Creates a phase showing the given colors at the given hour.
+    pub const fn new(hour: Float, day: Color, night: Color) -> Self {
+        Self { hour, day, night }
+    }
+
+    /// Returns the coloring interpolating between the phase's colors based
+    /// on luminance.
+    pub fn coloring(&self) -> Coloring {
+        Coloring::Luminance {
+            day: self.day,
+            night: self.night,
+        }
+    }
+
+    /// Returns the given coloring tinted with the phase's colors, its day
+    /// color filtered through the phase's day color and its night color
+    /// through the phase's night one. Without colors to tint, e.g. for
+    /// `Coloring::Texture`, the phase's own coloring is returned.
+    pub fn tint(&self, coloring: Option<Coloring>) -> Coloring {
+        match coloring {
+            Some(Coloring::Luminance { day, night }) => Coloring::Luminance {
+                day: day.tint(self.day),
+                night: night.tint(self.night),
+            },
+            _ => self.coloring(),
+        }
+    }
+}
+
+/// Built-in phases: cool blues at night, warm tones at dawn and dusk and
+/// bright colors around noon.
+const DEFAULT_PHASES: [DayPhase; 4] = [
+    DayPhase::new(0., Color::new(90, 110, 200), Color::new(10, 15, 45)),
+    DayPhase::new(6., Color::new(255, 170, 140), Color::new(60, 40, 80)),
+    DayPhase::new(12., Color::new(235, 240, 255), Color::new(40, 70, 130)),
+    DayPhase::new(18., Color::new(255, 140, 60), Color::new(70, 30, 70)),
+];
+
+/// Day phases blended into each other over the course of a day, so that a
+/// globe left running shifts its colors with the time.
+///
+/// Between two phases, colors are interpolated linearly, wrapping around
+/// midnight from the last phase of the day to the first.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorSchedule {
+    /// Phases ordered by their hour
+    phases: Vec<DayPhase>,
+}
+
+impl Default for ColorSchedule {
+    fn default() -> Self {
+        Self::new(DEFAULT_PHASES.to_vec())
+    }
+}
+
+impl ColorSchedule {
+    /// Creates a schedule going through the given phases. Hours outside of
+    /// the `0..24` range wrap around.
+    pub fn new(mut phases: Vec<DayPhase>) -> Self {
+        for phase in &mut phases {
+            phase.hour = phase.hour.rem_euclid(DAY_HOURS);
+        }
+        phases.sort_by(|a, b| a.hour.total_cmp(&b.hour));
+        Self { phases }
+    }
+
+    /// Returns the phases ordered by their hour.
+    pub fn phases(&self) -> &[DayPhase] {
+        &self.phases
+    }
+
+    /// Returns the colors shown at the given hour of the day, e.g. `13.5` for
+    /// half past one in the afternoon, blended from the phases around it.
+    ///
+    /// Returns `None` if the schedule has no phases.
+    pub fn phase_at(&self, hour: Float) -> Option<DayPhase> {
+        let hour = hour.rem_euclid(DAY_HOURS);
+        let next = self
+            .phases
+            .iter()
+            .position(|phase| phase.hour > hour)
+            .unwrap_or(0);
+        let prev = (next + self.phases.len().checked_sub(1)?) % self.phases.len();
+        let (from, to) = (self.phases[prev], self.phases[next]);
+
+        // hours wrap around midnight between the last and the first phase
+        let since = (hour - from.hour).rem_euclid(DAY_HOURS);
+        let span = (to.hour - from.hour).rem_euclid(DAY_HOURS);
+        let t = if span > 0. {
+            (since / span).min(1.)
+        } else {
+            0.
+        };
+        Some(DayPhase::new(
+            hour,
+            from.day.lerp(to.day, t),
+            from.night.lerp(to.night, t),
+        ))
+    }
+}
@@ -0,0 +1,98 @@
+//! Tracing batches of rays using SIMD instructions.
+//!
+//! Enabled with the `simd` feature, unless `fixed-point` or `f64` is enabled
+//! as well.
+
+use std::f32::consts::PI;
+
+use wide::{f32x8, CmpGe, CmpGt};
+
+use crate::{Float, Globe, Hit, BATCH};
+
+impl Globe {
+    /// Traces up to `BATCH` rays at once, each lane doing the same math as
+    /// `Globe::trace`.
+    ///
+    /// Results can differ from the scalar version in the last bits, as the
+    /// vectorized arctangent is an approximation.
+    pub(crate) fn trace_batch(&self, dirs: &[[Float; 3]]) -> [Option<Hit>; BATCH] {
+        // unused lanes are left zeroed, their results are dropped below
+        let mut lanes = [[0.; BATCH]; 3];
+        for (i, u) in dirs.iter().enumerate().take(BATCH) {
+            for axis in 0..3 {
+                lanes[axis][i] = u[axis];
+            }
+        }
+        let u = lanes.map(f32x8::new);
+        let o = [self.camera.x, self.camera.y, self.camera.z].map(f32x8::splat);
+
+        let radius = f32x8::splat(self.radius);
+        let dot_uo = u[0] * o[0] + u[1] * o[1] + u[2] * o[2];
+        let dot_oo = o[0] * o[0] + o[1] * o[1] + o[2] * o[2];
+        let discriminant = dot_uo * dot_uo - dot_oo + radius * radius;
+        let hit = discriminant.cmp_ge(f32x8::ZERO).move_mask();
+        if hit == 0 {
+            return [None; BATCH];
+        }
+
+        let distance = -discriminant.max(f32x8::ZERO).sqrt() - dot_uo;
+        let inter = [
+            o[0] + distance * u[0],
+            o[1] + distance * u[1],
+            o[2] + distance * u[2],
+        ];
+
+        let magnitude = (inter[0] * inter[0] + inter[1] * inter[1] + inter[2] * inter[2]).sqrt();
+        let l = match (self.light_direction, self.light_position) {
+            (Some(direction), _) => direction.map(f32x8::splat),
+            (None, Some(p)) => {
+                let l = [0, 1, 2].map(|axis| f32x8::splat(p[axis]) - inter[axis]);
+                let len = (l[0] * l[0] + l[1] * l[1] + l[2] * l[2]).sqrt();
+                l.map(|c| c / len)
+            }
+            (None, None) => [f32x8::ZERO; 3],
+        };
+        let n = inter.map(|c| c / magnitude);
+        let dot_nl = n[0] * l[0] + n[1] * l[1] + n[2] * l[2];
+        let luminance = (f32x8::splat(self.light.intensity) * dot_nl
+            + f32x8::splat(self.light.ambient))
+        .max(f32x8::ZERO)
+        .min(f32x8::ONE);
+
+        let longitude = inter[1].atan2(inter[0]);
+        let latitude = inter[2].atan2((inter[0] * inter[0] + inter[1] * inter[1]).sqrt());
+        let theta = longitude / f32x8::splat(2. * PI)
+            + f32x8::splat(0.5)
+            + f32x8::splat(self.angle / 2. / PI);
+        let phi = f32x8::splat(0.5) - latitude / f32x8::splat(PI);
+
+        // the highlight is rare enough to be finished lane by lane
+        let specular = self.specular.filter(|_| self.texture.specular.is_some());
+        let dot_nh = specular.map(|_| {
+            let h = [0, 1, 2].map(|axis| l[axis] - u[axis]);
+            let len = (h[0] * h[0] + h[1] * h[1] + h[2] * h[2]).sqrt();
+            let dot_nh = (n[0] * h[0] + n[1] * h[1] + n[2] * h[2]) / len;
+            (dot_nl.cmp_gt(f32x8::ZERO) & dot_nh).to_array()
+        });
+
+        let (theta, phi, luminance) = (theta.to_array(), phi.to_array(), luminance.to_array());
+        let light_cos = dot_nl.to_array();
+        let mut hits = [None; BATCH];
+        for (i, slot) in hits.iter_mut().enumerate().take(dirs.len()) {
+            if hit & (1 << i) != 0 {
+                let glint = match (specular, dot_nh) {
+                    (Some(specular), Some(dot_nh)) => specular.highlight(dot_nh[i]),
+                    _ => 0.,
+                };
+                *slot = Some(Hit {
+                    theta: theta[i],
+                    phi: phi[i],
+                    luminance: luminance[i],
+                    light_cos: light_cos[i],
+                    glint,
+                });
+            }
+        }
+        hits
+    }
+}
@@ -0,0 +1,95 @@
+//! Smoothing texture characters between consecutive frames.
+
+use alloc::vec::Vec;
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::float::FloatExt;
+use crate::target::smoothing_history;
+use crate::{Float, RenderTarget};
+
+/// Distance past the rounding point the smoothed index has to move before
+/// the cell switches characters, keeps values hovering around the midpoint
+/// between two indices from flipping back and forth.
+const HYSTERESIS: Float = 0.25;
+
+/// Per-cell state kept between frames for temporal smoothing, see
+/// `GlobeConfig::with_temporal_smoothing`.
+///
+/// Kept by `Canvas`, other render targets are drawn without smoothing.
+#[derive(Clone, Debug, Default)]
+pub struct SmoothingHistory {
+    cells: Vec<Option<CellHistory>>,
+    cols: usize,
+}
+
+impl SmoothingHistory {
+    /// Creates an empty history.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forgets the history of every cell.
+    pub fn reset(&mut self) {
+        self.cells.clear();
+    }
+
+    /// Returns the history of the given cell, starting over whenever the grid
+    /// size changes.
+    fn cell_mut(
+        &mut self,
+        col: usize,
+        row: usize,
+        (cols, rows): (usize, usize),
+    ) -> Option<&mut Option<CellHistory>> {
+        if col >= cols || row >= rows {
+            return None;
+        }
+        if self.cols != cols || self.cells.len() != cols * rows {
+            self.cells.clear();
+            self.cells.resize(cols * rows, None);
+            self.cols = cols;
+        }
+        self.cells.get_mut(row * cols + col)
+    }
+}
+
+/// Returns the smoothing history of the given cell, if the target keeps one.
+pub(crate) fn cell_history<T: RenderTarget>(
+    target: &mut T,
+    col: usize,
+    row: usize,
+) -> Option<&mut Option<CellHistory>> {
+    let grid = target.grid_size();
+    smoothing_history(target)?.cell_mut(col, row, grid)
+}
+
+/// Palette index history of a single canvas cell.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct CellHistory {
+    /// Exponentially smoothed palette index
+    value: Float,
+    /// Palette index drawn in the previous frame
+    shown: usize,
+}
+
+/// Blends the palette index with the cell's history, updating it, and returns
+/// the index to draw.
+pub(crate) fn smooth(history: &mut Option<CellHistory>, index: usize, strength: Float) -> usize {
+    let next = match *history {
+        Some(CellHistory { value, shown }) => {
+            let value = value * strength + index as Float * (1. - strength);
+            let shown = if (value - shown as Float).abs() > 0.5 + HYSTERESIS {
+                value.round() as usize
+            } else {
+                shown
+            };
+            CellHistory { value, shown }
+        }
+        None => CellHistory {
+            value: index as Float,
+            shown: index,
+        },
+    };
+    *history = Some(next);
+    next.shown
+}
@@ -0,0 +1,139 @@
+//! Small stills of the globe focused on a location, sized to fit into a chat
+//! message code block.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{
+    CameraConfig, Canvas, CanvasPreset, Float, GeoCoord, GlobeConfig, GlobeTemplate, Marker,
+    RenderStyle,
+};
+
+/// Options for `render_snapshot`, implementing the builder pattern.
+#[derive(Clone, Debug)]
+pub struct SnapshotOptions {
+    size: (u16, u16),
+    template: GlobeTemplate,
+    marker: char,
+    zoom: Float,
+    night: bool,
+    render_style: RenderStyle,
+    preset: CanvasPreset,
+}
+
+impl Default for SnapshotOptions {
+    fn default() -> Self {
+        Self {
+            size: (40, 20),
+            template: GlobeTemplate::Earth,
+            marker: 'X',
+            zoom: 1.7,
+            night: false,
+            render_style: RenderStyle::Ascii,
+            preset: CanvasPreset::Terminal4x8,
+        }
+    }
+}
+
+impl SnapshotOptions {
+    /// Creates options for a 40 by 20 character snapshot of the Earth, the
+    /// location marked with an `X`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of columns and rows of the snapshot.
+    pub fn with_size(mut self, cols: u16, rows: u16) -> Self {
+        self.size = (cols, rows);
+        self
+    }
+
+    /// Sets the globe to display.
+    pub fn with_template(mut self, template: GlobeTemplate) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// Sets the character marking the location.
+    pub fn with_marker(mut self, ch: char) -> Self {
+        self.marker = ch;
+        self
+    }
+
+    /// Sets the distance of the camera from the globe's center, closer
+    /// distances zooming in.
+    pub fn with_zoom(mut self, distance: Float) -> Self {
+        self.zoom = distance;
+        self
+    }
+
+    /// Enables displaying the night side of the globe.
+    pub fn display_night(mut self, b: bool) -> Self {
+        self.night = b;
+        self
+    }
+
+    /// Sets the style used for drawing the globe.
+    pub fn with_render_style(mut self, style: RenderStyle) -> Self {
+        self.render_style = style;
+        self
+    }
+
+    /// Sets the character size of the fonts the snapshot is displayed with,
+    /// keeping the globe round.
+    pub fn with_canvas_preset(mut self, preset: CanvasPreset) -> Self {
+        self.preset = preset;
+        self
+    }
+}
+
+/// Renders the globe focused on the location, marking it, and returns the
+/// frame as text trimmed of blank rows and columns around the globe.
+///
+/// The frame is resampled to fit within the size given in the options, rows
+/// separated by newlines, ready to be pasted into a code block.
+pub fn render_snapshot(coord: GeoCoord, options: &SnapshotOptions) -> String {
+    let mut globe = GlobeConfig::new()
+        .use_template(options.template)
+        .with_camera(CameraConfig::new(options.zoom, 0., 0.))
+        .display_night(options.night)
+        .with_render_style(options.render_style)
+        .build()
+        .expect("built-in templates are valid");
+    globe.focus(coord);
+    globe.add_marker(Marker::new(coord, options.marker));
+
+    let mut canvas = Canvas::for_terminal(options.size, options.preset);
+    globe.render_on(&mut canvas);
+    trim(&canvas)
+}
+
+/// Returns the canvas text cropped to the smallest rectangle holding all the
+/// non-blank characters.
+fn trim(canvas: &Canvas) -> String {
+    let rows: Vec<&[char]> = canvas.rows().collect();
+    let is_blank = |ch: &char| *ch == ' ';
+    let filled = |row: &&[char]| !row.iter().all(is_blank);
+    let top = match rows.iter().position(filled) {
+        Some(top) => top,
+        None => return String::new(),
+    };
+    let bottom = rows.iter().rposition(filled).unwrap_or(top);
+    let rows = &rows[top..=bottom];
+
+    let left = rows
+        .iter()
+        .filter_map(|row| row.iter().position(|ch| !is_blank(ch)))
+        .min()
+        .unwrap_or(0);
+    rows.iter()
+        .map(|row| {
+            let right = row
+                .iter()
+                .rposition(|ch| !is_blank(ch))
+                .map_or(0, |n| n + 1);
+            row[left.min(right)..right].iter().collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,139 @@
+//! Position of the sun over the Earth at a given time.
+//!
+//! Uses the low precision formulas of the Astronomical Almanac, good to about
+//! a hundredth of a degree for dates within a few centuries of 2000, which is
+//! well below what a character grid can show.
+
+use alloc::vec::Vec;
+
+#[cfg(not(any(feature = "std", test)))]
+use crate::float::FloatExt;
+use crate::{Float, GeoCoord};
+
+/// Unix time of the J2000.0 epoch, noon on the 1st of January 2000.
+const J2000_UNIX: f64 = 946_728_000.;
+/// Number of seconds in a day.
+const DAY_SECS: f64 = 86_400.;
+/// Days from the 1st of January of year 1 to the Unix epoch, counted in the
+/// Gregorian calendar extended backwards.
+const EPOCH_DAYS: i64 = 719_162;
+
+/// Returns the subsolar point, where the sun is directly overhead, at the
+/// given Unix time in seconds.
+pub fn subsolar_point(unix_secs: i64) -> GeoCoord {
+    let (lat, lon) = subsolar_lat_lon(unix_secs);
+    GeoCoord::from_lat_lon(lat as Float, lon as Float)
+}
+
+/// Returns the latitude and longitude of the subsolar point in degrees, see
+/// `subsolar_point`.
+pub fn subsolar_lat_lon(unix_secs: i64) -> (f64, f64) {
+    let d = days_since_j2000(unix_secs);
+    let l = ecliptic_longitude(d).to_radians();
+    let e = obliquity(d).to_radians();
+
+    let right_ascension = (e.cos() * l.sin()).atan2(l.cos()).to_degrees();
+    let declination = (e.sin() * l.sin()).asin().to_degrees();
+    (declination, longitude_below(right_ascension, d))
+}
+
+/// Returns the number of days, fractional, from the J2000.0 epoch to the
+/// given Unix time in seconds.
+pub(crate) fn days_since_j2000(unix_secs: i64) -> f64 {
+    (unix_secs as f64 - J2000_UNIX) / DAY_SECS
+}
+
+/// Returns the apparent ecliptic longitude of the sun in degrees, `d` days
+/// from J2000.0.
+pub(crate) fn ecliptic_longitude(d: f64) -> f64 {
+    // mean anomaly and mean longitude of the sun
+    let g = (357.529 + 0.985_600_28 * d).to_radians();
+    let q = 280.459 + 0.985_647_36 * d;
+    q + 1.915 * g.sin() + 0.020 * (2. * g).sin()
+}
+
+/// Returns the obliquity of the ecliptic in degrees, `d` days from J2000.0.
+pub(crate) fn obliquity(d: f64) -> f64 {
+    23.439 - 0.000_000_36 * d
+}
+
+/// Returns the longitude in degrees a body at the given right ascension in
+/// degrees is overhead at, `d` days from J2000.0.
+pub(crate) fn longitude_below(right_ascension: f64, d: f64) -> f64 {
+    (right_ascension - sidereal_time(d) + 180.).rem_euclid(360.) - 180.
+}
+
+/// Returns the Greenwich mean sidereal time in degrees, `d` days from
+/// J2000.0, the angle the Earth turned by relative to the stars.
+pub(crate) fn sidereal_time(d: f64) -> f64 {
+    280.460_618_37 + 360.985_647_366_29 * d
+}
+
+/// Returns the elevation of the sun above the horizon at the given location
+/// and Unix time in seconds, in degrees, negative while it's night there.
+///
+/// The sun is taken as a point and refraction is left out, which together
+/// make days come out a few minutes shorter than they are.
+pub fn elevation(coord: GeoCoord, unix_secs: i64) -> Float {
+    90. - coord.angle_to(subsolar_point(unix_secs)).to_degrees()
+}
+
+/// Returns the subsolar points from `from` up to `to`, Unix times in seconds,
+/// one every `step` seconds.
+pub fn subsolar_path(from: i64, to: i64, step: i64) -> Vec<GeoCoord> {
+    (from..to)
+        .step_by(step.max(1) as usize)
+        .map(subsolar_point)
+        .collect()
+}
+
+/// Returns the subsolar points at noon UTC on every day of the given year.
+///
+/// Together they trace the analemma, the figure eight the sun draws when
+/// looked at from the same place at the same time of day all year long,
+/// stretched north and south by the seasons and east and west by the
+/// equation of time.
+pub fn analemma(year: i32) -> Vec<GeoCoord> {
+    let day = DAY_SECS as i64;
+    subsolar_path(year_start(year) + day / 2, year_start(year + 1), day)
+}
+
+/// Returns the Unix time of midnight UTC starting the given year.
+pub(crate) fn year_start(year: i32) -> i64 {
+    let before = year as i64 - 1;
+    let days =
+        before * 365 + before.div_euclid(4) - before.div_euclid(100) + before.div_euclid(400);
+    (days - EPOCH_DAYS) * DAY_SECS as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// March equinox of 2024, 20th of March at 03:06 UTC.
+    const EQUINOX_2024: i64 = 1_710_903_960;
+    /// June solstice of 2024, 20th of June at 20:51 UTC.
+    const SOLSTICE_2024: i64 = 1_718_916_660;
+
+    #[test]
+    fn sun_is_over_the_equator_at_the_equinox() {
+        let (lat, lon) = subsolar_lat_lon(EQUINOX_2024);
+        assert!(lat.abs() < 0.02, "latitude {}", lat);
+        // nine hours before noon at Greenwich, with the sun running seven
+        // and a half minutes behind the clock in March
+        assert!((lon - 135.4).abs() < 0.1, "longitude {}", lon);
+    }
+
+    #[test]
+    fn sun_is_over_the_tropic_of_cancer_at_the_solstice() {
+        let (lat, _) = subsolar_lat_lon(SOLSTICE_2024);
+        assert!((lat - 23.44).abs() < 0.02, "latitude {}", lat);
+    }
+
+    #[test]
+    fn years_start_at_midnight_on_the_first_of_january() {
+        assert_eq!(year_start(1970), 0);
+        assert_eq!(year_start(2000), 946_684_800);
+        assert_eq!(year_start(2024), 1_704_067_200);
+    }
+}
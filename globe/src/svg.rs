@@ -0,0 +1,220 @@
+//! Exporting rendered globes as SVG images, e.g. for embedding a still of the
+//! globe in documentation or websites.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::fmt::Write;
+
+use crate::{view_extent, Canvas, Color, Float, Globe, Int};
+
+/// Builder for SVG documents, created either out of a rendered canvas or
+/// straight out of samples of the globe.
+#[derive(Clone, Debug)]
+pub struct SvgExporter {
+    scale: Float,
+    foreground: Color,
+    background: Option<Color>,
+    font_family: String,
+}
+
+impl Default for SvgExporter {
+    fn default() -> Self {
+        Self {
+            scale: 2.,
+            foreground: Color::new(255, 255, 255),
+            background: Some(Color::new(0, 0, 0)),
+            font_family: "monospace".to_string(),
+        }
+    }
+}
+
+impl SvgExporter {
+    /// Creates an exporter drawing white on a black background.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the size of a single canvas pixel in SVG user units. Character
+    /// cells are as large as the canvas' character size times the scale.
+    pub fn with_scale(mut self, scale: Float) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Sets the color used for characters and samples without colors.
+    pub fn with_foreground(mut self, color: Color) -> Self {
+        self.foreground = color;
+        self
+    }
+
+    /// Sets the color filling the whole image, or `None` for a transparent
+    /// background.
+    pub fn with_background(mut self, color: Option<Color>) -> Self {
+        self.background = color;
+        self
+    }
+
+    /// Sets the font used for drawing characters.
+    pub fn with_font_family(mut self, family: &str) -> Self {
+        self.font_family = family.to_string();
+        self
+    }
+
+    /// Exports the rendered canvas, drawing every character as a separate
+    /// `<text>` element.
+    ///
+    /// Placing characters one by one keeps them on the grid whatever the
+    /// font metrics are. Colors stored on the canvas are used where present,
+    /// with background colors drawn as rectangles behind the characters.
+    pub fn export_canvas(&self, canvas: &Canvas) -> String {
+        let (cols, rows) = canvas.grid_size();
+        let char_pix = canvas.char_pix();
+        let cell_w = char_pix.0 as Float * self.scale;
+        let cell_h = char_pix.1 as Float * self.scale;
+
+        let mut svg = self.header(cols as Float * cell_w, rows as Float * cell_h);
+        let mut backgrounds = String::new();
+        let mut text = String::new();
+        for row in 0..rows {
+            for col in 0..cols {
+                let cell = match canvas.cell(col, row) {
+                    Some(cell) => cell,
+                    None => continue,
+                };
+                let (x, y) = (col as Float * cell_w, row as Float * cell_h);
+                if let Some(bg) = cell.bg {
+                    rect(&mut backgrounds, (x, y), (cell_w, cell_h), bg, None);
+                }
+                if cell.ch.is_whitespace() {
+                    continue;
+                }
+                let _ = write!(
+                    text,
+                    r#"<text x="{}" y="{}""#,
+                    x + cell_w / 2.,
+                    y + cell_h / 2.
+                );
+                if let Some(fg) = cell.fg {
+                    let _ = write!(text, r#" fill="{}""#, hex(fg));
+                }
+                let _ = writeln!(text, ">{}</text>", escape(cell.ch));
+            }
+        }
+
+        if !backgrounds.is_empty() {
+            let _ = writeln!(svg, r#"<g shape-rendering="crispEdges">"#);
+            svg.push_str(&backgrounds);
+            svg.push_str("</g>\n");
+        }
+        let _ = writeln!(
+            svg,
+            r#"<g font-family="{}" font-size="{}" fill="{}" text-anchor="middle" dominant-baseline="central">"#,
+            escape_str(&self.font_family),
+            cell_h,
+            hex(self.foreground)
+        );
+        svg.push_str(&text);
+        svg.push_str("</g>\n</svg>\n");
+        svg
+    }
+
+    /// Exports the globe as seen by its camera, shooting a ray for every
+    /// point of a `cols` by `rows` grid and drawing the samples as squares.
+    ///
+    /// Colored samples are drawn using their color, others using the
+    /// foreground color with opacity matching the brightness of their
+    /// texture character. The grid can be as fine as needed, independent of
+    /// any canvas, but markers are left out.
+    pub fn export_samples(&self, globe: &Globe, (cols, rows): (usize, usize)) -> String {
+        let size = self.scale;
+        let mut svg = self.header(cols as Float * size, rows as Float * size);
+        let _ = writeln!(svg, r#"<g shape-rendering="crispEdges">"#);
+
+        let half_x = (cols / 2) as Int;
+        let half_y = (rows / 2) as Int;
+        // samples are drawn as squares
+        let extent = view_extent((cols, rows), (1, 1));
+        for yi in 0..rows {
+            for xi in 0..cols {
+                let ray_x = -((xi as Int - half_x) as Float + 0.5) / half_x as Float * extent.0;
+                let ray_y = ((yi as Int - half_y) as Float + 0.5) / half_y as Float * extent.1;
+                let sample = match globe.sample(ray_x, ray_y) {
+                    Some(sample) => sample,
+                    None => continue,
+                };
+                let pos = (xi as Float * size, yi as Float * size);
+                match sample.color {
+                    Some(color) => rect(&mut svg, pos, (size, size), color, None),
+                    None if sample.intensity > 0. => rect(
+                        &mut svg,
+                        pos,
+                        (size, size),
+                        self.foreground,
+                        Some(sample.intensity),
+                    ),
+                    None => (),
+                }
+            }
+        }
+
+        svg.push_str("</g>\n</svg>\n");
+        svg
+    }
+
+    /// Starts the document, filling in the background.
+    fn header(&self, width: Float, height: Float) -> String {
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#,
+            width, height
+        );
+        if let Some(bg) = self.background {
+            rect(&mut svg, (0., 0.), (width, height), bg, None);
+        }
+        svg
+    }
+}
+
+/// Writes out a filled rectangle element.
+fn rect(
+    svg: &mut String,
+    (x, y): (Float, Float),
+    (width, height): (Float, Float),
+    color: Color,
+    opacity: Option<Float>,
+) {
+    let _ = write!(
+        svg,
+        r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}""#,
+        x,
+        y,
+        width,
+        height,
+        hex(color)
+    );
+    if let Some(opacity) = opacity {
+        let _ = write!(svg, r#" fill-opacity="{:.2}""#, opacity);
+    }
+    svg.push_str("/>\n");
+}
+
+/// Formats the color as a hex triplet.
+fn hex(color: Color) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+}
+
+/// Escapes characters that have special meaning in XML.
+fn escape(ch: char) -> String {
+    match ch {
+        '&' => "&amp;".to_string(),
+        '<' => "&lt;".to_string(),
+        '>' => "&gt;".to_string(),
+        '"' => "&quot;".to_string(),
+        _ => ch.to_string(),
+    }
+}
+
+fn escape_str(s: &str) -> String {
+    s.chars().map(escape).collect()
+}
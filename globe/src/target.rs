@@ -0,0 +1,195 @@
+//! Destinations the globe can be rendered onto.
+
+use alloc::vec::Vec;
+
+use crate::{Canvas, CanvasPreset, CellColor, RayCache, SmoothingHistory};
+
+use self::sealed::{FrameState, Token};
+
+/// Renderer state kept by targets between frames, out of reach of code
+/// outside the crate.
+pub(crate) mod sealed {
+    use alloc::vec::Vec;
+
+    use crate::{RayCache, SmoothingHistory};
+
+    /// Argument only the crate can make, keeping
+    /// `RenderTarget::frame_state` from being called or overridden
+    /// elsewhere.
+    pub struct Token;
+
+    /// State a target keeps between frames, each part optional.
+    pub trait FrameState {
+        /// Returns the history kept for temporal smoothing. Targets without
+        /// one are rendered without smoothing.
+        fn smoothing_history(&mut self) -> Option<&mut SmoothingHistory> {
+            None
+        }
+        /// Returns the ray directions kept between frames. Targets without
+        /// a cache recompute every ray each frame.
+        fn ray_cache(&mut self) -> Option<&mut RayCache> {
+            None
+        }
+        /// Returns the cells markers and other overlays were last drawn on,
+        /// kept for erasing them from frames rendered only in part. Targets
+        /// without them are expected to be rendered whole.
+        fn overlay_cells(&mut self) -> Option<&mut Vec<(usize, usize)>> {
+            None
+        }
+    }
+}
+
+/// Grid of character cells the globe can be rendered onto.
+///
+/// Implemented by `Canvas` and `FixedCanvas`. Implement it for your own
+/// buffers, e.g. TUI framework widgets or GUI text grids, to render into them
+/// directly with `Globe::render_on`. Only the grid size and drawing single
+/// characters are required, the remaining methods have default
+/// implementations built on top of these.
+pub trait RenderTarget {
+    /// Returns the number of character columns and rows.
+    fn grid_size(&self) -> (usize, usize);
+    /// Returns how many pixels make up a character cell, horizontally and
+    /// vertically, for keeping the globe round on grids of any shape. Cells
+    /// are taken to be twice as tall as they are wide by default, like those
+    /// of most terminals.
+    fn char_pix(&self) -> (usize, usize) {
+        CanvasPreset::default().char_pix()
+    }
+    /// Draws a character at the given cell, ignoring cells out of bounds.
+    fn draw_point(&mut self, col: usize, row: usize, ch: char);
+    /// Returns true if colors are stored alongside the characters.
+    fn has_colors(&self) -> bool {
+        false
+    }
+    /// Draws a character along with its colors at the given cell. Colors are
+    /// dropped by default.
+    fn draw_colored_point(&mut self, col: usize, row: usize, ch: char, color: CellColor) {
+        let _ = color;
+        self.draw_point(col, row, ch);
+    }
+    /// Blanks out the given cell, drawing a space without colors by
+    /// default.
+    fn clear_cell(&mut self, col: usize, row: usize) {
+        self.draw_colored_point(col, row, ' ', CellColor::default());
+    }
+    /// Blanks out the given row.
+    fn clear_row(&mut self, row: usize) {
+        let (cols, _) = self.grid_size();
+        for col in 0..cols {
+            self.clear_cell(col, row);
+        }
+    }
+    /// Blanks out every cell.
+    fn clear(&mut self) {
+        let (_, rows) = self.grid_size();
+        for row in 0..rows {
+            self.clear_row(row);
+        }
+    }
+    /// Returns the state the renderer keeps on the target between frames.
+    /// Only targets of this crate keep any.
+    #[doc(hidden)]
+    fn frame_state(&mut self, _: Token) -> Option<&mut dyn FrameState> {
+        None
+    }
+}
+
+/// Returns the target's smoothing history, see `FrameState`.
+pub(crate) fn smoothing_history<T: RenderTarget + ?Sized>(
+    target: &mut T,
+) -> Option<&mut SmoothingHistory> {
+    target.frame_state(Token)?.smoothing_history()
+}
+
+/// Returns the target's ray cache, see `FrameState`.
+pub(crate) fn ray_cache<T: RenderTarget + ?Sized>(target: &mut T) -> Option<&mut RayCache> {
+    target.frame_state(Token)?.ray_cache()
+}
+
+/// Returns the cells overlays were last drawn on, see `FrameState`.
+pub(crate) fn overlay_cells<T: RenderTarget + ?Sized>(
+    target: &mut T,
+) -> Option<&mut Vec<(usize, usize)>> {
+    target.frame_state(Token)?.overlay_cells()
+}
+
+impl RenderTarget for Canvas {
+    fn grid_size(&self) -> (usize, usize) {
+        Canvas::grid_size(self)
+    }
+    fn char_pix(&self) -> (usize, usize) {
+        Canvas::char_pix(self)
+    }
+    fn draw_point(&mut self, col: usize, row: usize, ch: char) {
+        Canvas::draw_point(self, col, row, ch)
+    }
+    fn has_colors(&self) -> bool {
+        Canvas::has_colors(self)
+    }
+    fn draw_colored_point(&mut self, col: usize, row: usize, ch: char, color: CellColor) {
+        Canvas::draw_colored_point(self, col, row, ch, color)
+    }
+    fn clear_cell(&mut self, col: usize, row: usize) {
+        Canvas::clear_cell(self, col, row)
+    }
+    fn clear_row(&mut self, row: usize) {
+        Canvas::clear_row(self, row)
+    }
+    fn clear(&mut self) {
+        Canvas::clear(self)
+    }
+    fn frame_state(&mut self, _: Token) -> Option<&mut dyn FrameState> {
+        Some(self)
+    }
+}
+
+impl FrameState for Canvas {
+    fn smoothing_history(&mut self) -> Option<&mut SmoothingHistory> {
+        Some(&mut self.history)
+    }
+    fn ray_cache(&mut self) -> Option<&mut RayCache> {
+        Some(&mut self.rays)
+    }
+    fn overlay_cells(&mut self) -> Option<&mut Vec<(usize, usize)>> {
+        Some(&mut self.overlay_cells)
+    }
+}
+
+/// Render target passing everything on to the inner one, except for its
+/// smoothing history, for rendering cells again without counting them as
+/// another frame.
+pub(crate) struct WithoutHistory<'a, T>(pub &'a mut T);
+
+impl<T: RenderTarget> RenderTarget for WithoutHistory<'_, T> {
+    fn grid_size(&self) -> (usize, usize) {
+        self.0.grid_size()
+    }
+    fn char_pix(&self) -> (usize, usize) {
+        self.0.char_pix()
+    }
+    fn draw_point(&mut self, col: usize, row: usize, ch: char) {
+        self.0.draw_point(col, row, ch)
+    }
+    fn has_colors(&self) -> bool {
+        self.0.has_colors()
+    }
+    fn draw_colored_point(&mut self, col: usize, row: usize, ch: char, color: CellColor) {
+        self.0.draw_colored_point(col, row, ch, color)
+    }
+    fn clear_cell(&mut self, col: usize, row: usize) {
+        self.0.clear_cell(col, row)
+    }
+    fn frame_state(&mut self, _: Token) -> Option<&mut dyn FrameState> {
+        Some(self)
+    }
+}
+
+impl<T: RenderTarget> FrameState for WithoutHistory<'_, T> {
+    fn ray_cache(&mut self) -> Option<&mut RayCache> {
+        ray_cache(self.0)
+    }
+    fn overlay_cells(&mut self) -> Option<&mut Vec<(usize, usize)>> {
+        overlay_cells(self.0)
+    }
+}
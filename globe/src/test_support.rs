@@ -0,0 +1,248 @@
+//! Helpers for writing regression tests of rendered frames.
+//!
+//! Enabled with the `test-support` feature. Reference scenes render the
+//! built-in textures from fixed cameras, so their frames only change along
+//! with the renderer. Frames are then compared allowing small differences,
+//! measured as the distance between characters on the texture's palette, which
+//! keeps tests passing through shading and sampling tweaks that shift
+//! brightness by a step here and there.
+//!
+//! Frames rendered with the `fixed-point` or `f64` feature can differ from the
+//! default ones in a few cells along coastlines, where rays land on the
+//! neighboring texture cell. Allowing a few mismatches covers them.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+
+use crate::{CameraConfig, Canvas, CanvasPreset, Globe, GlobeConfig, GlobeTemplate, RenderStyle};
+
+/// Number of differing cells listed when reporting a failed comparison.
+const REPORTED_CELLS: usize = 10;
+
+/// Globe rendered from a fixed camera, for comparing against frames saved
+/// earlier.
+pub struct ReferenceScene {
+    /// Name of the scene, e.g. for naming files holding expected frames
+    pub name: &'static str,
+    /// Template the globe is built from
+    pub template: GlobeTemplate,
+    pub globe: Globe,
+    /// Size of the frame in characters
+    pub size: (u16, u16),
+}
+
+impl ReferenceScene {
+    /// Creates a scene showing the template as seen from the camera.
+    pub fn new(
+        name: &'static str,
+        template: GlobeTemplate,
+        camera: CameraConfig,
+        style: RenderStyle,
+        night: bool,
+    ) -> Self {
+        let globe = GlobeConfig::new()
+            .use_template(template)
+            .with_camera(camera)
+            .with_render_style(style)
+            .display_night(night)
+            .build()
+            .expect("built-in templates are valid");
+        Self {
+            name,
+            template,
+            globe,
+            size: (60, 30),
+        }
+    }
+
+    /// Renders the scene, returning the frame as text.
+    pub fn render(&self) -> String {
+        let mut canvas = Canvas::for_terminal(self.size, CanvasPreset::default());
+        self.globe.render_to_string(&mut canvas)
+    }
+}
+
+/// Returns the canonical scenes: every built-in template from the front, the
+/// Earth with its night side, from an angle and in braille.
+pub fn reference_scenes() -> Vec<ReferenceScene> {
+    use GlobeTemplate::{Earth, Mars, Moon};
+    use RenderStyle::{Ascii, Braille};
+
+    let front = || CameraConfig::new(1.7, 0., 0.);
+    alloc::vec![
+        ReferenceScene::new("earth", Earth, front(), Ascii, false),
+        ReferenceScene::new("earth-night", Earth, front(), Ascii, true),
+        ReferenceScene::new(
+            "earth-tilted",
+            Earth,
+            CameraConfig::new(2., 1., 0.6),
+            Ascii,
+            true
+        ),
+        ReferenceScene::new("earth-braille", Earth, front(), Braille, false),
+        ReferenceScene::new("moon", Moon, front(), Ascii, true),
+        ReferenceScene::new("mars", Mars, front(), Ascii, true),
+    ]
+}
+
+/// Compares frames cell by cell, tolerating characters a few steps apart on
+/// the palette.
+///
+/// Characters missing from the palette, like braille or block characters,
+/// have to match exactly.
+#[derive(Clone, Debug)]
+pub struct FrameComparer {
+    palette: Vec<char>,
+    tolerance: usize,
+    allowed_mismatches: usize,
+}
+
+impl FrameComparer {
+    /// Creates a comparer measuring distances on the given palette, ordered
+    /// from dark to bright. Without a tolerance set, frames have to match
+    /// exactly.
+    pub fn new(palette: &[char]) -> Self {
+        Self {
+            palette: palette.to_vec(),
+            tolerance: 0,
+            allowed_mismatches: 0,
+        }
+    }
+
+    /// Creates a comparer for frames of the built-in template.
+    pub fn for_template(template: GlobeTemplate) -> Self {
+        Self::new(template.palette())
+    }
+
+    /// Sets the number of palette steps characters can be apart.
+    pub fn with_tolerance(mut self, steps: usize) -> Self {
+        self.tolerance = steps;
+        self
+    }
+
+    /// Sets the number of cells allowed to differ by more than the
+    /// tolerance, e.g. for markers drawn differently at the edge of the
+    /// globe.
+    pub fn with_allowed_mismatches(mut self, cells: usize) -> Self {
+        self.allowed_mismatches = cells;
+        self
+    }
+
+    /// Compares the frames, given as text with rows separated by newlines.
+    pub fn compare(&self, expected: &str, actual: &str) -> FrameDiff {
+        let expected_rows: Vec<&str> = expected.lines().collect();
+        let actual_rows: Vec<&str> = actual.lines().collect();
+        let mut diff = FrameDiff {
+            size_mismatch: expected_rows.len() != actual_rows.len(),
+            cells: Vec::new(),
+            tolerance: self.tolerance,
+            allowed_mismatches: self.allowed_mismatches,
+        };
+        for (row, (expected, actual)) in expected_rows.iter().zip(&actual_rows).enumerate() {
+            if expected.chars().count() != actual.chars().count() {
+                diff.size_mismatch = true;
+            }
+            for (col, (expected, actual)) in expected.chars().zip(actual.chars()).enumerate() {
+                if expected != actual {
+                    diff.cells.push(CellDiff {
+                        col,
+                        row,
+                        expected,
+                        actual,
+                        distance: self.distance(expected, actual),
+                    });
+                }
+            }
+        }
+        diff
+    }
+
+    /// Compares the frames, panicking with a report of the differences if
+    /// they don't match.
+    #[track_caller]
+    pub fn assert_matches(&self, expected: &str, actual: &str) {
+        let diff = self.compare(expected, actual);
+        if !diff.passed() {
+            panic!("frames differ: {}", diff);
+        }
+    }
+
+    /// Returns the number of palette steps between the characters, `None` if
+    /// either is missing from the palette.
+    fn distance(&self, a: char, b: char) -> Option<usize> {
+        let index = |ch| self.palette.iter().position(|&p| p == ch);
+        let (a, b) = (index(a)?, index(b)?);
+        Some(a.abs_diff(b))
+    }
+}
+
+/// Cell differing between two compared frames.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CellDiff {
+    pub col: usize,
+    pub row: usize,
+    pub expected: char,
+    pub actual: char,
+    /// Number of palette steps between the characters, `None` if either is
+    /// missing from the palette
+    pub distance: Option<usize>,
+}
+
+/// Differences found comparing two frames, see `FrameComparer::compare`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FrameDiff {
+    /// Whether the frames differ in the number of rows or columns
+    pub size_mismatch: bool,
+    /// Cells holding different characters, within the tolerance or not
+    pub cells: Vec<CellDiff>,
+    tolerance: usize,
+    allowed_mismatches: usize,
+}
+
+impl FrameDiff {
+    /// Returns the differing cells beyond the tolerance.
+    pub fn mismatches(&self) -> impl Iterator<Item = &CellDiff> {
+        let tolerance = self.tolerance;
+        self.cells
+            .iter()
+            .filter(move |cell| cell.distance.is_none_or(|d| d > tolerance))
+    }
+
+    /// Returns true if the frames are the same size and differ by more than
+    /// the tolerance in no more cells than allowed.
+    pub fn passed(&self) -> bool {
+        !self.size_mismatch && self.mismatches().count() <= self.allowed_mismatches
+    }
+}
+
+impl fmt::Display for FrameDiff {
+    /// Writes out a summary followed by the first few mismatching cells.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.size_mismatch {
+            f.write_str("frame sizes differ, ")?;
+        }
+        write!(
+            f,
+            "{} cells differ, {} beyond the tolerance of {} steps ({} allowed)",
+            self.cells.len(),
+            self.mismatches().count(),
+            self.tolerance,
+            self.allowed_mismatches
+        )?;
+        for cell in self.mismatches().take(REPORTED_CELLS) {
+            write!(
+                f,
+                "\n  row {}, column {}: expected {:?}, got {:?}",
+                cell.row, cell.col, cell.expected, cell.actual
+            )?;
+            if let Some(distance) = cell.distance {
+                write!(f, " ({} steps apart)", distance)?;
+            }
+        }
+        if self.mismatches().count() > REPORTED_CELLS {
+            f.write_str("\n  ...")?;
+        }
+        f.write_char('\n')
+    }
+}
@@ -0,0 +1,245 @@
+//! Named looks bundling characters and colors.
+
+use alloc::vec::Vec;
+
+use crate::{Color, Coloring, Globe, Marker};
+
+/// Colors of the Okabe-Ito palette, told apart with all common color vision
+/// deficiencies: orange, sky blue, bluish green, yellow, blue, vermillion and
+/// reddish purple.
+pub const COLORBLIND_SAFE_COLORS: [Color; 7] = [
+    Color::new(230, 159, 0),
+    Color::new(86, 180, 233),
+    Color::new(0, 158, 115),
+    Color::new(240, 228, 66),
+    Color::new(0, 114, 178),
+    Color::new(213, 94, 0),
+    Color::new(204, 121, 167),
+];
+
+/// Coloring going from blue to yellow, which keeps its steps apart without
+/// relying on telling red from green.
+pub const COLORBLIND_SAFE_COLORING: Coloring = Coloring::Luminance {
+    day: Color::new(240, 228, 66),
+    night: Color::new(0, 60, 110),
+};
+
+/// Look of the markers shown on a themed globe.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MarkerStyle {
+    /// Character markers are drawn with, their own if `None`
+    pub ch: Option<char>,
+    /// Color markers are drawn in, their own if `None`
+    pub color: Option<Color>,
+}
+
+impl MarkerStyle {
+    /// Styles telling groups of markers apart by both character and color,
+    /// the colors taken from `COLORBLIND_SAFE_COLORS`.
+    pub const COLORBLIND_SAFE: [MarkerStyle; 7] = [
+        MarkerStyle::new('o', COLORBLIND_SAFE_COLORS[0]),
+        MarkerStyle::new('x', COLORBLIND_SAFE_COLORS[1]),
+        MarkerStyle::new('+', COLORBLIND_SAFE_COLORS[2]),
+        MarkerStyle::new('*', COLORBLIND_SAFE_COLORS[3]),
+        MarkerStyle::new('#', COLORBLIND_SAFE_COLORS[4]),
+        MarkerStyle::new('@', COLORBLIND_SAFE_COLORS[5]),
+        MarkerStyle::new('%', COLORBLIND_SAFE_COLORS[6]),
+    ];
+
+    /// Styles in pure, bright colors for dark backgrounds, telling groups of
+    /// markers apart by character as well.
+    pub const HIGH_CONTRAST: [MarkerStyle; 4] = [
+        MarkerStyle::new('X', Color::new(255, 255, 0)),
+        MarkerStyle::new('O', Color::new(0, 255, 255)),
+        MarkerStyle::new('#', Color::new(255, 255, 255)),
+        MarkerStyle::new('@', Color::new(255, 0, 255)),
+    ];
+
+    /// Creates a style drawing markers with the given character and color.
+    pub const fn new(ch: char, color: Color) -> Self {
+        Self {
+            ch: Some(ch),
+            color: Some(color),
+        }
+    }
+
+    /// Restyles the marker, keeping what the style leaves unset.
+    pub fn apply(&self, mut marker: Marker) -> Marker {
+        marker.ch = self.ch.unwrap_or(marker.ch);
+        marker.color = self.color.or(marker.color);
+        marker
+    }
+}
+
+/// Look of the globe and everything drawn around it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Theme {
+    /// Characters the globe is drawn with, ordered from dark to bright,
+    /// replacing the ones of the texture's palette
+    pub palette: Option<Vec<char>>,
+    /// Colors of the globe itself
+    pub colors: Option<Coloring>,
+    /// Color behind the globe
+    pub background: Option<Color>,
+    /// Look of the markers
+    pub marker_style: MarkerStyle,
+    /// Color of the line between day and night, the terminator's own if
+    /// `None`
+    pub terminator_color: Option<Color>,
+    /// Color of the heatmap, the heatmap's own if `None`
+    pub heatmap_color: Option<Color>,
+}
+
+impl Theme {
+    /// Names of the built-in themes, see `Theme::named`.
+    pub const NAMES: [&'static str; 5] = [
+        "classic",
+        "green-phosphor",
+        "amber",
+        "high-contrast",
+        "solarized",
+    ];
+
+    /// Returns the built-in theme with the given name, one of
+    /// `Theme::NAMES`.
+    pub fn named(name: &str) -> Option<Theme> {
+        match name {
+            "classic" => Some(Theme::classic()),
+            "green-phosphor" => Some(Theme::green_phosphor()),
+            "amber" => Some(Theme::amber()),
+            "high-contrast" => Some(Theme::high_contrast()),
+            "solarized" => Some(Theme::solarized()),
+            _ => None,
+        }
+    }
+
+    /// The globe as it comes, drawn with its texture's characters and
+    /// colors.
+    pub fn classic() -> Theme {
+        Theme::default()
+    }
+
+    /// Green glow of an old monochrome monitor.
+    pub fn green_phosphor() -> Theme {
+        Theme {
+            palette: None,
+            colors: Some(Coloring::Luminance {
+                day: Color::new(80, 255, 120),
+                night: Color::new(10, 70, 25),
+            }),
+            background: Some(Color::new(0, 12, 4)),
+            marker_style: MarkerStyle {
+                ch: None,
+                color: Some(Color::new(200, 255, 210)),
+            },
+            terminator_color: None,
+            heatmap_color: None,
+        }
+    }
+
+    /// Amber monochrome monitor.
+    pub fn amber() -> Theme {
+        Theme {
+            palette: None,
+            colors: Some(Coloring::Luminance {
+                day: Color::new(255, 176, 0),
+                night: Color::new(80, 45, 0),
+            }),
+            background: Some(Color::new(16, 8, 0)),
+            marker_style: MarkerStyle {
+                ch: None,
+                color: Some(Color::new(255, 230, 170)),
+            },
+            terminator_color: None,
+            heatmap_color: None,
+        }
+    }
+
+    /// White on black with few, clearly distinct characters.
+    pub fn high_contrast() -> Theme {
+        Theme {
+            palette: Some(alloc::vec![' ', '.', ':', '+', '#', '@']),
+            colors: Some(Coloring::Luminance {
+                day: Color::new(255, 255, 255),
+                night: Color::new(110, 110, 110),
+            }),
+            background: Some(Color::new(0, 0, 0)),
+            marker_style: MarkerStyle {
+                ch: Some('X'),
+                color: Some(Color::new(255, 255, 0)),
+            },
+            terminator_color: None,
+            heatmap_color: None,
+        }
+    }
+
+    /// Colors of the Solarized dark scheme.
+    pub fn solarized() -> Theme {
+        Theme {
+            palette: None,
+            colors: Some(Coloring::Luminance {
+                day: Color::new(42, 161, 152),
+                night: Color::new(7, 54, 66),
+            }),
+            background: Some(Color::new(0, 43, 54)),
+            marker_style: MarkerStyle {
+                ch: None,
+                color: Some(Color::new(181, 137, 0)),
+            },
+            terminator_color: None,
+            heatmap_color: None,
+        }
+    }
+
+    /// Returns the theme adjusted for color vision deficiencies, its colors
+    /// swapped for `COLORBLIND_SAFE_COLORING` and markers drawn in
+    /// vermillion, which stands out from it by brightness as well. The
+    /// terminator is drawn in reddish purple and the heatmap in sky blue,
+    /// also taken from `COLORBLIND_SAFE_COLORS`.
+    pub fn colorblind_safe(self) -> Theme {
+        Theme {
+            colors: Some(COLORBLIND_SAFE_COLORING),
+            marker_style: MarkerStyle {
+                color: Some(COLORBLIND_SAFE_COLORS[5]),
+                ..self.marker_style
+            },
+            terminator_color: Some(COLORBLIND_SAFE_COLORS[6]),
+            heatmap_color: Some(COLORBLIND_SAFE_COLORS[1]),
+            ..self
+        }
+    }
+
+    /// Returns the color text is printed in on outputs with a single
+    /// foreground color, the day color of the theme's coloring.
+    pub fn foreground(&self) -> Option<Color> {
+        match self.colors {
+            Some(Coloring::Luminance { day, .. }) => Some(day),
+            _ => None,
+        }
+    }
+}
+
+impl Globe {
+    /// Applies the theme to the globe and the markers, terminator and
+    /// heatmap already on it.
+    ///
+    /// The palette swaps characters of the texture, so going back to the
+    /// original ones takes building the globe anew.
+    pub fn apply_theme(&mut self, theme: &Theme) {
+        if let Some(palette) = &theme.palette {
+            self.texture.remap_palette(palette);
+        }
+        if theme.colors.is_some() {
+            self.coloring = theme.colors;
+        }
+        for marker in &mut self.markers {
+            *marker = theme.marker_style.apply(marker.clone());
+        }
+        if let (Some(color), Some(terminator)) = (theme.terminator_color, &mut self.terminator) {
+            terminator.color = Some(color);
+        }
+        if let (Some(color), Some(heatmap)) = (theme.heatmap_color, &mut self.heatmap) {
+            heatmap.color = Some(color);
+        }
+    }
+}
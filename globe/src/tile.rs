@@ -0,0 +1,110 @@
+//! Rendering one tile of a globe split across several displays.
+
+use core::convert::TryFrom;
+
+use crate::target::sealed::{FrameState, Token};
+use crate::target::{ray_cache, smoothing_history};
+use crate::{CellColor, CellRect, Globe, Int, RayCache, RenderTarget, SmoothingHistory};
+
+/// Render target presenting a window into a larger grid, e.g. one terminal of
+/// a video wall, as the whole grid.
+///
+/// Cells outside of the window are dropped.
+struct Tile<'a, T> {
+    inner: &'a mut T,
+    /// Number of columns and rows of the whole grid
+    grid: (usize, usize),
+    /// Position of the inner target's top left cell within the grid
+    offset: (Int, Int),
+}
+
+impl<T: RenderTarget> Tile<'_, T> {
+    /// Translates a cell of the whole grid to a cell of the inner target.
+    fn inner_cell(&self, col: usize, row: usize) -> Option<(usize, usize)> {
+        let (cols, rows) = self.inner.grid_size();
+        let col = usize::try_from(col as Int - self.offset.0).ok()?;
+        let row = usize::try_from(row as Int - self.offset.1).ok()?;
+        (col < cols && row < rows).then_some((col, row))
+    }
+
+    /// Returns the cells of the whole grid the inner target covers.
+    fn visible_rect(&self) -> CellRect {
+        let (cols, rows) = self.inner.grid_size();
+        let span = |offset: Int, len: usize, max: usize| {
+            let start = offset.clamp(0, max as Int) as usize;
+            let end = (offset + len as Int).clamp(0, max as Int) as usize;
+            (start, end - start)
+        };
+        let (x, width) = span(self.offset.0, cols, self.grid.0);
+        let (y, height) = span(self.offset.1, rows, self.grid.1);
+        CellRect::new(x, y, width, height)
+    }
+}
+
+impl<T: RenderTarget> RenderTarget for Tile<'_, T> {
+    fn grid_size(&self) -> (usize, usize) {
+        self.grid
+    }
+    fn char_pix(&self) -> (usize, usize) {
+        self.inner.char_pix()
+    }
+    fn draw_point(&mut self, col: usize, row: usize, ch: char) {
+        if let Some((col, row)) = self.inner_cell(col, row) {
+            self.inner.draw_point(col, row, ch);
+        }
+    }
+    fn has_colors(&self) -> bool {
+        self.inner.has_colors()
+    }
+    fn draw_colored_point(&mut self, col: usize, row: usize, ch: char, color: CellColor) {
+        if let Some((col, row)) = self.inner_cell(col, row) {
+            self.inner.draw_colored_point(col, row, ch, color);
+        }
+    }
+    fn clear_cell(&mut self, col: usize, row: usize) {
+        if let Some((col, row)) = self.inner_cell(col, row) {
+            self.inner.clear_cell(col, row);
+        }
+    }
+    fn clear(&mut self) {
+        self.inner.clear();
+    }
+    fn frame_state(&mut self, _: Token) -> Option<&mut dyn FrameState> {
+        Some(self)
+    }
+}
+
+// both are keyed by cells of the whole grid, which the inner target's
+// storage adapts to
+impl<T: RenderTarget> FrameState for Tile<'_, T> {
+    fn smoothing_history(&mut self) -> Option<&mut SmoothingHistory> {
+        smoothing_history(self.inner)
+    }
+    fn ray_cache(&mut self) -> Option<&mut RayCache> {
+        ray_cache(self.inner)
+    }
+}
+
+impl Globe {
+    /// Renders the part of a `grid` sized canvas covered by the target, its
+    /// top left cell placed at `offset` within the grid.
+    ///
+    /// Lets several displays show one globe together, each rendering only its
+    /// own tile of the whole picture. The offset can be negative, or the
+    /// target can reach past the grid, leaving the cells outside of it blank.
+    pub fn render_tile<T: RenderTarget>(
+        &self,
+        target: &mut T,
+        grid: (usize, usize),
+        offset: (Int, Int),
+    ) {
+        let mut tile = Tile {
+            inner: target,
+            grid,
+            offset,
+        };
+        tile.clear();
+        let rect = tile.visible_rect();
+        self.render_cells(&mut tile, rect);
+    }
+}
@@ -0,0 +1,176 @@
+//! Fields of vectors over the surface, like winds or ocean currents, drawn as
+//! arrows pointing the way they flow.
+
+use crate::consts::PI;
+use crate::error::{Error, Result};
+#[cfg(not(any(feature = "std", test)))]
+use crate::float::FloatExt;
+use crate::{CellColor, CellRect, Color, DataGrid, Float, GeoCoord, Globe, RenderTarget};
+
+/// Arrows vector fields are drawn with unless set otherwise, pointing east
+/// and going counterclockwise.
+const DEFAULT_ARROWS: [char; 8] = ['→', '↗', '↑', '↖', '←', '↙', '↓', '↘'];
+
+/// Columns and rows between the arrows unless set otherwise, keeping them
+/// about as far apart horizontally as vertically.
+const DEFAULT_SPACING: (usize, usize) = (4, 2);
+
+/// Degrees along the surface the direction of an arrow is measured over.
+const HEADING_STEP: Float = 0.5;
+
+/// Vectors laid over the surface on a grid of latitudes and longitudes, given
+/// by their eastward (u) and northward (v) components, e.g. winds or ocean
+/// currents.
+///
+/// Added to a globe, arrows are drawn on an even lattice of canvas cells,
+/// each pointing the way the vector under it points on the screen. Cells
+/// without data and vectors of zero length are left out.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VectorField {
+    u: DataGrid,
+    v: DataGrid,
+    /// Characters pointing east, north east, north, and so on going
+    /// counterclockwise
+    pub arrows: [char; 8],
+    /// Color the arrows are drawn in, the globe's own if `None`
+    pub color: Option<Color>,
+    /// Number of columns and rows from one arrow to the next
+    pub spacing: (usize, usize),
+    /// Whether the field is drawn
+    pub visible: bool,
+}
+
+impl VectorField {
+    /// Creates a field out of grids of the eastward and northward components
+    /// of its vectors, laid over the same cells.
+    ///
+    /// Fails if the grids differ in size.
+    pub fn new(u: DataGrid, v: DataGrid) -> Result<Self> {
+        if u.size() != v.size() {
+            return Err(Error::InvalidDataGrid);
+        }
+        Ok(Self {
+            u,
+            v,
+            arrows: DEFAULT_ARROWS,
+            color: None,
+            spacing: DEFAULT_SPACING,
+            visible: true,
+        })
+    }
+
+    /// Sets the characters pointing east, north east, north, and so on going
+    /// counterclockwise, e.g. for terminals without the arrows.
+    pub fn with_arrows(mut self, arrows: [char; 8]) -> Self {
+        self.arrows = arrows;
+        self
+    }
+
+    /// Sets the color the arrows are drawn in, `None` keeping the globe's.
+    pub fn with_color(mut self, color: Option<Color>) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Sets the number of columns and rows from one arrow to the next.
+    pub fn with_spacing(mut self, cols: usize, rows: usize) -> Self {
+        self.spacing = (cols, rows);
+        self
+    }
+
+    /// Returns the eastward and northward components of the vector at the
+    /// location, `None` if either holds no data.
+    pub fn vector_at(&self, coord: GeoCoord) -> Option<(f32, f32)> {
+        Some((self.u.value_at(coord)?, self.v.value_at(coord)?))
+    }
+}
+
+impl Globe {
+    /// Adds a vector field to be drawn over the surface, on top of the ones
+    /// added before it.
+    pub fn add_vector_field(&mut self, field: VectorField) {
+        self.vector_fields.push(field);
+    }
+
+    /// Returns the vector fields drawn over the surface.
+    pub fn vector_fields(&self) -> &[VectorField] {
+        &self.vector_fields
+    }
+
+    /// Returns the vector fields drawn over the surface, e.g. for hiding
+    /// them.
+    pub fn vector_fields_mut(&mut self) -> &mut [VectorField] {
+        &mut self.vector_fields
+    }
+
+    /// Removes all the vector fields.
+    pub fn clear_vector_fields(&mut self) {
+        self.vector_fields.clear();
+    }
+
+    /// Draws the arrows of the visible fields, skipping the cells outside of
+    /// `rect` if given.
+    pub(crate) fn render_vector_fields_within<S: RenderTarget>(
+        &self,
+        canvas: &mut S,
+        rect: Option<CellRect>,
+    ) {
+        let (cols, rows) = canvas.grid_size();
+        for field in self.vector_fields.iter().filter(|field| field.visible) {
+            let (step_x, step_y) = (field.spacing.0.max(1), field.spacing.1.max(1));
+            // arrows sit in the middle of their blocks of cells
+            for row in (step_y / 2..rows).step_by(step_y) {
+                for col in (step_x / 2..cols).step_by(step_x) {
+                    if rect.is_some_and(|rect| !rect.contains(col, row)) {
+                        continue;
+                    }
+                    let ch = match self.arrow_at(canvas, field, (col, row)) {
+                        Some(ch) => ch,
+                        None => continue,
+                    };
+                    let color = CellColor {
+                        fg: field.color,
+                        bg: None,
+                    };
+                    canvas.draw_colored_point(col, row, ch, color);
+                }
+            }
+        }
+    }
+
+    /// Returns the arrow showing the field at the given canvas cell, `None`
+    /// if the cell is off the globe or there's no vector to show.
+    fn arrow_at<S: RenderTarget>(
+        &self,
+        canvas: &S,
+        field: &VectorField,
+        cell: (usize, usize),
+    ) -> Option<char> {
+        let (lat, lon) = self.unproject(canvas, cell.0, cell.1)?;
+        let (u, v) = field.vector_at(GeoCoord::from_lat_lon(lat, lon))?;
+        let length = (u as Float).hypot(v as Float);
+        if length <= 0. {
+            return None;
+        }
+        // step a little way along the vector and see where it goes on the
+        // screen, degrees of longitude shrinking towards the poles
+        let (north, east) = (v as Float / length, u as Float / length);
+        let ahead = GeoCoord::from_lat_lon(
+            lat + north * HEADING_STEP,
+            lon + east * HEADING_STEP / lat.to_radians().cos().max(0.01),
+        );
+        let from = GeoCoord::from_lat_lon(lat, lon).sphere_point(self.angle, self.radius);
+        let to = ahead.sphere_point(self.angle, self.radius);
+        let (x0, y0) = self.screen_position(from, canvas)?;
+        let (x1, y1) = self.screen_position(to, canvas)?;
+        let (char_x, char_y) = canvas.char_pix();
+        let dx = (x1 - x0) * char_x as Float;
+        let dy = (y1 - y0) * char_y as Float;
+        if dx == 0. && dy == 0. {
+            return None;
+        }
+        // rows run downwards while angles go counterclockwise
+        let eighth = ((-dy).atan2(dx) / (PI / 4.)).round() as i32;
+        Some(field.arrows[eighth.rem_euclid(8) as usize])
+    }
+}
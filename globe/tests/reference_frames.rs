@@ -0,0 +1,95 @@
+//! Regression tests of rendered frames, comparing the reference scenes
+//! against frames saved in `tests/frames`.
+//!
+//! After an intended change to the renderer, run the tests with
+//! `UPDATE_FRAMES=1` to save the new frames, then look them over before
+//! committing.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use globe::test_support::{reference_scenes, FrameComparer};
+use globe::GlobeTemplate;
+
+/// Returns the path of the file holding the expected frame of the scene.
+fn frame_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("frames")
+        .join(format!("{}.txt", name))
+}
+
+#[test]
+fn reference_scenes_match_saved_frames() {
+    let update = env::var_os("UPDATE_FRAMES").is_some();
+    for scene in reference_scenes() {
+        let path = frame_path(scene.name);
+        let actual = scene.render();
+        if update {
+            fs::write(&path, &actual).unwrap();
+            continue;
+        }
+        let expected = fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("failed reading {}: {}", path.display(), e));
+        let diff = FrameComparer::for_template(scene.template)
+            .with_tolerance(1)
+            .with_allowed_mismatches(2)
+            .compare(&expected, &actual);
+        assert!(diff.passed(), "scene {} changed: {}", scene.name, diff);
+    }
+}
+
+/// Shifts every character of the frame one step along the palette, the way
+/// a tweak of the shading by a pixel's worth of brightness does.
+fn shift_brightness(frame: &str, palette: &[char]) -> String {
+    frame
+        .chars()
+        .map(|ch| match palette.iter().position(|&p| p == ch) {
+            Some(i) if i + 1 < palette.len() => palette[i + 1],
+            _ => ch,
+        })
+        .collect()
+}
+
+#[test]
+fn shading_tweaks_stay_within_tolerance() {
+    let scene = reference_scenes().remove(0);
+    let palette = scene.template.palette();
+    let frame = scene.render();
+    let tweaked = shift_brightness(&frame, palette);
+    assert_ne!(frame, tweaked);
+
+    let exact = FrameComparer::for_template(scene.template).compare(&frame, &tweaked);
+    assert!(!exact.passed());
+    let tolerant = FrameComparer::for_template(scene.template)
+        .with_tolerance(1)
+        .compare(&frame, &tweaked);
+    assert!(tolerant.passed(), "{}", tolerant);
+    assert_eq!(tolerant.cells.len(), exact.cells.len());
+}
+
+#[test]
+fn different_scenes_fail_the_comparison() {
+    let scenes = reference_scenes();
+    let earth = scenes.iter().find(|s| s.name == "earth").unwrap();
+    let tilted = scenes.iter().find(|s| s.name == "earth-tilted").unwrap();
+    let diff = FrameComparer::for_template(GlobeTemplate::Earth)
+        .with_tolerance(1)
+        .with_allowed_mismatches(2)
+        .compare(&earth.render(), &tilted.render());
+    assert!(!diff.passed());
+    assert!(diff.mismatches().count() > 2);
+}
+
+#[test]
+fn frames_of_another_size_fail_the_comparison() {
+    let mut scene = reference_scenes().remove(0);
+    let frame = scene.render();
+    scene.size = (scene.size.0 + 2, scene.size.1 + 1);
+    let diff = FrameComparer::for_template(scene.template)
+        .with_tolerance(1)
+        .compare(&frame, &scene.render());
+    assert!(diff.size_mismatch);
+    assert!(!diff.passed());
+}